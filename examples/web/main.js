@@ -0,0 +1,37 @@
+import init, { days, solve } from "./pkg/advent_of_code_2023.js";
+
+const day = document.getElementById("day");
+const input = document.getElementById("input");
+const button = document.getElementById("solve");
+const output = document.getElementById("output");
+
+await init();
+
+// only the days the module was built with, the no_std ones without the std feature
+for (const number of days()) {
+  const option = document.createElement("option");
+  option.value = number;
+  option.textContent = String(number).padStart(2, "0");
+  day.append(option);
+}
+button.disabled = false;
+
+button.addEventListener("click", () => {
+  output.className = "";
+
+  // the module has no clock of its own, so the call is timed here
+  const start = performance.now();
+  try {
+    const solution = solve(Number(day.value), input.value);
+    const elapsed = performance.now() - start;
+    output.textContent = [
+      `part 1: ${solution.part1 ?? "-"}`,
+      `part 2: ${solution.part2 ?? "-"}`,
+      `took ${elapsed.toFixed(2)} ms`,
+    ].join("\n");
+    solution.free();
+  } catch (error) {
+    output.className = "error";
+    output.textContent = String(error);
+  }
+});
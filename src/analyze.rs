@@ -0,0 +1,229 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use tracing::info;
+
+// how many of the most common characters are shown
+const TOP_CHARACTERS: usize = 10;
+
+// rough shape of a puzzle input, used to pick an algorithm and to sanity check a download
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Analysis {
+    pub lines: usize,
+    pub blank_lines: usize,
+    pub blocks: Vec<Block>,
+    pub characters: BTreeMap<char, usize>,
+    pub numbers: Option<NumberRange>,
+}
+
+// consecutive non blank lines
+#[derive(Debug, PartialEq, Eq)]
+pub struct Block {
+    pub lines: usize,
+    pub min_width: usize,
+    pub max_width: usize,
+}
+
+impl Block {
+    // every line has the same width, so the block can be read as a grid
+    pub fn is_grid(&self) -> bool {
+        self.lines > 1 && self.min_width == self.max_width
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NumberRange {
+    pub count: usize,
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Analysis {
+    pub fn new(input: &str) -> Self {
+        let mut analysis = Self::default();
+        let mut block: Option<Block> = None;
+
+        for line in input.lines() {
+            analysis.lines += 1;
+
+            if line.trim().is_empty() {
+                analysis.blank_lines += 1;
+                analysis.blocks.extend(block.take());
+                continue;
+            }
+
+            let width = line.chars().count();
+            let current = block.get_or_insert(Block {
+                lines: 0,
+                min_width: width,
+                max_width: width,
+            });
+            current.lines += 1;
+            current.min_width = current.min_width.min(width);
+            current.max_width = current.max_width.max(width);
+
+            for c in line.chars().filter(|c| !c.is_whitespace()) {
+                *analysis.characters.entry(c).or_insert(0) += 1;
+            }
+
+            for value in numbers(line) {
+                let range = analysis.numbers.get_or_insert(NumberRange {
+                    count: 0,
+                    min: value,
+                    max: value,
+                });
+                range.count += 1;
+                range.min = range.min.min(value);
+                range.max = range.max.max(value);
+            }
+        }
+
+        analysis.blocks.extend(block);
+        analysis
+    }
+
+    pub fn display(&self) {
+        info!("\n{}", self);
+    }
+}
+
+// signed integers in a line, a '-' only counts as a sign when it directly precedes a digit
+fn numbers(line: &str) -> Vec<i64> {
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut result = vec![];
+    let mut index = 0;
+
+    while index < chars.len() {
+        let negative = chars[index] == '-'
+            && chars.get(index + 1).is_some_and(|f| f.is_ascii_digit())
+            && (index == 0 || !chars[index - 1].is_ascii_alphanumeric());
+        let start = if negative { index + 1 } else { index };
+
+        if !chars[start].is_ascii_digit() {
+            index += 1;
+            continue;
+        }
+
+        let mut end = start;
+        while end < chars.len() && chars[end].is_ascii_digit() {
+            end += 1;
+        }
+
+        let text = chars[start..end].iter().collect::<String>();
+        // values that don't fit are skipped, they would only happen in generated inputs
+        if let Ok(value) = text.parse::<i64>() {
+            result.push(if negative { -value } else { value });
+        }
+
+        index = end;
+    }
+
+    result
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "lines: {} ({} blank, {} blocks)",
+            self.lines,
+            self.blank_lines,
+            self.blocks.len()
+        )?;
+
+        for (index, block) in self.blocks.iter().enumerate() {
+            if block.is_grid() {
+                writeln!(
+                    f,
+                    "block {}: grid {}x{}",
+                    index + 1,
+                    block.max_width,
+                    block.lines
+                )?;
+            } else {
+                writeln!(
+                    f,
+                    "block {}: {} lines, width {}..={}",
+                    index + 1,
+                    block.lines,
+                    block.min_width,
+                    block.max_width
+                )?;
+            }
+        }
+
+        match &self.numbers {
+            Some(range) => writeln!(
+                f,
+                "numbers: {} values in {}..={}",
+                range.count, range.min, range.max
+            )?,
+            None => writeln!(f, "numbers: none")?,
+        }
+
+        let mut characters = self.characters.iter().collect::<Vec<_>>();
+        // most common first, ties in character order so the output is stable
+        characters.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+        write!(f, "characters: {} distinct", characters.len())?;
+        for (c, count) in characters.iter().take(TOP_CHARACTERS) {
+            write!(f, ", '{}' {}", c, count)?;
+        }
+        writeln!(f)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_grid_input() {
+        let analysis = Analysis::new("#.#\n...\n#.#\n");
+
+        assert_eq!(analysis.lines, 3);
+        assert_eq!(analysis.blank_lines, 0);
+        assert_eq!(
+            analysis.blocks,
+            vec![Block {
+                lines: 3,
+                min_width: 3,
+                max_width: 3
+            }]
+        );
+        assert!(analysis.blocks[0].is_grid());
+        assert_eq!(analysis.characters.get(&'.'), Some(&5));
+        assert_eq!(analysis.numbers, None);
+        assert_eq!(
+            analysis.to_string(),
+            "lines: 3 (0 blank, 1 blocks)\nblock 1: grid 3x3\nnumbers: none\ncharacters: 2 distinct, '.' 5, '#' 4\n"
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_blocks_and_numbers() {
+        let analysis = Analysis::new("seeds: 79 14\n\nmap-to:\n-3 10-2 0\n\n\nx=5,y=-7\n");
+
+        assert_eq!(analysis.lines, 7);
+        assert_eq!(analysis.blank_lines, 3);
+        assert_eq!(analysis.blocks.len(), 3);
+        assert_eq!(analysis.blocks[1].lines, 2);
+        assert!(!analysis.blocks[1].is_grid());
+        assert_eq!(
+            analysis.numbers,
+            Some(NumberRange {
+                count: 8,
+                min: -7,
+                max: 79
+            })
+        );
+    }
+}
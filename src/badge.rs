@@ -0,0 +1,137 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use color_eyre::eyre::{bail, Result};
+
+use crate::bench::BenchRun;
+
+// a rough average width of the 11px Verdana shields.io draws badges with
+const CHARACTER_WIDTH: usize = 7;
+const PADDING: usize = 10;
+
+// "210 ms", rounded to what is worth reading on a badge
+pub fn format_time(time: Duration) -> String {
+    let micros = time.as_secs_f64() * 1e6;
+
+    if micros < 1e3 {
+        format!("{:.0} µs", micros)
+    } else if micros < 1e6 {
+        format!("{:.0} ms", micros / 1e3)
+    } else {
+        format!("{:.1} s", micros / 1e6)
+    }
+}
+
+// the shields.io colors, from green for instant days to red for the slow ones
+fn color(time: Duration) -> &'static str {
+    match time.as_millis() {
+        0..=9 => "#4c1",
+        10..=99 => "#97ca00",
+        100..=999 => "#dfb317",
+        1000..=9999 => "#fe7d37",
+        _ => "#e05d44",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// a flat shields-style badge, the label on grey and the time on its color
+pub fn svg(label: &str, time: Duration) -> String {
+    let message = format_time(time);
+    let label_width = label.chars().count() * CHARACTER_WIDTH + PADDING;
+    let message_width = message.chars().count() * CHARACTER_WIDTH + PADDING;
+    let width = label_width + message_width;
+    let (label, message) = (escape(label), escape(&message));
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">
+<title>{label}: {message}</title>
+<rect width="{label_width}" height="20" fill="#555"/>
+<rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/>
+<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+<text x="{label_x}" y="14">{label}</text>
+<text x="{message_x}" y="14">{message}</text>
+</g>
+</svg>
+"##,
+        color = color(time),
+        label_x = label_width / 2,
+        message_x = label_width + message_width / 2,
+    )
+}
+
+// dayNN.svg with the median of each day and total.svg with the sum of them, returns the files written
+pub fn write_badges(run: &BenchRun, directory: &Path) -> Result<Vec<PathBuf>> {
+    if run.is_empty() {
+        bail!("the run has no days to draw badges for");
+    }
+
+    fs::create_dir_all(directory)?;
+
+    let mut paths = vec![];
+    let mut total = Duration::ZERO;
+
+    for (day, stats) in run.days() {
+        let path = directory.join(format!("day{:0>2}.svg", day));
+        fs::write(&path, svg(&format!("day {:0>2}", day), stats.median))?;
+
+        total += stats.median;
+        paths.push(path);
+    }
+
+    let path = directory.join("total.svg");
+    fs::write(&path, svg("all days", total))?;
+    paths.push(path);
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_svg() {
+        assert_eq!(format_time(Duration::from_micros(420)), "420 µs");
+        assert_eq!(format_time(Duration::from_millis(210)), "210 ms");
+        assert_eq!(format_time(Duration::from_millis(2345)), "2.3 s");
+
+        let badge = svg("day 17", Duration::from_millis(210));
+        assert!(badge.contains(r#"aria-label="day 17: 210 ms""#));
+        assert!(badge.contains(r##"fill="#dfb317""##));
+        assert!(badge.contains(r#"width="104""#));
+
+        assert!(svg("<a&b>", Duration::ZERO).contains("&lt;a&amp;b&gt;"));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_write_badges() {
+        let directory = std::env::temp_dir().join("advent-of-code-2023-badge-test");
+        let run = BenchRun::parse("01 100000 100000 0\n17 210000000 200000000 0\n").unwrap();
+
+        let paths = write_badges(&run, &directory).unwrap();
+        let names = paths
+            .iter()
+            .map(|f| f.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["day01.svg", "day17.svg", "total.svg"]);
+
+        let total = fs::read_to_string(directory.join("total.svg")).unwrap();
+        assert!(total.contains("all days: 200 ms"));
+
+        assert!(write_badges(&BenchRun::new(), &directory).is_err());
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
@@ -0,0 +1,358 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use color_eyre::eyre::{bail, eyre, Result};
+
+use crate::{registry, solver, utils::charset};
+
+// changes smaller than this are reported as noise, like cargo bench does
+pub const NOISE_THRESHOLD: f64 = 5.0;
+
+// samples further than this many interquartile ranges outside the quartiles are outliers
+const OUTLIER_FENCE: f64 = 1.5;
+
+// saved runs live next to the inputs since they only mean something on this machine
+pub fn baseline_path(name: &str) -> Result<String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        bail!("{:?} is not a usable baseline name", name);
+    }
+
+    Ok(format!("input/bench/{}", name))
+}
+
+// timings of one day after outliers were dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub mean: Duration,
+    pub median: Duration,
+    // median absolute deviation from the median
+    pub mad: Duration,
+    pub outliers: usize,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+
+        let mut sorted = samples.iter().map(|f| f.as_secs_f64()).collect::<Vec<_>>();
+        sorted.sort_by(f64::total_cmp);
+
+        let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+        let fence = (q3 - q1) * OUTLIER_FENCE;
+        let kept = sorted
+            .iter()
+            .copied()
+            .filter(|f| (q1 - fence..=q3 + fence).contains(f))
+            .collect::<Vec<_>>();
+
+        let median = quantile(&kept, 0.5);
+        let mut deviations = kept.iter().map(|f| (f - median).abs()).collect::<Vec<_>>();
+        deviations.sort_by(f64::total_cmp);
+
+        Self {
+            mean: Duration::from_secs_f64(kept.iter().sum::<f64>() / kept.len() as f64),
+            median: Duration::from_secs_f64(median),
+            mad: Duration::from_secs_f64(quantile(&deviations, 0.5)),
+            outliers: sorted.len() - kept.len(),
+        }
+    }
+
+    // a run saved before only had the mean
+    fn from_mean(mean: Duration) -> Self {
+        Self {
+            mean,
+            median: mean,
+            mad: Duration::ZERO,
+            outliers: 0,
+        }
+    }
+}
+
+// linear interpolation between the closest ranks, sorted must not be empty
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = (sorted.len() - 1) as f64 * q;
+    let (low, high) = (position.floor() as usize, position.ceil() as usize);
+
+    sorted[low] + (sorted[high] - sorted[low]) * (position - low as f64)
+}
+
+// solve times of each day, with the default variant
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BenchRun {
+    days: BTreeMap<i32, Stats>,
+}
+
+impl BenchRun {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // solves the day iterations times from an input already in memory, so disk reads are not timed
+    // the warmup solves fill caches and let the cpu clock up, they are not measured
+    pub fn measure(&mut self, day: i32, warmup: u32, iterations: u32) -> Result<()> {
+        let variant = registry::variants(day)
+            .first()
+            .ok_or_else(|| eyre!("day {} is not implemented", day))?;
+        let input = solver::read_input(day)?;
+
+        for _ in 0..warmup {
+            (variant.solve)(&input)?;
+        }
+
+        let mut samples = vec![];
+        for _ in 0..iterations.max(1) {
+            let start = Instant::now();
+            (variant.solve)(&input)?;
+            samples.push(start.elapsed());
+        }
+
+        self.insert(day, Stats::from_samples(&samples));
+
+        Ok(())
+    }
+
+    pub fn insert(&mut self, day: i32, stats: Stats) {
+        self.days.insert(day, stats);
+    }
+
+    pub fn get(&self, day: i32) -> Option<Stats> {
+        self.days.get(&day).copied()
+    }
+
+    // in day order
+    pub fn days(&self) -> impl Iterator<Item = (i32, Stats)> + '_ {
+        self.days.iter().map(|(day, stats)| (*day, *stats))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+
+    // one "day mean median mad" line per day, in nanoseconds
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let text = self
+            .days
+            .iter()
+            .map(|(day, stats)| {
+                format!(
+                    "{:0>2} {} {} {}\n",
+                    day,
+                    stats.mean.as_nanos(),
+                    stats.median.as_nanos(),
+                    stats.mad.as_nanos()
+                )
+            })
+            .collect::<String>();
+        fs::write(path, text)?;
+
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|f| eyre!("could not read the baseline {}: {}", path.display(), f))?;
+
+        Self::parse(&content).map_err(|f| eyre!("{}: {}", path.display(), f))
+    }
+
+    // runs saved before the median was recorded only have "day mean"
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut days = BTreeMap::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            let day = parts.first().and_then(|f| f.parse::<i32>().ok());
+            let nanos = parts[1.min(parts.len())..]
+                .iter()
+                .map(|f| f.parse::<u64>().map(Duration::from_nanos))
+                .collect::<Result<Vec<_>, _>>();
+
+            let stats = match (day, nanos.as_deref()) {
+                (Some(_), Ok(&[mean])) => Stats::from_mean(mean),
+                (Some(_), Ok(&[mean, median, mad])) => Stats {
+                    mean,
+                    median,
+                    mad,
+                    outliers: 0,
+                },
+                _ => bail!(
+                    "line {}: expected \"day mean median mad\" in nanoseconds",
+                    index + 1
+                ),
+            };
+
+            days.insert(day.unwrap(), stats);
+        }
+
+        Ok(Self { days })
+    }
+
+    // the days of this run next to the same days of the baseline
+    pub fn compare<'a>(&'a self, baseline: &'a BenchRun) -> BenchComparison<'a> {
+        BenchComparison {
+            current: self,
+            baseline,
+        }
+    }
+}
+
+impl Display for BenchRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<5}  {:>12}  {:>12}  {:>12}  {:>8}",
+            "Day", "Median", "MAD", "Mean", "Outliers"
+        )?;
+
+        for (day, stats) in &self.days {
+            writeln!(
+                f,
+                "{:<5}  {:>12}  {:>12}  {:>12}  {:>8}",
+                format!("{:0>2}", day),
+                format!("{:.3?}", stats.median),
+                format!("{} {:.3?}", charset::glyph("±", "+/-"), stats.mad),
+                format!("{:.3?}", stats.mean),
+                stats.outliers
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct BenchComparison<'a> {
+    current: &'a BenchRun,
+    baseline: &'a BenchRun,
+}
+
+impl BenchComparison<'_> {
+    // percentage change of the median, negative is faster
+    pub fn change(&self, day: i32) -> Option<f64> {
+        let current = self.current.get(day)?.median.as_secs_f64();
+        let baseline = self.baseline.get(day)?.median.as_secs_f64();
+
+        Some((current - baseline) / baseline.max(f64::EPSILON) * 100.0)
+    }
+}
+
+impl Display for BenchComparison<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<5}  {:>12}  {:>12}  {:>8}",
+            "Day", "Baseline", "Current", "Change"
+        )?;
+
+        for (day, stats) in &self.current.days {
+            let baseline = self
+                .baseline
+                .get(*day)
+                .map(|f| format!("{:.3?}", f.median))
+                .unwrap_or_else(|| "-".to_string());
+
+            let change = match self.change(*day) {
+                Some(change) => {
+                    let verdict = if change <= -NOISE_THRESHOLD {
+                        "improved"
+                    } else if change >= NOISE_THRESHOLD {
+                        "regressed"
+                    } else {
+                        "no change"
+                    };
+
+                    format!("{:>+7.1}%  {}", change, verdict)
+                }
+                None => format!("{:>8}", "-"),
+            };
+
+            writeln!(
+                f,
+                "{:<5}  {:>12}  {:>12}  {}",
+                format!("{:0>2}", day),
+                baseline,
+                format!("{:.3?}", stats.median),
+                change
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_bench_run() {
+        let baseline = BenchRun::parse("01 1000000\n17 2100000000 2000000000 1000\n").unwrap();
+        let current = BenchRun::parse("01 1010000\n05 5000\n17 500000000\n").unwrap();
+
+        assert_eq!(baseline.get(1).unwrap().median, Duration::from_millis(1));
+        assert_eq!(baseline.get(17).unwrap().median, Duration::from_secs(2));
+        assert!(BenchRun::parse("01\n").is_err());
+        assert!(BenchRun::parse("01 1 2\n").is_err());
+        assert!(BenchRun::parse("01 fast\n").is_err());
+
+        let comparison = current.compare(&baseline);
+        assert_eq!(comparison.change(17), Some(-75.0));
+        assert_eq!(comparison.change(5), None);
+
+        let text = comparison.to_string();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert!(lines[1].starts_with("01") && lines[1].ends_with("+1.0%  no change"));
+        assert!(lines[2].starts_with("05") && lines[2].ends_with("-"));
+        assert!(lines[3].starts_with("17") && lines[3].ends_with("-75.0%  improved"));
+
+        assert!(baseline_path("before").is_ok());
+        assert!(baseline_path("../answers").is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_stats() {
+        let millis = |values: &[u64]| {
+            values
+                .iter()
+                .map(|f| Duration::from_millis(*f))
+                .collect::<Vec<_>>()
+        };
+
+        // the 100 ms sample is a hiccup and is dropped
+        let stats = Stats::from_samples(&millis(&[10, 12, 11, 13, 100, 11, 12]));
+        assert_eq!(stats.outliers, 1);
+        assert_eq!(stats.median.as_micros(), 11500);
+        assert_eq!(stats.mad.as_micros(), 500);
+        assert_eq!(stats.mean.as_micros(), 11500);
+
+        let stats = Stats::from_samples(&millis(&[7]));
+        assert_eq!(stats.median, Duration::from_millis(7));
+        assert_eq!(stats.mad, Duration::ZERO);
+        assert_eq!(stats.outliers, 0);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join("advent-of-code-2023-bench-test");
+        let run = BenchRun::parse("09 12345 12000 300\n").unwrap();
+
+        run.save(&path).unwrap();
+        assert_eq!(BenchRun::load(&path).unwrap(), run);
+
+        let _ = fs::remove_file(&path);
+    }
+}
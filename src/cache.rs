@@ -0,0 +1,143 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use color_eyre::eyre::Result;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{debug, warn};
+
+// parsed inputs saved by --parse-cache, next to the inputs they came from
+pub const CACHE_DIRECTORY: &str = "input/cache";
+
+// set once from the command line
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// a parsed input worth saving because parsing it is a measurable slice of the solve
+// bump FORMAT whenever the fields of the structure change, so older files are parsed again instead
+// of being misread
+pub trait Cached: FromStr + Serialize + DeserializeOwned {
+    const NAME: &'static str;
+    const FORMAT: u32;
+}
+
+// parses like input.parse(), but with --parse-cache an input seen before is read back instead
+pub fn parse<T: Cached>(input: &str) -> Result<T, T::Err> {
+    if !enabled() {
+        return input.parse();
+    }
+
+    parse_in(Path::new(CACHE_DIRECTORY), input)
+}
+
+// the cache only ever costs a parse, a file that can not be read or written is logged and skipped
+pub fn parse_in<T: Cached>(directory: &Path, input: &str) -> Result<T, T::Err> {
+    let path = cache_path::<T>(directory, input);
+
+    if let Ok(bytes) = fs::read(&path) {
+        match bincode::deserialize(&bytes) {
+            Ok(parsed) => {
+                debug!("read the parsed input from {}", path.display());
+                return Ok(parsed);
+            }
+            Err(error) => warn!("ignoring {}: {}", path.display(), error),
+        }
+    }
+
+    let parsed = input.parse::<T>()?;
+    if let Err(error) = save(&path, &parsed) {
+        warn!("could not save {}: {}", path.display(), error);
+    }
+
+    Ok(parsed)
+}
+
+fn save<T: Serialize>(path: &Path, parsed: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, bincode::serialize(parsed)?)?;
+
+    Ok(())
+}
+
+fn cache_path<T: Cached>(directory: &Path, input: &str) -> PathBuf {
+    directory.join(format!("{}-v{}-{:016x}", T::NAME, T::FORMAT, hash(input)))
+}
+
+// 64 bit FNV-1a, unlike DefaultHasher it hashes the same across builds and toolchains
+pub fn hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Numbers(Vec<i64>);
+
+    impl FromStr for Numbers {
+        type Err = std::num::ParseIntError;
+
+        fn from_str(input: &str) -> Result<Self, Self::Err> {
+            input
+                .split_whitespace()
+                .map(|f| f.parse())
+                .collect::<Result<_, _>>()
+                .map(Self)
+        }
+    }
+
+    impl Cached for Numbers {
+        const NAME: &'static str = "numbers";
+        const FORMAT: u32 = 1;
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_parse_in() {
+        let directory = std::env::temp_dir().join("advent-of-code-2023-cache-test");
+        let _ = fs::remove_dir_all(&directory);
+
+        let parsed = parse_in::<Numbers>(&directory, "1 2 3").unwrap();
+        assert_eq!(parsed, Numbers(vec![1, 2, 3]));
+
+        let path = cache_path::<Numbers>(&directory, "1 2 3");
+        assert!(path.exists());
+
+        // what is in the file wins, which shows the input was not parsed again
+        fs::write(&path, bincode::serialize(&Numbers(vec![4])).unwrap()).unwrap();
+        assert_eq!(
+            parse_in::<Numbers>(&directory, "1 2 3").unwrap(),
+            Numbers(vec![4])
+        );
+
+        // a damaged file is parsed over, and errors are the ones of the parse
+        fs::write(&path, "damaged").unwrap();
+        assert_eq!(
+            parse_in::<Numbers>(&directory, "1 2 3").unwrap(),
+            Numbers(vec![1, 2, 3])
+        );
+        assert!(parse_in::<Numbers>(&directory, "1 x").is_err());
+
+        assert_ne!(path, cache_path::<Numbers>(&directory, "1 2 4"));
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use color_eyre::eyre::{bail, Result};
+
+use crate::{registry, solver::read_input, utils::Part};
+
+// one line per day: the day, then the part 1 and part 2 answers, e.g. "01 54601 54078"
+pub const ANSWERS_PATH: &str = "input/answers";
+
+// stands in for a part that has no accepted answer yet, e.g. "12 7204 -"
+pub const OPEN_ANSWER: &str = "-";
+
+// the answers of the account of --profile, ANSWERS_PATH without one
+pub fn answers_path() -> String {
+    format!("{}/answers", crate::profile::input_directory())
+}
+
+// days solved quicker than this get the fast marker
+pub const FAST: Duration = Duration::from_secs(1);
+
+// december 2023 starts on a friday, columns are monday to sunday
+const FIRST_WEEKDAY: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    // no solution yet
+    Missing,
+    // solved here, but there is no input to run it on
+    Implemented,
+    // ran, but input/answers has nothing to check it against
+    Solved,
+    // ran and matched input/answers
+    Verified,
+    // ran and failed or disagreed with input/answers
+    Wrong,
+}
+
+impl Mark {
+    fn symbol(&self) -> char {
+        match self {
+            Mark::Missing => ' ',
+            Mark::Implemented => '.',
+            Mark::Solved => 'o',
+            Mark::Verified => 'v',
+            Mark::Wrong => 'x',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayStatus {
+    pub mark: Mark,
+    // how long the solve took, for days that ran
+    pub elapsed: Option<Duration>,
+}
+
+// the 25 days of the event laid out like the calendar on the site, one row per week
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calendar {
+    days: BTreeMap<i32, DayStatus>,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        let missing = DayStatus {
+            mark: Mark::Missing,
+            elapsed: None,
+        };
+
+        Self {
+            days: (1..=25).map(|f| (f, missing)).collect(),
+        }
+    }
+}
+
+impl Calendar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // solves every implemented day that has an input with its default variant
+    pub fn collect(answers: &BTreeMap<i32, (String, String)>) -> Self {
+        let mut calendar = Self::new();
+
+        for day in 1..=25 {
+            let Some(variant) = registry::variants(day).first() else {
+                continue;
+            };
+
+            let Ok(input) = read_input(day) else {
+                calendar.set(day, Mark::Implemented, None);
+                continue;
+            };
+
+            let start = Instant::now();
+            let answer = (variant.solve)(&input);
+            let elapsed = start.elapsed();
+
+            let mark = match (answer, answers.get(&day)) {
+                (Err(_), _) => Mark::Wrong,
+                (Ok(answer), Some((part1, part2))) => {
+                    let parts = [(&answer.part1, part1), (&answer.part2, part2)];
+                    let known = parts.iter().filter(|(_, f)| *f != OPEN_ANSWER);
+
+                    if known.clone().any(|(answer, f)| answer.as_ref() != Some(f)) {
+                        Mark::Wrong
+                    } else if known.count() == 2 {
+                        Mark::Verified
+                    } else {
+                        Mark::Solved
+                    }
+                }
+                (Ok(_), None) => Mark::Solved,
+            };
+
+            calendar.set(day, mark, Some(elapsed));
+        }
+
+        calendar
+    }
+
+    pub fn set(&mut self, day: i32, mark: Mark, elapsed: Option<Duration>) {
+        assert!((1..=25).contains(&day), "there is no day {}", day);
+        self.days.insert(day, DayStatus { mark, elapsed });
+    }
+
+    pub fn get(&self, day: i32) -> DayStatus {
+        self.days[&day]
+    }
+
+    pub fn count(&self, mark: Mark) -> usize {
+        self.days.values().filter(|f| f.mark == mark).count()
+    }
+}
+
+impl Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, " Mon    Tue    Wed    Thu    Fri    Sat    Sun")?;
+
+        let mut cells = vec!["      ".to_string(); FIRST_WEEKDAY];
+        for (day, status) in &self.days {
+            let fast = status.elapsed.is_some_and(|f| f < FAST);
+            cells.push(format!(
+                " {:0>2} {}{}",
+                day,
+                status.mark.symbol(),
+                if fast { '+' } else { ' ' }
+            ));
+        }
+
+        for week in cells.chunks(7) {
+            writeln!(f, "{}", week.join(" ").trim_end())?;
+        }
+
+        writeln!(
+            f,
+            "\nv verified {}, x wrong {}, o unchecked {}, . no input {}, + under {:?}",
+            self.count(Mark::Verified),
+            self.count(Mark::Wrong),
+            self.count(Mark::Solved),
+            self.count(Mark::Implemented),
+            FAST
+        )
+    }
+}
+
+pub fn read_answers(path: &Path) -> Result<BTreeMap<i32, (String, String)>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    parse_answers(&fs::read_to_string(path)?)
+}
+
+// the parts of a day without an accepted answer in the answers file
+pub fn open_parts(answers: &BTreeMap<i32, (String, String)>, day: i32) -> Vec<Part> {
+    let Some((part1, part2)) = answers.get(&day) else {
+        return vec![Part::One, Part::Two];
+    };
+
+    [(Part::One, part1), (Part::Two, part2)]
+        .into_iter()
+        .filter(|(_, f)| *f == OPEN_ANSWER)
+        .map(|(part, _)| part)
+        .collect()
+}
+
+// blank lines and lines starting with # are skipped
+pub fn parse_answers(content: &str) -> Result<BTreeMap<i32, (String, String)>> {
+    let mut answers = BTreeMap::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+        let (&[_, part1, part2], Ok(day)) = (&parts[..], parts[0].parse::<i32>()) else {
+            bail!(
+                "{}:{}: expected \"day part1 part2\"",
+                ANSWERS_PATH,
+                index + 1
+            );
+        };
+
+        answers.insert(day, (part1.to_string(), part2.to_string()));
+    }
+
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_parse_answers() {
+        let answers = parse_answers("# day part1 part2\n01 54601 54078\n\n9 114 2\n").unwrap();
+
+        assert_eq!(answers.len(), 2);
+        assert_eq!(answers[&9], ("114".to_string(), "2".to_string()));
+        assert!(parse_answers("01 54601\n").is_err());
+        assert!(parse_answers("one 1 2\n").is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_open_parts() {
+        let answers = parse_answers("01 54601 54078\n12 7204 -\n19 - -\n").unwrap();
+
+        assert_eq!(open_parts(&answers, 1), []);
+        assert_eq!(open_parts(&answers, 12), [Part::Two]);
+        assert_eq!(open_parts(&answers, 19), [Part::One, Part::Two]);
+        assert_eq!(open_parts(&answers, 20), [Part::One, Part::Two]);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_calendar() {
+        let mut calendar = Calendar::new();
+        calendar.set(1, Mark::Verified, Some(Duration::from_millis(3)));
+        calendar.set(2, Mark::Wrong, Some(Duration::from_secs(5)));
+        calendar.set(4, Mark::Solved, Some(Duration::from_millis(3)));
+        calendar.set(25, Mark::Implemented, None);
+
+        let text = calendar.to_string();
+        let lines = text.lines().collect::<Vec<_>>();
+
+        // the first of december is a friday
+        assert_eq!(lines[1], format!("{} 01 v+  02 x   03", " ".repeat(28)));
+        assert!(lines[2].starts_with(" 04 o+  05"));
+        assert!(lines[5].ends_with(" 25 ."));
+        assert!(text.contains("v verified 1, x wrong 1, o unchecked 1, . no input 1"));
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use color_eyre::eyre::Result;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{info, warn};
+
+use crate::cache;
+
+// progress of long loops, saved every --checkpoint-every seconds
+pub const CHECKPOINT_DIRECTORY: &str = "input/checkpoints";
+
+// set once from the command line, 0 means no checkpoints, which is what the tests get
+static INTERVAL_MILLIS: AtomicU64 = AtomicU64::new(0);
+static RESUME: AtomicBool = AtomicBool::new(false);
+
+pub fn set_interval(interval: Duration) {
+    INTERVAL_MILLIS.store(interval.as_millis() as u64, Ordering::Relaxed);
+}
+
+pub fn set_resume(resume: bool) {
+    RESUME.store(resume, Ordering::Relaxed);
+}
+
+fn interval() -> Option<Duration> {
+    match INTERVAL_MILLIS.load(Ordering::Relaxed) {
+        0 => None,
+        millis => Some(Duration::from_millis(millis)),
+    }
+}
+
+// the saved state of one loop, like the spin cycles of day 14
+// the key names the work, e.g. a hash of the input, so a checkpoint of another input is not resumed
+#[derive(Debug)]
+pub struct Checkpoint {
+    path: PathBuf,
+    interval: Option<Duration>,
+    resume: bool,
+    last_save: Instant,
+    // tight loops read the clock only every so many calls of save
+    calls_per_clock_read: u32,
+    calls: u32,
+}
+
+impl Checkpoint {
+    pub fn new(name: &str, key: &str) -> Self {
+        Self {
+            path: Path::new(CHECKPOINT_DIRECTORY).join(format!(
+                "{}-{:016x}",
+                name,
+                cache::hash(key)
+            )),
+            interval: interval(),
+            resume: RESUME.load(Ordering::Relaxed),
+            last_save: Instant::now(),
+            calls_per_clock_read: 1,
+            calls: 0,
+        }
+    }
+
+    pub fn with_calls_per_clock_read(mut self, calls: u32) -> Self {
+        self.calls_per_clock_read = calls.max(1);
+        self
+    }
+
+    // the settings of the command line, for tests
+    pub fn with_settings(mut self, directory: &Path, interval: Duration, resume: bool) -> Self {
+        self.path = directory.join(self.path.file_name().unwrap());
+        self.interval = Some(interval);
+        self.resume = resume;
+        self
+    }
+
+    // the last saved state with --resume, None to start over
+    pub fn resume<T: DeserializeOwned>(&self) -> Option<T> {
+        if !self.resume {
+            return None;
+        }
+
+        let bytes = fs::read(&self.path).ok()?;
+        match bincode::deserialize(&bytes) {
+            Ok(state) => {
+                info!("resuming from {}", self.path.display());
+                Some(state)
+            }
+            Err(error) => {
+                warn!("ignoring {}: {}", self.path.display(), error);
+                None
+            }
+        }
+    }
+
+    // saves what state returns once the interval has passed, meant to be called every iteration
+    pub fn save<T: Serialize>(&mut self, state: impl FnOnce() -> T) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+
+        self.calls += 1;
+        if self.calls < self.calls_per_clock_read {
+            return;
+        }
+        self.calls = 0;
+        if self.last_save.elapsed() < interval {
+            return;
+        }
+
+        // a failed save only means resuming from further back
+        if let Err(error) = write(&self.path, &state()) {
+            warn!("could not save {}: {}", self.path.display(), error);
+        }
+        self.last_save = Instant::now();
+    }
+
+    // the loop is done, there is nothing left to resume
+    pub fn finish(self) {
+        if self.interval.is_some() {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+// through a temporary file, so a crash while saving keeps the previous checkpoint intact
+fn write<T: Serialize>(path: &Path, state: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let temporary = path.with_extension("tmp");
+    fs::write(&temporary, bincode::serialize(state)?)?;
+    fs::rename(&temporary, path)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_checkpoint() {
+        let directory = std::env::temp_dir().join("advent-of-code-2023-checkpoint-test");
+        let _ = fs::remove_dir_all(&directory);
+        let checkpoint = |resume| {
+            Checkpoint::new("count", "1..10").with_settings(&directory, Duration::ZERO, resume)
+        };
+
+        let mut first = checkpoint(false);
+        for count in 0..5 {
+            first.save(|| (count, "counting"));
+        }
+        assert_eq!(first.resume::<(i32, String)>(), None);
+
+        // without --resume a run starts over, with it the last save is where it goes on
+        let second = checkpoint(true);
+        assert_eq!(
+            second.resume::<(i32, String)>(),
+            Some((4, "counting".to_string()))
+        );
+        assert_eq!(second.resume::<Vec<String>>(), None);
+        assert!(Checkpoint::new("count", "1..20")
+            .with_settings(&directory, Duration::ZERO, true)
+            .resume::<(i32, String)>()
+            .is_none());
+
+        second.finish();
+        assert_eq!(checkpoint(true).resume::<(i32, String)>(), None);
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
@@ -0,0 +1,85 @@
+use color_eyre::eyre::{bail, eyre, Result};
+use tracing::info;
+
+use crate::solver::Answer;
+
+// the value of --input that reads the puzzle input from the clipboard instead of a file
+pub const INPUT_SOURCE: &str = "clipboard";
+
+fn open() -> Result<arboard::Clipboard> {
+    arboard::Clipboard::new().map_err(|f| eyre!("could not open the clipboard: {}", f))
+}
+
+pub fn copy(text: &str) -> Result<()> {
+    open()?
+        .set_text(text)
+        .map_err(|f| eyre!("could not copy to the clipboard: {}", f))
+}
+
+// the puzzle input copied from the browser, usually without the final newline the files have
+pub fn paste() -> Result<String> {
+    let text = open()?
+        .get_text()
+        .map_err(|f| eyre!("could not read the clipboard: {}", f))?;
+
+    input_from(text)
+}
+
+fn input_from(mut text: String) -> Result<String> {
+    if text.trim().is_empty() {
+        bail!("the clipboard is empty, copy the puzzle input first");
+    }
+
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+
+    Ok(text)
+}
+
+// part is 1 or 2, the text as it is submitted on the site
+pub fn part_text(answer: &Answer, part: usize) -> Result<&str> {
+    let text = match part {
+        1 => answer.part1.as_deref(),
+        2 => answer.part2.as_deref(),
+        _ => bail!("there is no part {}", part),
+    };
+
+    text.ok_or_else(|| eyre!("part {} has no answer to copy", part))
+}
+
+pub fn copy_answer(answer: &Answer, part: usize) -> Result<()> {
+    let text = part_text(answer, part)?;
+    copy(text)?;
+    info!("copied the answer of part {} to the clipboard", part);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_part_text() {
+        let answer = Answer {
+            part1: Some("142".to_string()),
+            part2: None,
+        };
+
+        assert_eq!(part_text(&answer, 1).unwrap(), "142");
+        assert!(part_text(&answer, 2).is_err());
+        assert!(part_text(&answer, 3).is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_input_from() {
+        assert_eq!(input_from("1abc2".to_string()).unwrap(), "1abc2\n");
+        assert_eq!(input_from("1abc2\n".to_string()).unwrap(), "1abc2\n");
+        assert!(input_from(" \n".to_string()).is_err());
+    }
+}
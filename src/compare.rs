@@ -0,0 +1,431 @@
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use color_eyre::eyre::{bail, eyre, Result};
+use tracing::info;
+
+use crate::{
+    error::{Failure, FailureKind},
+    registry::{self, Variant},
+    solver::{self, Answer},
+};
+
+// a solve that is not in the registry, like a plugin, compared next to the variants of its day
+pub type ExtraSolve<'a> = (String, Box<dyn Fn(&str) -> Result<Answer> + 'a>);
+
+#[derive(Debug)]
+pub struct Run {
+    pub name: String,
+    pub answer: Answer,
+    pub elapsed: Duration,
+}
+
+impl Run {
+    fn new(variant: &Variant, input: &str) -> Result<Self> {
+        Self::timed(variant.short_name(), variant.solve, input)
+    }
+
+    fn timed(name: &str, solve: impl FnOnce(&str) -> Result<Answer>, input: &str) -> Result<Self> {
+        let start = Instant::now();
+        let answer = solve(input)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            answer,
+            elapsed: start.elapsed(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Comparison {
+    pub day: i32,
+    pub runs: Vec<Run>,
+}
+
+impl Comparison {
+    // runs every variant on the same input, fails if any of them disagree
+    pub fn new(day: i32, input: &str) -> Result<Self> {
+        Self::with_extra(day, input, vec![])
+    }
+
+    // the variants, then the extra solves
+    pub fn with_extra(day: i32, input: &str, extra: Vec<ExtraSolve>) -> Result<Self> {
+        let mut runs: Vec<Run> = vec![];
+        let variants = registry::variants(day).iter().map(|f| {
+            (
+                f.short_name().to_string(),
+                Box::new(f.solve) as Box<dyn Fn(&str) -> _>,
+            )
+        });
+
+        for (name, solve) in variants.chain(extra) {
+            let run = Run::timed(&name, solve, input)?;
+
+            if let Some(first) = runs.first() {
+                if first.answer != run.answer {
+                    bail!(Failure::new(
+                        FailureKind::Mismatch,
+                        format!(
+                            "day {:0>2}: {} answered {:?}, but {} answered {:?}",
+                            day, run.name, run.answer, first.name, first.answer
+                        )
+                    ));
+                }
+            }
+
+            runs.push(run);
+        }
+
+        if runs.is_empty() {
+            bail!("day {:0>2} has no variants", day);
+        }
+
+        Ok(Self { day, runs })
+    }
+
+    pub fn display(&self) {
+        info!("\n{}", self);
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.runs.iter().map(|f| f.name.len()).max().unwrap_or(0);
+        let fastest = self
+            .runs
+            .iter()
+            .map(|f| f.elapsed)
+            .min()
+            .unwrap_or_default();
+
+        writeln!(f, "Day {:0>2}, all answers agree", self.day)?;
+
+        for run in &self.runs {
+            let ratio = run.elapsed.as_secs_f64() / fastest.as_secs_f64().max(f64::EPSILON);
+            writeln!(
+                f,
+                "{:<width$}  {:>12.3?}  {:>8.2}x",
+                run.name,
+                run.elapsed,
+                ratio,
+                width = width
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// the default variant of a day on two inputs, to see where a trimmed input starts to diverge
+#[derive(Debug)]
+pub struct InputDiff {
+    pub day: i32,
+    pub names: [String; 2],
+    pub runs: [Run; 2],
+}
+
+impl InputDiff {
+    // inputs are (name, content) pairs, the name is only used for the table header
+    pub fn new(day: i32, a: (&str, &str), b: (&str, &str)) -> Result<Self> {
+        let Some(variant) = registry::variants(day).first() else {
+            bail!("day {:0>2} has no variants", day);
+        };
+
+        Ok(Self {
+            day,
+            names: [a.0.to_string(), b.0.to_string()],
+            runs: [Run::new(variant, a.1)?, Run::new(variant, b.1)?],
+        })
+    }
+
+    pub fn is_same(&self) -> bool {
+        self.runs[0].answer == self.runs[1].answer
+    }
+
+    pub fn display(&self) {
+        info!("\n{}", self);
+    }
+}
+
+impl Display for InputDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b] = &self.runs;
+        let rows = [
+            (
+                "part 1",
+                a.answer.part1.as_deref(),
+                b.answer.part1.as_deref(),
+            ),
+            (
+                "part 2",
+                a.answer.part2.as_deref(),
+                b.answer.part2.as_deref(),
+            ),
+        ];
+
+        let width = rows
+            .iter()
+            .flat_map(|(_, a, b)| [a.unwrap_or("-").len(), b.unwrap_or("-").len()])
+            .chain(self.names.iter().map(|f| f.len()))
+            .chain([12])
+            .max()
+            .unwrap_or(0);
+
+        writeln!(f, "Day {:0>2}, {}", self.day, a.name)?;
+        writeln!(
+            f,
+            "{:<6}  {:>width$}  {:>width$}",
+            "",
+            self.names[0],
+            self.names[1],
+            width = width
+        )?;
+
+        for (label, a, b) in rows {
+            let marker = if a == b { "" } else { "  differs" };
+            writeln!(
+                f,
+                "{:<6}  {:>width$}  {:>width$}{}",
+                label,
+                a.unwrap_or("-"),
+                b.unwrap_or("-"),
+                marker,
+                width = width
+            )?;
+        }
+
+        writeln!(
+            f,
+            "{:<6}  {:>width$}  {:>width$}",
+            "time",
+            format!("{:.3?}", a.elapsed),
+            format!("{:.3?}", b.elapsed),
+            width = width
+        )
+    }
+}
+
+// one variant of a day on every file of a directory, e.g. the inputs of several accounts
+#[derive(Debug)]
+pub struct Batch {
+    pub day: i32,
+    pub variant: &'static str,
+    // by file name, a file that can not be read or solved keeps its error and the rest still run
+    pub runs: Vec<(String, Result<Run, String>)>,
+}
+
+impl Batch {
+    // the default variant unless one is named, hidden files are skipped
+    pub fn new(day: i32, directory: &Path, algo: Option<&str>) -> Result<Self> {
+        let variant = match algo {
+            Some(name) => registry::find(day, name)?,
+            None => match registry::variants(day).first() {
+                Some(variant) => *variant,
+                None => bail!("day {:0>2} has no variants", day),
+            },
+        };
+
+        let mut paths = vec![];
+        for entry in fs::read_dir(directory)
+            .map_err(|f| eyre!("could not read {}: {}", directory.display(), f))?
+        {
+            let path = entry?.path();
+            let hidden = path
+                .file_name()
+                .is_some_and(|f| f.to_string_lossy().starts_with('.'));
+            if path.is_file() && !hidden {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        if paths.is_empty() {
+            bail!("there are no inputs in {}", directory.display());
+        }
+
+        let runs = paths
+            .iter()
+            .map(|path| {
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                let run = solver::read_file(path)
+                    .and_then(|f| Run::new(&variant, &f))
+                    .map_err(|f| f.to_string());
+
+                (name, run)
+            })
+            .collect();
+
+        Ok(Self {
+            day,
+            variant: variant.short_name(),
+            runs,
+        })
+    }
+
+    pub fn failures(&self) -> usize {
+        self.runs.iter().filter(|(_, f)| f.is_err()).count()
+    }
+
+    pub fn display(&self) {
+        info!("\n{}", self);
+    }
+}
+
+impl Display for Batch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self
+            .runs
+            .iter()
+            .map(|(name, run)| match run {
+                Ok(run) => [
+                    name.as_str(),
+                    run.answer.part1.as_deref().unwrap_or("-"),
+                    run.answer.part2.as_deref().unwrap_or("-"),
+                ],
+                Err(_) => [name.as_str(), "-", "-"],
+            })
+            .collect::<Vec<_>>();
+        let header = ["file", "part 1", "part 2"];
+        let widths: [usize; 3] = std::array::from_fn(|column| {
+            rows.iter()
+                .map(|f| f[column].len())
+                .chain([header[column].len()])
+                .max()
+                .unwrap_or(0)
+        });
+
+        writeln!(
+            f,
+            "Day {:0>2}, {} on {} input(s)",
+            self.day,
+            self.variant,
+            self.runs.len()
+        )?;
+        writeln!(
+            f,
+            "{:<a$}  {:>b$}  {:>c$}  {:>12}",
+            header[0],
+            header[1],
+            header[2],
+            "time",
+            a = widths[0],
+            b = widths[1],
+            c = widths[2]
+        )?;
+
+        for (row, (_, run)) in rows.iter().zip(&self.runs) {
+            let last = match run {
+                Ok(run) => format!("{:>12.3?}", run.elapsed),
+                // only the first line, parse errors go on with the offending line of the input
+                Err(error) => format!("error: {}", error.lines().next().unwrap_or_default()),
+            };
+            writeln!(
+                f,
+                "{:<a$}  {:>b$}  {:>c$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                last,
+                a = widths[0],
+                b = widths[1],
+                c = widths[2]
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_compare() -> Result<()> {
+        let input = "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+        let comparison = Comparison::new(10, input)?;
+
+        assert_eq!(comparison.runs.len(), 2);
+        assert_eq!(comparison.runs[1].name, "shoelace");
+        assert_eq!(comparison.runs[1].answer.part2, Some("4".to_string()));
+        comparison.display();
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_unknown_day() {
+        assert!(Comparison::new(42, "").is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_input_diff() -> Result<()> {
+        let a = "0 3 6 9 12 15\n1 3 6 10 15 21\n";
+        let b = "0 3 6 9 12 15\n";
+        let diff = InputDiff::new(9, ("full", a), ("trimmed", b))?;
+
+        assert!(!diff.is_same());
+        assert_eq!(diff.runs[0].answer.as_i64(1), Some(46));
+        assert_eq!(diff.runs[1].answer.as_i64(1), Some(18));
+
+        let text = diff.to_string();
+        assert!(text.contains("trimmed"));
+        assert!(text.contains("differs"));
+
+        assert!(InputDiff::new(9, ("a", b), ("b", b))?.is_same());
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_batch() -> Result<()> {
+        let directory = std::env::temp_dir().join("advent-of-code-2023-batch-test");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory)?;
+        fs::write(directory.join("a"), "0 3 6 9 12 15\n1 3 6 10 15 21\n")?;
+        fs::write(directory.join("b"), "0 3 6 9 12 15\r\n")?;
+        fs::write(directory.join("c"), "0 3 x\n")?;
+        fs::write(directory.join(".gitkeep"), "")?;
+
+        let batch = Batch::new(9, &directory, None)?;
+        let names = batch
+            .runs
+            .iter()
+            .map(|(f, _)| f.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(batch.runs[0].1.as_ref().unwrap().answer.as_i64(1), Some(46));
+        assert_eq!(batch.runs[1].1.as_ref().unwrap().answer.as_i64(1), Some(18));
+        assert_eq!(batch.failures(), 1);
+
+        let text = batch.to_string();
+        assert!(text.contains("Day 09, default on 3 input(s)"));
+        assert!(text.lines().nth(4).unwrap().contains("error: "));
+
+        assert!(Batch::new(9, &directory, Some("nonexistent")).is_err());
+        assert!(Batch::new(9, &directory.join("missing"), None).is_err());
+
+        let _ = fs::remove_dir_all(&directory);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use color_eyre::eyre::{eyre, Result};
+
+use crate::stars::{self, Stars};
+
+// the entry the session token is stored under, one per user of the os and profile
+#[cfg(feature = "keyring")]
+const SERVICE: &str = "advent-of-code-2023";
+
+// what the browser shows is either the bare cookie value or the whole "session=..." pair
+pub fn clean_token(text: &str) -> Option<String> {
+    let text = text.trim();
+    let token = text.strip_prefix("session=").unwrap_or(text);
+    let token = token.trim_end_matches(';').trim();
+
+    match token.is_empty() || token.contains(char::is_whitespace) {
+        true => None,
+        false => Some(token.to_string()),
+    }
+}
+
+// who a token logs in as, read off the calendar it was used to fetch
+#[derive(Debug, PartialEq, Eq)]
+pub struct Account {
+    pub name: String,
+    pub stars: u32,
+}
+
+// a cheap authenticated request, so an expired token shows up at login and not as a failed download
+// on the morning of a puzzle
+pub fn verify(token: &str, calendar: impl FnOnce(&str) -> Result<String>) -> Result<Account> {
+    let html = calendar(token).map_err(|f| eyre!("could not download the calendar: {}", f))?;
+    let stars = Stars::parse(&html)?;
+
+    Ok(Account {
+        name: stars::account(&html).unwrap_or_else(|| "an account without a name".to_string()),
+        stars: stars.total(),
+    })
+}
+
+// the token saved by login, None when there is none or the store can not be reached
+pub fn stored_token() -> Option<String> {
+    stored_token_of(crate::profile::name())
+}
+
+pub fn stored_token_of(profile: Option<&str>) -> Option<String> {
+    #[cfg(feature = "keyring")]
+    {
+        match entry_of(profile).and_then(|f| f.get_password()) {
+            Ok(token) => clean_token(&token),
+            Err(keyring::Error::NoEntry) => None,
+            Err(error) => {
+                tracing::warn!(
+                    "could not read the session token from the keyring: {}",
+                    error
+                );
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    {
+        let _ = profile;
+        None
+    }
+}
+
+pub fn store(token: &str) -> Result<()> {
+    #[cfg(feature = "keyring")]
+    {
+        entry()
+            .and_then(|f| f.set_password(token))
+            .map_err(|f| eyre!("could not save the session token in the keyring: {}", f))
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    {
+        let _ = token;
+        Err(missing_feature("login"))
+    }
+}
+
+// false when there was nothing to remove
+pub fn delete() -> Result<bool> {
+    delete_of(crate::profile::name())
+}
+
+pub fn delete_of(profile: Option<&str>) -> Result<bool> {
+    #[cfg(feature = "keyring")]
+    {
+        match entry_of(profile)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    {
+        let _ = profile;
+        Err(missing_feature("logout"))
+    }
+}
+
+// asks for the token without echoing it, pasting it on the command line would leave it in the
+// shell history, a token piped in from a password manager is read from stdin instead
+pub fn prompt_token() -> Result<String> {
+    #[cfg(feature = "keyring")]
+    {
+        use std::io::{self, IsTerminal};
+
+        let text = match io::stdin().is_terminal() {
+            true => rpassword::prompt_password("session cookie of adventofcode.com: ")?,
+            false => {
+                let mut line = String::new();
+                io::stdin().read_line(&mut line)?;
+                line
+            }
+        };
+        clean_token(&text).ok_or_else(|| eyre!("that does not look like a session cookie"))
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    Err(missing_feature("login"))
+}
+
+#[cfg(not(feature = "keyring"))]
+fn missing_feature(command: &str) -> color_eyre::Report {
+    eyre!(
+        "{} needs a build with the keyring feature, e.g. cargo run --features keyring",
+        command
+    )
+}
+
+#[cfg(feature = "keyring")]
+fn entry() -> keyring::Result<keyring::Entry> {
+    entry_of(crate::profile::name())
+}
+
+#[cfg(feature = "keyring")]
+fn entry_of(profile: Option<&str>) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, &crate::profile::keyring_user_of(profile))
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_clean_token() {
+        assert_eq!(clean_token("  53616c74\n").as_deref(), Some("53616c74"));
+        assert_eq!(
+            clean_token("session=53616c74;").as_deref(),
+            Some("53616c74")
+        );
+        assert_eq!(clean_token("session="), None);
+        assert_eq!(clean_token("53616c74 deadbeef"), None);
+        assert_eq!(clean_token(""), None);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_verify() {
+        let calendar = |token: &str| -> Result<String> {
+            assert_eq!(token, "abc");
+            Ok(
+                r#"<div class="user">harry <span class="star-count">3*</span></div>
+<a aria-label="Day 1, two stars" href="/2023/day/1">
+<a aria-label="Day 2, one star" href="/2023/day/2">"#
+                    .to_string(),
+            )
+        };
+        assert_eq!(
+            verify("abc", calendar).unwrap(),
+            Account {
+                name: "harry".to_string(),
+                stars: 3
+            }
+        );
+
+        let expired = verify("abc", |_| Ok(r#"<a href="/2023/auth/login">"#.to_string()));
+        assert!(expired.unwrap_err().to_string().contains("still valid"));
+
+        let offline = verify("abc", |_| Err(eyre!("no network")));
+        assert!(offline.unwrap_err().to_string().contains("no network"));
+    }
+}
@@ -1,38 +1,84 @@
-use std::vec;
+use std::str::FromStr;
 
-use color_eyre::eyre::Result;
+use crate::{
+    error::{Error, Result},
+    solver::Answer,
+};
 
-use crate::solver::Answer;
+#[derive(Debug)]
+pub struct Game {
+    pub id: i32,
+    pub sets: Vec<Set>,
+}
 
-struct Game {
-    id: i32,
-    sets: Vec<Set>,
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Set {
+    pub red: i32,
+    pub green: i32,
+    pub blue: i32,
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct Set {
-    red: i32,
-    green: i32,
-    blue: i32,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
 }
 
 impl Set {
-    fn power(&self) -> i32 {
+    pub fn power(&self) -> i32 {
         self.red * self.green * self.blue
     }
+
+    pub fn get(&self, color: Color) -> i32 {
+        match color {
+            Color::Red => self.red,
+            Color::Green => self.green,
+            Color::Blue => self.blue,
+        }
+    }
 }
 
-impl Game {
-    fn new(input: &str) -> Self {
-        let v: Vec<&str> = input.split(':').collect();
-        assert_eq!(v.len(), 2);
+// every game of the input, for asking questions that the puzzle doesn't
+#[derive(Debug)]
+pub struct Games {
+    games: Vec<Game>,
+}
+
+impl Games {
+    pub fn new(input: &str) -> Result<Self, Error> {
+        let games = input
+            .lines()
+            .enumerate()
+            .filter(|(_, f)| !f.is_empty())
+            .map(|(index, line)| line.parse::<Game>().map_err(|f| f.on_line(index)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { games })
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Game> {
+        self.games.iter()
+    }
+}
 
-        let id = Game::get_game_id(v.first().unwrap());
-        let sets = Game::get_sets(v.last().unwrap());
-        Self { id, sets }
+// filters that chain on any iterator of games, e.g.
+// games.iter().possible_with(bag).with_color_at_least(Color::Red, 5)
+pub trait GameFilter<'a>: Iterator<Item = &'a Game> + Sized {
+    fn possible_with(self, bag: Set) -> impl Iterator<Item = &'a Game> {
+        self.filter(move |f| f.possible_with_bag(&bag))
     }
 
-    fn possible_with_bag(&self, bag: &Set) -> bool {
+    // games where some set shows at least n cubes of the color
+    fn with_color_at_least(self, color: Color, n: i32) -> impl Iterator<Item = &'a Game> {
+        self.filter(move |f| f.sets.iter().any(|set| set.get(color) >= n))
+    }
+}
+
+impl<'a, T: Iterator<Item = &'a Game>> GameFilter<'a> for T {}
+
+impl Game {
+    pub fn possible_with_bag(&self, bag: &Set) -> bool {
         for set in self.sets.iter() {
             if set.red > bag.red || set.green > bag.green || set.blue > bag.blue {
                 return false;
@@ -42,7 +88,8 @@ impl Game {
         true
     }
 
-    fn minimum_bag(&self) -> Set {
+    // fewest cubes of each color that make the game possible
+    pub fn min_bag(&self) -> Set {
         let mut bag = Set {
             red: 0,
             green: 0,
@@ -57,51 +104,86 @@ impl Game {
 
         bag
     }
+}
 
-    fn get_sets(input: &str) -> Vec<Set> {
-        let mut result = vec![];
-        for set_str in input.split(';').map(|s| s.trim()) {
-            assert!(!set_str.is_empty());
+// "Game 20: 3 blue, 4 red; 1 red, 2 green"
+impl FromStr for Game {
+    type Err = Error;
 
-            result.push(Self::get_set(set_str));
-        }
+    fn from_str(input: &str) -> Result<Self, Error> {
+        let (name, sets) = input.split_once(':').ok_or_else(|| {
+            Error::parse(0, "expected a game").with_hint("games look like `Game 1: 3 blue, 4 red`")
+        })?;
 
-        result
+        // convert "Game 20" into 20
+        let id = name
+            .trim()
+            .strip_prefix("Game ")
+            .and_then(|f| f.trim().parse::<i32>().ok())
+            .ok_or_else(|| Error::parse(0, "expected a game id").at_token(input, name))?;
+
+        let sets = sets
+            .split(';')
+            .map(|f| parse_set(input, f))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { id, sets })
     }
+}
 
-    fn get_set(input: &str) -> Set {
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
-        for v in input.split(',').map(|f| f.trim()) {
-            let t: Vec<&str> = v.split_whitespace().collect();
-            assert_eq!(t.len(), 2);
-
-            let value = t.first().unwrap().parse::<i32>().unwrap();
-
-            if v.contains("red") {
-                red += value;
-            } else if v.contains("blue") {
-                blue += value;
-            } else if v.contains("green") {
-                green += value;
-            }
-        }
+// "3 blue, 4 red"
+impl FromStr for Set {
+    type Err = Error;
 
-        Set { red, green, blue }
+    fn from_str(input: &str) -> Result<Self, Error> {
+        parse_set(input, input)
     }
+}
 
-    fn get_game_id(input: &str) -> i32 {
-        // convert "Game 20" into 20
+impl FromStr for Color {
+    type Err = Error;
 
-        let v: Vec<&str> = input.split_whitespace().collect();
+    fn from_str(input: &str) -> Result<Self, Error> {
+        match input {
+            "red" => Ok(Color::Red),
+            "green" => Ok(Color::Green),
+            "blue" => Ok(Color::Blue),
+            _ => Err(Error::parse(0, "unknown color").at_token(input, input)),
+        }
+    }
+}
 
-        assert_eq!(v.len(), 2);
+// line is what input was sliced from, so errors point at the right column
+fn parse_set(line: &str, input: &str) -> Result<Set, Error> {
+    let mut set = Set {
+        red: 0,
+        green: 0,
+        blue: 0,
+    };
 
-        let id = v.last().unwrap().parse::<i32>().unwrap();
+    if input.trim().is_empty() {
+        return Err(Error::parse(0, "empty set").at_token(line, input));
+    }
 
-        id
+    for cubes in input.split(',').map(|f| f.trim()) {
+        let (count, color) = cubes
+            .split_once(' ')
+            .ok_or_else(|| Error::parse(0, "expected a count and a color").at_token(line, cubes))?;
+        let count = count
+            .parse::<i32>()
+            .map_err(|_| Error::parse(0, "expected a number").at_token(line, count))?;
+        let color = color
+            .parse::<Color>()
+            .map_err(|f| f.at_token(line, color))?;
+
+        match color {
+            Color::Red => set.red += count,
+            Color::Green => set.green += count,
+            Color::Blue => set.blue += count,
+        }
     }
+
+    Ok(set)
 }
 
 pub fn solve(input: &str) -> Result<Answer> {
@@ -110,18 +192,10 @@ pub fn solve(input: &str) -> Result<Answer> {
         green: 13,
         blue: 14,
     };
-    let mut part1 = 0;
-    let mut part2 = 0;
-
-    for line in input.lines() {
-        let game = Game::new(line);
-
-        if game.possible_with_bag(&bag) {
-            part1 += game.id;
-        }
+    let games = Games::new(input)?;
 
-        part2 += game.minimum_bag().power();
-    }
+    let part1: i32 = games.iter().possible_with(bag).map(|f| f.id).sum();
+    let part2: i32 = games.iter().map(|f| f.min_bag().power()).sum();
 
     Ok(Answer {
         part1: Some(part1.to_string()),
@@ -131,16 +205,11 @@ pub fn solve(input: &str) -> Result<Answer> {
 
 #[cfg(test)]
 mod tests {
-    use color_eyre::eyre::Result;
     use tracing_test::traced_test;
 
-    use super::{Game, Set};
+    use crate::test_support::fixture;
 
-    const TEST_INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+    use super::{Color, Error, Game, GameFilter, Games, Set};
 
     #[traced_test]
     #[test]
@@ -148,9 +217,12 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
         let vec = vec![("Game 20", 20), ("Game 100", 100), ("Game 1", 1)];
 
         for v in vec {
-            let id = Game::get_game_id(v.0);
-            assert_eq!(id, v.1);
+            let game = format!("{}: 1 red", v.0).parse::<Game>().unwrap();
+            assert_eq!(game.id, v.1);
         }
+
+        let error = "Game x: 1 red".parse::<Game>().unwrap_err();
+        assert_eq!(error.to_string(), "line 1: expected a game id");
     }
 
     #[traced_test]
@@ -184,9 +256,12 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
         ];
 
         for v in vec {
-            let id = Game::get_set(v.0);
+            let id = v.0.parse::<Set>().unwrap();
             assert_eq!(id, v.1);
         }
+
+        let error = "1 blue, 2 purple".parse::<Set>().unwrap_err();
+        assert_eq!(error, Error::parse(0, "unknown color").at(10, 16));
     }
 
     #[traced_test]
@@ -214,28 +289,52 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
         )];
 
         for v in vec {
-            let id = Game::get_sets(v.0);
+            let id = format!("Game 1: {}", v.0).parse::<Game>().unwrap().sets;
             assert_eq!(id, v.1);
         }
     }
 
     #[traced_test]
     #[test]
-    fn test_part1() -> Result<()> {
-        let answer = super::solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part1, Some("8".to_string()));
+    fn test_games_filters() {
+        let games = Games::new(&fixture("day02")).unwrap();
+        let bag = Set {
+            red: 12,
+            green: 13,
+            blue: 14,
+        };
 
-        Ok(())
+        let ids = |games: Vec<&Game>| games.iter().map(|f| f.id).collect::<Vec<_>>();
+
+        assert_eq!(
+            ids(games.iter().possible_with(bag).collect()),
+            vec![1, 2, 5]
+        );
+        assert_eq!(
+            ids(games.iter().with_color_at_least(Color::Red, 14).collect()),
+            vec![3, 4]
+        );
+        assert_eq!(
+            ids(games
+                .iter()
+                .possible_with(bag)
+                .with_color_at_least(Color::Blue, 6)
+                .collect()),
+            vec![1]
+        );
+
+        let min_bag = games.iter().next().unwrap().min_bag();
+        assert_eq!(
+            min_bag,
+            Set {
+                red: 4,
+                green: 2,
+                blue: 6,
+            }
+        );
     }
 
-    #[traced_test]
-    #[test]
-    fn test_part2() -> Result<()> {
-        let answer = super::solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part2, Some("2286".to_string()));
+    fixture_test!(test_part1, super::solve, "day02", part1 = "8");
 
-        Ok(())
-    }
+    fixture_test!(test_part2, super::solve, "day02", part2 = "2286");
 }
@@ -1,123 +1,129 @@
-use std::collections::{HashMap, HashSet};
-
-use crate::solver::Answer;
+use crate::{
+    solver::Answer,
+    utils::{grid::Grid, Coordinate},
+};
 use color_eyre::eyre::Result;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct NumberSpan {
+    id: usize,
+    x: usize,
+    y: usize,
+    length: usize,
+    value: i32,
+}
+
 struct Schematic {
-    symbols: HashMap<(i32, i32), String>,
-    numbers: HashMap<(i32, i32), (i32, i32)>,
+    cells: Grid<char>,
+    numbers: Vec<NumberSpan>,
+    number_ids: Grid<Option<usize>>, // which number span covers each cell
 }
 
 impl Schematic {
     fn new(input: &str) -> Self {
-        let mut y_stack = vec![];
-        let mut symbols = HashMap::new();
-        let mut numbers = HashMap::new();
+        let rows = input
+            .lines()
+            .filter(|f| !f.is_empty())
+            .map(|f| f.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let cells = Grid::from_rows(rows);
+        let mut numbers = vec![];
+        let mut number_ids = Grid::new(cells.width(), cells.height(), None);
+
+        for (y, row) in cells.rows().enumerate() {
+            let mut x = 0;
+
+            while x < row.len() {
+                if !row[x].is_ascii_digit() {
+                    x += 1;
+                    continue;
+                }
 
-        let mut number_id = 0; // to prevent calculating the same number multiple times
+                let length = row[x..].iter().take_while(|f| f.is_ascii_digit()).count();
+                let value = row[x..x + length]
+                    .iter()
+                    .collect::<String>()
+                    .parse()
+                    .unwrap();
+                let id = numbers.len();
+
+                numbers.push(NumberSpan {
+                    id,
+                    x,
+                    y,
+                    length,
+                    value,
+                });
+
+                for span_x in x..x + length {
+                    number_ids.set(Coordinate::new(span_x as i32, y as i32), Some(id));
+                }
 
-        for line in input.lines() {
-            if line.is_empty() {
-                continue;
+                x += length;
             }
+        }
 
-            let mut x_stack = vec![];
-            // make peekable to see 1 char ahead
-            let mut x_iterator = line.chars().peekable();
-
-            let mut number_stacks = vec![];
-            let mut number_location = vec![];
-
-            while let Some(value) = x_iterator.next() {
-                let value_string = value.to_string();
-                let coordinate = (x_stack.len() as i32, y_stack.len() as i32);
-
-                if value.is_numeric() {
-                    number_stacks.push(value);
-                    number_location.push(coordinate);
-                } else if value != '.' {
-                    symbols.insert(coordinate, value_string.clone());
-                    number_stacks.clear();
-                    number_location.clear();
-                } else {
-                    number_stacks.clear();
-                    number_location.clear();
-                }
+        Self {
+            cells,
+            numbers,
+            number_ids,
+        }
+    }
 
-                // peek, if next is none or not a number, that means the number sequence is done
-                if x_iterator.peek().is_none() || !x_iterator.peek().unwrap().is_numeric() {
-                    let n = number_stacks.iter().collect::<String>();
+    fn is_symbol(c: &char) -> bool {
+        !c.is_ascii_digit() && *c != '.'
+    }
 
-                    for location in number_location.iter() {
-                        numbers.insert(*location, (number_id, n.parse::<i32>().unwrap()));
-                    }
+    // distinct number ids around a coordinate, in reading order
+    fn adjacent_number_ids(&self, coordinate: Coordinate<i32>) -> Vec<usize> {
+        let mut ids = vec![];
 
-                    number_stacks.clear();
-                    number_location.clear();
-                    number_id += 1;
+        for (_, id) in self.number_ids.neighbors(coordinate) {
+            if let Some(id) = id {
+                if !ids.contains(id) {
+                    ids.push(*id);
                 }
-
-                x_stack.push(value_string);
             }
-            y_stack.push(x_stack);
         }
 
-        Self { symbols, numbers }
+        ids
     }
 
     fn get_all_number_around_symbols(&self) -> Vec<i32> {
-        let mut results = vec![];
-        let mut seen = HashSet::new();
-
-        for ((base_x, base_y), _) in self.symbols.iter() {
-            for y in [-1, 0, 1] {
-                for x in [-1, 0, 1] {
-                    if x == 0 && y == 0 {
-                        continue;
-                    }
-
-                    if let Some((id, value)) = self.numbers.get(&(base_x + x, base_y + y)) {
-                        if !seen.contains(id) {
-                            results.push(*value);
-                        }
-                        seen.insert(*id);
-                    }
+        let mut seen = vec![false; self.numbers.len()];
+
+        for (_, _, neighbors) in self
+            .cells
+            .neighborhoods()
+            .filter(|(_, c, _)| Self::is_symbol(c))
+        {
+            for (coordinate, _) in neighbors {
+                if let Some(Some(id)) = self.number_ids.get(coordinate) {
+                    seen[*id] = true;
                 }
             }
         }
 
-        results
+        self.numbers
+            .iter()
+            .filter(|f| seen[f.id])
+            .map(|f| f.value)
+            .collect()
     }
 
     fn get_gear_ratio(&self) -> Vec<i32> {
         let mut results = vec![];
 
-        for ((base_x, base_y), symbol) in self.symbols.iter() {
-            if symbol != "*" {
+        for (coordinate, symbol) in self.cells.cells() {
+            if *symbol != '*' {
                 continue;
             }
 
-            let mut current = vec![];
-            let mut seen = HashSet::new();
-
-            for y in [-1, 0, 1] {
-                for x in [-1, 0, 1] {
-                    if x == 0 && y == 0 {
-                        continue;
-                    }
-
-                    if let Some((id, value)) = self.numbers.get(&(base_x + x, base_y + y)) {
-                        if !seen.contains(id) {
-                            current.push(*value);
-                        }
-                        seen.insert(*id);
-                    }
-                }
-            }
+            let ids = self.adjacent_number_ids(coordinate);
 
-            if seen.len() == 2 {
-                assert_eq!(current.len(), seen.len());
-                results.push(current.iter().product());
+            if ids.len() == 2 {
+                results.push(ids.iter().map(|&id| self.numbers[id].value).product());
             };
         }
 
@@ -140,23 +146,54 @@ pub fn solve(input: &str) -> Result<Answer> {
 mod tests {
     use tracing_test::traced_test;
 
-    use super::Schematic;
+    use super::{NumberSpan, Schematic};
+    use crate::test_support::fixture;
+
+    #[traced_test]
+    #[test]
+    fn test_number_spans() {
+        let schematic = Schematic::new("467..114..\n...*......\n..35..633.");
+
+        assert_eq!(
+            schematic.numbers,
+            vec![
+                NumberSpan {
+                    id: 0,
+                    x: 0,
+                    y: 0,
+                    length: 3,
+                    value: 467
+                },
+                NumberSpan {
+                    id: 1,
+                    x: 5,
+                    y: 0,
+                    length: 3,
+                    value: 114
+                },
+                NumberSpan {
+                    id: 2,
+                    x: 2,
+                    y: 2,
+                    length: 2,
+                    value: 35
+                },
+                NumberSpan {
+                    id: 3,
+                    x: 6,
+                    y: 2,
+                    length: 3,
+                    value: 633
+                },
+            ]
+        );
+        assert_eq!(schematic.get_all_number_around_symbols(), vec![467, 35]);
+    }
 
     #[traced_test]
     #[test]
     fn test_part1() {
-        let input = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..";
-
-        let schematic = Schematic::new(input);
+        let schematic = Schematic::new(&fixture("day03"));
         let v: i32 = schematic.get_all_number_around_symbols().iter().sum();
 
         assert_eq!(v, 4361)
@@ -165,23 +202,10 @@ mod tests {
     #[traced_test]
     #[test]
     fn test_part2() {
-        let input = "467..114..
-...*......
-..35..633.
-......#...
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598..";
-
-        let schematic = Schematic::new(input);
+        let schematic = Schematic::new(&fixture("day03"));
         let gear_ratio = &schematic.get_gear_ratio();
 
-        assert_eq!(gear_ratio.len(), 2);
-        assert!(gear_ratio.contains(&451490));
-        assert!(gear_ratio.contains(&16345));
+        assert_eq!(gear_ratio, &vec![16345, 451490]);
 
         let value: i32 = gear_ratio.iter().sum();
 
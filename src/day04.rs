@@ -1,86 +1,124 @@
-use color_eyre::eyre::Result;
+use std::{
+    collections::{HashSet, VecDeque},
+    io::BufRead,
+    str::FromStr,
+};
 
-use std::collections::{HashSet, VecDeque};
-
-use crate::solver::Answer;
+use crate::{
+    error::{Error, Result},
+    solver::Answer,
+};
 
 #[derive(Debug)]
-struct Card {
+pub struct Card {
     winning_numbers: HashSet<u32>,
     our_numbers: HashSet<u32>,
 }
 
-impl Card {
-    fn new(input: &str) -> Self {
-        // input: "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
-
-        // text: ["Card 1", "41 48 83 86 17 | 83 86  6 31 17  9 48 53"]
-        let text = input.split(':').map(|f| f.trim()).collect::<Vec<&str>>();
-        assert!(text.len() == 2);
-
-        // text: ["41 48 83 86 17", "83 86  6 31 17  9 48 53"]
-        let text = text
-            .last()
-            .unwrap()
-            .split('|')
-            .map(|f| f.trim())
-            .collect::<Vec<&str>>();
-        assert!(text.len() == 2);
+// "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+impl FromStr for Card {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Error> {
+        let (_, numbers) = input.split_once(':').ok_or_else(|| {
+            Error::parse(0, "expected a card").with_hint("cards look like `Card 1: 41 48 | 83 86`")
+        })?;
+        let (winning, ours) = numbers.split_once('|').ok_or_else(|| {
+            Error::parse(0, "expected `|` between the winning numbers and ours")
+                .at_token(input, numbers)
+        })?;
+
+        Ok(Self {
+            winning_numbers: Self::parse_numbers(input, winning)?,
+            our_numbers: Self::parse_numbers(input, ours)?,
+        })
+    }
+}
 
-        let mut winning_numbers = HashSet::new();
-        let mut our_numbers = HashSet::new();
+impl Card {
+    // our numbers that are also winning numbers, in ascending order
+    pub fn matching_numbers(&self) -> Vec<u32> {
+        let mut numbers = self
+            .our_numbers
+            .intersection(&self.winning_numbers)
+            .copied()
+            .collect::<Vec<_>>();
+        numbers.sort();
+
+        numbers
+    }
 
-        Self::insert_numbers(text.first().unwrap(), &mut winning_numbers);
-        Self::insert_numbers(text.last().unwrap(), &mut our_numbers);
+    pub fn match_count(&self) -> u32 {
+        self.our_numbers.intersection(&self.winning_numbers).count() as u32
+    }
 
-        Self {
-            winning_numbers,
-            our_numbers,
+    pub fn score(&self) -> u32 {
+        match self.match_count() {
+            0 => 0,
+            count => 2_u32.pow(count - 1),
         }
     }
 
-    fn get_score(&self, card_stacks: &mut VecDeque<u32>) -> (u32, u32) {
-        let win_counter = self.our_numbers.intersection(&self.winning_numbers).count() as u32;
+    fn parse_numbers(line: &str, text: &str) -> Result<HashSet<u32>, Error> {
+        text.split_whitespace()
+            .map(|f| {
+                f.parse::<u32>()
+                    .map_err(|_| Error::parse(0, "expected a number").at_token(line, f))
+            })
+            .collect()
+    }
+}
+
+// tracks copies won for the upcoming cards, a card can only win copies of the next few cards
+#[derive(Debug, Default)]
+struct CopyCounter {
+    pending: VecDeque<u32>,
+}
 
-        let cards_processed = card_stacks.pop_front().unwrap_or(1_u32);
+impl CopyCounter {
+    // number of copies of the next card, including the original
+    fn next(&mut self, card: &Card) -> u32 {
+        let copies = self.pending.pop_front().unwrap_or(0) + 1;
 
-        for index in 0..win_counter as usize {
-            if card_stacks.len() <= index {
-                // number of current processed card + 1 original card
-                card_stacks.push_back(cards_processed + 1);
+        for index in 0..card.match_count() as usize {
+            if self.pending.len() <= index {
+                self.pending.push_back(copies);
             } else {
-                card_stacks[index] += cards_processed;
+                self.pending[index] += copies;
             }
         }
 
-        let score = if win_counter > 0 {
-            2_u32.pow(win_counter - 1)
-        } else {
-            0
-        };
-
-        (score, cards_processed)
+        copies
     }
+}
 
-    fn insert_numbers(text: &str, numbers: &mut HashSet<u32>) {
-        for number in text.split_whitespace().map(|f| f.parse::<u32>().unwrap()) {
-            numbers.insert(number);
-        }
-    }
+// number of copies of each card after the winning cascade, including the original
+pub fn card_copies(cards: &[Card]) -> Vec<u32> {
+    let mut counter = CopyCounter::default();
+
+    cards.iter().map(|f| counter.next(f)).collect()
 }
 
 pub fn solve(input: &str) -> Result<Answer> {
+    solve_reader(input.as_bytes())
+}
+
+// processes one card at a time, only the pending copies are kept in memory
+pub fn solve_reader<R: BufRead>(reader: R) -> Result<Answer> {
     let mut part1 = 0;
     let mut part2 = 0;
+    let mut counter = CopyCounter::default();
 
-    let mut card_stacks = VecDeque::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
 
-    for line in input.lines() {
-        let card = Card::new(line);
-        let (score, cards_processed) = card.get_score(&mut card_stacks);
+        let card = line.parse::<Card>().map_err(|f| f.on_line(index))?;
 
-        part1 += score;
-        part2 += cards_processed;
+        part1 += card.score();
+        part2 += counter.next(&card);
     }
 
     Ok(Answer {
@@ -91,32 +129,21 @@ pub fn solve(input: &str) -> Result<Answer> {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::VecDeque;
-
-    use crate::day04::Card;
-    use color_eyre::eyre::Result;
+    use crate::day04::{card_copies, Card};
+    use crate::error::Error;
+    use crate::test_support::fixture;
     use tracing_test::traced_test;
 
-    use super::solve;
-
-    const TEST_INPUT: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-
     #[traced_test]
     #[test]
     fn test_part1() {
         let scores = [8, 2, 2, 1, 0, 0, 0];
         let total: u32 = scores.iter().sum();
-        let mut copies = VecDeque::new();
         let mut current_score = 0;
 
-        for (index, line) in TEST_INPUT.lines().enumerate() {
-            let card = Card::new(line);
-            let (score, _) = card.get_score(&mut copies);
+        for (index, line) in fixture("day04").lines().enumerate() {
+            let card = line.parse::<Card>().unwrap();
+            let score = card.score();
 
             assert_eq!(score, scores[index]);
             current_score += score;
@@ -127,11 +154,32 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[traced_test]
     #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    fn test_card_matches() {
+        let card = fixture("day04")
+            .lines()
+            .next()
+            .unwrap()
+            .parse::<Card>()
+            .unwrap();
+
+        assert_eq!(card.matching_numbers(), vec![17, 48, 83, 86]);
+        assert_eq!(card.match_count(), 4);
+        assert_eq!(card.score(), 8);
+
+        let error = "Card 1: 41 4x | 83".parse::<Card>().unwrap_err();
+        assert_eq!(error, Error::parse(0, "expected a number").at(11, 13));
+    }
 
-        assert_eq!(answer.part2, Some("30".to_string()));
+    #[traced_test]
+    #[test]
+    fn test_card_copies() {
+        let cards = fixture("day04")
+            .lines()
+            .map(|f| f.parse::<Card>().unwrap())
+            .collect::<Vec<_>>();
 
-        Ok(())
+        assert_eq!(card_copies(&cards), vec![1, 2, 4, 8, 14, 1]);
     }
+
+    fixture_test!(test_part2, super::solve, "day04", part2 = "30");
 }
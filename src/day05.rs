@@ -1,13 +1,34 @@
-use std::{collections::VecDeque, i64, str::FromStr};
+use std::{collections::VecDeque, fmt::Write as _, ops, str::FromStr};
 
 use color_eyre::eyre::Result;
 use num_traits::{PrimInt, Zero};
+#[cfg(feature = "tooling")]
+use serde::{Deserialize, Serialize};
 use strum::EnumString;
-
-use crate::solver::Answer;
+use tracing::info_span;
+use winnow::{
+    ascii::{alpha1, space0, space1},
+    combinator::{delimited, preceded, separated_pair, terminated},
+    Parser,
+};
+
+#[cfg(feature = "tooling")]
+use crate::{
+    cache::{self, Cached},
+    checkpoint::Checkpoint,
+};
+use crate::{
+    error::Error,
+    solver::Answer,
+    utils::{
+        parse::{expected, hint, number, numbers, parse_line},
+        Part,
+    },
+};
 
 #[derive(EnumString, Debug, PartialEq, Eq, Clone)]
-enum Category {
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
+pub enum Category {
     #[strum(ascii_case_insensitive)]
     Seed,
     #[strum(ascii_case_insensitive)]
@@ -27,13 +48,15 @@ enum Category {
 }
 
 #[derive(Debug)]
-struct Almanac {
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
+pub struct Almanac {
     seeds_one: Vec<Range<i64>>,
     seeds_range: Vec<Range<i64>>,
     maps: Vec<Map>,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
 struct Range<T> {
     start: T,
     end: T,
@@ -81,6 +104,7 @@ impl<T: PrimInt + std::fmt::Debug> FillGaps for Vec<Range<T>> {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
 struct Map {
     source_category: Category,
     destination_category: Category,
@@ -88,71 +112,239 @@ struct Map {
 }
 
 impl Map {
-    fn new(mut input: VecDeque<String>) -> Self {
-        assert!(input.len() > 1);
-
+    // lines are paired with their index in the input, so errors can point at them
+    // a line that does not parse is an error, entries that parse but can not be mapped are
+    // returned next to the map, fill_gaps would quietly produce a wrong map out of them
+    fn parse(mut input: VecDeque<(usize, &str)>) -> Result<(Self, Vec<Error>), Error> {
         // first line is always contains source / destination category
-        let first_line = input.pop_front().unwrap();
-
-        let mut vec = first_line.split("-to-").collect::<Vec<&str>>();
-
-        assert_eq!(vec.len(), 2);
+        let (index, first_line) = input.pop_front().unwrap();
+        let (first, last) = parse_line(
+            index,
+            first_line,
+            terminated(
+                separated_pair(alpha1, "-to-", alpha1),
+                (space1, "map:", space0),
+            )
+            .context(expected("a source-to-destination header"))
+            .context(hint("map headers look like \"seed-to-soil map:\"")),
+        )?;
 
         // get category from the string
-        let last = vec.pop().unwrap();
-        let first = vec.pop().unwrap();
-        let source_category = Category::from_str(first).unwrap();
-        let destination_category = Category::from_str(last).unwrap();
-        let mut formulas = vec![];
+        let category = |text: &str| {
+            Category::from_str(text).map_err(|_| {
+                Error::parse(index, "unknown category")
+                    .at_token(first_line, text)
+                    .with_hint("categories are seed, soil, fertilizer, water, light, temperature, humidity and location")
+            })
+        };
+        let source_category = category(first)?;
+        let destination_category = category(last)?;
+        let mut entries = vec![];
+        let mut problems = vec![];
+
+        if input.is_empty() {
+            return Err(Error::parse(index, "map has no entries")
+                .with_hint("every map needs at least one \"destination source length\" line"));
+        }
 
         // parse all number ranges
-        for line in input.iter() {
-            let mut line = line
-                .split_whitespace()
-                .map(|f| f.parse().unwrap())
-                .collect::<Vec<i64>>();
-
-            assert_eq!(line.len(), 3);
-
-            let interval = line.pop().unwrap();
-            let src = line.pop().unwrap();
-            let dst = line.pop().unwrap();
+        for (index, line) in input {
+            let (dst, _, src, _, interval) = parse_line(
+                index,
+                line,
+                delimited(space0, (number, space1, number, space1, number), space0)
+                    .context(expected("3 whitespace-separated numbers in map entry"))
+                    .context(hint("map entries are \"destination source length\"")),
+            )?;
+
+            if interval < 0 {
+                let length = line.split_whitespace().last().unwrap_or(line);
+                problems.push(
+                    Error::parse(index, "negative range length")
+                        .at_token(line, length)
+                        .with_hint("the last number is how many values the entry maps"),
+                );
+                continue;
+            }
 
-            let formula = Range::new(src, src + interval, dst - src);
-            formulas.push(formula);
+            entries.push((index, line, Range::new(src, src + interval, dst - src)));
         }
 
-        formulas.sort();
+        entries.sort_by(|a, b| a.2.cmp(&b.2));
+        problems.extend(overlaps(&entries));
+
+        let mut formulas = entries.into_iter().map(|f| f.2).collect::<Vec<_>>();
         formulas.fill_gaps();
 
-        Self {
+        let map = Self {
             source_category,
             destination_category,
             formulas,
+        };
+
+        Ok((map, problems))
+    }
+
+    // formulas cover every value after fill_gaps, so a lookup always finds one
+    fn lookup(&self, value: i64) -> i64 {
+        let formula = self
+            .formulas
+            .iter()
+            .find(|f| value >= f.start && value < f.end)
+            .unwrap();
+
+        value + formula.diff
+    }
+}
+
+// every entry whose source range starts inside the one before it, entries sorted by source
+// a value in both would be mapped twice, fill_gaps keeps whichever comes first
+fn overlaps(entries: &[(usize, &str, Range<i64>)]) -> Vec<Error> {
+    entries
+        .windows(2)
+        .filter(|f| f[1].2.start < f[0].2.end)
+        .map(|f| {
+            let ((before, _, first), (index, line, second)) = (&f[0], &f[1]);
+            let message = format!(
+                "source range {}..{} overlaps {}..{} of line {}",
+                second.start,
+                second.end,
+                first.start,
+                first.end,
+                before + 1
+            );
+            let source = line.split_whitespace().nth(1).unwrap_or(line);
+
+            Error::parse(*index, &message)
+                .at_token(line, source)
+                .with_hint("the source ranges of a map must not share values")
+        })
+        .collect()
+}
+
+// the chain of maps from seed to location, a category with no map out of it is pointed at on the
+// header of the map leading into it
+fn check_chain(maps: &[Map], headers: &[usize]) -> Vec<Error> {
+    let hint = "maps chain from seed to location, each starting where the one before ends";
+    let mut category = Category::Seed;
+    let mut visited = vec![];
+    let mut previous = 0;
+
+    while category != Category::Location {
+        if visited.contains(&category) {
+            return vec![Error::parse(previous, "the maps go round in a circle").with_hint(hint)];
+        }
+
+        let Some(next) = maps.iter().position(|f| f.source_category == category) else {
+            let message = format!("no map from {:?}", category).to_lowercase();
+            return vec![Error::parse(previous, &message).with_hint(hint)];
+        };
+
+        previous = headers[next];
+        visited.push(category);
+        category = maps[next].destination_category.clone();
+    }
+
+    vec![]
+}
+
+// a piece of the seed to location function: seeds in start..end move by offset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piece {
+    pub start: i64,
+    pub end: i64,
+    pub offset: i64,
+}
+
+// all category maps folded into one sorted, gapless list of pieces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComposedMap {
+    pieces: Vec<Piece>,
+}
+
+impl ComposedMap {
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    pub fn lookup(&self, seed: i64) -> i64 {
+        // pieces are sorted and gapless, so the piece holding the seed is the last one starting at or before it
+        let index = self.pieces.partition_point(|f| f.start <= seed);
+        let piece = &self.pieces[index.saturating_sub(1)];
+
+        seed + piece.offset
+    }
+
+    // lowest location for any seed in start..end, each piece is increasing so only its first seed matters
+    pub fn min_location(&self, start: i64, end: i64) -> Option<i64> {
+        self.pieces
+            .iter()
+            .filter(|f| f.start < end && f.end > start)
+            .map(|f| f.start.max(start) + f.offset)
+            .min()
+    }
+}
+
+#[cfg(feature = "tooling")]
+impl Cached for Almanac {
+    const NAME: &'static str = "day05";
+    // 2 since the maps are validated, an almanac cached before may not pass
+    const FORMAT: u32 = 2;
+}
+
+impl FromStr for Almanac {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Error> {
+        let (almanac, mut problems) = Self::parse(input)?;
+
+        match problems.is_empty() {
+            true => Ok(almanac),
+            false => Err(problems.remove(0)),
         }
     }
 }
 
+// every problem of the almanac in input order, not just the first one parsing stops at
+pub fn diagnostics(input: &str) -> Vec<Error> {
+    match Almanac::parse(input) {
+        Ok((_, problems)) => problems,
+        Err(error) => vec![error],
+    }
+}
+
 impl Almanac {
-    fn new(input: &str) -> Self {
+    fn parse(input: &str) -> Result<(Self, Vec<Error>), Error> {
         let mut seeds_one = vec![];
         let mut seeds_range = vec![];
         let mut maps = vec![];
+        let mut headers = vec![];
+        let mut problems = vec![];
 
-        let mut line_iter = input.lines();
+        let mut line_iter = input.lines().enumerate();
 
-        while let Some(line) = line_iter.next() {
+        while let Some((line_index, line)) = line_iter.next() {
             if line.is_empty() {
                 continue;
             }
 
             // handle first line, it should always has initial seeds
             if seeds_one.is_empty() {
-                let v = line.replace("seeds:", "").trim().to_string();
+                let seeds = parse_line(
+                    line_index,
+                    line,
+                    delimited(
+                        "seeds:"
+                            .context(expected("the seeds line"))
+                            .context(hint("the input starts with \"seeds: 79 14 55 13\"")),
+                        preceded(space0, numbers).context(expected("the seeds")),
+                        space0,
+                    ),
+                )?;
                 let mut start = 0;
                 let mut end;
 
-                for (index, x) in v.split_whitespace().map(|f| f.parse().unwrap()).enumerate() {
+                for (index, x) in seeds.into_iter().enumerate() {
                     seeds_one.push(Range::new(x, x + 1, 0));
                     if index % 2 == 0 {
                         start = x;
@@ -161,39 +353,103 @@ impl Almanac {
                         seeds_range.push(Range::new(start, start + end, 0));
                     }
                 }
-            }
-
-            assert!(!seeds_one.is_empty());
 
-            if line.contains("map:") {
-                let mut map_stacks = VecDeque::from([line.replace("map:", "").trim().to_string()]);
+                continue;
+            }
 
-                for l in line_iter.by_ref() {
-                    if l.is_empty() {
-                        break;
-                    }
+            let mut map_stacks = VecDeque::from([(line_index, line)]);
 
-                    map_stacks.push_back(l.to_string());
+            for (index, l) in line_iter.by_ref() {
+                if l.is_empty() {
+                    break;
                 }
 
-                let map = Map::new(map_stacks);
-                maps.push(map);
+                map_stacks.push_back((index, l));
             }
+
+            let (map, map_problems) = Map::parse(map_stacks)?;
+            maps.push(map);
+            headers.push(line_index);
+            problems.extend(map_problems);
         }
 
         seeds_one.sort();
         seeds_range.sort();
+        problems.extend(check_chain(&maps, &headers));
+        problems.sort_by_key(|f| {
+            let Error::Parse { line, column, .. } = f;
+            (*line, *column)
+        });
 
-        Self {
+        let almanac = Self {
             seeds_one,
             seeds_range,
             maps,
+        };
+
+        Ok((almanac, problems))
+    }
+
+    // maps in the order a seed travels through them
+    fn ordered_maps(&self) -> Vec<&Map> {
+        let mut result = vec![];
+        let mut source_category = Category::Seed;
+
+        while source_category != Category::Location {
+            let map = self
+                .maps
+                .iter()
+                .find(|f| f.source_category == source_category)
+                .unwrap();
+
+            result.push(map);
+            source_category = map.destination_category.clone();
+        }
+
+        result
+    }
+
+    pub fn composed_map(&self) -> ComposedMap {
+        let mut pieces = vec![Piece {
+            start: 0,
+            end: i64::MAX,
+            offset: 0,
+        }];
+
+        for map in self.ordered_maps() {
+            let mut next_pieces = vec![];
+
+            for piece in &pieces {
+                // where the piece lands in the source space of this map
+                let start = piece.start.saturating_add(piece.offset);
+                let end = piece.end.saturating_add(piece.offset);
+
+                for formula in &map.formulas {
+                    let overlap_start = start.max(formula.start);
+                    let overlap_end = end.min(formula.end);
+
+                    if overlap_start >= overlap_end {
+                        continue;
+                    }
+
+                    next_pieces.push(Piece {
+                        start: overlap_start - piece.offset,
+                        end: overlap_end - piece.offset,
+                        offset: piece.offset + formula.diff,
+                    });
+                }
+            }
+
+            next_pieces.sort_by_key(|f| f.start);
+            pieces = next_pieces;
         }
+
+        ComposedMap { pieces }
     }
 
     fn get_next_range(
         &self,
-        source_range: &Vec<Range<i64>>,
+        source_range: &[Range<i64>],
         source_category: Category,
     ) -> (Vec<Range<i64>>, Category) {
         let map = self
@@ -202,60 +458,223 @@ impl Almanac {
             .find(|f| f.source_category == source_category)
             .unwrap();
 
+        let mut result = source_range
+            .iter()
+            .flat_map(|src| Self::split_range(map, src))
+            .collect::<Vec<_>>();
+
+        result.sort();
+
+        (result, map.destination_category.clone())
+    }
+
+    // the shifted pieces of src, one for each formula it overlaps, with the shift in diff
+    // pieces may be empty where src only touches a formula
+    fn split_range(map: &Map, src: &Range<i64>) -> Vec<Range<i64>> {
         let mut result = vec![];
 
-        for src in source_range {
-            let mut new_range;
-            for dst in map.formulas.iter() {
-                // dbg!(&src, &dst);
-                let diff = dst.diff;
-                if src.start >= dst.start && src.end <= dst.end {
-                    // src is subset of dst
-                    new_range = Range::new(src.start + diff, src.end + diff, 0);
-                } else if src.start < dst.start && src.end > dst.end {
-                    // src is superset of dst
-                    new_range = Range::new(dst.start + diff, dst.end + diff, 0);
-                } else if src.start < dst.start && src.end <= dst.end && src.end >= dst.start {
-                    // src overlaps in the left hand side of dst
-                    new_range = Range::new(dst.start + diff, src.end + diff, 0);
-                } else if src.start >= dst.start && src.end > dst.end && src.start <= dst.end {
-                    // src overlaps in the right hand side of dst
-                    new_range = Range::new(src.start + diff, dst.end + diff, 0);
-                } else {
-                    continue;
+        for dst in map.formulas.iter() {
+            let diff = dst.diff;
+            let new_range = if src.start >= dst.start && src.end <= dst.end {
+                // src is subset of dst
+                Range::new(src.start + diff, src.end + diff, diff)
+            } else if src.start < dst.start && src.end > dst.end {
+                // src is superset of dst
+                Range::new(dst.start + diff, dst.end + diff, diff)
+            } else if src.start < dst.start && src.end <= dst.end && src.end >= dst.start {
+                // src overlaps in the left hand side of dst
+                Range::new(dst.start + diff, src.end + diff, diff)
+            } else if src.start >= dst.start && src.end > dst.end && src.start <= dst.end {
+                // src overlaps in the right hand side of dst
+                Range::new(src.start + diff, dst.end + diff, diff)
+            } else {
+                continue;
+            };
+            result.push(new_range);
+        }
+
+        result
+    }
+
+    // graphviz dot of how the seed ranges of the part are split and shifted on their way to
+    // locations, one column per category and one edge per piece labelled with its shift
+    pub fn mapping_dot(&self, part: Part) -> String {
+        let name = |category: &Category| format!("{:?}", category).to_lowercase();
+        let mut text = String::new();
+        let mut cluster = |category: &Category, ranges: &[Range<i64>]| {
+            let _ = writeln!(text, "    subgraph cluster_{} {{", name(category));
+            let _ = writeln!(text, "        label=\"{}\";", name(category));
+            for (index, range) in ranges.iter().enumerate() {
+                let _ = writeln!(
+                    text,
+                    "        {}_{} [label=\"{}..{}\"];",
+                    name(category),
+                    index,
+                    range.start,
+                    range.end
+                );
+            }
+            text.push_str("    }\n");
+        };
+
+        let mut ranges = match part {
+            Part::One => self.seeds_one.clone(),
+            Part::Two => self.seeds_range.clone(),
+        };
+        let mut edges = vec![];
+
+        for map in self.ordered_maps() {
+            cluster(&map.source_category, &ranges);
+
+            let mut next = vec![];
+            for (index, range) in ranges.iter().enumerate() {
+                for piece in Self::split_range(map, range) {
+                    if piece.start >= piece.end {
+                        continue;
+                    }
+
+                    edges.push(format!(
+                        "    {}_{} -> {}_{} [label=\"{:+}\"];",
+                        name(&map.source_category),
+                        index,
+                        name(&map.destination_category),
+                        next.len(),
+                        piece.diff
+                    ));
+                    next.push(piece);
                 }
-                result.push(new_range);
             }
+
+            ranges = next;
         }
 
-        result.sort();
+        cluster(&Category::Location, &ranges);
 
-        (result, map.destination_category.clone())
+        let header = "digraph almanac {\n    rankdir=LR;\n    node [shape=box];\n";
+        format!("{}{}{}\n}}\n", header, text, edges.join("\n"))
     }
 
-    fn solve(&self, seeds: &[Range<i64>]) -> i64 {
-        let mut min_value = i64::MAX;
+    // seeds as listed for the part, part 2 reads the numbers as start and length pairs
+    pub fn seeds(&self, part: Part) -> Vec<ops::Range<i64>> {
+        let seeds = match part {
+            Part::One => &self.seeds_one,
+            Part::Two => &self.seeds_range,
+        };
+
+        seeds.iter().map(|f| f.start..f.end).collect()
+    }
+
+    // the values of the target category that the seeds end up as, sorted and merged
+    pub fn map_to(&self, seeds: &[ops::Range<i64>], target: Category) -> Vec<ops::Range<i64>> {
+        let seeds = seeds
+            .iter()
+            .map(|f| Range::new(f.start, f.end, 0))
+            .collect::<Vec<_>>();
+        let mut merged: Vec<ops::Range<i64>> = vec![];
+
+        for range in self.map_ranges(&seeds, target) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range.start..range.end),
+            }
+        }
+
+        merged
+    }
+
+    // follows the maps from seed until the target category, ranges may overlap or be empty
+    fn map_ranges(&self, seeds: &[Range<i64>], target: Category) -> Vec<Range<i64>> {
         let mut current = seeds.to_owned();
+        let mut source_category = Category::Seed;
+
+        while source_category != target {
+            (current, source_category) = self.get_next_range(&current, source_category);
+        }
+
+        current.retain(|f| f.start < f.end);
+        current.sort();
+        current
+    }
 
+    fn solve(&self, seeds: &[Range<i64>]) -> i64 {
+        self.map_ranges(seeds, Category::Location)
+            .iter()
+            .map(|f| f.start)
+            .min()
+            .unwrap_or(i64::MAX)
+    }
+
+    fn location(&self, seed: i64) -> i64 {
+        let mut value = seed;
         let mut source_category = Category::Seed;
 
         while source_category != Category::Location {
-            (current, source_category) = self.get_next_range(&current, source_category);
+            let map = self
+                .maps
+                .iter()
+                .find(|f| f.source_category == source_category)
+                .unwrap();
+
+            value = map.lookup(value);
+            source_category = map.destination_category.clone();
         }
 
-        for r in current.iter() {
-            min_value = std::cmp::min(min_value, r.start);
+        value
+    }
+
+    // maps every single seed, only usable on small inputs but handy to verify the range version
+    // with --resume it goes on from the last checkpoint of the same almanac and seeds
+    fn solve_brute_force(&self, seeds: &[Range<i64>]) -> i64 {
+        #[cfg(feature = "tooling")]
+        let mut checkpoint =
+            Checkpoint::new("day05-brute-force", &format!("{:?}{:?}", self.maps, seeds))
+                .with_calls_per_clock_read(1 << 16);
+        // the range and the seed in it to go on from, and the lowest location so far
+        #[cfg(feature = "tooling")]
+        let (start_index, start_seed, mut lowest) = checkpoint
+            .resume::<(usize, i64, i64)>()
+            .unwrap_or((0, i64::MIN, i64::MAX));
+        #[cfg(not(feature = "tooling"))]
+        let (start_index, start_seed, mut lowest) = (0, i64::MIN, i64::MAX);
+
+        for (index, range) in seeds.iter().enumerate().skip(start_index) {
+            let start = if index == start_index {
+                range.start.max(start_seed)
+            } else {
+                range.start
+            };
+
+            for seed in start..range.end {
+                lowest = lowest.min(self.location(seed));
+                #[cfg(feature = "tooling")]
+                checkpoint.save(|| (index, seed + 1, lowest));
+            }
         }
+        #[cfg(feature = "tooling")]
+        checkpoint.finish();
 
-        min_value
+        lowest
     }
 }
 
+// with --parse-cache an almanac seen before is read back instead of parsed
+#[cfg(feature = "tooling")]
+fn parse_almanac(input: &str) -> Result<Almanac, Error> {
+    cache::parse(input)
+}
+
+#[cfg(not(feature = "tooling"))]
+fn parse_almanac(input: &str) -> Result<Almanac, Error> {
+    input.parse()
+}
+
 pub fn solve(input: &str) -> Result<Answer> {
-    let almanac = Almanac::new(input);
+    let almanac = info_span!("parse").in_scope(|| parse_almanac(input))?;
 
-    let part1 = almanac.solve(&almanac.seeds_one);
-    let part2 = almanac.solve(&almanac.seeds_range);
+    let part1 =
+        info_span!("part", part = Part::One.name()).in_scope(|| almanac.solve(&almanac.seeds_one));
+    let part2 = info_span!("part", part = Part::Two.name())
+        .in_scope(|| almanac.solve(&almanac.seeds_range));
 
     let answer = Answer {
         part1: Some(part1.to_string()),
@@ -265,51 +684,57 @@ pub fn solve(input: &str) -> Result<Answer> {
     Ok(answer)
 }
 
-#[cfg(test)]
-mod tests {
-    use tracing_test::traced_test;
+pub fn solve_brute_force(input: &str) -> Result<Answer> {
+    let almanac = parse_almanac(input)?;
 
-    use crate::day05::Almanac;
+    let part1 = almanac.solve_brute_force(&almanac.seeds_one);
+    let part2 = almanac.solve_brute_force(&almanac.seeds_range);
 
-    const TEST_INPUT: &str = "seeds: 79 14 55 13
+    let answer = Answer {
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    };
+
+    Ok(answer)
+}
 
-seed-to-soil map:
-50 98 2
-52 50 48
+pub fn mapping_dot(input: &str, part: Part) -> Result<String> {
+    Ok(parse_almanac(input)?.mapping_dot(part))
+}
 
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
+// every seed goes through a single precomposed lookup
+pub fn solve_composed(input: &str) -> Result<Answer> {
+    let almanac = parse_almanac(input)?;
+    let composed = almanac.composed_map();
 
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
+    let location = |seeds: &[Range<i64>]| {
+        seeds
+            .iter()
+            .filter_map(|f| composed.min_location(f.start, f.end))
+            .min()
+            .unwrap_or(i64::MAX)
+    };
 
-water-to-light map:
-88 18 7
-18 25 70
+    let answer = Answer {
+        part1: Some(location(&almanac.seeds_one).to_string()),
+        part2: Some(location(&almanac.seeds_range).to_string()),
+    };
 
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
+    Ok(answer)
+}
 
-temperature-to-humidity map:
-0 69 1
-1 0 69
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
 
-humidity-to-location map:
-60 56 37
-56 93 4
-";
+    use crate::day05::{diagnostics, Almanac, Category};
+    use crate::test_support::fixture;
+    use crate::utils::Part;
 
     #[traced_test]
     #[test]
     fn test_part1() {
-        let almanac = Almanac::new(TEST_INPUT);
+        let almanac = fixture("day05").parse::<Almanac>().unwrap();
         let solution = almanac.solve(&almanac.seeds_one);
         assert_eq!(solution, 35);
     }
@@ -317,8 +742,140 @@ humidity-to-location map:
     #[traced_test]
     #[test]
     fn test_part2() {
-        let almanac = Almanac::new(TEST_INPUT);
+        let almanac = fixture("day05").parse::<Almanac>().unwrap();
         let solution = almanac.solve(&almanac.seeds_range);
         assert_eq!(solution, 46);
     }
+
+    #[traced_test]
+    #[test]
+    fn test_map_to() {
+        let almanac = fixture("day05").parse::<Almanac>().unwrap();
+        let seeds = almanac.seeds(Part::One);
+
+        assert_eq!(seeds, vec![13..14, 14..15, 55..56, 79..80]);
+        assert_eq!(
+            almanac.map_to(&seeds, Category::Seed),
+            vec![13..15, 55..56, 79..80]
+        );
+        assert_eq!(
+            almanac.map_to(&seeds, Category::Soil),
+            vec![13..15, 57..58, 81..82]
+        );
+        assert_eq!(
+            almanac.map_to(&seeds, Category::Location),
+            vec![35..36, 43..44, 82..83, 86..87]
+        );
+
+        let locations = almanac.map_to(&almanac.seeds(Part::Two), Category::Location);
+        assert_eq!(locations.first().unwrap().start, 46);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_brute_force() {
+        let almanac = fixture("day05").parse::<Almanac>().unwrap();
+        assert_eq!(almanac.solve_brute_force(&almanac.seeds_one), 35);
+        assert_eq!(almanac.solve_brute_force(&almanac.seeds_range), 46);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_parse_error() {
+        let input = fixture("day05").replace("52 50 48", "52 50");
+        let error = input.parse::<Almanac>().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "line 5: expected 3 whitespace-separated numbers in map entry"
+        );
+
+        let input = fixture("day05").replace("soil-to-fertilizer", "soil-to-fertiliser");
+        let error = input.parse::<Almanac>().unwrap_err();
+
+        assert_eq!(error.to_string(), "line 7: unknown category");
+        assert!(error.render("input/05", &input).contains("fertiliser"));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_diagnostics() {
+        assert!(diagnostics(&fixture("day05")).is_empty());
+
+        // seed-to-soil is "50 98 2" and "52 50 48", 60 20 overlaps 50..98 and the length goes negative
+        let input = fixture("day05")
+            .replace("52 50 48", "52 50 48\n0 60 20")
+            .replace("0 15 37", "0 15 -37");
+        let messages = diagnostics(&input)
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            [
+                "line 6: source range 60..80 overlaps 50..98 of line 5",
+                "line 9: negative range length",
+            ]
+        );
+
+        // the first problem is what parsing fails with
+        let error = input.parse::<Almanac>().unwrap_err();
+        assert!(error.render("input/05", &input).contains("0 60 20"));
+
+        let input = fixture("day05").replace("light-to-temperature", "humidity-to-temperature");
+        let messages = diagnostics(&input)
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["line 18: no map from light"]);
+
+        let input = fixture("day05").replace("fertilizer-to-water", "fertilizer-to-seed");
+        assert_eq!(
+            diagnostics(&input)[0].to_string(),
+            "line 12: the maps go round in a circle"
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_composed_map() {
+        let almanac = fixture("day05").parse::<Almanac>().unwrap();
+        let composed = almanac.composed_map();
+
+        for (seed, location) in [(79, 82), (14, 43), (55, 86), (13, 35)] {
+            assert_eq!(composed.lookup(seed), location);
+            assert_eq!(almanac.location(seed), location);
+        }
+
+        // gapless from 0 to the end of the value range
+        let pieces = composed.pieces();
+        assert_eq!(pieces.first().unwrap().start, 0);
+        assert!(pieces.windows(2).all(|f| f[0].end == f[1].start));
+
+        assert_eq!(composed.min_location(79, 93), Some(46));
+    }
+
+    fixture_test!(
+        test_composed,
+        super::solve_composed,
+        "day05",
+        part1 = "35",
+        part2 = "46"
+    );
+
+    #[traced_test]
+    #[test]
+    fn test_mapping_dot() {
+        let almanac = fixture("day05").parse::<Almanac>().unwrap();
+        let dot = almanac.mapping_dot(Part::Two);
+
+        assert!(dot.starts_with("digraph almanac {\n"));
+        assert!(dot.contains("        seed_1 [label=\"79..93\"];\n"));
+        assert!(dot.contains("    seed_0 -> soil_0 [label=\"+2\"];\n"));
+        // the lowest location is one of the pieces the seed ranges were split into
+        assert!(dot.contains("        location_5 [label=\"46..56\"];\n"));
+        assert_eq!(dot.matches(" -> location_").count(), 7);
+        assert_eq!(dot.matches(" -> ").count(), 2 + 2 + 3 + 3 + 4 + 4 + 7);
+        assert!(dot.ends_with("}\n"));
+    }
 }
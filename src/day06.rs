@@ -1,6 +1,13 @@
-use color_eyre::eyre::Result;
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 
-use crate::solver::Answer;
+use crate::{
+    error::{Error, Result},
+    solver::Answer,
+};
 
 struct Race {
     time: u64,
@@ -20,19 +27,50 @@ impl Race {
 }
 
 pub fn solve(input: &str) -> Result<Answer> {
-    let mut answer = Answer::default();
+    let (races, long_race) = parse(input)?;
+
+    let part1 = races
+        .iter()
+        .map(|f| f.get_win_possibilities())
+        .product::<u64>();
+    let part2 = long_race.get_win_possibilities();
+
+    Ok(Answer {
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    })
+}
+
+// counts the hold times of every race in parallel on the gpu
+#[cfg(feature = "gpu")]
+pub fn solve_gpu(input: &str) -> Result<Answer> {
+    let gpu = crate::gpu::Gpu::get()?;
+    let (races, long_race) = parse(input)?;
+
     let mut part1 = 1;
+    for race in &races {
+        part1 *= gpu.race_wins(race.time, race.distance)?;
+    }
+    let part2 = gpu.race_wins(long_race.time, long_race.distance)?;
 
+    Ok(Answer {
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    })
+}
+
+// the races of part 1, and the one long race of part 2 their digits make up
+fn parse(input: &str) -> Result<(Vec<Race>, Race)> {
     let mut time_vec = vec![];
     let mut distance_vec = vec![];
 
     for (index, line) in input.lines().enumerate() {
         match index {
             0 => {
-                insert_to_vec(line, &mut time_vec);
+                insert_to_vec(index, line, &mut time_vec)?;
             }
             1 => {
-                insert_to_vec(line, &mut distance_vec);
+                insert_to_vec(index, line, &mut distance_vec)?;
             }
             _ => break,
         }
@@ -40,72 +78,55 @@ pub fn solve(input: &str) -> Result<Answer> {
 
     assert_eq!(time_vec.len(), distance_vec.len());
 
-    for index in 0..time_vec.len() {
-        let time = time_vec[index];
-        let distance = distance_vec[index];
-
-        let race = Race::new(time, distance);
-        part1 *= race.get_win_possibilities();
-    }
-
-    let time = time_vec
-        .iter()
-        .map(|f| f.to_string())
-        .collect::<String>()
-        .parse::<u64>()
-        .unwrap();
-    let distance = distance_vec
+    let races = time_vec
         .iter()
-        .map(|f| f.to_string())
-        .collect::<String>()
-        .parse::<u64>()
-        .unwrap();
-
-    let race = Race::new(time, distance);
-    let part2 = race.get_win_possibilities();
-
-    answer.part1 = Some(part1.to_string());
-    answer.part2 = Some(part2.to_string());
+        .zip(&distance_vec)
+        .map(|(time, distance)| Race::new(*time, *distance))
+        .collect();
 
-    Ok(answer)
+    let concatenate = |values: &[u64]| {
+        values
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<String>()
+            .parse::<u64>()
+            .unwrap()
+    };
+    let long_race = Race::new(concatenate(&time_vec), concatenate(&distance_vec));
+
+    Ok((races, long_race))
 }
 
-fn insert_to_vec(line: &str, time_vec: &mut Vec<u64>) {
-    let vec = line.split(':').collect::<Vec<&str>>();
-    assert_eq!(vec.len(), 2);
-    let value = vec.last().unwrap();
+fn insert_to_vec(index: usize, line: &str, time_vec: &mut Vec<u64>) -> Result<(), Error> {
+    let (_, value) = line.split_once(':').ok_or_else(|| {
+        Error::parse(index, "missing ':' separator").with_hint("lines look like \"Time: 7 15 30\"")
+    })?;
     *time_vec = value
         .split_whitespace()
-        .map(|x| x.parse::<u64>().unwrap())
-        .collect();
+        .map(|x| {
+            x.parse::<u64>()
+                .map_err(|_| Error::parse(index, "expected a number").at_token(line, x))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(())
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::solve;
-    use color_eyre::eyre::Result;
-    use tracing_test::traced_test;
 
-    const TEST_INPUT: &str = "Time:      7  15   30
-Distance:  9  40  200";
-
-    #[traced_test]
-    #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    use tracing_test::traced_test;
 
-        assert_eq!(answer.part1, Some("288".to_string()));
+    fixture_test!(test_part1, super::solve, "day06", part1 = "288");
 
-        Ok(())
-    }
+    fixture_test!(test_part2, super::solve, "day06", part2 = "71503");
 
     #[traced_test]
     #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part2, Some("71503".to_string()));
+    fn test_parse_error() {
+        let error = solve("Time:      7  15   30\nDistance:  9  x  200").unwrap_err();
 
-        Ok(())
+        assert_eq!(error.to_string(), "line 2: expected a number");
     }
 }
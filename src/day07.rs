@@ -1,8 +1,12 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::{cmp::Ordering, collections::BTreeMap, str::FromStr};
 
-use color_eyre::eyre::Result;
+use crate::{
+    error::{Error, Result},
+    solver::Answer,
+    utils::explain,
+};
 
-use crate::solver::Answer;
+const CARDS: &str = "AKQJT98765432";
 
 #[derive(Debug)]
 enum HandStrength {
@@ -66,10 +70,11 @@ trait ToVecCardTrait {
 
 trait VecHandTrait {
     fn sort_hands(&mut self);
+    fn explain(&self, part: &str);
     fn calculate(&self) -> u32;
 }
 
-impl ToVecCardTrait for HashMap<char, u32> {
+impl ToVecCardTrait for BTreeMap<char, u32> {
     fn to_card_vec(&self) -> Vec<Card> {
         let mut vec = vec![];
 
@@ -105,6 +110,28 @@ impl VecHandTrait for Vec<Hand> {
         })
     }
 
+    fn explain(&self, part: &str) {
+        for (rank, hand) in self.iter().enumerate() {
+            explain::line(|| {
+                let joker = match hand.joker {
+                    Some(symbol) => format!(", J as {}", symbol),
+                    None => String::new(),
+                };
+
+                format!(
+                    "{} rank {:>4}: {} {:?}{}, bid {} -> {}",
+                    part,
+                    rank + 1,
+                    hand.symbols,
+                    hand.kind,
+                    joker,
+                    hand.bid,
+                    (rank as u32 + 1) * hand.bid
+                )
+            });
+        }
+    }
+
     fn calculate(&self) -> u32 {
         self.iter()
             .enumerate()
@@ -114,30 +141,80 @@ impl VecHandTrait for Vec<Hand> {
 }
 
 #[derive(Debug)]
-struct Hand {
+pub struct Hand {
+    symbols: String,
     raw_cards: Vec<u32>,
+    kind: HandStrength,
     strength: u32,
+    // the card the jokers stand in for, only with_joker and when the hand has a J
+    joker: Option<char>,
     bid: u32,
 }
 
-impl Hand {
-    fn new(input: &str, with_joker: bool) -> Self {
-        let vec = input.split_whitespace().collect::<Vec<&str>>();
+// "32T3K 765", read with J as a jack, see with_joker for part 2
+impl FromStr for Hand {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Error> {
+        let (symbols, bid) = input
+            .split_once(' ')
+            .ok_or_else(|| Error::parse(0, "expected a hand and a bid"))?;
+        let bid = bid
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| Error::parse(0, "expected a bid").at_token(input, bid))?;
+
+        if let Some((index, _)) = symbols.char_indices().find(|(_, c)| !CARDS.contains(*c)) {
+            return Err(Error::parse(0, "unknown card")
+                .at(index, index + 1)
+                .with_hint("cards are AKQJT98765432"));
+        }
 
-        assert_eq!(vec.len(), 2);
+        if symbols.len() != 5 {
+            return Err(Error::parse(0, "a hand has 5 cards").at_token(input, symbols));
+        }
 
-        let bid = vec.last().unwrap().parse::<u32>().unwrap();
-        let (cards, raw_cards) = Self::parse_card(vec.first().unwrap(), with_joker);
-        let strength = Self::get_strength(cards.clone(), with_joker);
+        Ok(Self::score(symbols.to_string(), bid, false))
+    }
+}
+
+impl Hand {
+    fn score(symbols: String, bid: u32, with_joker: bool) -> Self {
+        let (cards, raw_cards) = Self::parse_card(&symbols, with_joker);
+        let kind = Self::get_strength(cards.clone(), with_joker);
+        let joker = Self::get_joker(&cards, with_joker);
 
         Self {
+            symbols,
             raw_cards,
-            strength,
+            strength: kind.get_rank(),
+            kind,
+            joker,
             bid,
         }
     }
 
-    fn get_strength(cards: Vec<Card>, with_joker: bool) -> u32 {
+    // the same hand with J read as a joker instead of a jack
+    pub fn with_joker(self) -> Self {
+        Self::score(self.symbols, self.bid, true)
+    }
+
+    // jokers join the most common other card, JJJJJ becomes five aces
+    fn get_joker(cards: &[Card], with_joker: bool) -> Option<char> {
+        if !with_joker || !cards.iter().any(|f| f.symbol == 'J') {
+            return None;
+        }
+
+        let symbol = cards
+            .iter()
+            .find(|f| f.symbol != 'J')
+            .map(|f| f.symbol)
+            .unwrap_or('A');
+
+        Some(symbol)
+    }
+
+    fn get_strength(cards: Vec<Card>, with_joker: bool) -> HandStrength {
         assert!(cards.len() <= 5);
 
         // if with_joker, remove J from current cards
@@ -149,7 +226,7 @@ impl Hand {
 
         if filtered.is_empty() {
             // this can only happens if with_joker and hands is JJJJJ
-            return HandStrength::FiveOfKind.get_rank();
+            return HandStrength::FiveOfKind;
         }
 
         let mut first_count = filtered.first().unwrap().count;
@@ -172,7 +249,7 @@ impl Hand {
         // in case of Joker, remove the Joker from card stacks
         // and then add the number of the Joker to the most cards in the stack
 
-        let strength = match filtered.len() {
+        match filtered.len() {
             1 => HandStrength::FiveOfKind,
             2 => {
                 if first_count == 4 {
@@ -191,17 +268,13 @@ impl Hand {
             4 => HandStrength::OnePair,
             5 => HandStrength::HighCard,
             _ => unreachable!(),
-        };
-
-        strength.get_rank()
+        }
     }
 
     fn parse_card(input: &str, with_joker: bool) -> (Vec<Card>, Vec<u32>) {
-        let mut map: HashMap<char, u32> = HashMap::new();
+        let mut map: BTreeMap<char, u32> = BTreeMap::new();
         let mut raw_cards = vec![];
 
-        assert_eq!(input.len(), 5);
-
         for c in input.chars() {
             let kind: u32 = match c {
                 'A' => 14,
@@ -210,7 +283,7 @@ impl Hand {
                 'J' if with_joker => 1,
                 'J' => 11,
                 'T' => 10,
-                _ => c.to_string().parse::<u32>().unwrap(),
+                _ => c.to_digit(10).unwrap(),
             };
 
             raw_cards.push(kind);
@@ -227,28 +300,26 @@ impl Hand {
 
 pub fn solve(input: &str) -> Result<Answer> {
     let mut answer = Answer::default();
-    let mut hands = vec![];
+    let mut hands = input
+        .lines()
+        .enumerate()
+        .filter(|(_, f)| !f.is_empty())
+        .map(|(index, line)| line.parse::<Hand>().map_err(|f| f.on_line(index)))
+        .collect::<Result<Vec<_>, _>>()?;
 
     // part 1
-    for line in input.lines() {
-        let card = Hand::new(line, false);
-        hands.push(card);
-    }
 
     hands.sort_hands();
+    hands.explain("part1");
     let part1: u32 = hands.calculate();
 
     answer.part1 = Some(part1.to_string());
 
     // part 2
-    hands.clear();
-
-    for line in input.lines() {
-        let hand = Hand::new(line, true);
-        hands.push(hand);
-    }
+    let mut hands = hands.into_iter().map(Hand::with_joker).collect::<Vec<_>>();
 
     hands.sort_hands();
+    hands.explain("part2");
     let part2: u32 = hands.calculate();
 
     answer.part2 = Some(part2.to_string());
@@ -258,34 +329,41 @@ pub fn solve(input: &str) -> Result<Answer> {
 
 #[cfg(test)]
 mod tests {
-    use color_eyre::eyre::Result;
     use tracing_test::traced_test;
 
-    use crate::day07::solve;
-
-    const TEST_INPUT: &str = "32T3K 765
-T55J5 684
-KK677 28
-KTJJT 220
-QQQJA 483";
+    use super::*;
 
     #[traced_test]
     #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part1, Some("6440".to_string()));
-
-        Ok(())
+    fn test_joker() {
+        let hand = "KTJJT 220".parse::<Hand>().unwrap();
+        assert!(matches!(hand.kind, HandStrength::TwoPair));
+        assert_eq!(hand.joker, None);
+
+        let hand = "KTJJT 220".parse::<Hand>().unwrap().with_joker();
+        assert!(matches!(hand.kind, HandStrength::FourOfKind));
+        assert_eq!(hand.joker, Some('T'));
+
+        let hand = "JJJJJ 1".parse::<Hand>().unwrap().with_joker();
+        assert!(matches!(hand.kind, HandStrength::FiveOfKind));
+        assert_eq!(hand.joker, Some('A'));
+
+        // ties between counts resolve in symbol order, not hash order
+        let hand = "9J8T7 1".parse::<Hand>().unwrap().with_joker();
+        assert!(matches!(hand.kind, HandStrength::OnePair));
+        assert_eq!(hand.joker, Some('7'));
+
+        let error = "KTXJT 220".parse::<Hand>().unwrap_err();
+        assert_eq!(
+            error,
+            Error::parse(0, "unknown card")
+                .at(2, 3)
+                .with_hint("cards are AKQJT98765432")
+        );
+        assert!("KTJJ 220".parse::<Hand>().is_err());
     }
 
-    #[traced_test]
-    #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part2, Some("5905".to_string()));
+    fixture_test!(test_part1, super::solve, "day07", part1 = "6440");
 
-        Ok(())
-    }
+    fixture_test!(test_part2, super::solve, "day07", part2 = "5905");
 }
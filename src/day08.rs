@@ -1,59 +1,148 @@
 use std::collections::{HashMap, HashSet};
 
-use color_eyre::eyre::Result;
-
-use crate::solver::Answer;
+use color_eyre::eyre::{bail, Result};
+use rayon::prelude::*;
+use winnow::{
+    combinator::{delimited, separated_pair},
+    token::take_while,
+    Parser,
+};
+
+use crate::{
+    error::Error,
+    solver::Answer,
+    utils::{
+        guard,
+        parse::{expected, hint, name, parse_line, spaced},
+    },
+};
+
+// real inputs need tens of thousands of steps, anything far beyond is a broken input
+const DEFAULT_MAX_STEPS: u64 = 10_000_000;
+
+// how many nodes of a detected cycle are listed in the error
+const CYCLE_PREVIEW: usize = 10;
+
+// how one ghost walks, each ghost is independent of the others
+#[derive(Debug, PartialEq, Eq)]
+struct GhostCycle {
+    start: String,
+    // the shortest number of steps between two nodes ending with Z
+    length: u64,
+    // steps from the start at which the ghost stood on a node ending with Z
+    offsets: Vec<u64>,
+}
 
 #[derive(Debug)]
 struct Map {
     instruction: Vec<usize>,
     nodes: HashMap<String, [String; 2]>,
+    max_steps: u64,
 }
 
 impl Map {
-    fn new(input: &str) -> Self {
-        let mut vec = input.lines();
-        let mut instruction = vec![];
+    fn new(input: &str) -> Result<Self, Error> {
+        let mut lines = input.lines().enumerate();
         let mut nodes = HashMap::new();
 
-        for c in vec.next().unwrap().chars() {
-            let direction = match c {
-                'L' => 0,
-                'R' => 1,
-                _ => unreachable!(),
-            };
-
-            instruction.push(direction);
+        let instruction = parse_line(
+            0,
+            lines.next().map_or("", |(_, f)| f),
+            take_while(1.., ['L', 'R'])
+                .map(|f: &str| f.chars().map(|f| (f == 'R') as usize).collect::<Vec<_>>())
+                .context(expected("the instructions"))
+                .context(hint("the first line is the instructions, like `LLR`")),
+        )?;
+
+        if let Some((index, line)) = lines.next().filter(|(_, f)| !f.is_empty()) {
+            return Err(
+                Error::parse(index, "expected a blank line after the instructions")
+                    .at_token(line, line),
+            );
         }
 
-        assert_eq!(vec.next().unwrap().len(), 0);
+        let mut targets = vec![];
+        for (index, line) in lines {
+            let (current, (left, right)) = parse_line(
+                index,
+                line,
+                separated_pair(
+                    name,
+                    spaced("="),
+                    delimited('(', separated_pair(name, spaced(","), name), ')'),
+                )
+                .context(expected("a node"))
+                .context(hint("nodes look like `AAA = (BBB, CCC)`")),
+            )?;
+
+            nodes.insert(current.to_string(), [left.to_string(), right.to_string()]);
+            targets.push((index, line, left, right));
+        }
 
-        for line in vec {
-            let vec: Vec<String> = line.split('=').map(|f| f.trim().to_string()).collect();
-            assert_eq!(vec.len(), 2);
+        // a typo in a node name would otherwise only show up half way through a walk
+        for (index, line, left, right) in targets {
+            if let Some(target) = [left, right].into_iter().find(|f| !nodes.contains_key(*f)) {
+                return Err(
+                    Error::parse(index, &format!("node {} is not defined", target))
+                        .at_token(line, target)
+                        .with_hint("every node in the parentheses needs a line of its own"),
+                );
+            }
+        }
 
-            let current = vec.first().unwrap();
-            let node: Vec<String> = vec
-                .last()
-                .unwrap()
-                .split(',')
-                .map(|f| f.replace(['(', ')'], "").trim().to_string())
-                .collect();
+        Ok(Map {
+            instruction,
+            nodes,
+            max_steps: DEFAULT_MAX_STEPS,
+        })
+    }
 
-            assert_eq!(node.len(), 2);
+    fn with_max_steps(mut self, max_steps: u64) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
 
-            nodes.insert(current.clone(), [node[0].clone(), node[1].clone()]);
+    fn travel_to_zzz(&self) -> Result<i32> {
+        // examples of part 2 only have ghost nodes, so Map::new does not ask for AAA
+        if !self.nodes.contains_key("AAA") {
+            bail!("there is no node AAA to start from");
         }
 
-        Map { instruction, nodes }
-    }
-
-    fn travel_to_zzz(&self) -> i32 {
         let mut current = "AAA".to_string();
         let mut index = 0;
         let mut steps = 0;
 
+        // the walk is stuck once a node comes up again at the same instruction
+        let mut seen: HashMap<(String, u64), usize> = HashMap::new();
+        let mut path = vec![];
+
         while current != "ZZZ" {
+            if steps as u64 >= self.max_steps {
+                bail!("ZZZ not reached after {} steps", self.max_steps);
+            }
+
+            if let Some(&start) = seen.get(&(current.clone(), index)) {
+                let cycle = &path[start..];
+                let mut preview = cycle
+                    .iter()
+                    .take(CYCLE_PREVIEW)
+                    .cloned()
+                    .collect::<Vec<String>>();
+                if cycle.len() > CYCLE_PREVIEW {
+                    preview.push("...".to_string());
+                }
+
+                bail!(
+                    "ZZZ is unreachable, after {} steps the walk repeats a cycle of {} steps: {}",
+                    steps,
+                    cycle.len(),
+                    preview.join(" -> ")
+                );
+            }
+
+            seen.insert((current.clone(), index), path.len());
+            path.push(current.clone());
+
             current = self.travel(index, current);
 
             steps += 1;
@@ -61,67 +150,92 @@ impl Map {
             index %= self.instruction.len() as u64; // prevent index out of bound
         }
 
-        steps
+        Ok(steps)
     }
 
+    // Map::new checked that every node leads to a defined one
     fn travel(&self, index: u64, current: String) -> String {
         let direction = &self.instruction[index as usize];
         self.nodes.get(&current).unwrap()[*direction].clone()
     }
 
-    fn travel_to_end_z(&self) -> u64 {
+    fn travel_to_end_z(&self) -> Result<u64> {
         // Least Common Multiple (LCM) problem
         // First, We need to determine the minimum denominator for each starting point
+        let cycles = self.ghost_cycles()?;
 
-        let current_vec: Vec<String> = self
-            .nodes
-            .keys()
-            .filter(|f| f.ends_with('A'))
-            .map(|f| f.to_string())
-            .collect();
-
-        let mut numbers = vec![];
+        Ok(cycles
+            .iter()
+            .fold(1, |acc, f| num::integer::lcm(acc, f.length)))
+    }
 
-        for v in current_vec.iter() {
-            let mut current = v.clone();
-            let mut ends_with_z: HashSet<u64> = HashSet::new();
+    // one cycle per node ending with A, in the order of their names
+    fn ghost_cycles(&self) -> Result<Vec<GhostCycle>> {
+        let mut starts: Vec<&String> = self.nodes.keys().filter(|f| f.ends_with('A')).collect();
+        starts.sort();
 
-            let mut index = 0;
-            let mut distance_traveled = 0;
+        starts
+            .into_par_iter()
+            .map(|f| self.ghost_cycle(f))
+            .collect()
+    }
 
-            loop {
-                distance_traveled += 1;
-                current = self.travel(index, current.clone());
+    fn ghost_cycle(&self, start: &str) -> Result<GhostCycle> {
+        let mut current = start.to_string();
+        let mut ends_with_z: HashSet<u64> = HashSet::new();
+        let mut offsets = vec![];
 
-                if current.ends_with('Z') {
-                    if ends_with_z.contains(&distance_traveled) {
-                        break;
-                    }
-                    ends_with_z.insert(distance_traveled);
-                    distance_traveled = 0;
-                }
+        let mut index = 0;
+        let mut distance_traveled = 0;
+        let mut steps = 0;
 
-                index += 1;
-                index %= self.instruction.len() as u64; // prevent index out of bound
+        loop {
+            if steps >= self.max_steps {
+                bail!(
+                    "{} did not reach a node ending with Z twice within {} steps",
+                    start,
+                    self.max_steps
+                );
             }
 
-            let mut ends_with_z_vec: Vec<u64> = ends_with_z.into_iter().collect();
-            ends_with_z_vec.sort();
+            steps += 1;
+            distance_traveled += 1;
+            current = self.travel(index, current.clone());
+
+            if current.ends_with('Z') {
+                offsets.push(steps);
+
+                if ends_with_z.contains(&distance_traveled) {
+                    break;
+                }
+                ends_with_z.insert(distance_traveled);
+                distance_traveled = 0;
+            }
 
-            numbers.push(*ends_with_z_vec.first().unwrap());
+            index += 1;
+            index %= self.instruction.len() as u64; // prevent index out of bound
         }
 
-        numbers.iter().fold(1, |acc, &x| num::integer::lcm(acc, x))
+        Ok(GhostCycle {
+            start: start.to_string(),
+            length: *ends_with_z.iter().min().unwrap(),
+            offsets,
+        })
     }
 }
 
 pub fn solve(input: &str) -> Result<Answer> {
+    solve_with_max_steps(input, guard::max_steps().unwrap_or(DEFAULT_MAX_STEPS))
+}
+
+// fails instead of walking on when either part needs more than max_steps
+pub fn solve_with_max_steps(input: &str, max_steps: u64) -> Result<Answer> {
     let mut answer = Answer::default();
 
-    let map = Map::new(input);
+    let map = Map::new(input)?.with_max_steps(max_steps);
 
-    answer.part1 = Some(map.travel_to_zzz().to_string());
-    answer.part2 = Some(map.travel_to_end_z().to_string());
+    answer.part1 = Some(map.travel_to_zzz()?.to_string());
+    answer.part2 = Some(map.travel_to_end_z()?.to_string());
     Ok(answer)
 }
 
@@ -129,37 +243,102 @@ pub fn solve(input: &str) -> Result<Answer> {
 mod tests {
     use tracing_test::traced_test;
 
-    use crate::day08::Map;
+    use crate::day08::{GhostCycle, Map};
+    use crate::error::Error;
+    use crate::test_support::fixture;
 
     #[traced_test]
     #[test]
     fn test_part1() {
-        let input = "LLR
+        let map = Map::new(&fixture("day08-part1")).unwrap();
+
+        assert_eq!(map.travel_to_zzz().unwrap(), 6);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_part2() {
+        let map = Map::new(&fixture("day08-part2")).unwrap();
+
+        assert_eq!(map.travel_to_end_z().unwrap(), 6);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_ghost_cycles() {
+        let map = Map::new(&fixture("day08-part2")).unwrap();
+
+        assert_eq!(
+            map.ghost_cycles().unwrap(),
+            vec![
+                GhostCycle {
+                    start: "11A".to_string(),
+                    length: 2,
+                    offsets: vec![2, 4],
+                },
+                GhostCycle {
+                    start: "22A".to_string(),
+                    length: 3,
+                    offsets: vec![3, 6],
+                },
+            ]
+        );
+
+        // one ghost running out of steps fails the whole analysis
+        let error = map.with_max_steps(4).ghost_cycles().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "22A did not reach a node ending with Z twice within 4 steps"
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_unreachable_zzz() {
+        let input =
+            "LR\n\nAAA = (BBB, CCC)\nBBB = (AAA, AAA)\nCCC = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+        let error = Map::new(input).unwrap().travel_to_zzz().unwrap_err();
 
-AAA = (BBB, BBB)
-BBB = (AAA, ZZZ)
-ZZZ = (ZZZ, ZZZ)";
+        assert_eq!(
+            error.to_string(),
+            "ZZZ is unreachable, after 2 steps the walk repeats a cycle of 2 steps: AAA -> BBB"
+        );
 
-        let map = Map::new(input);
+        let map = Map::new(&fixture("day08-part1")).unwrap().with_max_steps(5);
+        let error = map.travel_to_zzz().unwrap_err();
 
-        assert_eq!(map.travel_to_zzz(), 6);
+        assert_eq!(error.to_string(), "ZZZ not reached after 5 steps");
     }
 
     #[traced_test]
     #[test]
-    fn test_part2() {
-        let input = "LR
-
-11A = (11B, XXX)
-11B = (XXX, 11Z)
-11Z = (11B, XXX)
-22A = (22B, XXX)
-22B = (22C, 22C)
-22C = (22Z, 22Z)
-22Z = (22B, 22B)
-XXX = (XXX, XXX)";
-        let map = Map::new(input);
-
-        assert_eq!(map.travel_to_end_z(), 6);
+    fn test_parse_errors() {
+        let error = Map::new("LRX\n\nAAA = (BBB, CCC)\n").unwrap_err();
+        assert_eq!(error, Error::parse(0, "unexpected text").at(2, 2));
+
+        let input = "LR\n\nAAA = (BBB, CCC)\nBBB = (AAA CCC)\n";
+        let error = Map::new(input).unwrap_err();
+        assert_eq!(error.to_string(), "line 4: expected a node");
+        assert!(error.render("input/08", input).contains("nodes look like"));
+
+        let input = "LR\n\nAAA = (BBB, CCC)\nBBB = (AAA, AAA)\n";
+        let error = Map::new(input).unwrap_err();
+        assert_eq!(
+            error,
+            Error::parse(2, "node CCC is not defined")
+                .at(12, 15)
+                .with_hint("every node in the parentheses needs a line of its own")
+        );
+        assert!(error.render("input/08", input).contains("input/08:3:13"));
+
+        let map = Map::new("LR\n\nBBB = (BBB, BBB)\n").unwrap();
+        let error = map.travel_to_zzz().unwrap_err();
+        assert_eq!(error.to_string(), "there is no node AAA to start from");
+
+        let error = Map::new("LR\nAAA = (BBB, CCC)\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected a blank line after the instructions"
+        );
     }
 }
@@ -1,108 +1,198 @@
-use std::collections::HashSet;
+use alloc::{format, string::ToString, vec, vec::Vec};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io::BufRead;
 
-use color_eyre::eyre::Result;
+use crate::{
+    error::{Error, Result},
+    solver::Answer,
+};
 
-use crate::solver::Answer;
-
-struct Sequence {
+pub struct Sequence {
     values: Vec<i32>,
 }
 
-impl Sequence {
-    fn new(input: &str) -> Self {
+// "0 3 6 9 12 15"
+impl FromStr for Sequence {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Error> {
         let values = input
             .split_whitespace()
-            .map(|f| f.parse().unwrap())
-            .collect();
+            .map(|f| {
+                f.parse()
+                    .map_err(|_| Error::parse(0, "expected a number").at_token(input, f))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if values.is_empty() {
+            return Err(Error::parse(0, "empty sequence"));
+        }
+
+        Ok(Self { values })
+    }
+}
+
+// errors from extrapolating are reported on line 0, see Error::on_line
+impl Sequence {
+    pub fn get_previous_value(&self) -> Result<i32, Error> {
+        Ok(self.extrapolate_back(1)?[0])
+    }
 
-        Self { values }
+    pub fn get_next_value(&self) -> Result<i32, Error> {
+        Ok(self.extrapolate(1)?[0])
     }
 
-    fn get_previous_value(&self) -> i32 {
-        let mut values = self.values.clone();
-        values.reverse();
-        Self::get_next_value_internal(&values)
+    // the next n values of the sequence, in order
+    pub fn extrapolate(&self, n: usize) -> Result<Vec<i32>, Error> {
+        let edges = self.pyramid()?.iter().map(|f| *f.last().unwrap()).collect();
+        self.extend(edges, n, i32::checked_add)
     }
 
-    fn get_next_value(&self) -> i32 {
-        Self::get_next_value_internal(&self.values)
+    // the n values before the sequence, the nearest one first
+    pub fn extrapolate_back(&self, n: usize) -> Result<Vec<i32>, Error> {
+        let edges = self.pyramid()?.iter().map(|f| f[0]).collect();
+        self.extend(edges, n, i32::checked_sub)
     }
 
-    fn get_next_value_internal(values: &Vec<i32>) -> i32 {
-        let mut diffs = vec![];
-        let mut diffs_set = HashSet::new();
+    // the sequence, then its differences, then theirs, down to the first row that is constant
+    fn pyramid(&self) -> Result<Vec<Vec<i32>>, Error> {
+        let mut rows = vec![self.values.clone()];
+
+        while rows.last().unwrap().windows(2).any(|f| f[0] != f[1]) {
+            let depth = rows.len();
+            let diffs = rows
+                .last()
+                .unwrap()
+                .windows(2)
+                .map(|f| f[1].checked_sub(f[0]).ok_or_else(|| self.overflow(depth)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            rows.push(diffs);
+        }
 
-        for index in 0..values.len() - 1 {
-            let current = values[index];
-            let next = values[index + 1];
-            let diff = next - current;
+        Ok(rows)
+    }
 
-            diffs_set.insert(diff);
-            diffs.push(diff);
+    // adds n columns to one side of the pyramid, given the value of each row on that side
+    // step combines a row's edge with the new edge of the row below it, which for the constant
+    // bottom row is 0
+    fn extend(
+        &self,
+        mut edges: Vec<i32>,
+        n: usize,
+        step: impl Fn(i32, i32) -> Option<i32>,
+    ) -> Result<Vec<i32>, Error> {
+        let mut result = Vec::with_capacity(n);
+
+        for _ in 0..n {
+            let mut below = 0;
+            for (depth, edge) in edges.iter_mut().enumerate().rev() {
+                *edge = step(*edge, below).ok_or_else(|| self.overflow(depth))?;
+                below = *edge;
+            }
+
+            result.push(edges[0]);
         }
 
-        let next_diff = if diffs_set.len() > 1 {
-            Self::get_next_value_internal(&diffs)
-        } else {
-            diffs.pop().unwrap()
-        };
+        Ok(result)
+    }
 
-        values.last().unwrap() + next_diff
+    // depth 0 is the sequence itself, 1 its differences and so on
+    fn overflow(&self, depth: usize) -> Error {
+        let message = format!("value out of range at depth {}", depth);
+        Error::parse(0, &message).with_hint("values have to fit in an i32")
     }
 }
 
 pub fn solve(input: &str) -> Result<Answer> {
-    let mut answer = Answer::default();
     let mut part1 = 0;
     let mut part2 = 0;
 
-    for line in input.lines() {
-        if line.is_empty() {
-            continue;
-        }
-        let sequence = Sequence::new(line);
-        let next = sequence.get_next_value();
-        part1 += next;
+    for (index, line) in input.lines().enumerate() {
+        add_sequence(index, line, &mut part1, &mut part2)?;
+    }
+
+    Ok(to_answer(part1, part2))
+}
+
+// sequences are independent, so they are extrapolated one line at a time
+#[cfg(feature = "std")]
+pub fn solve_reader<R: BufRead>(reader: R) -> Result<Answer> {
+    let mut part1 = 0;
+    let mut part2 = 0;
+
+    for (index, line) in reader.lines().enumerate() {
+        add_sequence(index, &line?, &mut part1, &mut part2)?;
+    }
 
-        let previous = sequence.get_previous_value();
-        part2 += previous;
+    Ok(to_answer(part1, part2))
+}
 
-        // dbg!(&line, &next);
+fn add_sequence(index: usize, line: &str, part1: &mut i32, part2: &mut i32) -> Result<(), Error> {
+    if line.is_empty() {
+        return Ok(());
     }
 
-    answer.part1 = Some(part1.to_string());
-    answer.part2 = Some(part2.to_string());
+    let on_line = |error: Error| error.on_line(index);
+    let sequence = line.parse::<Sequence>().map_err(on_line)?;
+    let sum_overflow = || Error::parse(index, "sum of the extrapolated values is out of range");
+
+    *part1 = part1
+        .checked_add(sequence.get_next_value().map_err(on_line)?)
+        .ok_or_else(sum_overflow)?;
+    *part2 = part2
+        .checked_add(sequence.get_previous_value().map_err(on_line)?)
+        .ok_or_else(sum_overflow)?;
+
+    Ok(())
+}
 
-    Ok(answer)
+fn to_answer(part1: i32, part2: i32) -> Answer {
+    Answer {
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
-    use color_eyre::eyre::Result;
     use tracing_test::traced_test;
 
-    use crate::day09::solve;
-    const TEST_INPUT: &str = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45";
+    use super::*;
 
     #[traced_test]
     #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part1, Some("114".to_string()));
+    fn test_overflow() {
+        let error = solve("0 3 6\n2147483647 -2147483648\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: value out of range at depth 1");
 
-        Ok(())
+        let error = solve("0 1073741824 2147483647\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 1: value out of range at depth 0");
     }
 
     #[traced_test]
     #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    fn test_extrapolate() {
+        let sequence = "10 13 16 21 30 45".parse::<Sequence>().unwrap();
+
+        assert_eq!(sequence.extrapolate(3).unwrap(), [68, 101, 146]);
+        assert_eq!(sequence.extrapolate_back(3).unwrap(), [5, -4, -19]);
+        assert!(sequence.extrapolate(0).unwrap().is_empty());
+
+        // a single value is its own constant row
+        let sequence = "7".parse::<Sequence>().unwrap();
+        assert_eq!(sequence.extrapolate(2).unwrap(), [7, 7]);
+
+        let sequence = "0 1073741823".parse::<Sequence>().unwrap();
+        assert_eq!(sequence.extrapolate(1).unwrap(), [2147483646]);
+        assert_eq!(
+            sequence.extrapolate(2).unwrap_err().to_string(),
+            "line 1: value out of range at depth 0"
+        );
+    }
 
-        assert_eq!(answer.part2, Some("2".to_string()));
+    fixture_test!(test_part1, super::solve, "day09", part1 = "114");
 
-        Ok(())
-    }
+    fixture_test!(test_part2, super::solve, "day09", part2 = "2");
 }
@@ -1,10 +1,20 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::{self, Display},
+};
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{bail, Result};
 
 use tracing::info;
 
-use crate::solver::Answer;
+use crate::{
+    solver::{Answer, SolveContext},
+    utils::{
+        charset, color, dump, explain,
+        grid::{Grid, GridCell},
+        polygon, Coordinate,
+    },
+};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 enum Direction {
@@ -23,6 +33,19 @@ impl Direction {
             Direction::Right => (1, 0),
         }
     }
+
+    // direction of a single step between neighbouring tiles
+    fn from_step(from: Coordinate<i32>, to: Coordinate<i32>) -> Direction {
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+        .into_iter()
+        .find(|f| f.value() == (to.x - from.x, to.y - from.y))
+        .unwrap()
+    }
     // determine whether we can travel to the next tile
     // returns tuples:
     // - can travel to next tile or not
@@ -78,24 +101,6 @@ impl Tile {
         matches!(self, Tile::Pipe(_))
     }
 
-    fn display(&self) -> &str {
-        match self {
-            Tile::Ground => "•",
-            Tile::StartingPoint => "S",
-            Tile::Pipe(c) => match c {
-                // feels a bit redundant
-                '|' => "┃",
-                '-' => "━",
-                'L' => "┗",
-                'J' => "┛",
-                '7' => "┓",
-                'F' => "┏",
-                unknown => unreachable!("got unknown char: {}", unknown),
-            },
-            Tile::Inside => "X",
-        }
-    }
-
     fn get_direction_pair(&self) -> [Direction; 2] {
         match self {
             Tile::Pipe(c) => {
@@ -233,6 +238,30 @@ impl Tile {
     }
 }
 
+impl Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Tile::Ground => charset::glyph("•", "."),
+            Tile::StartingPoint => "S",
+            Tile::Pipe(c) => match c {
+                // feels a bit redundant
+                '|' => charset::glyph("┃", "|"),
+                '-' => charset::glyph("━", "-"),
+                'L' => charset::glyph("┗", "L"),
+                'J' => charset::glyph("┛", "J"),
+                '7' => charset::glyph("┓", "7"),
+                'F' => charset::glyph("┏", "F"),
+                _ => Self::UNKNOWN,
+            },
+            Tile::Inside => "X",
+        };
+
+        write!(f, "{}", text)
+    }
+}
+
+impl GridCell for Tile {}
+
 #[derive(Debug, Clone)]
 struct Queue {
     coordinates: (i32, i32),
@@ -258,10 +287,17 @@ impl Queue {
     }
 }
 
+// the loop and the tiles it encloses, marked on top of the maze instead of in a second grid
+#[derive(Debug, Default)]
+struct Overlay {
+    fence: HashSet<(i32, i32)>,
+    inside: HashSet<(i32, i32)>,
+}
+
 #[derive(Debug)]
-struct Maze {
+pub struct Maze {
     map: Vec<Vec<Tile>>,
-    fence_map: Vec<Vec<Tile>>,
+    overlay: Overlay,
     starting_position: (i32, i32),
     starting_pipe_direction: Vec<Direction>,
     longest_starting_queue: Option<Queue>,
@@ -269,9 +305,8 @@ struct Maze {
 }
 
 impl Maze {
-    fn new(input: &str) -> Self {
+    pub fn new(input: &str) -> Self {
         let mut map = vec![];
-        let mut fence_map = vec![];
         let mut starting_position = (i32::MAX, i32::MAX);
 
         for line in input.lines() {
@@ -280,7 +315,6 @@ impl Maze {
             }
 
             let mut normal_line_vec = vec![];
-            let mut fence_line_vec = vec![];
             for c in line.chars() {
                 let kind = match c {
                     '.' => Tile::Ground,
@@ -288,11 +322,9 @@ impl Maze {
                     _ => Tile::Pipe(c),
                 };
                 normal_line_vec.push(kind);
-                fence_line_vec.push(Tile::Ground);
             }
 
             map.push(normal_line_vec);
-            fence_map.push(fence_line_vec);
         }
 
         // Reverse the Y-axis
@@ -313,7 +345,7 @@ impl Maze {
 
         Self {
             map,
-            fence_map,
+            overlay: Overlay::default(),
             starting_position,
             longest_starting_queue: None,
             starting_pipe_direction: vec![],
@@ -322,105 +354,216 @@ impl Maze {
     }
 
     fn display(&self, fence_view: bool) {
-        let mut text = "\n".to_string();
-        let mut map = match fence_view {
-            true => self.fence_map.clone(),
-            false => self.map.clone(),
-        };
+        info!("\n{}", self.render(fence_view));
+    }
 
-        assert!(!map.is_empty());
+    // the maze as a grid in reading order, rows from the top so that it prints like in the website
+    fn view<T>(&self, cell: impl Fn(Coordinate<i32>) -> T) -> Grid<T> {
+        assert!(!self.map.is_empty());
 
-        map.reverse(); // reverse back so that map prints like in the website
+        let rows = (0..self.map.len() as i32)
+            .rev()
+            .map(|y| {
+                (0..self.map[0].len() as i32)
+                    .map(|x| cell(Coordinate::new(x, y)))
+                    .collect()
+            })
+            .collect();
 
-        for line in map.iter() {
-            for c in line {
-                text.push_str(c.display());
-            }
-            text.push('\n');
-        }
+        Grid::from_rows(rows)
+    }
 
-        info!("{}", text);
+    fn render(&self, fence_view: bool) -> String {
+        self.view(|f| match fence_view {
+            true => self.get_fence_tile((f.x, f.y)).unwrap(),
+            false => self.get_tile((f.x, f.y)).unwrap(),
+        })
+        .to_string()
     }
 
-    fn get_tile(&self, coordinates: (i32, i32), fence_map: bool) -> Option<Tile> {
-        let map = if fence_map {
-            &self.fence_map
-        } else {
-            &self.map
-        };
+    // the maze with the loop colored by distance from the starting point, blue next to it and red
+    // at the far end, the farthest tile is a bold * and tiles off the loop keep their plain glyph
+    fn render_heatmap(&self, distances: &Grid<Option<i32>>) -> String {
+        let farthest = farthest_tile(distances);
+        let max = farthest.map_or(1, |(_, f)| f.max(1));
+
+        let mut text = self
+            .view(|f| (f, self.get_tile((f.x, f.y)).unwrap()))
+            .render(
+                |_, &(coordinate, tile)| match *distances.get(coordinate).unwrap() {
+                    Some(distance) if farthest.is_some_and(|(f, _)| f == coordinate) => {
+                        color::paint("*", format!("1;38;5;{}", heat(distance, max)))
+                    }
+                    Some(distance) => {
+                        color::paint(&tile.to_string(), format!("38;5;{}", heat(distance, max)))
+                    }
+                    None => tile.to_string(),
+                },
+            );
+
+        if let Some((_, distance)) = farthest {
+            text.push_str(&format!(
+                "* is the farthest tile, {} steps from S\n",
+                distance
+            ));
+        }
+
+        text
+    }
 
+    fn get_tile(&self, coordinates: (i32, i32)) -> Option<Tile> {
         let x = coordinates.0;
         let y = coordinates.1;
 
-        if x < 0 || y < 0 || x >= map[0].len() as i32 || y >= map.len() as i32 {
+        if x < 0 || y < 0 || x >= self.map[0].len() as i32 || y >= self.map.len() as i32 {
             return None;
         }
 
-        Some(map[y as usize][x as usize])
+        Some(self.map[y as usize][x as usize])
     }
 
-    fn max_distance(&mut self) -> i32 {
-        let mut walk_distance = i32::MIN;
-        let mut longest_starting_queue = None;
-        let mut turning_directions = None;
+    // the tile with only the loop and what it encloses, the starting point shows its actual pipe
+    fn get_fence_tile(&self, coordinates: (i32, i32)) -> Option<Tile> {
+        let tile = self.get_tile(coordinates)?;
 
-        for (coordinates_mod, possible_targets, direction) in [
-            (
-                (1, 0),
-                [Tile::Pipe('-'), Tile::Pipe('7'), Tile::Pipe('J')],
-                Direction::Right,
-            ), // from starting point to right
-            (
-                (-1, 0),
-                [Tile::Pipe('-'), Tile::Pipe('L'), Tile::Pipe('F')],
-                Direction::Left,
-            ), // from starting point to left
-            (
-                (0, 1),
-                [Tile::Pipe('|'), Tile::Pipe('7'), Tile::Pipe('F')],
+        if self.overlay.inside.contains(&coordinates) {
+            return Some(Tile::Inside);
+        }
+
+        if !self.overlay.fence.contains(&coordinates) {
+            return Some(Tile::Ground);
+        }
+
+        match (tile, &self.starting_pipe_direction[..]) {
+            (Tile::StartingPoint, [first, last]) => Some(Tile::from_vec([first, last])),
+            _ => Some(tile),
+        }
+    }
+
+    // tiles connected to the given tile, a connection requires both pipes to point at each other
+    fn get_connected_coordinates(&self, coordinates: (i32, i32)) -> Vec<(i32, i32)> {
+        let directions = match self.get_tile(coordinates) {
+            Some(Tile::StartingPoint) => vec![
                 Direction::Up,
-            ), // from starting point to up
-            (
-                (0, -1),
-                [Tile::Pipe('|'), Tile::Pipe('L'), Tile::Pipe('J')],
                 Direction::Down,
-            ), // from starting point to down
-        ] {
-            if let Some(next) = self.get_tile(
-                (
-                    self.starting_position.0 + coordinates_mod.0,
-                    self.starting_position.1 + coordinates_mod.1,
-                ),
-                false,
-            ) {
-                if possible_targets.contains(&next) {
-                    self.starting_pipe_direction.push(direction);
-                    let initial_queue = Queue::new(self.starting_position, direction, 0);
-
-                    let (next_walk_distance, local_turning_directions) =
-                        self.walk(initial_queue.clone(), false, None);
-
-                    if next_walk_distance > walk_distance {
-                        walk_distance = next_walk_distance;
-                        longest_starting_queue = Some(initial_queue.clone());
-
-                        if !local_turning_directions.is_empty() {
-                            turning_directions = local_turning_directions
-                                .iter()
-                                .max_by(|a, b| a.1.cmp(b.1))
-                                .map(|(key, _)| *key);
-                        }
+                Direction::Left,
+                Direction::Right,
+            ],
+            Some(tile @ Tile::Pipe(_)) => tile.get_direction_pair().to_vec(),
+            _ => vec![],
+        };
+
+        directions
+            .into_iter()
+            .filter_map(|direction| {
+                let (x, y) = direction.value();
+                let next_coordinates = (coordinates.0 + x, coordinates.1 + y);
+
+                match self.get_tile(next_coordinates)? {
+                    Tile::StartingPoint => Some(next_coordinates),
+                    tile @ Tile::Pipe(_)
+                        if tile
+                            .get_direction_pair()
+                            .contains(&direction.get_inverted()) =>
+                    {
+                        Some(next_coordinates)
                     }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    // BFS from the starting point along the main loop, the path of loop_path, other tiles have no
+    // distance, so pipes that merely touch the starting point or form loops of their own are skipped
+    pub fn distance_map(&self, path: &[Coordinate<i32>]) -> Grid<Option<i32>> {
+        let main_loop = path.iter().collect::<HashSet<_>>();
+        let mut distances = Grid::new(self.map[0].len(), self.map.len(), None);
+        let mut queue = VecDeque::from([(self.starting_position, 0)]);
+
+        while let Some((coordinates, distance)) = queue.pop_front() {
+            let coordinate = Coordinate::new(coordinates.0, coordinates.1);
+
+            if !main_loop.contains(&coordinate) || distances.get(coordinate).unwrap().is_some() {
+                continue;
+            }
+
+            distances.set(coordinate, Some(distance));
+
+            for next_coordinates in self.get_connected_coordinates(coordinates) {
+                queue.push_back((next_coordinates, distance + 1));
+            }
+        }
+
+        distances
+    }
+
+    // tiles of the main loop in walking order, starting with the starting point
+    // an error when no loop passes through S, e.g. only decoy loops or a dead end next to it
+    pub fn loop_path(&self) -> Result<Vec<Coordinate<i32>>> {
+        for first in self.get_connected_coordinates(self.starting_position) {
+            let mut path = vec![self.starting_position];
+            let mut previous = self.starting_position;
+            let mut current = first;
+
+            while current != self.starting_position {
+                path.push(current);
+
+                let next = self
+                    .get_connected_coordinates(current)
+                    .into_iter()
+                    .find(|&f| f != previous);
+
+                match next {
+                    Some(next) => (previous, current) = (current, next),
+                    // dead end, this neighbour is not part of the loop
+                    None => break,
                 }
             }
+
+            if current == self.starting_position {
+                return Ok(path
+                    .into_iter()
+                    .map(|(x, y)| Coordinate::new(x, y))
+                    .collect());
+            }
         }
 
-        self.longest_starting_queue = longest_starting_queue;
+        bail!("no loop passes through S")
+    }
+
+    // tiles enclosed by the loop of loop_path, using the shoelace formula and pick's theorem
+    pub fn enclosed_tiles(path: &[Coordinate<i32>]) -> i64 {
+        let vertices = path
+            .iter()
+            .map(|f| Coordinate::new(f.x as i64, f.y as i64))
+            .collect::<Vec<_>>();
+        let area = polygon::shoelace(&vertices).abs() / 2;
+
+        // pick's theorem: area = interior + boundary / 2 - 1
+        area - vertices.len() as i64 / 2 + 1
+    }
+
+    // find which starting direction walks the main loop and which way the loop turns
+    // the loop is traced from the starting point, so pipes outside of it never get walked
+    fn find_loop(&mut self, path: &[Coordinate<i32>]) {
+        assert!(path.len() > 2, "no loop through the starting point");
 
-        assert!(turning_directions.is_some());
-        self.turning_directions = turning_directions;
+        let start = path[0];
+        let first_direction = Direction::from_step(start, path[1]);
+        let last_direction = Direction::from_step(start, *path.last().unwrap());
+        self.starting_pipe_direction = vec![first_direction, last_direction];
 
-        num::Integer::div_ceil(&walk_distance, &2)
+        let initial_queue = Queue::new(self.starting_position, first_direction, 0);
+        let (_, turning_directions) = self.walk(initial_queue.clone(), false, None);
+
+        self.longest_starting_queue = Some(initial_queue);
+        self.turning_directions = turning_directions
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1))
+            .map(|(key, _)| *key);
+
+        assert!(self.turning_directions.is_some());
     }
 
     fn walk(
@@ -440,15 +583,13 @@ impl Maze {
 
         while let Some(queue) = queues.pop() {
             let current_coordinates = queue.coordinates;
-            let current_tile = self.get_tile(current_coordinates, false);
+            let current_tile = self.get_tile(current_coordinates);
             if mark_fence {
-                let tile = self.get_tile(current_coordinates, false).unwrap();
-                self.fence_map[current_coordinates.1 as usize][current_coordinates.0 as usize] =
-                    tile;
+                self.overlay.fence.insert(current_coordinates);
             }
 
             let next_coordinates = queue.get_next_coordinate();
-            let next_tile = self.get_tile(next_coordinates, false);
+            let next_tile = self.get_tile(next_coordinates);
 
             #[allow(clippy::unnecessary_unwrap)]
             if floodfill_side.is_some() && current_tile.is_some_and(|f| f.is_pipe()) {
@@ -460,10 +601,10 @@ impl Maze {
                 let mut stacks = vec![(queue.coordinates.0 + x, queue.coordinates.1 + y)];
 
                 while let Some(coordinates) = stacks.pop() {
-                    let current_tile = self.get_tile(coordinates, true);
-                    if current_tile == Some(Tile::Ground) {
-                        self.fence_map[coordinates.1 as usize][coordinates.0 as usize] =
-                            Tile::Inside;
+                    if self.get_tile(coordinates).is_some()
+                        && !self.overlay.fence.contains(&coordinates)
+                        && self.overlay.inside.insert(coordinates)
+                    {
                         // this should be current coordinates +(1,0).... instead of (1,0)
                         for c in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
                             stacks.push((coordinates.0 + c.0, coordinates.1 + c.1))
@@ -514,7 +655,6 @@ impl Maze {
 
     fn fill_fence_map(&mut self) -> i32 {
         assert!(self.longest_starting_queue.is_some());
-        let mut inside_count = 0;
 
         // mark fence first
         self.walk(
@@ -522,6 +662,7 @@ impl Maze {
             true,
             None,
         );
+        dump::frame("day10-fence", || self.render(true));
 
         // and then floodfill
         self.walk(
@@ -529,130 +670,204 @@ impl Maze {
             false,
             self.turning_directions,
         );
+        dump::frame("day10-floodfill", || self.render(true));
+
+        self.overlay.inside.len() as i32
+    }
+}
 
-        // replace starting point with actual pipe in fence map
-        assert_eq!(self.starting_pipe_direction.len(), 2);
-        self.fence_map[self.starting_position.1 as usize][self.starting_position.0 as usize] =
-            Tile::from_vec([
-                self.starting_pipe_direction.first().unwrap(),
-                self.starting_pipe_direction.last().unwrap(),
-            ]);
+// the tile with the longest distance from the starting point, along with that distance
+pub fn farthest_tile(distances: &Grid<Option<i32>>) -> Option<(Coordinate<i32>, i32)> {
+    let mut farthest: Option<(Coordinate<i32>, i32)> = None;
 
-        for y_row in &self.fence_map {
-            inside_count += y_row.iter().filter(|&x| x == &Tile::Inside).count() as i32;
-        }
+    for y in 0..distances.height() as i32 {
+        for x in 0..distances.width() as i32 {
+            let coordinate = Coordinate::new(x, y);
 
-        inside_count
+            if let Some(distance) = *distances.get(coordinate).unwrap() {
+                if farthest.is_none_or(|(_, max)| distance > max) {
+                    farthest = Some((coordinate, distance));
+                }
+            }
+        }
     }
+
+    farthest
 }
 
+// colors of the 256 color palette, from blue for the start through green and yellow to red
+const HEAT: [u8; 16] = [
+    21, 27, 33, 39, 45, 51, 49, 47, 46, 82, 118, 154, 190, 226, 214, 196,
+];
+
+fn heat(distance: i32, max: i32) -> u8 {
+    HEAT[distance as usize * (HEAT.len() - 1) / max as usize]
+}
+
+// tiles of the main loop in walking order, deposited in the SolveContext
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopPath(pub Vec<Coordinate<i32>>);
+
 pub fn solve(input: &str) -> Result<Answer> {
+    solve_with_context(input, &mut SolveContext::new())
+}
+
+pub fn solve_with_context(input: &str, context: &mut SolveContext) -> Result<Answer> {
     let mut answer = Answer::default();
 
     let mut maze = Maze::new(input);
-    maze.display(false);
-    let part1 = maze.max_distance();
+    dump::frame("day10-maze", || maze.render(false));
+    let path = maze.loop_path()?;
+    let distances = maze.distance_map(&path);
+    // S is on the loop, so there is a farthest tile
+    let (farthest_coordinate, part1) = farthest_tile(&distances).unwrap();
+    info!("Farthest tile: {:?}", farthest_coordinate);
+
+    if explain::is_enabled() || dump::is_enabled() {
+        let heatmap = maze.render_heatmap(&distances);
+        explain::line(|| format!("\n{}", heatmap));
+        dump::frame("day10-heatmap", || heatmap);
+    }
+
+    maze.find_loop(&path);
     let part2 = maze.fill_fence_map();
     maze.display(true);
+    context.insert(LoopPath(path));
 
     answer.part1 = Some(part1.to_string());
     answer.part2 = Some(part2.to_string());
     Ok(answer)
 }
 
+// same answers as solve, from the loop length and its area instead of walking and flood filling
+pub fn solve_shoelace(input: &str) -> Result<Answer> {
+    let path = Maze::new(input).loop_path()?;
+
+    let part1 = path.len() / 2;
+    let part2 = Maze::enclosed_tiles(&path);
+
+    Ok(Answer {
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use color_eyre::eyre::Result;
     use tracing_test::traced_test;
 
-    use crate::day10::solve;
+    use crate::test_support::fixture;
+    use crate::{
+        day10::{farthest_tile, solve, solve_shoelace, Maze},
+        utils::{
+            charset::{self, Charset},
+            color, Coordinate,
+        },
+    };
 
     #[traced_test]
     #[test]
-    fn test_part1_1() -> Result<()> {
-        let input = "7-F7-
-.FJ|7
-SJLL7
-|F--J
-LJ.LJ";
-        let answer = solve(input)?;
+    fn test_distance_map() {
+        let maze = Maze::new(&fixture("day10-part1-1"));
+        let distances = maze.distance_map(&maze.loop_path().unwrap());
 
-        assert_eq!(answer.part1, Some("8".to_string()));
+        // y is counted from the bottom row
+        assert_eq!(distances.get(Coordinate::new(0, 2)), Some(&Some(0)));
+        assert_eq!(distances.get(Coordinate::new(0, 1)), Some(&Some(1)));
+        assert_eq!(distances.get(Coordinate::new(1, 2)), Some(&Some(1)));
+        assert_eq!(distances.get(Coordinate::new(0, 4)), Some(&None));
 
-        Ok(())
+        assert_eq!(farthest_tile(&distances), Some((Coordinate::new(4, 2), 8)));
     }
 
     #[traced_test]
     #[test]
-    fn test_part1_2() -> Result<()> {
-        let input = ".....
-.....
-F---7
-|---|
-S---J";
-        let answer = solve(input)?;
+    fn test_heatmap() {
+        let maze = Maze::new(&fixture("day10-part1-1"));
+        let distances = maze.distance_map(&maze.loop_path().unwrap());
+        let view = color::scoped(true, || maze.render_heatmap(&distances));
+
+        // every tile of the 16 long loop is colored, S with the first color and * with the last
+        assert_eq!(view.matches("\x1b[0m").count(), 16);
+        assert!(view.contains("\x1b[38;5;21mS\x1b[0m"));
+        assert!(view.contains("\x1b[1;38;5;196m*\x1b[0m"));
+        assert!(view.ends_with("* is the farthest tile, 8 steps from S\n"));
+
+        // without the colors it is the maze with the farthest tile replaced
+        let plain = color::scoped(false, || maze.render_heatmap(&distances));
+        let expected = maze.render(false);
+        assert_eq!(plain.lines().nth(2), Some("S┛┗┗*"));
+        assert_eq!(expected.lines().nth(2), Some("S┛┗┗┓"));
+        assert_eq!(plain.lines().count(), expected.lines().count() + 1);
+    }
 
-        assert_eq!(answer.part1, Some("6".to_string()));
+    fixture_test!(test_part1_1, super::solve, "day10-part1-1", part1 = "8");
 
-        Ok(())
-    }
+    fixture_test!(test_part1_2, super::solve, "day10-part1-2", part1 = "6");
+
+    fixture_test!(test_part2_1, super::solve, "day10-part2-1", part2 = "4");
+
+    fixture_test!(test_part2_2, super::solve, "day10-part2-2", part2 = "8");
+
+    fixture_test!(test_part2_3, super::solve, "day10-part2-3", part2 = "10");
 
     #[traced_test]
     #[test]
-    fn test_part2_1() -> Result<()> {
-        let input = "...........
-.S-------7.
-.|F-----7|.
-.||.....||.
-.||.....||.
-.|L-7.F-J|.
-.|..|.|..|.
-.L--J.L--J.
-...........";
-        let answer = solve(input)?;
-
-        assert_eq!(answer.part2, Some("4".to_string()));
+    fn test_shoelace() -> Result<()> {
+        let input = fixture("day10-part2-3");
+
+        assert_eq!(solve_shoelace(&input)?, solve(&input)?);
+
+        assert_answers!(solve_shoelace, &fixture("day10-part1-1"), part1 = "8");
 
         Ok(())
     }
 
     #[traced_test]
     #[test]
-    fn test_part2_2() -> Result<()> {
-        let input = ".F----7F7F7F7F-7....
-.|F--7||||||||FJ....
-.||.FJ||||||||L7....
-FJL7L7LJLJ||LJ.L-7..
-L--J.L7...LJS7F-7L7.
-....F-J..F7FJ|L7L7L7
-....L7.F7||L7|.L7L7|
-.....|FJLJ|FJ|F7|.LJ
-....FJL-7.||.||||...
-....L---J.LJ.LJLJ...";
-        let answer = solve(input)?;
-
-        assert_eq!(answer.part2, Some("8".to_string()));
+    fn test_decoy_loops() -> Result<()> {
+        // a pipe dead ending into S on the left, a separate loop top right and one inside the main loop
+        let input = ".....F-7\n-S--7|.|\n.|F7|L-J\n.|LJ|...\n.L--J...\n";
+
+        let maze = Maze::new(input);
+        let path = maze.loop_path()?;
+        assert_eq!(path.len(), 12);
+        assert_eq!(
+            maze.distance_map(&path).get(Coordinate::new(0, 3)),
+            Some(&None)
+        );
+
+        assert_answers!(solve, input, part1 = "6", part2 = "4");
+        assert_answers!(solve_shoelace, input, part1 = "6", part2 = "4");
+
+        // only the decoys are loops, the pipes next to S dead end
+        let input = ".....F-7\n-S--7|.|\n.|F7|L-J\n.|LJ....\n.L--J...\n";
+        for solve in [solve, solve_shoelace] {
+            let error = solve(input).unwrap_err();
+            assert_eq!(error.to_string(), "no loop passes through S");
+        }
 
         Ok(())
     }
 
     #[traced_test]
     #[test]
-    fn test_part2_3() -> Result<()> {
-        let input = "FF7FSF7F7F7F7F7F---7
-L|LJ||||||||||||F--J
-FL-7LJLJ||||||LJL-77
-F--JF--7||LJLJ7F7FJ-
-L---JF-JLJ.||-FJLJJ7
-|F|F-JF---7F7-L7L|7|
-|FFJF7L7F-JF7|JL---7
-7-L-JL7||F7|L7F-7F7|
-L.L7LFJ|||||FJL7||LJ
-L7JLJL-JLJLJL--JLJ.L";
-        let answer = solve(input)?;
-
-        assert_eq!(answer.part2, Some("10".to_string()));
-
-        Ok(())
+    fn test_fence_view() {
+        let mut maze = Maze::new(&fixture("day10-part2-1"));
+        maze.find_loop(&maze.loop_path().unwrap());
+
+        assert_eq!(maze.fill_fence_map(), 4);
+
+        // only the loop and the tiles it encloses, with the starting point drawn as its pipe
+        let view = maze.render(true);
+        let lines = view.lines().collect::<Vec<_>>();
+        assert_eq!(lines[1], "•┏━━━━━━━┓•");
+        assert_eq!(lines[6], "•┃XX┃•┃XX┃•");
+        assert_eq!(maze.render(false).lines().nth(1), Some("•S━━━━━━━┓•"));
+
+        let view = charset::scoped(Charset::Ascii, || maze.render(true));
+        assert_eq!(view.lines().nth(6), Some(".|XX|.|XX|."));
+        assert!(view.is_ascii());
     }
 }
@@ -1,9 +1,17 @@
-use std::collections::{HashMap, HashSet};
-
-use crate::solver::Answer;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+};
+
+use crate::{
+    solver::Answer,
+    utils::{
+        charset,
+        grid::{Grid, GridCell},
+    },
+};
 
 use color_eyre::eyre::Result;
-use tracing::info;
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 enum SpaceItem {
@@ -19,15 +27,21 @@ impl SpaceItem {
             _ => unreachable!(),
         }
     }
+}
 
-    fn display(&self) -> &str {
-        match self {
+impl Display for SpaceItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
             SpaceItem::Galaxy => "#",
-            SpaceItem::Empty => "·",
-        }
+            SpaceItem::Empty => charset::glyph("·", "."),
+        };
+
+        write!(f, "{}", text)
     }
 }
 
+impl GridCell for SpaceItem {}
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
 struct Coordinate {
     x: i64,
@@ -36,9 +50,9 @@ struct Coordinate {
 
 #[derive(Debug)]
 struct SpaceObjects {
-    y: HashSet<i64>,
-    x: HashSet<i64>,
-    coordinates: HashMap<i64, Coordinate>,
+    y: BTreeSet<i64>,
+    x: BTreeSet<i64>,
+    coordinates: BTreeMap<i64, Coordinate>,
 }
 
 #[derive(Debug)]
@@ -51,9 +65,9 @@ impl Image {
     fn new(input: &str) -> Self {
         let mut map = vec![];
         let mut space_objects = SpaceObjects {
-            y: HashSet::new(),
-            x: HashSet::new(),
-            coordinates: HashMap::new(),
+            y: BTreeSet::new(),
+            x: BTreeSet::new(),
+            coordinates: BTreeMap::new(),
         };
         let mut lines = input.lines().collect::<Vec<&str>>();
         lines.reverse();
@@ -87,26 +101,17 @@ impl Image {
     }
 
     fn display(&self) {
-        let mut text = "\n".to_string();
         let mut map = self.map.clone();
         assert!(!map.is_empty());
 
         map.reverse(); // reverse back so that map prints like in the website
 
-        for line in map.iter() {
-            for c in line {
-                text.push_str(c.display());
-            }
-            text.push('\n');
-        }
-
-        info!("{}", text);
+        Grid::from_rows(map).display();
     }
 
     fn solve(&self, expansion_factor: i64) -> i64 {
         let mut distance = 0;
-        let mut iterator = self.space_objects.coordinates.keys().collect::<Vec<&i64>>();
-        iterator.sort();
+        let iterator = self.space_objects.coordinates.keys().collect::<Vec<&i64>>();
 
         for left_index in &iterator {
             for right_index in &iterator {
@@ -151,7 +156,7 @@ impl Image {
         &self,
         start: i64,
         end: i64,
-        set: &HashSet<i64>,
+        set: &BTreeSet<i64>,
         expansion_factor: i64,
     ) -> i64 {
         assert!(expansion_factor > 1);
@@ -186,27 +191,16 @@ pub fn solve(input: &str) -> Result<Answer> {
 
 #[cfg(test)]
 mod tests {
-
     use tracing::info;
     use tracing_test::traced_test;
 
     use crate::day11::{Coordinate, Image};
-
-    const TEST_INPUT: &str = "...#......
-.......#..
-#.........
-..........
-......#...
-.#........
-.........#
-..........
-.......#..
-#...#.....";
+    use crate::test_support::fixture;
 
     #[traced_test]
     #[test]
     fn test_image_get_distance() {
-        let image = Image::new(TEST_INPUT);
+        let image = Image::new(&fixture("day11"));
         image.display();
         let items = Vec::from([
             (Coordinate { x: 1, y: 4 }, Coordinate { x: 4, y: 0 }, 9), // 5 to 9
@@ -235,7 +229,7 @@ mod tests {
     #[traced_test]
     #[test]
     fn test_part1() {
-        let image = Image::new(TEST_INPUT);
+        let image = Image::new(&fixture("day11"));
         image.display();
 
         let distance = image.solve(2);
@@ -245,7 +239,7 @@ mod tests {
     #[traced_test]
     #[test]
     fn test_part2() {
-        let image = Image::new(TEST_INPUT);
+        let image = Image::new(&fixture("day11"));
         image.display();
 
         let distance = image.solve(10);
@@ -1,11 +1,12 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{self, Display},
+};
 
-use crate::solver::Answer;
+use crate::{solver::Answer, utils::grid::GridCell};
 
 use color_eyre::eyre::Result;
 
-use tracing::info;
-
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 enum Condition {
     Good,
@@ -13,17 +14,6 @@ enum Condition {
     Unknown,
 }
 
-trait DisplayVecCondition {
-    fn display(&self);
-}
-
-impl DisplayVecCondition for Vec<Condition> {
-    fn display(&self) {
-        let text = self.iter().map(|f| f.display()).collect::<String>();
-        info!("{}", text);
-    }
-}
-
 impl Condition {
     fn new(c: &char) -> Self {
         match c {
@@ -34,19 +24,25 @@ impl Condition {
         }
     }
 
-    fn display(&self) -> &str {
-        match self {
+    fn from_line(line: &str) -> VecDeque<Self> {
+        line.chars().map(|f| Self::new(&f)).collect()
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
             Condition::Good => ".",
             Condition::Bad => "#",
             Condition::Unknown => "?",
-        }
-    }
+        };
 
-    fn from_line(line: &str) -> VecDeque<Self> {
-        line.chars().map(|f| Self::new(&f)).collect()
+        write!(f, "{}", text)
     }
 }
 
+impl GridCell for Condition {}
+
 #[derive(Debug)]
 struct Spring {
     raw: VecDeque<Condition>,
@@ -58,14 +54,12 @@ impl Spring {
         let vec = input.split_whitespace().collect::<Vec<&str>>();
         assert_eq!(vec.len(), 2);
 
-        let value = std::iter::repeat(*vec.first().unwrap())
-            .take(multiplier)
+        let value = std::iter::repeat_n(*vec.first().unwrap(), multiplier)
             .collect::<Vec<_>>()
             .join("?");
         let raw = Condition::from_line(&value);
 
-        let valid_state = std::iter::repeat(*vec.last().unwrap())
-            .take(multiplier)
+        let valid_state = std::iter::repeat_n(*vec.last().unwrap(), multiplier)
             .collect::<Vec<_>>()
             .join(",")
             .split(',')
@@ -151,6 +145,98 @@ impl Spring {
 
         inner(&self.raw, &self.valid_state, &mut HashMap::new())
     }
+
+    // tries every assignment of the unknowns, only usable on part 1 sized rows but handy to verify
+    // valid_count, with prune a branch is dropped as soon as its prefix cannot match the groups
+    fn enumerate_count(&self, prune: bool) -> i64 {
+        struct Walk<'a> {
+            row: &'a [Condition],
+            groups: &'a [i64],
+            prune: bool,
+            // springs from an index on that are or may be damaged, and that surely are
+            may_be_bad: Vec<i64>,
+            must_be_bad: Vec<i64>,
+        }
+
+        impl Walk<'_> {
+            // formed holds the groups closed so far, run the damaged springs right before index
+            fn count(&self, index: usize, formed: &mut Vec<i64>, run: i64) -> i64 {
+                if self.prune && !self.feasible(index, formed, run) {
+                    return 0;
+                }
+
+                if index == self.row.len() {
+                    let matches = match run {
+                        0 => formed[..] == self.groups[..],
+                        run => {
+                            formed.len() + 1 == self.groups.len()
+                                && formed[..] == self.groups[..formed.len()]
+                                && self.groups[formed.len()] == run
+                        }
+                    };
+
+                    return matches as i64;
+                }
+
+                let mut total = 0;
+
+                if self.row[index] != Condition::Bad {
+                    if run > 0 {
+                        formed.push(run);
+                    }
+                    total += self.count(index + 1, formed, 0);
+                    if run > 0 {
+                        formed.pop();
+                    }
+                }
+
+                if self.row[index] != Condition::Good {
+                    total += self.count(index + 1, formed, run + 1);
+                }
+
+                total
+            }
+
+            fn feasible(&self, index: usize, formed: &[i64], run: i64) -> bool {
+                let done = formed.len();
+
+                // the last group closed has to be the one the list asks for
+                if done > self.groups.len() || formed.last() != self.groups[..done].last() {
+                    return false;
+                }
+
+                // the open group may not outgrow its size
+                if run > 0 && self.groups.get(done).is_none_or(|f| run > *f) {
+                    return false;
+                }
+
+                // the damaged springs still required have to fit in what is left, and what surely
+                // is damaged may not exceed them
+                let required = self.groups[done..].iter().sum::<i64>() - run;
+                required <= self.may_be_bad[index] && self.must_be_bad[index] <= required
+            }
+        }
+
+        let row = self.raw.iter().copied().collect::<Vec<_>>();
+        let groups = self.valid_state.iter().copied().collect::<Vec<_>>();
+        let suffix = |keep: fn(&Condition) -> bool| {
+            let mut counts = vec![0; row.len() + 1];
+            for index in (0..row.len()).rev() {
+                counts[index] = counts[index + 1] + keep(&row[index]) as i64;
+            }
+            counts
+        };
+
+        let walk = Walk {
+            row: &row,
+            groups: &groups,
+            prune,
+            may_be_bad: suffix(|f| *f != Condition::Good),
+            must_be_bad: suffix(|f| *f == Condition::Bad),
+        };
+
+        walk.count(0, &mut vec![], 0)
+    }
 }
 
 pub fn solve(input: &str) -> Result<Answer> {
@@ -176,27 +262,36 @@ pub fn solve(input: &str) -> Result<Answer> {
     Ok(answer)
 }
 
+// part 1 by walking the assignments with pruning, to check valid_count against
+// the unfolded rows of part 2 are out of reach of any enumeration, so part 2 is counted as usual
+pub fn solve_enumerate(input: &str) -> Result<Answer> {
+    let mut part1 = 0;
+    let mut part2 = 0;
+
+    for line in input.lines().filter(|f| !f.is_empty()) {
+        part1 += Spring::new(line, 1).enumerate_count(true);
+        part2 += Spring::new(line, 5).valid_count();
+    }
+
+    Ok(Answer {
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use color_eyre::eyre::Result;
+    use proptest::prelude::*;
     use tracing_test::traced_test;
 
-    use crate::day12::solve;
-
     use super::Spring;
-
-    const TEST_INPUT: &str = "???.### 1,1,3
-.??..??...?##. 1,1,3
-?#?#?#?#?#?#?#? 1,3,1,6
-????.#...#... 4,1,1
-????.######..#####. 1,6,5
-?###???????? 3,2,1";
+    use crate::test_support::fixture;
 
     #[traced_test]
     #[test]
     fn test_valid_count() {
         let mut stacks = vec![];
-        for line in TEST_INPUT.lines() {
+        for line in fixture("day12").lines() {
             if line.is_empty() {
                 continue;
             }
@@ -211,19 +306,45 @@ mod tests {
 
     #[traced_test]
     #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    fn test_enumerate_count() {
+        for line in fixture("day12").lines().filter(|f| !f.is_empty()) {
+            let spring = Spring::new(line, 1);
+            let expected = spring.valid_count();
 
-        assert_eq!(answer.part1, Some("21".to_string()));
-        Ok(())
-    }
+            assert_eq!(spring.enumerate_count(false), expected, "{}", line);
+            assert_eq!(spring.enumerate_count(true), expected, "{}", line);
+        }
 
-    #[traced_test]
-    #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+        // 2^40 assignments without pruning, the pruned walk only follows the ones that fit
+        let spring = Spring::new("???????????????????????????????????????? 20,19", 1);
+        assert_eq!(spring.enumerate_count(true), spring.valid_count());
+        assert_eq!(spring.enumerate_count(true), 1);
+    }
 
-        assert_eq!(answer.part2, Some("525152".to_string()));
-        Ok(())
+    proptest! {
+        #[test]
+        fn test_enumerate_matches_valid_count(
+            row in "[.#?]{1,14}",
+            groups in prop::collection::vec(1..5i64, 1..5),
+        ) {
+            let groups = groups.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+            let spring = Spring::new(&format!("{} {}", row, groups.join(",")), 1);
+            let expected = spring.valid_count();
+
+            prop_assert_eq!(spring.enumerate_count(false), expected);
+            prop_assert_eq!(spring.enumerate_count(true), expected);
+        }
     }
+
+    fixture_test!(test_part1, super::solve, "day12", part1 = "21");
+
+    fixture_test!(test_part2, super::solve, "day12", part2 = "525152");
+
+    fixture_test!(
+        test_enumerate,
+        super::solve_enumerate,
+        "day12",
+        part1 = "21",
+        part2 = "525152"
+    );
 }
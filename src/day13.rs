@@ -1,9 +1,12 @@
 use crate::{
     solver::Answer,
-    utils::{get_column, get_row},
+    utils::{blocks, get_column, get_row},
 };
 
+use std::fmt;
+
 use color_eyre::eyre::Result;
+use rayon::prelude::*;
 use tracing::info;
 
 #[derive(Debug, Clone)]
@@ -12,9 +15,9 @@ struct Pattern {
 }
 
 impl Pattern {
-    fn new(input: &str) -> Self {
+    fn new(lines: &[&str]) -> Self {
         let mut map = vec![];
-        for line in input.lines() {
+        for line in lines {
             map.push(line.chars().collect::<Vec<_>>());
         }
 
@@ -104,60 +107,134 @@ impl Pattern {
     }
 
     fn get_reflection_value(&self, smudge: bool) -> i32 {
+        self.reflection(smudge).value()
+    }
+
+    fn reflection(&self, smudge: bool) -> Reflection {
         let max_column = self.map[0].len();
         let max_row = self.map.len();
 
         let column = Self::check_reflection(&self.map, max_column, get_column, smudge);
 
         if let Some(value) = column {
-            value + 1
+            Reflection::Column(value as usize + 1)
         } else {
             let row = Self::check_reflection(&self.map, max_row, get_row, smudge);
-            (row.unwrap() + 1) * 100
+            Reflection::Row(row.unwrap() as usize + 1)
         }
     }
 
-    fn display(&self) {
-        let mut text = "\n".to_string();
+    // the cell that differs from its mirror image, on the top or left side of the axis
+    // None when the reflection is exact, like it is for part 1
+    fn smudge(&self, reflection: Reflection) -> Option<(usize, usize)> {
+        let (height, width) = (self.map.len(), self.map[0].len());
+
+        match reflection {
+            Reflection::Column(n) => (0..n.min(width - n)).find_map(|k| {
+                let (left, right) = (n - 1 - k, n + k);
+                (0..height)
+                    .find(|&y| self.map[y][left] != self.map[y][right])
+                    .map(|y| (y, left))
+            }),
+            Reflection::Row(n) => (0..n.min(height - n)).find_map(|k| {
+                let (top, bottom) = (n - 1 - k, n + k);
+                (0..width)
+                    .find(|&x| self.map[top][x] != self.map[bottom][x])
+                    .map(|x| (top, x))
+            }),
+        }
+    }
 
-        for y_row in &self.map {
-            text.push_str(&y_row.iter().collect::<String>());
+    // the pattern drawn the way the puzzle does, >< above and below a vertical axis and v ^ left
+    // of a horizontal one, the smudge is replaced by *
+    fn render(&self, smudge: bool) -> String {
+        let reflection = self.reflection(smudge);
+        let cell = self.smudge(reflection).filter(|_| smudge);
+
+        let marker = match reflection {
+            Reflection::Column(n) => format!(" {}><\n", " ".repeat(n - 1)),
+            Reflection::Row(_) => String::new(),
+        };
+
+        let mut text = marker.clone();
+        for (y, row) in self.map.iter().enumerate() {
+            text.push(match reflection {
+                Reflection::Row(n) if y + 1 == n => 'v',
+                Reflection::Row(n) if y == n => '^',
+                _ => ' ',
+            });
+            for (x, c) in row.iter().enumerate() {
+                text.push(if cell == Some((y, x)) { '*' } else { *c });
+            }
             text.push('\n');
         }
+        text.push_str(&marker);
+
+        if let Some((y, x)) = cell {
+            text.push_str(&format!(
+                "smudge at row {}, column {}, was {:?}\n",
+                y + 1,
+                x + 1,
+                self.map[y][x]
+            ));
+        }
 
-        info!("{}", text);
+        text
     }
-}
 
-pub fn solve(input: &str) -> Result<Answer> {
-    let mut part1 = 0;
-    let mut part2 = 0;
-    let mut answer = Answer::default();
-    let mut stacks = vec![];
+    fn display(&self) {
+        info!(
+            "\npart 1, {}\n{}part 2, {}\n{}",
+            self.reflection(false),
+            self.render(false),
+            self.reflection(true),
+            self.render(true)
+        );
+    }
+}
 
-    fn create_pattern(stacks: &mut Vec<&str>) -> (i32, i32) {
-        let pattern = Pattern::new(&stacks.join("\n"));
-        pattern.display();
-        let p1 = pattern.get_reflection_value(false);
-        let p2 = pattern.get_reflection_value(true);
+// where the mirror is, with how many columns are left of it or rows above it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reflection {
+    Column(usize),
+    Row(usize),
+}
 
-        stacks.clear();
-        (p1, p2)
+impl Reflection {
+    fn value(&self) -> i32 {
+        match self {
+            Reflection::Column(n) => *n as i32,
+            Reflection::Row(n) => *n as i32 * 100,
+        }
     }
+}
 
-    for line in input.lines() {
-        if line.is_empty() {
-            let (p1, p2) = create_pattern(&mut stacks);
-            part1 += p1;
-            part2 += p2
-        } else {
-            stacks.push(line);
+impl fmt::Display for Reflection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reflection::Column(n) => write!(f, "vertical mirror after column {}", n),
+            Reflection::Row(n) => write!(f, "horizontal mirror after row {}", n),
         }
     }
+}
 
-    let (p1, p2) = create_pattern(&mut stacks);
-    part1 += p1;
-    part2 += p2;
+pub fn solve(input: &str) -> Result<Answer> {
+    let mut answer = Answer::default();
+
+    let patterns = blocks(input)
+        .iter()
+        .map(|f| Pattern::new(f))
+        .collect::<Vec<_>>();
+
+    // displayed up front, the parallel pass below would interleave the output
+    for pattern in &patterns {
+        pattern.display();
+    }
+
+    let (part1, part2) = patterns
+        .par_iter()
+        .map(|f| (f.get_reflection_value(false), f.get_reflection_value(true)))
+        .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
 
     answer.part1 = Some(part1.to_string());
     answer.part2 = Some(part2.to_string());
@@ -167,46 +244,40 @@ pub fn solve(input: &str) -> Result<Answer> {
 
 #[cfg(test)]
 mod tests {
-    use color_eyre::eyre::Result;
-
     use tracing_test::traced_test;
 
-    use crate::day13::solve;
-
-    const TEST_INPUT: &str = "#.##..##.
-..#.##.#.
-##......#
-##......#
-..#.##.#.
-..##..##.
-#.#.##.#.
-
-#...##..#
-#....#..#
-..##..###
-#####.##.
-#####.##.
-..##..###
-#....#..#
-";
-
-    #[traced_test]
-    #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    use super::*;
+    use crate::{test_support::fixture, utils::blocks};
 
-        assert_eq!(answer.part1, Some("405".to_string()));
+    fixture_test!(test_part1, super::solve, "day13", part1 = "405");
 
-        Ok(())
-    }
+    fixture_test!(test_part2, super::solve, "day13", part2 = "400");
 
     #[traced_test]
     #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part2, Some("400".to_string()));
-
-        Ok(())
+    fn test_render() {
+        let input = fixture("day13");
+        let patterns = blocks(&input)
+            .iter()
+            .map(|f| Pattern::new(f))
+            .collect::<Vec<_>>();
+
+        assert_eq!(patterns[0].reflection(false), Reflection::Column(5));
+        assert_eq!(
+            patterns[0].render(false),
+            "     ><\n #.##..##.\n ..#.##.#.\n ##......#\n ##......#\n ..#.##.#.\n ..##..##.\n #.#.##.#.\n     ><\n"
+        );
+
+        // the smudge of the second pattern is in the fifth column of its first row
+        assert_eq!(patterns[1].reflection(true), Reflection::Row(1));
+        assert_eq!(
+            patterns[1].render(true),
+            "v#...*#..#\n^#....#..#\n ..##..###\n #####.##.\n #####.##.\n ..##..###\n #....#..#\nsmudge at row 1, column 5, was '#'\n"
+        );
+        assert_eq!(
+            patterns[0].smudge(patterns[0].reflection(true)),
+            Some((0, 0))
+        );
+        assert_eq!(patterns[1].smudge(Reflection::Row(4)), None);
     }
 }
@@ -1,82 +1,221 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashMap,
+    fmt::{self, Display},
     iter,
+    str::FromStr,
 };
 
+#[cfg(feature = "tooling")]
+use crate::checkpoint::Checkpoint;
 use crate::{
-    solver::Answer,
-    utils::{get_column, get_row, update_column, update_row, Direction},
+    solver::{Answer, SolveContext},
+    utils::{
+        dump, get_column, get_row,
+        grid::{Grid, GridCell},
+        guard::Guard,
+        update_column, update_row, Direction, Part,
+    },
 };
 
-use color_eyre::eyre::Result;
-use tracing::info;
+use color_eyre::eyre::{bail, Result};
+#[cfg(feature = "tooling")]
+use serde::{Deserialize, Serialize};
+use tracing::{info, info_span};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
 enum Item {
-    RoundRock,
+    // the index of its kind in Legend::round
+    RoundRock(u8),
     CubeRock,
     Empty,
 }
 
 impl Item {
-    fn new(input: &char) -> Self {
-        match input {
-            '#' => Self::CubeRock,
-            'O' => Self::RoundRock,
-            '.' => Self::Empty,
-            _ => unreachable!(),
+    fn new(input: &char, legend: &Legend) -> Option<Self> {
+        if *input == legend.cube {
+            return Some(Self::CubeRock);
+        }
+        if *input == legend.empty {
+            return Some(Self::Empty);
         }
+
+        legend
+            .round
+            .iter()
+            .position(|(c, _)| c == input)
+            .map(|f| Self::RoundRock(f as u8))
     }
+}
 
-    fn display(&self) -> &str {
-        match self {
+impl Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
             Item::CubeRock => "#",
-            Item::RoundRock => "O",
+            Item::RoundRock(_) => "O",
             Item::Empty => ".",
+        };
+
+        write!(f, "{}", text)
+    }
+}
+
+// which character stands for what, and how much each kind of round rock weighs on the beams
+// e.g. "cube=X empty=_ O=1 @=5" for a platform of X walls with light O and heavy @ rocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Legend {
+    pub cube: char,
+    pub empty: char,
+    pub round: Vec<(char, u32)>,
+}
+
+impl Default for Legend {
+    fn default() -> Self {
+        Self {
+            cube: '#',
+            empty: '.',
+            round: vec![('O', 1)],
+        }
+    }
+}
+
+impl Legend {
+    fn symbol(&self, item: &Item) -> char {
+        match item {
+            Item::RoundRock(kind) => self.round[*kind as usize].0,
+            Item::CubeRock => self.cube,
+            Item::Empty => self.empty,
+        }
+    }
+
+    fn weight(&self, item: &Item) -> u32 {
+        match item {
+            Item::RoundRock(kind) => self.round[*kind as usize].1,
+            _ => 0,
+        }
+    }
+}
+
+// round rocks given here replace the puzzle's O, cube and empty default to # and .
+impl FromStr for Legend {
+    type Err = color_eyre::Report;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let mut legend = Self::default();
+        let mut round = vec![];
+
+        for entry in text.split_whitespace() {
+            let Some((key, value)) = entry.split_once('=') else {
+                bail!("expected key=value in the legend, got {:?}", entry);
+            };
+            let mut chars = value.chars();
+            let single = match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => None,
+            };
+
+            match (key, single) {
+                ("cube", Some(c)) => legend.cube = c,
+                ("empty", Some(c)) => legend.empty = c,
+                ("cube" | "empty", None) => {
+                    bail!("{} takes a single character, got {:?}", key, value)
+                }
+                _ => {
+                    let mut chars = key.chars();
+                    let (Some(c), None) = (chars.next(), chars.next()) else {
+                        bail!(
+                            "unknown legend key {:?}, expected cube, empty or a rock character",
+                            key
+                        );
+                    };
+                    let weight = value.parse().map_err(|_| {
+                        color_eyre::eyre::eyre!(
+                            "the weight of {:?} should be a number, got {:?}",
+                            c,
+                            value
+                        )
+                    })?;
+                    round.push((c, weight));
+                }
+            }
         }
+
+        if !round.is_empty() {
+            legend.round = round;
+        }
+
+        let mut symbols = legend.round.iter().map(|f| f.0).collect::<Vec<_>>();
+        symbols.extend([legend.cube, legend.empty]);
+        symbols.sort();
+        if symbols.windows(2).any(|f| f[0] == f[1]) {
+            bail!("every character of the legend should mean one thing");
+        }
+        if legend.round.len() > u8::MAX as usize {
+            bail!("at most {} kinds of round rock", u8::MAX);
+        }
+
+        Ok(legend)
     }
 }
 
-#[derive(Debug)]
-struct Platform {
+impl GridCell for Item {}
+
+// the puzzle's spin cycle
+const SPIN_CYCLE: [Direction; 4] = [
+    Direction::North,
+    Direction::West,
+    Direction::South,
+    Direction::East,
+];
+
+// how many times part 2 runs the spin cycle
+pub const SPIN_CYCLES: usize = 1_000_000_000;
+
+#[derive(Debug, Clone)]
+pub struct Platform {
     map: Vec<Vec<Item>>,
+    legend: Legend,
 }
 
 impl Platform {
-    fn new(input: &str) -> Self {
+    pub fn new(input: &str) -> Result<Self> {
+        Self::with_legend(input, Legend::default())
+    }
+
+    pub fn with_legend(input: &str, legend: Legend) -> Result<Self> {
         let mut map = vec![];
 
-        for line in input.lines() {
+        for (index, line) in input.lines().enumerate() {
             if line.is_empty() {
                 continue;
             }
 
             let mut line_vec = vec![];
             for c in line.chars() {
-                line_vec.push(Item::new(&c));
+                let Some(item) = Item::new(&c, &legend) else {
+                    bail!("line {}: {:?} is not in the legend", index + 1, c);
+                };
+                line_vec.push(item);
             }
 
             map.push(line_vec);
         }
 
-        Self { map }
+        Ok(Self { map, legend })
     }
 
-    fn display(&self) {
-        let mut text = "\n".to_string();
-
-        for y_row in &self.map {
-            text.push_str(&y_row.iter().map(|f| f.display()).collect::<String>());
-            text.push('\n');
-        }
+    pub fn display(&self) {
+        info!("\n{}", self.render());
+    }
 
-        info!("{}", text);
+    fn render(&self) -> String {
+        Grid::from_rows(self.map.clone()).render(|_, f| self.legend.symbol(f).to_string())
     }
 
     fn as_string(&self) -> String {
         let mut text = String::new();
         for y_row in &self.map {
-            text.push_str(&y_row.iter().map(|f| f.display()).collect::<String>());
+            text.extend(y_row.iter().map(|f| self.legend.symbol(f)));
         }
 
         text
@@ -109,7 +248,8 @@ impl Platform {
             let mut elements: Vec<Item> = get_elements(&self.map, index as i32).unwrap();
             let mut new_elements = vec![];
 
-            let mut round_count = 0;
+            // rocks of different weights can not pass each other, so they keep their order
+            let mut rounds = vec![];
             let mut empty_count = 0;
 
             if should_reverse {
@@ -118,15 +258,12 @@ impl Platform {
 
             for current in &elements {
                 match current {
-                    Item::RoundRock => round_count += 1,
+                    Item::RoundRock(_) => rounds.push(*current),
                     Item::CubeRock => {
-                        if round_count > 0 {
-                            new_elements.extend(iter::repeat(Item::RoundRock).take(round_count));
-                            round_count = 0;
-                        }
+                        new_elements.append(&mut rounds);
 
                         if empty_count > 0 {
-                            new_elements.extend(iter::repeat(Item::Empty).take(empty_count));
+                            new_elements.extend(iter::repeat_n(Item::Empty, empty_count));
                             empty_count = 0;
                         }
 
@@ -136,25 +273,85 @@ impl Platform {
                 }
             }
 
-            if round_count > 0 {
-                new_elements.extend(iter::repeat(Item::RoundRock).take(round_count));
-            }
+            new_elements.append(&mut rounds);
 
             if empty_count > 0 {
-                new_elements.extend(iter::repeat(Item::Empty).take(empty_count));
+                new_elements.extend(iter::repeat_n(Item::Empty, empty_count));
             }
 
             update_elements(&mut self.map, &new_elements, index as i32, should_reverse);
         }
     }
 
-    fn get_weight(&self) -> i32 {
+    // tilts in the given order once per cycle, skipping ahead once the platform starts repeating
+    // with --resume it goes on from the last checkpoint of the same platform and cycles
+    pub fn spin(&mut self, directions: &[Direction], cycles: usize) -> Result<Option<CycleInfo>> {
+        #[cfg(feature = "tooling")]
+        let mut checkpoint = Checkpoint::new(
+            "day14-spin",
+            &format!(
+                "{}{:?}{}{:?}",
+                self.as_string(),
+                directions,
+                cycles,
+                self.legend
+            ),
+        );
+        #[cfg(feature = "tooling")]
+        let (mut current_cycle, mut seen, mut cycle_info) = match checkpoint.resume() {
+            Some((current_cycle, map, seen, cycle_info)) => {
+                self.map = map;
+                (current_cycle, seen, cycle_info)
+            }
+            None => (0, HashMap::<String, usize>::new(), None),
+        };
+        #[cfg(not(feature = "tooling"))]
+        let (mut current_cycle, mut seen, mut cycle_info) = (0, HashMap::new(), None);
+        let mut guard = Guard::new("day 14 spin cycles");
+
+        while current_cycle < cycles {
+            guard.step()?;
+
+            for direction in directions {
+                self.tilt(direction);
+                dump::frame(
+                    &format!("day14-cycle{}-{:?}", current_cycle, direction).to_lowercase(),
+                    || self.render(),
+                );
+            }
+
+            if cycle_info.is_none() {
+                let key = self.as_string();
+
+                if let Some(&start) = seen.get(&key) {
+                    let length = current_cycle - start;
+                    cycle_info = Some(CycleInfo { start, length });
+
+                    // whole repeats end where they started, so only the remainder has to be run
+                    current_cycle += (cycles - current_cycle - 1) / length * length;
+                } else {
+                    seen.insert(key, current_cycle);
+                }
+            }
+
+            current_cycle += 1;
+            #[cfg(feature = "tooling")]
+            checkpoint.save(|| (current_cycle, &self.map, &seen, cycle_info));
+        }
+        #[cfg(feature = "tooling")]
+        checkpoint.finish();
+
+        Ok(cycle_info)
+    }
+
+    // load on the north support beams, every rock weighing what the legend says
+    pub fn get_weight(&self) -> i32 {
         let mut result = 0;
         let len = self.map.len();
 
         for (index, row) in self.map.iter().enumerate() {
-            let round_count = row.iter().filter(|&f| f == &Item::RoundRock).count();
-            let value = round_count * (len - index);
+            let weight = row.iter().map(|f| self.legend.weight(f)).sum::<u32>() as usize;
+            let value = weight * (len - index);
 
             result += value;
         }
@@ -163,61 +360,67 @@ impl Platform {
     }
 }
 
-pub fn solve(input: &str) -> Result<Answer> {
-    let mut part1 = 0;
-    let mut answer = Answer::default();
-
-    let mut platform = Platform::new(input);
-    platform.display();
-
-    let mut current_cycle = 0;
-    let max_cycle = 1000000000;
-
-    let directions = [
-        Direction::North,
-        Direction::West,
-        Direction::South,
-        Direction::East,
-    ];
-
-    let mut cache: HashMap<String, Vec<usize>> = HashMap::new();
-
-    while current_cycle < max_cycle {
-        for direction in &directions {
-            platform.tilt(direction);
+// the spin cycle after which the platform starts repeating, deposited in the SolveContext
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
+pub struct CycleInfo {
+    // 0 based index of the first cycle of the repeating part
+    pub start: usize,
+    pub length: usize,
+}
 
-            if current_cycle == 0 && direction == &Direction::North {
-                part1 = platform.get_weight();
-            }
-        }
+pub fn solve(input: &str) -> Result<Answer> {
+    solve_with_context(input, &mut SolveContext::new())
+}
 
-        let key = platform.as_string();
+// tilts written as letters, e.g. "NWSE" for the puzzle's spin cycle
+pub fn parse_tilts(text: &str) -> Result<Vec<Direction>> {
+    text.chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            'N' => Ok(Direction::North),
+            'W' => Ok(Direction::West),
+            'S' => Ok(Direction::South),
+            'E' => Ok(Direction::East),
+            _ => bail!("unknown tilt {:?}, expected N, W, S or E", c),
+        })
+        .collect()
+}
 
-        if let Some(vec) = cache.get_mut(&key) {
-            vec.push(current_cycle);
+pub fn solve_with_context(input: &str, context: &mut SolveContext) -> Result<Answer> {
+    solve_with_cycles(input, context, SPIN_CYCLES)
+}
 
-            if vec.len() > 4 {
-                let diff: HashSet<usize> =
-                    vec.windows(2).map(|window| window[1] - window[0]).collect();
+// part 2 after a number of spin cycles other than the puzzle's
+pub fn solve_with_cycles(input: &str, context: &mut SolveContext, cycles: usize) -> Result<Answer> {
+    solve_with_legend(input, context, cycles, Legend::default())
+}
 
-                if diff.len() == 1 {
-                    let range = max_cycle - current_cycle;
-                    let diff = *diff.iter().next().unwrap();
-                    let multiplier = num::Integer::div_floor(&range, &diff);
+// a platform drawn with other characters, or with rocks of several weights
+pub fn solve_with_legend(
+    input: &str,
+    context: &mut SolveContext,
+    cycles: usize,
+    legend: Legend,
+) -> Result<Answer> {
+    let mut answer = Answer::default();
 
-                    current_cycle += diff * multiplier;
+    let mut platform = info_span!("parse").in_scope(|| Platform::with_legend(input, legend))?;
+    platform.display();
 
-                    assert!(current_cycle < max_cycle);
-                }
-            }
-        } else {
-            cache.insert(key, vec![current_cycle]);
-        };
+    let part1 = info_span!("part", part = Part::One.name()).in_scope(|| {
+        let mut north = platform.clone();
+        north.tilt(&Direction::North);
+        north.get_weight()
+    });
 
-        current_cycle += 1;
-    }
+    let part2 = {
+        let _span = info_span!("part", part = Part::Two.name()).entered();
+        if let Some(cycle_info) = platform.spin(&SPIN_CYCLE, cycles)? {
+            context.insert(cycle_info);
+        }
 
-    let part2 = platform.get_weight();
+        platform.get_weight()
+    };
 
     answer.part1 = Some(part1.to_string());
     answer.part2 = Some(part2.to_string());
@@ -229,42 +432,89 @@ mod tests {
     use tracing::info;
     use tracing_test::traced_test;
 
-    use color_eyre::eyre::Result;
-
+    use crate::test_support::fixture;
     use crate::{
-        day14::{solve, Platform},
+        day14::{
+            parse_tilts, solve_with_context, solve_with_cycles, solve_with_legend, CycleInfo,
+            Legend, Platform,
+        },
+        solver::SolveContext,
         utils::Direction,
     };
 
-    const TEST_INPUT: &str = "O....#....
-O.OO#....#
-.....##...
-OO.#O....O
-.O.....O#.
-O.#..O.#.#
-..O..#O..O
-.......O..
-#....###..
-#OO..#....";
+    fixture_test!(test_part1, super::solve, "day14", part1 = "136");
+
+    fixture_test!(test_part2, super::solve, "day14", part2 = "64");
 
     #[traced_test]
     #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part1, Some("136".to_string()));
-
-        Ok(())
+    fn test_cycle_info() {
+        let mut context = SolveContext::new();
+        solve_with_context(&fixture("day14"), &mut context).unwrap();
+
+        assert_eq!(
+            context.get::<CycleInfo>(),
+            Some(&CycleInfo {
+                start: 2,
+                length: 7
+            })
+        );
     }
 
     #[traced_test]
     #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part2, Some("64".to_string()));
+    fn test_spin() {
+        let mut platform = Platform::new(&fixture("day14")).unwrap();
+        let cycle_info = platform.spin(&parse_tilts("nwse").unwrap(), 3).unwrap();
+        assert_eq!(cycle_info, None);
+        assert_eq!(platform.get_weight(), 69);
+
+        // tilting back and forth settles after the first cycle
+        let mut platform = Platform::new(&fixture("day14")).unwrap();
+        let cycle_info = platform.spin(&parse_tilts("NS").unwrap(), 1000).unwrap();
+        assert_eq!(
+            cycle_info,
+            Some(CycleInfo {
+                start: 0,
+                length: 1
+            })
+        );
+
+        let mut expected = Platform::new(&fixture("day14")).unwrap();
+        expected.spin(&parse_tilts("NS").unwrap(), 1).unwrap();
+        assert_eq!(platform.as_string(), expected.as_string());
+
+        assert!(parse_tilts("NX").is_err());
+
+        // the puzzle lists the load after the first few spin cycles
+        let answer = solve_with_cycles(&fixture("day14"), &mut SolveContext::new(), 3).unwrap();
+        assert_eq!(answer.part2.as_deref(), Some("69"));
+    }
 
-        Ok(())
+    #[traced_test]
+    #[test]
+    fn test_legend() {
+        let legend = "cube=X empty=_ O=1 @=5".parse::<Legend>().unwrap();
+        assert_eq!(legend.round, [('O', 1), ('@', 5)]);
+        assert!("cube=XX".parse::<Legend>().is_err());
+        assert!("O=heavy".parse::<Legend>().is_err());
+        assert!("cube=O".parse::<Legend>().is_err());
+        assert!("rock=O".parse::<Legend>().is_err());
+
+        // the example drawn with other characters has the same loads
+        let input = fixture("day14").replace('#', "X").replace('.', "_");
+        let answer =
+            solve_with_legend(&input, &mut SolveContext::new(), 3, legend.clone()).unwrap();
+        assert_eq!(answer.part1.as_deref(), Some("136"));
+        assert_eq!(answer.part2.as_deref(), Some("69"));
+        assert!(Platform::with_legend(&fixture("day14"), legend.clone()).is_err());
+        assert!(Platform::new(&input).is_err());
+
+        // a heavy rock keeps its place in the line of rocks it slides with
+        let mut platform = Platform::with_legend("_\nO\n@\n_\nO\n", legend).unwrap();
+        platform.tilt(&Direction::North);
+        assert_eq!(platform.render(), "O\n@\nO\n_\n_\n");
+        assert_eq!(platform.get_weight(), 5 + 5 * 4 + 3);
     }
 
     #[traced_test]
@@ -277,12 +527,12 @@ O.#..O.#.#
             (Direction::East, "....O#.....OOO#....#.....##....OO#....OO......OO#..O#...O#.#....O#..OO.........O#....###..#..OO#...."),
         ];
 
-        let platform = Platform::new(TEST_INPUT);
+        let platform = Platform::new(&fixture("day14")).unwrap();
         platform.display();
 
         for (direction, expected_output) in pairs {
             info!("Running test for direction {:?}", direction);
-            let mut platform = Platform::new(TEST_INPUT);
+            let mut platform = Platform::new(&fixture("day14")).unwrap();
 
             platform.tilt(&direction);
             platform.display();
@@ -1,6 +1,13 @@
-use crate::solver::Answer;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "simd")]
+use core::iter;
 
-use color_eyre::eyre::Result;
+use crate::{error::Result, solver::Answer};
 
 trait HashAlgorithmTrait {
     fn calculate(&self, item: &str) -> u32 {
@@ -34,8 +41,62 @@ impl HashAlgorithm {
     }
 }
 
-#[derive(Debug, Clone)]
-enum HashMapOperation {
+// 17^exponent mod 256
+#[cfg(feature = "simd")]
+const fn power_of_17(exponent: u32) -> u16 {
+    let mut value = 1u16;
+    let mut i = 0;
+    while i < exponent {
+        value = value.wrapping_mul(17) & 255;
+        i += 1;
+    }
+
+    value
+}
+
+// the hash unrolled is the sum of every byte times 17 to the power of its distance from the end,
+// so 16 bytes are multiplied at once and summed, u16 lanes wrap at a multiple of 256
+#[cfg(feature = "simd")]
+fn calculate_simd(item: &str) -> u32 {
+    use wide::u16x16;
+
+    const LANES: usize = 16;
+    const WEIGHTS: [u16; LANES] = {
+        let mut weights = [0; LANES];
+        let mut i = 0;
+        while i < LANES {
+            weights[i] = power_of_17((LANES - i) as u32);
+            i += 1;
+        }
+
+        weights
+    };
+    const CHUNK_POWER: u16 = power_of_17(LANES as u32);
+
+    let bytes = item.as_bytes();
+    // the remainder goes first, padded with leading zeroes that add nothing
+    let (head, chunks) = bytes.split_at(bytes.len() % LANES);
+    let weights = u16x16::new(WEIGHTS);
+    let mut value = 0u16;
+
+    for chunk in iter::once(head).chain(chunks.chunks(LANES)) {
+        let mut lanes = [0u16; LANES];
+        for (lane, byte) in lanes[LANES - chunk.len()..].iter_mut().zip(chunk) {
+            *lane = *byte as u16;
+        }
+
+        let sum = (u16x16::new(lanes) * weights)
+            .to_array()
+            .iter()
+            .fold(0u16, |sum, f| sum.wrapping_add(*f));
+        value = value.wrapping_mul(CHUNK_POWER).wrapping_add(sum);
+    }
+
+    (value & 255) as u32
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HashMapOperation {
     Reduce,
     Upsert(u32),
 }
@@ -49,10 +110,10 @@ impl HashMapOperation {
     }
 }
 
-#[derive(Debug, Clone)]
-struct HashMapItem {
-    label: String,
-    operation: HashMapOperation,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashMapItem {
+    pub label: String,
+    pub operation: HashMapOperation,
 }
 
 impl HashMapItem {
@@ -73,49 +134,120 @@ impl HashMapItem {
     }
 }
 
+// an applied operation and the box its label hashed to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub item: HashMapItem,
+    pub box_index: u32,
+}
+
+// applies the initialization sequence one operation at a time, so the boxes can be inspected in between
 #[derive(Debug)]
-struct HashMapAlgorithm {
+pub struct HashMapAlgorithm {
     items: Vec<HashMapItem>,
+    // the box of every item when hashed ahead of time, empty to hash them step by step
+    box_indices: Vec<u32>,
     boxes: Vec<Vec<HashMapItem>>,
+    history: Vec<Step>,
 }
 
 impl HashAlgorithmTrait for HashMapAlgorithm {}
 
 impl HashMapAlgorithm {
-    fn new(input: &str) -> Self {
+    pub fn new(input: &str) -> Self {
         let items = input.trim().split(',').map(HashMapItem::new).collect();
 
         Self {
             items,
+            box_indices: vec![],
             boxes: vec![Vec::new(); 256],
+            history: vec![],
         }
     }
 
-    fn execute_sequence(&mut self) {
-        for item in &self.items {
-            let box_index = self.calculate(&item.label);
-            let current_box = &self.boxes[box_index as usize];
+    #[cfg(feature = "simd")]
+    fn with_simd_hashes(mut self) -> Self {
+        self.box_indices = self
+            .items
+            .iter()
+            .map(|f| calculate_simd(&f.label))
+            .collect();
+        self
+    }
 
-            match item.operation {
-                HashMapOperation::Reduce => {
-                    self.boxes[box_index as usize] = current_box
-                        .iter()
-                        .filter(|f| f.label != item.label)
-                        .cloned()
-                        .collect();
-                }
-                HashMapOperation::Upsert(_) => {
-                    if let Some(index) = current_box.iter().position(|f| f.label == item.label) {
-                        self.boxes[box_index as usize][index] = item.clone();
-                    } else {
-                        self.boxes[box_index as usize].push(item.clone())
-                    }
+    pub fn execute_sequence(&mut self) {
+        while self.step().is_some() {}
+    }
+
+    // applies the next operation, None once the sequence is done
+    pub fn step(&mut self) -> Option<&Step> {
+        let item = self.items.get(self.history.len())?.clone();
+        let box_index = match self.box_indices.get(self.history.len()) {
+            Some(box_index) => *box_index,
+            None => self.calculate(&item.label),
+        };
+        let current_box = &mut self.boxes[box_index as usize];
+
+        match item.operation {
+            HashMapOperation::Reduce => {
+                current_box.retain(|f| f.label != item.label);
+            }
+            HashMapOperation::Upsert(_) => {
+                if let Some(index) = current_box.iter().position(|f| f.label == item.label) {
+                    current_box[index] = item.clone();
+                } else {
+                    current_box.push(item.clone())
                 }
             }
         }
+
+        self.history.push(Step { item, box_index });
+        self.history.last()
+    }
+
+    // leaves the boxes as they are after the first n operations, going back replays from the start
+    pub fn replay_to(&mut self, n: usize) {
+        if n < self.history.len() {
+            self.boxes.iter_mut().for_each(|f| f.clear());
+            self.history.clear();
+        }
+
+        while self.history.len() < n && self.step().is_some() {}
+    }
+
+    // operations applied so far, in order
+    pub fn history(&self) -> &[Step] {
+        &self.history
+    }
+
+    pub fn boxes(&self) -> &[Vec<HashMapItem>] {
+        &self.boxes
+    }
+
+    // non empty boxes like the puzzle shows them, "Box 0: [rn 1] [cm 2]"
+    pub fn render_boxes(&self) -> String {
+        let mut text = String::new();
+
+        for (box_index, current_box) in self.boxes.iter().enumerate() {
+            if current_box.is_empty() {
+                continue;
+            }
+
+            text.push_str(&format!("Box {}:", box_index));
+            for lens in current_box {
+                text.push_str(&format!(
+                    " [{} {}]",
+                    lens.label,
+                    lens.operation.get_focal_length()
+                ));
+            }
+            text.push('\n');
+        }
+
+        text
     }
 
-    fn get_focusing_power(&self) -> u32 {
+    pub fn get_focusing_power(&self) -> u32 {
         let mut result = 0;
 
         for (box_index, current_box) in self.boxes.iter().enumerate() {
@@ -145,20 +277,32 @@ pub fn solve(input: &str) -> Result<Answer> {
     Ok(answer)
 }
 
-#[cfg(test)]
-mod tests {
+// the same sequence with every hash vectorized, compare with --compare
+#[cfg(feature = "simd")]
+pub fn solve_simd(input: &str) -> Result<Answer> {
+    let part1 = input.trim().split(',').map(calculate_simd).sum::<u32>();
+
+    let mut hashmap_algorithm = HashMapAlgorithm::new(input).with_simd_hashes();
+    hashmap_algorithm.execute_sequence();
+    let part2 = hashmap_algorithm.get_focusing_power();
+
+    Ok(Answer {
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+    })
+}
 
+#[cfg(all(test, feature = "std"))]
+mod tests {
     use tracing_test::traced_test;
 
     use super::*;
-    use color_eyre::eyre::Result;
-
-    const TEST_INPUT: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+    use crate::test_support::fixture;
 
     #[traced_test]
     #[test]
     fn test_hash_algorithm_calculate_all() {
-        let hash_algorithm = HashAlgorithm::new(TEST_INPUT);
+        let hash_algorithm = HashAlgorithm::new(&fixture("day15"));
 
         let result = [30, 253, 97, 47, 14, 180, 9, 197, 48, 214, 231];
 
@@ -167,21 +311,56 @@ mod tests {
 
     #[traced_test]
     #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    fn test_replay() {
+        let mut hashmap_algorithm = HashMapAlgorithm::new(&fixture("day15"));
+
+        let step = hashmap_algorithm.step().unwrap();
+        assert_eq!(step.item.label, "rn");
+        assert_eq!(step.box_index, 0);
+
+        hashmap_algorithm.replay_to(4);
+        assert_eq!(hashmap_algorithm.history().len(), 4);
+        assert_eq!(
+            hashmap_algorithm.render_boxes(),
+            "Box 0: [rn 1] [cm 2]\nBox 1: [qp 3]\n"
+        );
+
+        hashmap_algorithm.replay_to(2);
+        assert_eq!(hashmap_algorithm.history().len(), 2);
+        assert_eq!(hashmap_algorithm.render_boxes(), "Box 0: [rn 1]\n");
+
+        hashmap_algorithm.execute_sequence();
+        assert_eq!(hashmap_algorithm.history().len(), 11);
+        assert_eq!(hashmap_algorithm.get_focusing_power(), 145);
+        assert!(hashmap_algorithm.step().is_none());
+    }
 
-        assert_eq!(answer.part1, Some("1320".to_string()));
+    fixture_test!(test_part1, super::solve, "day15", part1 = "1320");
 
-        Ok(())
-    }
+    fixture_test!(test_part2, super::solve, "day15", part2 = "145");
 
+    #[cfg(feature = "simd")]
     #[traced_test]
     #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part2, Some("145".to_string()));
+    fn test_simd() {
+        let hash_algorithm = HashAlgorithm::new("");
+        for item in [
+            "",
+            "H",
+            "HASH",
+            "rn=1",
+            "abcdefghijklmnop",
+            "a long label of 37 bytes, past 2 lanes",
+        ] {
+            assert_eq!(
+                calculate_simd(item),
+                hash_algorithm.calculate(item),
+                "{}",
+                item
+            );
+        }
 
-        Ok(())
+        let input = fixture("day15");
+        assert_eq!(solve_simd(&input).unwrap(), solve(&input).unwrap());
     }
 }
@@ -1,99 +1,181 @@
-use std::collections::{HashMap, HashSet};
-
-use crate::{solver::Answer, utils::Coordinate};
-
-use color_eyre::eyre::Result;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{self, Display},
+    rc::Rc,
+};
+
+use crate::{
+    solver::Answer,
+    utils::{
+        charset, dump,
+        grid::{self, GridCell},
+        guard::Guard,
+        BitGrid, Coordinate,
+    },
+};
+
+use color_eyre::eyre::{bail, Result};
 use strum::IntoEnumIterator;
 use tracing::info;
 
 use crate::utils::Direction;
 
-#[derive(Debug, Clone, Copy)]
-enum Node {
+// an optical element besides the built in ones, like a prism or an absorber
+pub trait Optic: fmt::Debug {
+    // directions the beam leaves in after entering in the given direction, none absorbs it
+    fn next_directions(&self, direction: Direction) -> Vec<Direction>;
+
+    fn symbol(&self) -> char;
+}
+
+#[derive(Debug, Clone)]
+pub enum Node {
     Empty,
     Mirror(char),
     Splitter(char),
+    Custom(Rc<dyn Optic>),
 }
 
 impl Node {
-    fn from_char(c: char) -> Self {
+    // custom optics take precedence, so they can also replace a built in element
+    fn from_char(c: char, optics: &HashMap<char, Rc<dyn Optic>>) -> Self {
+        if let Some(optic) = optics.get(&c) {
+            return Self::Custom(optic.clone());
+        }
+
+        match Self::builtin(c) {
+            Some(node) => node,
+            None => unreachable!(),
+        }
+    }
+
+    // one of the puzzle's own elements, None for any other character
+    pub fn builtin(c: char) -> Option<Self> {
         match c {
-            '/' | '\\' => Self::Mirror(c),
-            '|' | '-' => Self::Splitter(c),
-            '.' => Self::Empty,
-            _ => unreachable!(),
+            '/' | '\\' => Some(Self::Mirror(c)),
+            '|' | '-' => Some(Self::Splitter(c)),
+            '.' => Some(Self::Empty),
+            _ => None,
         }
     }
 
-    fn get_direction_pair(&self) -> HashMap<Direction, Vec<Direction>> {
-        let pairs = match self {
-            Node::Mirror(c) => match c {
-                '/' => [
-                    (Direction::Up, vec![Direction::Right]),
-                    (Direction::Right, vec![Direction::Up]),
-                    (Direction::Down, vec![Direction::Left]),
-                    (Direction::Left, vec![Direction::Down]),
-                ],
-                '\\' => [
-                    (Direction::Up, vec![Direction::Left]),
-                    (Direction::Left, vec![Direction::Up]),
-                    (Direction::Down, vec![Direction::Right]),
-                    (Direction::Right, vec![Direction::Down]),
-                ],
+    fn get_next_direction(&self, direction: &Direction) -> DirectionSet {
+        let direction = *direction;
+
+        let directions: &[Direction] = match self {
+            Node::Empty => &[direction],
+            Node::Mirror('/') => match direction {
+                Direction::Up => &[Direction::Right],
+                Direction::Right => &[Direction::Up],
+                Direction::Down => &[Direction::Left],
+                Direction::Left => &[Direction::Down],
                 _ => unreachable!(),
             },
-            Node::Splitter(c) => match c {
-                '-' => [
-                    (Direction::Left, vec![Direction::Left]),
-                    (Direction::Right, vec![Direction::Right]),
-                    (Direction::Up, vec![Direction::Left, Direction::Right]),
-                    (Direction::Down, vec![Direction::Left, Direction::Right]),
-                ],
-                '|' => [
-                    (Direction::Up, vec![Direction::Up]),
-                    (Direction::Down, vec![Direction::Down]),
-                    (Direction::Left, vec![Direction::Up, Direction::Down]),
-                    (Direction::Right, vec![Direction::Up, Direction::Down]),
-                ],
+            Node::Mirror('\\') => match direction {
+                Direction::Up => &[Direction::Left],
+                Direction::Left => &[Direction::Up],
+                Direction::Down => &[Direction::Right],
+                Direction::Right => &[Direction::Down],
                 _ => unreachable!(),
             },
-            Node::Empty => [
-                (Direction::Left, vec![Direction::Left]),
-                (Direction::Right, vec![Direction::Right]),
-                (Direction::Down, vec![Direction::Down]),
-                (Direction::Up, vec![Direction::Up]),
-            ],
+            Node::Splitter('-') if direction.is_vertical() => &[Direction::Left, Direction::Right],
+            Node::Splitter('|') if direction.is_horizontal() => &[Direction::Up, Direction::Down],
+            Node::Splitter(_) => &[direction],
+            Node::Custom(optic) => {
+                return DirectionSet::from_directions(&optic.next_directions(direction))
+            }
+            Node::Mirror(_) => unreachable!(),
         };
 
-        pairs.into_iter().collect()
+        DirectionSet::from_directions(directions)
     }
+}
 
-    fn get_next_direction(&self, direction: &Direction) -> Vec<Direction> {
-        let pairs = self.get_direction_pair();
+// up to four directions as a bit mask, one bit per direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct DirectionSet(u8);
+
+impl DirectionSet {
+    const DIRECTIONS: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
 
-        pairs.get(direction).unwrap().clone()
+    fn bit(direction: Direction) -> u8 {
+        let index = Self::DIRECTIONS
+            .iter()
+            .position(|f| *f == direction)
+            .unwrap();
+
+        1 << index
     }
 
-    fn display(&self) -> &str {
-        match self {
-            Node::Empty => "·",
+    fn from_directions(directions: &[Direction]) -> Self {
+        let mut set = Self::default();
+        for direction in directions {
+            set.insert(*direction);
+        }
+
+        set
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    // returns false if the direction was already in the set
+    fn insert(&mut self, direction: Direction) -> bool {
+        let bit = Self::bit(direction);
+        let is_new = self.0 & bit == 0;
+        self.0 |= bit;
+
+        is_new
+    }
+
+    fn iter(self) -> impl Iterator<Item = Direction> {
+        Self::DIRECTIONS
+            .into_iter()
+            .filter(move |f| self.0 & Self::bit(*f) != 0)
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Node::Empty => charset::glyph("·", "."),
             Node::Mirror(c) | Node::Splitter(c) => match c {
-                '/' => "╱",
-                '\\' => "╲",
-                '|' => "│",
-                '-' => "━",
-                _ => unreachable!(),
+                '/' => charset::glyph("╱", "/"),
+                '\\' => charset::glyph("╲", "\\"),
+                '|' => charset::glyph("│", "|"),
+                '-' => charset::glyph("━", "-"),
+                _ => Self::UNKNOWN,
             },
-        }
+            Node::Custom(optic) => return write!(f, "{}", optic.symbol()),
+        };
+
+        write!(f, "{}", text)
     }
 }
 
-struct Grid {
+impl GridCell for Node {}
+
+pub struct Grid {
     map: Vec<Vec<Node>>,
+    // the tiles each beam start energized, so a grid with one node changed only reruns the beams
+    // that went through it
+    energized: RefCell<HashMap<(Coordinate<i32>, Direction), BitGrid>>,
 }
 
 impl Grid {
-    fn new(input: &str) -> Self {
+    pub fn new(input: &str) -> Self {
+        Self::with_optics(input, HashMap::new())
+    }
+
+    // characters in optics are read as those elements instead of the puzzle's own
+    pub fn with_optics(input: &str, optics: HashMap<char, Rc<dyn Optic>>) -> Self {
         let mut map = vec![];
         for line in input.lines() {
             if line.is_empty() {
@@ -102,7 +184,7 @@ impl Grid {
 
             let mut line_vec = vec![];
             for c in line.chars() {
-                let node = Node::from_char(c);
+                let node = Node::from_char(c, &optics);
                 line_vec.push(node);
             }
 
@@ -111,46 +193,89 @@ impl Grid {
 
         map.reverse();
 
-        Self { map }
+        Self {
+            map,
+            energized: RefCell::default(),
+        }
     }
 
-    fn display(&self, traveled: HashSet<Coordinate<i32>>) {
-        let mut text = "\n".to_string();
+    // coordinates are counted from the bottom left like in travel
+    pub fn node(&self, coordinate: Coordinate<i32>) -> Option<&Node> {
+        let y = usize::try_from(coordinate.y).ok()?;
+        let x = usize::try_from(coordinate.x).ok()?;
 
-        let map = self.map.clone();
-        // map.reverse();
+        self.map.get(y)?.get(x)
+    }
 
-        for (y_index, y_row) in map.iter().enumerate() {
-            for (x_index, value) in y_row.iter().enumerate() {
-                let coordinate = Coordinate::new(x_index as i32, y_index as i32);
-                let t = if traveled.contains(&coordinate) {
-                    "#"
-                } else {
-                    value.display()
-                };
+    // a copy of the grid with one node replaced, the beams that never touched that tile keep
+    // their energized tiles, the others are traced again on their next energized_from
+    pub fn with_node(&self, coordinate: Coordinate<i32>, node: Node) -> Result<Self> {
+        if self.node(coordinate).is_none() {
+            bail!("{:?} is outside the grid", coordinate);
+        }
 
-                text.push_str(t);
-            }
+        let mut map = self.map.clone();
+        map[coordinate.y as usize][coordinate.x as usize] = node;
+
+        let energized = self
+            .energized
+            .borrow()
+            .iter()
+            .filter(|(_, f)| !f.get(coordinate))
+            .map(|(start, f)| (*start, f.clone()))
+            .collect();
+
+        Ok(Self {
+            map,
+            energized: RefCell::new(energized),
+        })
+    }
 
-            text.push('\n');
+    // how many tiles the beam entering from start in the direction energizes, remembered per start
+    pub fn energized_from(&self, start: Coordinate<i32>, direction: Direction) -> Result<usize> {
+        if let Some(traveled) = self.energized.borrow().get(&(start, direction)) {
+            return Ok(traveled.count());
         }
 
-        info!("{}", text);
+        let traveled = self.travel(start, direction)?;
+        let count = traveled.count();
+        self.energized
+            .borrow_mut()
+            .insert((start, direction), traveled);
+
+        Ok(count)
+    }
+
+    fn display(&self, traveled: &BitGrid) {
+        info!("\n{}", self.render(traveled));
     }
 
-    fn travel(
+    fn render(&self, traveled: &BitGrid) -> String {
+        grid::Grid::from_rows(self.map.clone()).render(|coordinate, value| {
+            match traveled.get(coordinate) {
+                true => "#".to_string(),
+                false => value.to_string(),
+            }
+        })
+    }
+
+    // coordinates are counted from the bottom left, the beam starts outside the grid
+    pub fn travel(
         &self,
         initial_coordinate: Coordinate<i32>,
         initial_direction: Direction,
-    ) -> HashSet<Coordinate<i32>> {
+    ) -> Result<BitGrid> {
         let mut queue = vec![(initial_coordinate, initial_direction)];
-        let mut traveled = HashSet::new();
-        let mut cache = HashSet::new(); // prevent forever-loop
-
+        let mut guard = Guard::new("day 16 beam queue");
         let max_y = self.map.len();
         let max_x = self.map[0].len();
 
+        // directions each tile was entered in, a beam entering the same way twice is a loop
+        let mut entered = vec![DirectionSet::default(); max_x * max_y];
+
         while let Some((current_coordinate, current_direction)) = queue.pop() {
+            guard.step()?;
+
             let (mod_x, mod_y) = current_direction.get_modifier(1);
             let next_coordinate = current_coordinate.add(mod_x, mod_y);
 
@@ -163,26 +288,30 @@ impl Grid {
                 continue;
             };
 
-            if cache.contains(&(next_coordinate, current_direction)) {
+            let index = next_coordinate.y as usize * max_x + next_coordinate.x as usize;
+            if !entered[index].insert(current_direction) {
                 continue;
-            } else {
-                cache.insert((next_coordinate, current_direction));
-                traveled.insert(next_coordinate);
             }
 
             let next_node = &self.map[next_coordinate.y as usize][next_coordinate.x as usize];
 
-            let next_directions = next_node.get_next_direction(&current_direction);
-
-            for next_direction in next_directions {
+            for next_direction in next_node.get_next_direction(&current_direction).iter() {
                 queue.push((next_coordinate, next_direction));
             }
         }
 
-        traveled
+        let mut traveled = BitGrid::new(max_x, max_y);
+        for (index, _) in entered.iter().enumerate().filter(|(_, f)| !f.is_empty()) {
+            traveled.insert(Coordinate::new(
+                (index % max_x) as i32,
+                (index / max_x) as i32,
+            ));
+        }
+
+        Ok(traveled)
     }
 
-    fn maximum_energized(&self) -> i32 {
+    pub fn maximum_energized(&self) -> Result<i32> {
         let max_x = self.map[0].len() as i32;
         let max_y = self.map.len() as i32;
         let mut max = 0;
@@ -217,12 +346,20 @@ impl Grid {
         for (initial_direction, initial_coordinate_raw) in stacks {
             let initial_coordinate =
                 Coordinate::new(initial_coordinate_raw.0, initial_coordinate_raw.1);
-            let traveled = self.travel(initial_coordinate, initial_direction);
-
-            max = std::cmp::max(max, traveled.len() as i32);
+            let traveled = self.travel(initial_coordinate, initial_direction)?;
+            dump::frame(
+                &format!(
+                    "day16-{}-{}-{:?}",
+                    initial_coordinate.x, initial_coordinate.y, initial_direction
+                )
+                .to_lowercase(),
+                || self.render(&traveled),
+            );
+
+            max = std::cmp::max(max, traveled.count() as i32);
         }
 
-        max
+        Ok(max)
     }
 }
 
@@ -230,60 +367,163 @@ pub fn solve(input: &str) -> Result<Answer> {
     let mut answer = Answer::default();
 
     let grid = Grid::new(input);
-    grid.display(HashSet::new());
+    grid.display(&BitGrid::new(grid.map[0].len(), grid.map.len()));
 
     let traveled = grid.travel(
         Coordinate::new(-1, grid.map.len() as i32 - 1),
         Direction::Right,
-    );
-    let part1 = traveled.len();
+    )?;
+    let part1 = traveled.count();
     info!("Part 1");
-    grid.display(traveled);
+    grid.display(&traveled);
+    dump::frame("day16-part1", || grid.render(&traveled));
 
     info!("Part 2");
-    let part2 = grid.maximum_energized();
+    let part2 = grid.maximum_energized()?;
 
     answer.part1 = Some(part1.to_string());
     answer.part2 = Some(part2.to_string());
     Ok(answer)
 }
 
+// every beam start on the gpu at once, only for the built in optics
+#[cfg(feature = "gpu")]
+pub fn solve_gpu(input: &str) -> Result<Answer> {
+    use crate::gpu::{BeamStart, Gpu};
+
+    let gpu = Gpu::get()?;
+    let rows = input
+        .lines()
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .collect::<Vec<_>>();
+    let height = rows.len() as i32;
+    let width = rows.first().map_or(0, |f| f.len()) as i32;
+
+    // directions are 0 up, 1 right, 2 down, 3 left, the first start is the one of part 1
+    let start = |x, y, direction| BeamStart { x, y, direction };
+    let starts = std::iter::once(start(0, 0, 1))
+        .chain((0..height).flat_map(|y| [start(0, y, 1), start(width - 1, y, 3)]))
+        .chain((0..width).flat_map(|x| [start(x, 0, 2), start(x, height - 1, 0)]))
+        .collect::<Vec<_>>();
+
+    let energized = gpu.energized(&rows, &starts)?;
+
+    Ok(Answer {
+        part1: Some(energized[0].to_string()),
+        part2: Some(energized.iter().max().unwrap().to_string()),
+    })
+}
+
 #[cfg(test)]
 mod tests {
-
     use tracing_test::traced_test;
 
     use super::*;
-    use color_eyre::eyre::Result;
-
-    const TEST_INPUT: &str = r".|...\....
-|.-.\.....
-.....|-...
-........|.
-..........
-.........\
-..../.\\..
-.-.-/..|..
-.|....-|.\
-..//.|....";
+    use crate::{test_support::fixture, utils::charset::Charset};
 
-    #[traced_test]
-    #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    // stops every beam that enters it
+    #[derive(Debug)]
+    struct Absorber;
+
+    impl Optic for Absorber {
+        fn next_directions(&self, _direction: Direction) -> Vec<Direction> {
+            vec![]
+        }
+
+        fn symbol(&self) -> char {
+            '#'
+        }
+    }
+
+    // splits a beam into itself and both of its sides
+    #[derive(Debug)]
+    struct Prism;
+
+    impl Optic for Prism {
+        fn next_directions(&self, direction: Direction) -> Vec<Direction> {
+            match direction {
+                Direction::Up | Direction::Down => {
+                    vec![direction, Direction::Left, Direction::Right]
+                }
+                _ => vec![direction, Direction::Up, Direction::Down],
+            }
+        }
 
-        assert_eq!(answer.part1, Some("46".to_string()));
+        fn symbol(&self) -> char {
+            '*'
+        }
+    }
 
-        Ok(())
+    #[traced_test]
+    #[test]
+    fn test_custom_optics() {
+        let optics: HashMap<char, Rc<dyn Optic>> = HashMap::from([
+            ('#', Rc::new(Absorber) as Rc<dyn Optic>),
+            ('*', Rc::new(Prism) as _),
+        ]);
+
+        let grid = Grid::with_optics("..#..\n", optics.clone());
+        let traveled = grid
+            .travel(Coordinate::new(-1, 0), Direction::Right)
+            .unwrap();
+        assert_eq!(traveled.count(), 3);
+        assert_eq!(grid.render(&BitGrid::new(5, 1)), "··#··\n");
+        assert_eq!(
+            charset::scoped(Charset::Ascii, || grid.render(&BitGrid::new(5, 1))),
+            "..#..\n"
+        );
+
+        let grid = Grid::with_optics(".....\n.*...\n.....\n", optics);
+        let traveled = grid
+            .travel(Coordinate::new(-1, 1), Direction::Right)
+            .unwrap();
+        assert_eq!(traveled.count(), 7);
     }
 
     #[traced_test]
     #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    fn test_with_node() {
+        let input = fixture("day16");
+        let grid = Grid::new(&input);
+        let top_left = Coordinate::new(-1, 9);
+
+        assert_eq!(grid.energized_from(top_left, Direction::Right).unwrap(), 46);
+        // the beam along the bottom row from the right never reaches the top left corner
+        assert_eq!(
+            grid.energized_from(Coordinate::new(10, 0), Direction::Left)
+                .unwrap(),
+            grid.travel(Coordinate::new(10, 0), Direction::Left)
+                .unwrap()
+                .count()
+        );
+
+        // the first tile of part 1 turned into a mirror that sends the beam down the first column
+        let corner = Coordinate::new(0, 9);
+        assert!(matches!(grid.node(corner), Some(Node::Empty)));
+        let changed = grid
+            .with_node(corner, Node::builtin('\\').unwrap())
+            .unwrap();
+        assert_eq!(changed.energized.borrow().len(), 1);
+
+        let mut rows = input.lines().map(|f| f.to_string()).collect::<Vec<_>>();
+        rows[0].replace_range(0..1, "\\");
+        let expected = Grid::new(&rows.join("\n"))
+            .travel(top_left, Direction::Right)
+            .unwrap()
+            .count();
+        assert_eq!(
+            changed.energized_from(top_left, Direction::Right).unwrap(),
+            expected
+        );
+        assert_ne!(expected, 46);
+
+        // the original grid is left as it was
+        assert_eq!(grid.energized_from(top_left, Direction::Right).unwrap(), 46);
+        assert!(grid.with_node(Coordinate::new(10, 0), Node::Empty).is_err());
+    }
 
-        assert_eq!(answer.part2, Some("51".to_string()));
+    fixture_test!(test_part1, super::solve, "day16", part1 = "46");
 
-        Ok(())
-    }
+    fixture_test!(test_part2, super::solve, "day16", part2 = "51");
 }
@@ -1,64 +1,183 @@
-use std::{
-    cmp::Ordering,
-    collections::{HashMap, HashSet, VecDeque},
-};
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    solver::Answer,
-    utils::{Coordinate, Direction, Part},
+    solver::{Answer, SolveContext},
+    utils::{
+        color::paint, dump, explain, guard::Guard, Coordinate, Direction, Part, PriorityQueue,
+    },
 };
 
-use color_eyre::eyre::Result;
-use tracing::info;
+use color_eyre::eyre::{bail, Result};
+use tracing::{info, info_span};
 
 struct Map {
     data: Vec<Vec<i32>>,
 }
 
-trait PriorityQueue {
-    fn priority_push(&mut self, new_queue: Queue);
-}
-
-#[derive(Debug, Eq)]
-struct Queue {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct State {
     coordinate: Coordinate<i32>,
     previous_direction: Direction,
     steps_in_this_direction: i32,
-    heat_loss: i32,
-    visited: HashSet<(Coordinate<i32>, Direction, i32)>,
 }
 
-impl PartialEq for Queue {
-    fn eq(&self, other: &Self) -> bool {
-        self.visited.len() == other.visited.len() && self.heat_loss == other.heat_loss
-    }
+// how far a crucible has to move in a straight line before it can turn, and how far it may
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crucible {
+    pub min_run: i32,
+    pub max_run: i32,
 }
 
-impl PartialOrd for Queue {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl Crucible {
+    // part 1
+    pub const REGULAR: Crucible = Crucible {
+        min_run: 1,
+        max_run: 3,
+    };
+    // part 2
+    pub const ULTRA: Crucible = Crucible {
+        min_run: 4,
+        max_run: 10,
+    };
+
+    pub fn new(min_run: i32, max_run: i32) -> Result<Self> {
+        // the steps of a search state are packed into STEP_BITS
+        let limit = (1 << STEP_BITS) - 1;
+
+        if min_run < 1 || min_run > max_run || max_run > limit {
+            bail!(
+                "runs of {}..={} are not supported, they have to satisfy 1 <= min <= max <= {}",
+                min_run,
+                max_run,
+                limit
+            );
+        }
+
+        Ok(Self { min_run, max_run })
     }
-}
 
-impl Ord for Queue {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.heat_loss
-            .cmp(&other.heat_loss)
-            .then_with(|| self.visited.len().cmp(&other.visited.len()))
+    fn for_part(part: Part) -> Self {
+        match part {
+            Part::One => Self::REGULAR,
+            Part::Two => Self::ULTRA,
+        }
     }
 }
 
-impl PriorityQueue for VecDeque<Queue> {
-    fn priority_push(&mut self, new_queue: Queue) {
-        let position = self.iter().position(|f| f > &new_queue);
+// how many expanded states between two search frames with --dump-state
+const DUMP_INTERVAL: usize = 1000;
+
+// the directions a search state can have, in the order of their packed index
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+// low bits of a packed state hold the steps in this direction, then 2 bits for the direction
+const STEP_BITS: u32 = 4;
+const NO_PARENT: u32 = u32::MAX;
+
+// every search state has a fixed slot, packed as (tile index, direction, steps) into a u32
+// so the visited set and the parents are flat vectors instead of hash maps of State
+struct StateTable {
+    width: usize,
+    visited: Vec<bool>,
+    came_from: Vec<u32>,
+    expanded: usize,
+}
+
+impl StateTable {
+    fn new(width: usize, height: usize) -> Self {
+        let len = (width * height) << (STEP_BITS + 2);
+        assert!(
+            len < NO_PARENT as usize,
+            "map is too large to pack its states"
+        );
+
+        Self {
+            width,
+            visited: vec![false; len],
+            came_from: vec![NO_PARENT; len],
+            expanded: 0,
+        }
+    }
+
+    fn pack(&self, state: &State) -> u32 {
+        let tile = state.coordinate.y as usize * self.width + state.coordinate.x as usize;
+        let direction = DIRECTIONS
+            .iter()
+            .position(|f| *f == state.previous_direction)
+            .unwrap();
+
+        assert!(state.steps_in_this_direction < 1 << STEP_BITS);
+
+        ((tile << (STEP_BITS + 2)) | (direction << STEP_BITS)) as u32
+            | state.steps_in_this_direction as u32
+    }
+
+    fn unpack(&self, key: u32) -> State {
+        let tile = (key >> (STEP_BITS + 2)) as usize;
+
+        State {
+            coordinate: Coordinate::new((tile % self.width) as i32, (tile / self.width) as i32),
+            previous_direction: DIRECTIONS[(key >> STEP_BITS) as usize & 0b11],
+            steps_in_this_direction: (key & ((1 << STEP_BITS) - 1)) as i32,
+        }
+    }
+
+    fn is_visited(&self, key: u32) -> bool {
+        self.visited[key as usize]
+    }
+
+    fn visit(&mut self, key: u32) {
+        self.visited[key as usize] = true;
+        self.expanded += 1;
+    }
 
-        match position {
-            Some(index) => self.insert(index, new_queue),
-            None => self.push_back(new_queue),
+    fn parent(&self, key: u32) -> Option<u32> {
+        match self.came_from[key as usize] {
+            NO_PARENT => None,
+            parent => Some(parent),
         }
     }
 }
 
+// optimal path, each step with the heat loss accumulated up to and including it
+struct Route {
+    heat_loss: i32,
+    steps: Vec<(State, i32)>,
+    // states the search expanded to find it
+    expanded: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Search {
+    Dijkstra,
+    // dijkstra from both ends, meeting in the middle
+    Bidirectional,
+}
+
+// one move of the optimal route, coordinates are counted from the top left like the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteStep {
+    pub coordinate: Coordinate<i32>,
+    pub direction: Direction,
+    // heat loss accumulated up to and including this tile
+    pub heat_loss: i32,
+}
+
+// ansi escape codes, so the path stands out from the digits around it
+fn direction_color(direction: Direction) -> u8 {
+    match direction {
+        Direction::North | Direction::Up => 36,
+        Direction::South | Direction::Down => 35,
+        Direction::East | Direction::Left => 33,
+        Direction::West | Direction::Right => 32,
+    }
+}
+
 impl Map {
     fn new(input: &str) -> Self {
         let mut data = vec![];
@@ -80,24 +199,34 @@ impl Map {
         Self { data }
     }
 
-    fn display(&self, queue: Option<&Queue>) {
-        let mut text = "\n".to_string();
+    // the grid and the legend of every step are part of --explain, not of every solve
+    fn display(&self, route: Option<&Route>) {
+        if explain::is_enabled() {
+            info!("\n{}", self.render(route));
+        }
+    }
 
-        let mut set = HashMap::new();
-        if let Some(queue) = queue {
-            queue.visited.iter().for_each(|f| {
-                set.insert(f.0, f.1);
-            });
-        };
+    fn render(&self, route: Option<&Route>) -> String {
+        let mut text = String::new();
+        let path = route
+            .map(|f| {
+                f.steps
+                    .iter()
+                    .map(|(state, _)| (state.coordinate, state.previous_direction))
+                    .collect::<HashMap<_, _>>()
+            })
+            .unwrap_or_default();
 
         for y_index in (0..self.data.len()).rev() {
             for x_index in 0..self.data[0].len() {
                 let coordinate = Coordinate::new(x_index as i32, y_index as i32);
+                let digit = self.data[y_index][x_index].to_string();
 
-                let value = if let Some(value) = set.get(&coordinate) {
-                    value.display().to_owned()
-                } else {
-                    self.data[y_index][x_index].to_string()
+                let value = match (route, path.get(&coordinate)) {
+                    (_, Some(&direction)) => paint(direction.display(), direction_color(direction)),
+                    // dim the digits that are not part of the path
+                    (Some(_), None) => paint(&digit, 2),
+                    (None, None) => digit,
                 };
 
                 text.push_str(&value);
@@ -105,22 +234,142 @@ impl Map {
             text.push('\n');
         }
 
-        info!("{}", text);
+        if let Some(route) = route {
+            text.push_str(&format!("\nheat loss: {}\n", route.heat_loss));
+
+            for (index, (state, heat_loss)) in route.steps.iter().enumerate() {
+                let direction = state.previous_direction;
+                text.push_str(&format!(
+                    "{:>4} {} ({}, {}) +{} = {}\n",
+                    index + 1,
+                    paint(direction.display(), direction_color(direction)),
+                    state.coordinate.x,
+                    // rows are counted from the top, like the input
+                    self.data.len() as i32 - 1 - state.coordinate.y,
+                    self.data[state.coordinate.y as usize][state.coordinate.x as usize],
+                    heat_loss
+                ));
+            }
+        }
+
+        text
     }
 
+    // tiles the search has already expanded, drawn over the digits
+    fn render_visited(&self, states: &StateTable) -> String {
+        let coordinates = (0..states.visited.len() as u32)
+            .filter(|&f| states.is_visited(f))
+            .map(|f| states.unpack(f).coordinate)
+            .collect::<HashSet<_>>();
+        let mut text = String::new();
+
+        for y_index in (0..self.data.len()).rev() {
+            for x_index in 0..self.data[0].len() {
+                let coordinate = Coordinate::new(x_index as i32, y_index as i32);
+
+                if coordinates.contains(&coordinate) {
+                    text.push('#');
+                } else {
+                    text.push_str(&self.data[y_index][x_index].to_string());
+                }
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    // walks the parents back from the final state
+    fn build_route(&self, states: &StateTable, last: u32, heat_loss: i32) -> Route {
+        let mut keys = vec![last];
+
+        while let Some(previous) = states.parent(*keys.last().unwrap()) {
+            keys.push(previous);
+        }
+
+        keys.reverse();
+
+        self.route_from_keys(states, keys, heat_loss, states.expanded)
+    }
+
+    fn route_from_keys(
+        &self,
+        states: &StateTable,
+        keys: Vec<u32>,
+        heat_loss: i32,
+        expanded: usize,
+    ) -> Route {
+        let mut total = 0;
+        let steps = keys
+            .into_iter()
+            .map(|key| {
+                let state = states.unpack(key);
+                total += self.data[state.coordinate.y as usize][state.coordinate.x as usize];
+                (state, total)
+            })
+            .collect();
+
+        Route {
+            heat_loss,
+            steps,
+            expanded,
+        }
+    }
+
+    fn display_route(&self, route: &Route, part: Part) {
+        self.display(Some(route));
+        dump::frame(&format!("day17-{}-route", part.name()), || {
+            self.render(Some(route))
+        });
+    }
+
+    fn to_route_steps(&self, route: &Route) -> Vec<RouteStep> {
+        route
+            .steps
+            .iter()
+            .map(|(state, heat_loss)| RouteStep {
+                coordinate: Coordinate::new(
+                    state.coordinate.x,
+                    self.data.len() as i32 - 1 - state.coordinate.y,
+                ),
+                direction: state.previous_direction,
+                heat_loss: *heat_loss,
+            })
+            .collect()
+    }
+
+    // from the top left to the bottom right corner
+    fn travel_corners(
+        &self,
+        part: Part,
+        crucible: Crucible,
+        search: Search,
+    ) -> Result<Option<Route>> {
+        let initial = Coordinate::new(0, self.data.len() as i32 - 1);
+        let target = Coordinate::new(self.data[0].len() as i32 - 1, 0);
+
+        match search {
+            Search::Dijkstra => self.travel(initial, target, part, crucible),
+            Search::Bidirectional => self.travel_bidirectional(initial, target, part, crucible),
+        }
+    }
+
+    // part only names the dumped frames, the crucible decides how the route may move
     fn travel(
         &self,
         initial_coordinate: Coordinate<i32>,
         target_coordinate: Coordinate<i32>,
         part: Part,
-    ) -> Option<i32> {
-        let mut stacks = VecDeque::new();
-        let mut visited = HashSet::new();
-
+        crucible: Crucible,
+    ) -> Result<Option<Route>> {
+        let mut queue = PriorityQueue::new();
+        let mut guard = Guard::new("day 17 search expansions");
         let max_y = self.data.len() as i32;
         let max_x = self.data[0].len() as i32;
 
-        // initially fill up stacks
+        let mut states = StateTable::new(max_x as usize, max_y as usize);
+
+        // initially fill up queue
         for direction in [
             Direction::Up,
             Direction::Left,
@@ -139,40 +388,39 @@ impl Map {
             }
 
             let next_heat_loss = self.data[next_coordinate.y as usize][next_coordinate.x as usize];
-            let queue = Queue {
+            let state = State {
                 coordinate: next_coordinate,
                 previous_direction: direction,
                 steps_in_this_direction: 1,
-                heat_loss: next_heat_loss,
-                visited: HashSet::from([(next_coordinate, direction, next_heat_loss)]),
             };
 
-            stacks.priority_push(queue);
+            queue.push(states.pack(&state), next_heat_loss);
         }
 
-        while let Some(current_queue) = stacks.pop_front() {
-            if current_queue.coordinate == target_coordinate {
-                if part == Part::Two && current_queue.steps_in_this_direction < 4 {
+        while let Some((current_key, heat_loss)) = queue.pop() {
+            let current_state = states.unpack(current_key);
+
+            if current_state.coordinate == target_coordinate {
+                if current_state.steps_in_this_direction < crucible.min_run {
                     continue;
                 }
 
-                self.display(Some(&current_queue));
-                return Some(current_queue.heat_loss);
+                let route = self.build_route(&states, current_key, heat_loss);
+                self.display_route(&route, part);
+                return Ok(Some(route));
             }
 
-            if visited.contains(&(
-                current_queue.coordinate,
-                current_queue.previous_direction,
-                current_queue.steps_in_this_direction,
-            )) {
-                continue;
-            }
+            guard.step()?;
+
+            // states are popped with their final heat loss, so each one only needs to expand once
+            states.visit(current_key);
 
-            visited.insert((
-                current_queue.coordinate,
-                current_queue.previous_direction,
-                current_queue.steps_in_this_direction,
-            ));
+            if states.expanded.is_multiple_of(DUMP_INTERVAL) {
+                dump::frame(
+                    &format!("day17-{}-expanded{}", part.name(), states.expanded),
+                    || self.render_visited(&states),
+                );
+            }
 
             for next_direction in [
                 Direction::Up,
@@ -180,19 +428,14 @@ impl Map {
                 Direction::Right,
                 Direction::Left,
             ] {
-                if next_direction == current_queue.previous_direction.reverse() {
+                if next_direction == current_state.previous_direction.reverse() {
                     // cannot go in reverse
                     continue;
                 }
 
-                let straight_limit = match part {
-                    Part::One => 3,
-                    Part::Two => 10,
-                };
-
                 let mut next_steps_in_this_direction = 1;
                 let modifier = next_direction.get_modifier(1);
-                let next_coordinate = current_queue.coordinate.add(modifier.0, modifier.1);
+                let next_coordinate = current_state.coordinate.add(modifier.0, modifier.1);
 
                 if next_coordinate.x < 0
                     || next_coordinate.y < 0
@@ -202,81 +445,330 @@ impl Map {
                     continue;
                 }
 
-                let next_heat_loss = current_queue.heat_loss
-                    + self.data[next_coordinate.y as usize][next_coordinate.x as usize];
+                let next_heat_loss =
+                    heat_loss + self.data[next_coordinate.y as usize][next_coordinate.x as usize];
 
-                if current_queue.previous_direction == next_direction {
-                    if current_queue.steps_in_this_direction == straight_limit {
-                        // cannot go straight more than 3 or 10 times
+                if current_state.previous_direction == next_direction {
+                    if current_state.steps_in_this_direction == crucible.max_run {
+                        // cannot go straight any further
                         continue;
                     }
 
-                    next_steps_in_this_direction = current_queue.steps_in_this_direction + 1;
+                    next_steps_in_this_direction = current_state.steps_in_this_direction + 1;
                 }
 
-                if part == Part::Two
-                    && current_queue.previous_direction != next_direction
-                    && current_queue.steps_in_this_direction < 4
+                if current_state.previous_direction != next_direction
+                    && current_state.steps_in_this_direction < crucible.min_run
                 {
-                    // need to go at least 4 times straight
+                    // has to keep going straight before it can turn
                     continue;
                 }
 
-                if visited.contains(&(
-                    next_coordinate,
-                    next_direction,
-                    next_steps_in_this_direction,
-                )) {
-                    continue;
-                }
-
-                let mut next_visited = current_queue.visited.clone();
-                next_visited.insert((
-                    next_coordinate,
-                    next_direction,
-                    next_steps_in_this_direction,
-                ));
-
-                let next_queue = Queue {
+                let next_state = State {
                     coordinate: next_coordinate,
                     previous_direction: next_direction,
                     steps_in_this_direction: next_steps_in_this_direction,
-                    heat_loss: next_heat_loss,
-                    visited: HashSet::new(),
                 };
 
-                stacks.priority_push(next_queue);
+                let next_key = states.pack(&next_state);
+
+                if states.is_visited(next_key) {
+                    continue;
+                }
+
+                // updates the queued heat loss in place if this route is cheaper
+                if queue.push(next_key, next_heat_loss) {
+                    states.came_from[next_key as usize] = current_key;
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    // the forward search gives each state the heat loss up to and including its tile, the backward
+    // search walks moves in reverse from the target and gives it the heat loss of the tiles after it,
+    // so a route through a state costs the sum of both and the searches can stop once their
+    // frontiers together cost more than the cheapest route seen
+    fn travel_bidirectional(
+        &self,
+        initial_coordinate: Coordinate<i32>,
+        target_coordinate: Coordinate<i32>,
+        part: Part,
+        crucible: Crucible,
+    ) -> Result<Option<Route>> {
+        let mut guard = Guard::new("day 17 search expansions");
+        let max_y = self.data.len() as i32;
+        let max_x = self.data[0].len() as i32;
+        let inside = |f: Coordinate<i32>| f.x >= 0 && f.y >= 0 && f.x < max_x && f.y < max_y;
+        let tile = |f: Coordinate<i32>| self.data[f.y as usize][f.x as usize];
+
+        // the parents of the backward table point at the next state towards the target
+        let mut forward = StateTable::new(max_x as usize, max_y as usize);
+        let mut backward = StateTable::new(max_x as usize, max_y as usize);
+        let mut forward_costs = vec![i32::MAX; forward.visited.len()];
+        let mut backward_costs = vec![i32::MAX; backward.visited.len()];
+        let mut forward_queue = PriorityQueue::new();
+        let mut backward_queue = PriorityQueue::new();
+
+        for direction in DIRECTIONS {
+            let modifier = direction.get_modifier(1);
+            let coordinate = initial_coordinate.add(modifier.0, modifier.1);
+
+            if inside(coordinate) {
+                let key = forward.pack(&State {
+                    coordinate,
+                    previous_direction: direction,
+                    steps_in_this_direction: 1,
+                });
+                forward_costs[key as usize] = tile(coordinate);
+                forward_queue.push(key, tile(coordinate));
+            }
+
+            // any state that is allowed to stop at the target ends a route
+            for steps in crucible.min_run..=crucible.max_run {
+                let key = backward.pack(&State {
+                    coordinate: target_coordinate,
+                    previous_direction: direction,
+                    steps_in_this_direction: steps,
+                });
+                backward_costs[key as usize] = 0;
+                backward_queue.push(key, 0);
             }
         }
 
-        None
+        // the cheapest complete route seen so far, and the state where its halves meet
+        let mut best: Option<(i32, u32)> = None;
+
+        for key in 0..forward_costs.len() as u32 {
+            meet(key, &forward_costs, &backward_costs, &mut best);
+        }
+
+        while let (Some(forward_top), Some(backward_top)) =
+            (forward_queue.peek(), backward_queue.peek())
+        {
+            if best.is_some_and(|(cost, _)| forward_top + backward_top >= cost) {
+                break;
+            }
+
+            guard.step()?;
+
+            if forward_top <= backward_top {
+                let (key, heat_loss) = forward_queue.pop().unwrap();
+                let state = forward.unpack(key);
+                forward.visit(key);
+
+                for next_direction in DIRECTIONS {
+                    let turning = next_direction != state.previous_direction;
+
+                    if next_direction == state.previous_direction.reverse()
+                        || (turning && state.steps_in_this_direction < crucible.min_run)
+                        || (!turning && state.steps_in_this_direction == crucible.max_run)
+                    {
+                        continue;
+                    }
+
+                    let modifier = next_direction.get_modifier(1);
+                    let coordinate = state.coordinate.add(modifier.0, modifier.1);
+                    if !inside(coordinate) {
+                        continue;
+                    }
+
+                    let next_key = forward.pack(&State {
+                        coordinate,
+                        previous_direction: next_direction,
+                        steps_in_this_direction: if turning {
+                            1
+                        } else {
+                            state.steps_in_this_direction + 1
+                        },
+                    });
+                    let next_heat_loss = heat_loss + tile(coordinate);
+
+                    if !forward.is_visited(next_key) && forward_queue.push(next_key, next_heat_loss)
+                    {
+                        forward_costs[next_key as usize] = next_heat_loss;
+                        forward.came_from[next_key as usize] = key;
+                        meet(next_key, &forward_costs, &backward_costs, &mut best);
+                    }
+                }
+            } else {
+                let (key, heat_loss) = backward_queue.pop().unwrap();
+                let state = backward.unpack(key);
+                backward.visit(key);
+
+                // the state was entered from the tile behind it, which costs this tile's heat loss
+                let modifier = state.previous_direction.get_modifier(1);
+                let coordinate = state.coordinate.add(-modifier.0, -modifier.1);
+                if !inside(coordinate) {
+                    continue;
+                }
+
+                let previous_heat_loss = heat_loss + tile(state.coordinate);
+                let mut previous_states = vec![];
+
+                if state.steps_in_this_direction > 1 {
+                    previous_states.push(State {
+                        coordinate,
+                        previous_direction: state.previous_direction,
+                        steps_in_this_direction: state.steps_in_this_direction - 1,
+                    });
+                } else {
+                    for direction in DIRECTIONS {
+                        if direction == state.previous_direction
+                            || direction == state.previous_direction.reverse()
+                        {
+                            continue;
+                        }
+
+                        for steps in crucible.min_run..=crucible.max_run {
+                            previous_states.push(State {
+                                coordinate,
+                                previous_direction: direction,
+                                steps_in_this_direction: steps,
+                            });
+                        }
+                    }
+                }
+
+                for previous in previous_states {
+                    let previous_key = backward.pack(&previous);
+
+                    if !backward.is_visited(previous_key)
+                        && backward_queue.push(previous_key, previous_heat_loss)
+                    {
+                        backward_costs[previous_key as usize] = previous_heat_loss;
+                        backward.came_from[previous_key as usize] = key;
+                        meet(previous_key, &forward_costs, &backward_costs, &mut best);
+                    }
+                }
+            }
+        }
+
+        let Some((heat_loss, meeting)) = best else {
+            return Ok(None);
+        };
+
+        let mut keys = vec![meeting];
+        while let Some(previous) = forward.parent(*keys.last().unwrap()) {
+            keys.push(previous);
+        }
+        keys.reverse();
+
+        while let Some(next) = backward.parent(*keys.last().unwrap()) {
+            keys.push(next);
+        }
+
+        let expanded = forward.expanded + backward.expanded;
+        let route = self.route_from_keys(&forward, keys, heat_loss, expanded);
+        self.display_route(&route, part);
+
+        Ok(Some(route))
     }
 }
 
+// keeps the cheapest route through a state both searches have reached
+fn meet(key: u32, forward_costs: &[i32], backward_costs: &[i32], best: &mut Option<(i32, u32)>) {
+    let (a, b) = (forward_costs[key as usize], backward_costs[key as usize]);
+
+    if a != i32::MAX && b != i32::MAX && best.is_none_or(|(cost, _)| a + b < cost) {
+        *best = Some((a + b, key));
+    }
+}
+
+// the route with the least heat loss, without the starting tile since it costs nothing
+pub fn optimal_route(input: &str, part: Part) -> Result<Option<Vec<RouteStep>>> {
+    let map = Map::new(input);
+    let route = map.travel_corners(part, Crucible::for_part(part), Search::Dijkstra)?;
+
+    Ok(route.map(|f| map.to_route_steps(&f)))
+}
+
+// the routes behind both answers, deposited in the SolveContext
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimalRoutes {
+    pub part1: Vec<RouteStep>,
+    pub part2: Vec<RouteStep>,
+}
+
 pub fn solve(input: &str) -> Result<Answer> {
+    solve_with_context(input, &mut SolveContext::new())
+}
+
+pub fn solve_with_context(input: &str, context: &mut SolveContext) -> Result<Answer> {
+    solve_with_crucible(input, context, Crucible::ULTRA)
+}
+
+// part 2 with a crucible other than the puzzle's ultra crucible
+pub fn solve_with_crucible(
+    input: &str,
+    context: &mut SolveContext,
+    ultra: Crucible,
+) -> Result<Answer> {
+    solve_with_search(input, context, ultra, Search::Dijkstra)
+}
+
+// searches from both corners at once, expanding fewer states on large maps
+pub fn solve_bidirectional(input: &str) -> Result<Answer> {
+    solve_with_search(
+        input,
+        &mut SolveContext::new(),
+        Crucible::ULTRA,
+        Search::Bidirectional,
+    )
+}
+
+fn solve_with_search(
+    input: &str,
+    context: &mut SolveContext,
+    ultra: Crucible,
+    search: Search,
+) -> Result<Answer> {
     let mut answer = Answer::default();
 
-    let map = Map::new(input);
+    let map = info_span!("parse").in_scope(|| Map::new(input));
     map.display(None);
 
-    let part1 = map
-        .travel(
-            Coordinate::new(0, map.data.len() as i32 - 1),
-            Coordinate::new(map.data[0].len() as i32 - 1, 0),
-            Part::One,
-        )
-        .unwrap();
-
-    let part2 = map
-        .travel(
-            Coordinate::new(0, map.data.len() as i32 - 1),
-            Coordinate::new(map.data[0].len() as i32 - 1, 0),
-            Part::Two,
-        )
-        .unwrap();
-
-    answer.part1 = Some(part1.to_string());
-    answer.part2 = Some(part2.to_string());
+    let mut heat_losses = vec![];
+    let mut routes = vec![];
+
+    for (part, crucible) in [(Part::One, Crucible::REGULAR), (Part::Two, ultra)] {
+        let _span = info_span!("part", part = part.name()).entered();
+
+        let Some(route) =
+            info_span!("search").in_scope(|| map.travel_corners(part, crucible, search))?
+        else {
+            bail!("no route to the bottom right corner for {}", part.name());
+        };
+        info!("{}: expanded {} states", part.name(), route.expanded);
+        let steps = map.to_route_steps(&route);
+
+        for (index, step) in steps.iter().enumerate() {
+            explain::line(|| {
+                format!(
+                    "{} step {:>3}: {} to ({}, {}), heat loss {}",
+                    part.name(),
+                    index + 1,
+                    step.direction.display(),
+                    step.coordinate.x,
+                    step.coordinate.y,
+                    step.heat_loss
+                )
+            });
+        }
+
+        heat_losses.push(route.heat_loss);
+        routes.push(steps);
+    }
+
+    let part2_route = routes.pop().unwrap();
+    context.insert(OptimalRoutes {
+        part1: routes.pop().unwrap(),
+        part2: part2_route,
+    });
+
+    answer.part1 = Some(heat_losses[0].to_string());
+    answer.part2 = Some(heat_losses[1].to_string());
 
     Ok(answer)
 }
@@ -286,69 +778,140 @@ mod tests {
     use tracing_test::traced_test;
 
     use super::*;
-    use color_eyre::eyre::Result;
-
-    const TEST_INPUT: &str = "2413432311323
-3215453535623
-3255245654254
-3446585845452
-4546657867536
-1438598798454
-4457876987766
-3637877979653
-4654967986887
-4564679986453
-1224686865563
-2546548887735
-4322674655533";
+    use crate::{test_support::fixture, utils::color};
+
+    fixture_test!(test_part1, super::solve, "day17", part1 = "102");
+
+    fixture_test!(test_part2, super::solve, "day17", part2 = "94");
 
     #[traced_test]
     #[test]
-    fn test_priority_push() {
-        fn create_queues(items: &[i32]) -> VecDeque<Queue> {
-            let mut queues = VecDeque::new();
-
-            for item in items {
-                let new_queue = Queue {
-                    coordinate: Coordinate::new(0, 0),
-                    previous_direction: Direction::Up,
-                    steps_in_this_direction: 0,
-                    heat_loss: *item,
-                    visited: HashSet::new(),
-                };
-                queues.priority_push(new_queue)
-            }
+    fn test_route() {
+        let map = Map::new(&fixture("day17"));
+        let target = Coordinate::new(12, 0);
+        let route = map
+            .travel(Coordinate::new(0, 12), target, Part::One, Crucible::REGULAR)
+            .unwrap()
+            .unwrap();
+
+        let (last, heat_loss) = route.steps.last().unwrap();
+        assert_eq!(last.coordinate, target);
+        assert_eq!(*heat_loss, route.heat_loss);
+
+        // the route starts next to the top left corner and moves one tile per step
+        assert_eq!(route.steps[0].0.coordinate, Coordinate::new(1, 12));
+        for pair in route.steps.windows(2) {
+            let (a, b) = (pair[0].0.coordinate, pair[1].0.coordinate);
+            assert_eq!((a.x - b.x).abs() + (a.y - b.y).abs(), 1);
+        }
 
-            queues
+        // escape codes only go to a terminal
+        let plain = color::scoped(false, || map.render(Some(&route)));
+        assert!(!plain.contains('\x1b'));
+        assert!(plain.contains("heat loss: 102"));
+        assert!(color::scoped(true, || map.render(Some(&route))).contains("\x1b[2m"));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_optimal_route() {
+        let input = fixture("day17");
+        let steps = optimal_route(&input, Part::Two).unwrap().unwrap();
+
+        // the route ends in the bottom right corner, in input coordinates
+        let last = steps.last().unwrap();
+        assert_eq!(last.coordinate, Coordinate::new(12, 12));
+        assert_eq!(last.heat_loss, 94);
+
+        // the heat loss grows by the digit of each tile entered
+        let rows = input.lines().collect::<Vec<_>>();
+        let mut total = 0;
+        for step in &steps {
+            let digit = rows[step.coordinate.y as usize].as_bytes()[step.coordinate.x as usize];
+            total += (digit - b'0') as i32;
+            assert_eq!(step.heat_loss, total);
         }
+    }
 
-        let mut items = vec![100, 20, 50, 20, 30, 0, 20, -5, 0];
+    #[traced_test]
+    #[test]
+    fn test_crucible() {
+        let input = "111111111111\n999999999991\n999999999991\n999999999991\n999999999991\n";
+        let mut context = SolveContext::new();
 
-        let queues = create_queues(&items);
+        let answer = solve_with_context(input, &mut context).unwrap();
+        assert_eq!(answer.part2.as_deref(), Some("71"));
 
-        let result = queues.iter().map(|f| f.heat_loss).collect::<Vec<_>>();
+        // with the regular crucible's runs part 2 is just part 1 again
+        let crucible = Crucible::new(1, 3).unwrap();
+        let answer = solve_with_crucible(input, &mut context, crucible).unwrap();
+        assert_eq!(answer.part1, answer.part2);
 
-        items.sort();
-        assert_eq!(items, result);
+        assert!(Crucible::new(0, 3).is_err());
+        assert!(Crucible::new(4, 3).is_err());
+        assert!(Crucible::new(4, 16).is_err());
     }
 
     #[traced_test]
     #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part1, Some("102".to_string()));
+    fn test_bidirectional() {
+        // a bigger map where meeting in the middle pays off
+        let large = (0..80)
+            .map(|y| {
+                let row = (0..80).map(|x| char::from(b'1' + ((x * 7 + y * 13 + x * y) % 9) as u8));
+                row.collect::<String>() + "\n"
+            })
+            .collect::<String>();
+
+        for input in [
+            fixture("day17"),
+            "111111111111\n999999999991\n999999999991\n999999999991\n999999999991\n".to_string(),
+            large.clone(),
+        ] {
+            assert_eq!(solve_bidirectional(&input).unwrap(), solve(&input).unwrap());
+        }
 
-        Ok(())
+        let map = Map::new(&large);
+        for part in [Part::One, Part::Two] {
+            let crucible = Crucible::for_part(part);
+            let dijkstra = map
+                .travel_corners(part, crucible, Search::Dijkstra)
+                .unwrap()
+                .unwrap();
+            let route = map
+                .travel_corners(part, crucible, Search::Bidirectional)
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(route.heat_loss, dijkstra.heat_loss);
+            assert_eq!(route.steps.last().unwrap().1, route.heat_loss);
+            assert!(
+                route.expanded < dijkstra.expanded,
+                "{} {}",
+                route.expanded,
+                dijkstra.expanded
+            );
+        }
     }
 
     #[traced_test]
     #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part2, Some("94".to_string()));
+    fn test_pack_states() {
+        let states = StateTable::new(13, 13);
 
-        Ok(())
+        for state in [
+            State {
+                coordinate: Coordinate::new(0, 0),
+                previous_direction: Direction::Up,
+                steps_in_this_direction: 1,
+            },
+            State {
+                coordinate: Coordinate::new(12, 7),
+                previous_direction: Direction::Left,
+                steps_in_this_direction: 10,
+            },
+        ] {
+            assert_eq!(states.unpack(states.pack(&state)), state);
+        }
     }
 }
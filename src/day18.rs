@@ -2,19 +2,80 @@ use std::{ops::Div, str::FromStr};
 
 use crate::{
     solver::Answer,
-    utils::{Coordinate, Direction, Part},
+    utils::{
+        polygon::{self, Orientation},
+        Coordinate, Direction,
+    },
 };
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{bail, Result};
+use tracing::info;
+
+// turns one line of the dig plan into a direction and a number of steps
+pub trait Decoder {
+    fn decode(&self, line: &str) -> (Direction, i64);
+}
+
+// any closure can be used as a one off decoder
+impl<F: Fn(&str) -> (Direction, i64)> Decoder for F {
+    fn decode(&self, line: &str) -> (Direction, i64) {
+        self(line)
+    }
+}
+
+// part 1, "R 6 (#70c710)" reads as 6 steps to the right
+pub struct PlainDecoder;
+
+impl Decoder for PlainDecoder {
+    fn decode(&self, line: &str) -> (Direction, i64) {
+        let vec = line.split_whitespace().collect::<Vec<&str>>();
+
+        assert_eq!(vec.len(), 3);
+
+        let direction = Direction::from_str(vec[0]).unwrap();
+        let steps = vec[1].parse::<i64>().unwrap();
+
+        (direction, steps)
+    }
+}
+
+// part 2, the first five hex digits of the color are the steps and the last one the direction
+pub struct HexDecoder;
+
+impl Decoder for HexDecoder {
+    fn decode(&self, line: &str) -> (Direction, i64) {
+        let vec = line.split_whitespace().collect::<Vec<&str>>();
+
+        assert_eq!(vec.len(), 3);
+
+        let hex_str = vec[2].replace(['(', ')', '#'], "");
+
+        let direction_str = match hex_str.chars().last().unwrap() {
+            '0' => "R",
+            '1' => "D",
+            '2' => "L",
+            '3' => "U",
+            _ => unreachable!(),
+        };
+
+        let direction = Direction::from_str(direction_str).unwrap();
+        let steps = i64::from_str_radix(&hex_str[0..hex_str.len() - 1], 16).unwrap();
+
+        (direction, steps)
+    }
+}
 
 #[derive(Debug)]
 struct Map {
+    // counter-clockwise after normalizing
     coordinates: Vec<Coordinate<i64>>,
     perimeter: i64,
+    // how the plan itself winds, None if the trench encloses nothing
+    orientation: Option<Orientation>,
 }
 
 impl Map {
-    fn new(input: &str, part: Part) -> Self {
+    fn new(input: &str, decoder: &dyn Decoder) -> Result<Self> {
         let mut coordinates = Vec::new();
         let mut coordinate = Coordinate::new(0, 0);
         let mut perimeter = 0;
@@ -24,32 +85,7 @@ impl Map {
                 continue;
             }
 
-            let vec = line.split_whitespace().collect::<Vec<&str>>();
-
-            assert_eq!(vec.len(), 3);
-
-            let (direction_str, steps) = match part {
-                Part::One => (vec[0], vec[1].parse::<i64>().unwrap()),
-                Part::Two => {
-                    let mut hex_str = vec[2].to_owned();
-
-                    hex_str = hex_str.replace(['(', ')', '#'], "");
-
-                    let direction_str = match hex_str.chars().last().unwrap() {
-                        '0' => "R",
-                        '1' => "D",
-                        '2' => "L",
-                        '3' => "U",
-                        _ => unreachable!(),
-                    };
-
-                    let steps = i64::from_str_radix(&hex_str[0..hex_str.len() - 1], 16).unwrap();
-
-                    (direction_str, steps)
-                }
-            };
-
-            let direction = Direction::from_str(direction_str).unwrap();
+            let (direction, steps) = decoder.decode(line);
             let modifier = direction.get_modifier(steps as i32);
 
             coordinate = coordinate.add(modifier.0 as i64, modifier.1 as i64);
@@ -58,39 +94,167 @@ impl Map {
             perimeter += steps;
         }
 
-        Self {
+        if coordinate != Coordinate::new(0, 0) {
+            bail!(
+                "the dig plan does not return to where it started, it ends at ({}, {})",
+                coordinate.x,
+                coordinate.y
+            );
+        }
+
+        let orientation = polygon::orientation(&coordinates);
+        polygon::normalize(&mut coordinates);
+
+        Ok(Self {
             coordinates,
             perimeter,
-        }
+            orientation,
+        })
     }
 
     fn calculate_area(&self) -> i64 {
         // reference:
         // https://en.wikipedia.org/wiki/Pick%27s_theorem
-        // https://en.wikipedia.org/wiki/Shoelace_formula
 
-        let mut area = 0;
+        let area = polygon::shoelace(&self.coordinates);
+
+        area.div(2) + self.perimeter.div(2) + 1
+    }
+}
+
+// a horizontal stretch of dug tiles, start and end included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    pub y: i64,
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Run {
+    pub fn len(&self) -> i64 {
+        self.end - self.start + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end < self.start
+    }
+}
+
+// the dug tiles of a plan, trench included, row by row without ever holding the whole lagoon
+// rows between two corners all look the same, so the edges are only swept once per corner
+pub struct Lagoon {
+    corners: Vec<Coordinate<i64>>,
+    // the distinct y of the corners, in order
+    rows: Vec<i64>,
+}
 
-        for index in 0..self.coordinates.len() {
-            let current = self.coordinates[index];
-            let next = self.coordinates[(index + 1) % self.coordinates.len()];
+impl Lagoon {
+    pub fn new(input: &str, decoder: &dyn Decoder) -> Result<Self> {
+        let corners = Map::new(input, decoder)?.coordinates;
+        let mut rows = corners.iter().map(|f| f.y).collect::<Vec<_>>();
+        rows.sort_unstable();
+        rows.dedup();
 
-            area += current.x * next.y;
-            area -= next.x * current.y;
+        Ok(Self { corners, rows })
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Coordinate<i64>, Coordinate<i64>)> + '_ {
+        self.corners
+            .iter()
+            .zip(self.corners.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    }
+
+    // the runs of row y, left to right
+    pub fn row(&self, y: i64) -> Vec<Run> {
+        let mut spans = vec![];
+
+        // the inside just above and just below the row, each vertical edge crossing the line
+        // between those flips in and out
+        for crosses in [
+            (|low, high, y| low <= y && y < high) as fn(i64, i64, i64) -> bool,
+            |low, high, y| low < y && y <= high,
+        ] {
+            let mut crossings = self
+                .edges()
+                .filter(|(a, b)| a.x == b.x && crosses(a.y.min(b.y), a.y.max(b.y), y))
+                .map(|(a, _)| a.x)
+                .collect::<Vec<_>>();
+            crossings.sort_unstable();
+            spans.extend(crossings.chunks_exact(2).map(|f| (f[0], f[1])));
         }
 
-        area.abs().div(2) + self.perimeter.div(2) + 1
+        // the trench itself, which is all there is of a plan that encloses nothing
+        for (a, b) in self.edges() {
+            if a.y.min(b.y) <= y && y <= a.y.max(b.y) {
+                spans.push((a.x.min(b.x), a.x.max(b.x)));
+            }
+        }
+
+        merge(spans)
+            .into_iter()
+            .map(|(start, end)| Run { y, start, end })
+            .collect()
     }
+
+    // every run, by increasing y
+    pub fn runs(&self) -> impl Iterator<Item = Run> + '_ {
+        let corner_rows = self.rows.iter().map(move |f| self.row(*f));
+        let between = self
+            .rows
+            .windows(2)
+            .map(move |f| (f[0] + 1..f[1], self.row(f[0] + 1)));
+
+        corner_rows
+            .zip(between.map(Some).chain([None]))
+            .flat_map(|(corner, between)| {
+                let between = between.into_iter().flat_map(|(ys, runs)| {
+                    ys.flat_map(move |y| runs.clone().into_iter().map(move |f| Run { y, ..f }))
+                });
+                corner.into_iter().chain(between)
+            })
+    }
+
+    pub fn tiles(&self) -> impl Iterator<Item = Coordinate<i64>> + '_ {
+        self.runs()
+            .flat_map(|f| (f.start..=f.end).map(move |x| Coordinate::new(x, f.y)))
+    }
+
+    pub fn contains(&self, tile: Coordinate<i64>) -> bool {
+        self.row(tile.y)
+            .iter()
+            .any(|f| f.start <= tile.x && tile.x <= f.end)
+    }
+}
+
+// overlapping and touching spans become one
+fn merge(mut spans: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+    spans.sort_unstable();
+
+    let mut merged: Vec<(i64, i64)> = vec![];
+    for (start, end) in spans {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    merged
+}
+
+// tiles dug out by the plan, trench included
+pub fn lagoon_area(input: &str, decoder: &dyn Decoder) -> Result<i64> {
+    let map = Map::new(input, decoder)?;
+    info!("dig plan winds {:?}", map.orientation);
+
+    Ok(map.calculate_area())
 }
 
 pub fn solve(input: &str) -> Result<Answer> {
     let mut answer = Answer::default();
 
-    let map = Map::new(input, Part::One);
-    let part1 = map.calculate_area();
-
-    let map = Map::new(input, Part::Two);
-    let part2 = map.calculate_area();
+    let part1 = lagoon_area(input, &PlainDecoder)?;
+    let part2 = lagoon_area(input, &HexDecoder)?;
 
     answer.part1 = Some(part1.to_string());
     answer.part2 = Some(part2.to_string());
@@ -99,44 +263,111 @@ pub fn solve(input: &str) -> Result<Answer> {
 
 #[cfg(test)]
 mod tests {
-
     use tracing_test::traced_test;
 
     use super::*;
-    use color_eyre::eyre::Result;
-
-    const TEST_INPUT: &str = "R 6 (#70c710)
-D 5 (#0dc571)
-L 2 (#5713f0)
-D 2 (#d2c081)
-R 2 (#59c680)
-D 2 (#411b91)
-L 5 (#8ceee2)
-U 2 (#caa173)
-L 1 (#1b58a2)
-U 2 (#caa171)
-R 2 (#7807d2)
-U 3 (#a77fa3)
-L 2 (#015232)
-U 2 (#7a21e3)";
 
     #[traced_test]
     #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part1, Some("62".to_string()));
+    fn test_decoders() {
+        assert_eq!(PlainDecoder.decode("R 6 (#70c710)"), (Direction::Right, 6));
+        assert_eq!(
+            HexDecoder.decode("R 6 (#70c710)"),
+            (Direction::Right, 461937)
+        );
+        assert_eq!(HexDecoder.decode("D 5 (#0dc571)"), (Direction::Down, 56407));
+
+        // a 3 by 3 square from a compact encoding like "R3"
+        let compact = |line: &str| {
+            let (direction, steps) = line.split_at(1);
+            (
+                Direction::from_str(direction).unwrap(),
+                steps.parse().unwrap(),
+            )
+        };
+        assert_eq!(lagoon_area("R2\nD2\nL2\nU2\n", &compact).unwrap(), 9);
+    }
 
-        Ok(())
+    #[traced_test]
+    #[test]
+    fn test_orientation() {
+        let clockwise = Map::new(
+            "R 2 (#000000)\nD 2 (#000000)\nL 2 (#000000)\nU 2 (#000000)\n",
+            &PlainDecoder,
+        )
+        .unwrap();
+        let counter_clockwise = Map::new(
+            "D 2 (#000000)\nR 2 (#000000)\nU 2 (#000000)\nL 2 (#000000)\n",
+            &PlainDecoder,
+        )
+        .unwrap();
+
+        assert_eq!(clockwise.orientation, Some(Orientation::Clockwise));
+        assert_eq!(
+            counter_clockwise.orientation,
+            Some(Orientation::CounterClockwise)
+        );
+        assert_eq!(clockwise.calculate_area(), 9);
+        assert_eq!(counter_clockwise.calculate_area(), 9);
+
+        let error = lagoon_area("R 2 (#000000)\nD 2 (#000000)\n", &PlainDecoder).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the dig plan does not return to where it started, it ends at (2, -2)"
+        );
     }
 
     #[traced_test]
     #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    fn test_lagoon() {
+        let input = crate::test_support::fixture("day18");
+        let lagoon = Lagoon::new(&input, &PlainDecoder).unwrap();
+
+        assert_eq!(lagoon.runs().map(|f| f.len()).sum::<i64>(), 62);
+        assert_eq!(lagoon.tiles().count(), 62);
+        assert!(lagoon.runs().all(|f| !f.is_empty()));
+
+        let runs = lagoon.runs().collect::<Vec<_>>();
+        assert!(runs
+            .windows(2)
+            .all(|f| (f[0].y, f[0].start) < (f[1].y, f[1].start)));
+        assert!(lagoon.tiles().all(|f| lagoon.contains(f)));
+        assert!(lagoon.contains(Coordinate::new(0, 0)));
+        assert!(!lagoon.contains(Coordinate::new(-1, 0)));
+        assert!(!lagoon.contains(Coordinate::new(0, 1)));
+
+        // a trench that encloses nothing is still dug
+        let line = Lagoon::new("R 3 (#000000)\nL 3 (#000000)\n", &PlainDecoder).unwrap();
+        assert_eq!(
+            line.runs().collect::<Vec<_>>(),
+            [Run {
+                y: 0,
+                start: 0,
+                end: 3
+            }]
+        );
+
+        // the part 2 lagoon has too many tiles to list, its rows are still cheap to ask about
+        let lagoon = Lagoon::new(&input, &HexDecoder).unwrap();
+        assert_eq!(
+            lagoon.runs().take(1000).map(|f| f.y).max(),
+            lagoon.runs().nth(999).map(|f| f.y)
+        );
+        let area = lagoon_area(&input, &HexDecoder).unwrap();
+        let rows = lagoon
+            .rows
+            .windows(2)
+            .map(|f| {
+                let corner = lagoon.row(f[0]).iter().map(|f| f.len()).sum::<i64>();
+                let between = lagoon.row(f[0] + 1).iter().map(|f| f.len()).sum::<i64>();
+                corner + between * (f[1] - f[0] - 1)
+            })
+            .sum::<i64>();
+        let last = lagoon.row(*lagoon.rows.last().unwrap());
+        assert_eq!(rows + last.iter().map(|f| f.len()).sum::<i64>(), area);
+    }
 
-        assert_eq!(answer.part2, Some("952408144115".to_string()));
+    fixture_test!(test_part1, super::solve, "day18", part1 = "62");
 
-        Ok(())
-    }
+    fixture_test!(test_part2, super::solve, "day18", part2 = "952408144115");
 }
@@ -1,26 +1,112 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+#[cfg(feature = "tooling")]
+use crate::cache::{self, Cached};
+use crate::{
+    error::{Error, Result},
+    solver::Answer,
+    utils::{
+        graph::Graph,
+        parse::{self, expected, hint, name, parse_line},
+    },
+};
+use color_eyre::eyre::bail;
+use rayon::prelude::*;
+#[cfg(feature = "tooling")]
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use winnow::{
+    ascii::{alpha1, digit1},
+    combinator::{alt, delimited, separated, separated_pair},
+    token::one_of,
+    Parser,
+};
+
+// inclusive (min, max) range of a category value
+type Interval = (i32, i32);
+
+// one comma separated step of a workflow, as slices of its line so errors can point at them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step<'a> {
+    // category, operator, value and where matching parts go, like "a<2006:qkq"
+    Condition(&'a str, &'a str, &'a str, &'a str),
+    Target(&'a str),
+}
+
+fn step<'a>(input: &mut &'a str) -> parse::Result<Step<'a>> {
+    let condition = (
+        one_of(['x', 'm', 'a', 's']).take(),
+        one_of(['<', '>']).take(),
+        digit1,
+        ':',
+        name,
+    )
+        .map(|(category, op, value, _, target)| Step::Condition(category, op, value, target));
+
+    alt((condition, name.map(Step::Target))).parse_next(input)
+}
+
+// "px{a<2006:qkq,m>2090:A,rfg}"
+fn workflow<'a>(input: &mut &'a str) -> parse::Result<(&'a str, Vec<Step<'a>>)> {
+    (name, delimited('{', separated(1.., step, ','), '}'))
+        .context(expected("a workflow"))
+        .context(hint("workflows look like `px{a<2006:qkq,rfg}`"))
+        .parse_next(input)
+}
+
+// where a rule sends an item, workflow names are interned to their index in System::workflows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
+enum Target {
+    Accept,
+    Reject,
+    Workflow(usize),
+}
 
-use crate::solver::Answer;
-use color_eyre::eyre::Result;
-use regex::Regex;
+impl Target {
+    fn new(name: &str, names: &HashMap<&str, usize>) -> Option<Self> {
+        match name {
+            "A" => Some(Self::Accept),
+            "R" => Some(Self::Reject),
+            _ => names.get(name).map(|f| Self::Workflow(*f)),
+        }
+    }
+}
 
 #[derive(Debug)]
-struct System {
-    workflows: HashMap<String, Rule>,
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
+pub struct System {
+    workflows: Vec<Rule>,
+    // indexed like workflows, only kept for reporting
+    names: Vec<String>,
+    start: usize,
     items: Vec<Item>,
 }
 
-impl System {
-    fn new(input: &str) -> Self {
+#[cfg(feature = "tooling")]
+impl Cached for System {
+    const NAME: &'static str = "day19";
+    const FORMAT: u32 = 1;
+}
+
+// workflows like "px{a<2006:qkq,m>2090:A,rfg}", a blank line, then parts like "{x=787,m=2655,a=1222,s=2876}"
+impl FromStr for System {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Error> {
         enum Mode {
             Workflow,
             Input,
         }
         let mut mode = Mode::Workflow;
-        let mut workflows = HashMap::new();
+        let mut parsed = vec![];
         let mut items = vec![];
 
-        for line in input.lines() {
+        for (index, line) in input.lines().enumerate() {
             if line.is_empty() {
                 mode = Mode::Input;
                 continue;
@@ -28,123 +114,347 @@ impl System {
 
             match mode {
                 Mode::Workflow => {
-                    let vec = line.split('{').collect::<Vec<&str>>();
+                    let (name, steps) = parse_line(index, line, workflow)?;
 
-                    assert_eq!(vec.len(), 2);
-
-                    let id = vec[0].to_owned();
-                    let rule_str = vec[1].replace('}', "").to_owned();
-
-                    let rule = Rule::new(&rule_str);
-                    workflows.insert(id, rule);
+                    // rules may point at workflows defined further down, so they are resolved once every name is known
+                    parsed.push((index, line, name, steps));
                 }
                 Mode::Input => {
-                    let item = Item::new(line);
+                    let item = line.parse::<Item>().map_err(|f| f.on_line(index))?;
                     items.push(item);
                 }
             }
         }
 
-        Self { workflows, items }
+        let names = parsed
+            .iter()
+            .enumerate()
+            .map(|(index, (_, _, name, _))| (*name, index))
+            .collect::<HashMap<&str, usize>>();
+
+        let workflows = parsed
+            .iter()
+            .map(|(index, line, _, steps)| {
+                Rule::new(line, steps, &names).map_err(|f| f.on_line(*index))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let start = *names
+            .get("in")
+            .ok_or_else(|| Error::parse(0, "there is no `in` workflow"))?;
+
+        Ok(Self {
+            workflows,
+            names: parsed
+                .iter()
+                .map(|(_, _, name, _)| name.to_string())
+                .collect(),
+            start,
+            items,
+        })
+    }
+}
+
+impl System {
+    // a part that comes back to a workflow it went through will keep going around
+    fn is_accepted(&self, item: &Item) -> Result<bool> {
+        let mut visited = vec![false; self.workflows.len()];
+        let mut current = self.start;
+
+        loop {
+            if std::mem::replace(&mut visited[current], true) {
+                bail!(
+                    "part {} loops through workflow `{}` forever",
+                    item,
+                    self.names[current]
+                );
+            }
+
+            match self.workflows[current].process(item) {
+                Target::Accept => return Ok(true),
+                Target::Reject => return Ok(false),
+                Target::Workflow(next) => current = next,
+            }
+        }
+    }
+
+    fn get_accepted_value(&self) -> Result<i32> {
+        self.items
+            .par_iter()
+            .map(|item| Ok(self.is_accepted(item)? as i32 * item.get_total()))
+            .sum()
     }
 
-    fn get_accepted_value(&self) -> i32 {
+    fn get_accepted_combinations(&self) -> Result<i64> {
+        let ranges = HashMap::from([
+            (Category::X, (1, 4000)),
+            (Category::M, (1, 4000)),
+            (Category::A, (1, 4000)),
+            (Category::S, (1, 4000)),
+        ]);
+
+        let mut on_path = vec![false; self.workflows.len()];
+        self.count_accepted(Target::Workflow(self.start), ranges, &mut on_path)
+    }
+
+    // on_path marks the workflows the ranges went through to get here, the ranges are never empty,
+    // so coming back to one of them means some parts go around forever
+    fn count_accepted(
+        &self,
+        target: Target,
+        ranges: HashMap<Category, Interval>,
+        on_path: &mut [bool],
+    ) -> Result<i64> {
+        let index = match target {
+            Target::Accept => {
+                return Ok(ranges
+                    .values()
+                    .map(|(min, max)| (max - min + 1) as i64)
+                    .product())
+            }
+            Target::Reject => return Ok(0),
+            Target::Workflow(index) => index,
+        };
+        if on_path[index] {
+            bail!(
+                "parts can loop through workflow `{}` forever",
+                self.names[index]
+            );
+        }
+
+        on_path[index] = true;
+        let total = self.count_workflow(&self.workflows[index], ranges, on_path);
+        on_path[index] = false;
+
+        total
+    }
+
+    fn count_workflow(
+        &self,
+        rule: &Rule,
+        mut ranges: HashMap<Category, Interval>,
+        on_path: &mut [bool],
+    ) -> Result<i64> {
         let mut total = 0;
-        for item in &self.items {
-            let mut current_id = "in";
 
-            loop {
-                let rule = self.workflows.get(current_id).unwrap();
-                current_id = rule.process(item);
+        for condition in &rule.conditions {
+            let (min, max) = *ranges.get(&condition.category).unwrap();
+            let (matched, rest) = condition.check.split(min, max);
+
+            if let Some(matched) = matched {
+                let mut next_ranges = ranges.clone();
+                next_ranges.insert(condition.category, matched);
+                total += self.count_accepted(condition.check.destination, next_ranges, on_path)?;
+            }
 
-                if current_id == "A" || current_id == "R" {
-                    if current_id == "A" {
-                        total += item.get_total();
-                    }
+            match rest {
+                Some(rest) => ranges.insert(condition.category, rest),
+                None => return Ok(total), // nothing left to fall through to the next condition
+            };
+        }
+
+        Ok(total + self.count_accepted(rule.default, ranges, on_path)?)
+    }
 
-                    break;
+    // mistakes that still parse: conditions that never match, workflows nothing sends parts to and
+    // workflows that can send a part back to themselves, which would never finish
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = vec![];
+
+        for (index, rule) in self.workflows.iter().enumerate() {
+            // the parts that fall through every condition so far, each condition only narrows its
+            // own category, so this is exact and it is empty as soon as one category is
+            let mut ranges = [(1, 4000); 4];
+            let mut exhausted = false;
+
+            for (position, condition) in rule.conditions.iter().enumerate() {
+                let category = condition.category as usize;
+                let (min, max) = ranges[category];
+                let (matched, rest) = match exhausted {
+                    false => condition.check.split(min, max),
+                    true => (None, None),
+                };
+
+                // earlier conditions already took every value this one matches
+                if matched.is_none() {
+                    lints.push(Lint::UnreachableCondition {
+                        workflow: self.names[index].clone(),
+                        position,
+                    });
                 }
+
+                match rest {
+                    Some(rest) => ranges[category] = rest,
+                    None => exhausted = true,
+                }
+            }
+
+            if exhausted {
+                lints.push(Lint::UnreachableDefault(self.names[index].clone()));
             }
         }
 
-        total
+        let mut graph = Graph::new();
+        for index in 0..self.workflows.len() {
+            graph.add_node(index);
+        }
+        for (index, rule) in self.workflows.iter().enumerate() {
+            let targets = rule.conditions.iter().map(|f| f.check.destination);
+
+            for target in targets.chain([rule.default]) {
+                if let Target::Workflow(next) = target {
+                    graph.add_edge(index, next, 1);
+                }
+            }
+        }
+
+        let mut reachable = vec![false; self.workflows.len()];
+        let mut stack = vec![self.start];
+        while let Some(index) = stack.pop() {
+            if !std::mem::replace(&mut reachable[index], true) {
+                stack.extend(graph.neighbors(index).iter().map(|(next, _)| *next));
+            }
+        }
+
+        for (index, name) in self.names.iter().enumerate() {
+            if !reachable[index] {
+                lints.push(Lint::UnusedWorkflow(name.clone()));
+            }
+        }
+
+        for mut component in graph.strongly_connected_components() {
+            let looping = component.len() > 1
+                || graph
+                    .neighbors(component[0])
+                    .iter()
+                    .any(|(f, _)| *f == component[0]);
+
+            if looping {
+                component.sort();
+                lints.push(Lint::Cycle(
+                    component.iter().map(|f| self.names[*f].clone()).collect(),
+                ));
+            }
+        }
+
+        lints
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lint {
+    // position is the index of the condition within the workflow, starting at 0
+    UnreachableCondition { workflow: String, position: usize },
+    UnreachableDefault(String),
+    UnusedWorkflow(String),
+    Cycle(Vec<String>),
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lint::UnreachableCondition { workflow, position } => write!(
+                f,
+                "condition {} of workflow `{}` can never match",
+                position + 1,
+                workflow
+            ),
+            Lint::UnreachableDefault(workflow) => {
+                write!(
+                    f,
+                    "no part ever reaches the default of workflow `{}`",
+                    workflow
+                )
+            }
+            Lint::UnusedWorkflow(workflow) => {
+                write!(f, "workflow `{}` is never reached from `in`", workflow)
+            }
+            Lint::Cycle(workflows) => {
+                write!(f, "workflows `{}` can loop forever", workflows.join("`, `"))
+            }
+        }
     }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
 struct Condition {
     category: Category,
     check: Check,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
 struct Rule {
     conditions: Vec<Condition>,
-    default: String,
+    default: Target,
 }
 
 impl Rule {
-    fn new(rule_str: &str) -> Self {
-        let mut conditions = vec![];
-        let mut default = String::new();
-        let mut iterator = rule_str.split(',').peekable();
-
-        while let Some(item) = iterator.next() {
-            if iterator.peek().is_none() {
-                default = item.to_owned()
-            } else {
-                let re = Regex::new(r"([xmas])([<>])(\d*):(.*)").unwrap();
-                let captures = re.captures(item).unwrap();
-
-                assert_eq!(captures.len(), 5);
-
-                let category = Category::new(captures.get(1).unwrap().as_str());
-
-                let check = Check::new(
-                    captures.get(2).unwrap().as_str(),
-                    captures.get(3).unwrap().as_str(),
-                    captures.get(4).unwrap().as_str(),
-                );
-
-                conditions.push(Condition { category, check });
+    // line is what the steps were sliced from, so errors point at the right column
+    fn new(line: &str, steps: &[Step], names: &HashMap<&str, usize>) -> Result<Self, Error> {
+        let target = |name: &str| {
+            Target::new(name, names)
+                .ok_or_else(|| Error::parse(0, "unknown workflow").at_token(line, name))
+        };
+        let (last, steps) = steps.split_last().unwrap();
+
+        let default = match last {
+            Step::Target(name) => target(name)?,
+            Step::Condition(category, ..) => {
+                return Err(Error::parse(0, "expected a default workflow")
+                    .at_token(line, category)
+                    .with_hint("the last rule of a workflow is just where parts go, like `rfg`"))
             }
-        }
-
-        Self {
+        };
+
+        let conditions = steps
+            .iter()
+            .map(|f| match *f {
+                Step::Condition(category, op, value, destination) => Ok(Condition {
+                    category: category.parse::<Category>()?,
+                    check: Check {
+                        op: op.to_owned(),
+                        value: value.parse().map_err(|_| {
+                            Error::parse(0, "value out of range").at_token(line, value)
+                        })?,
+                        destination: target(destination)?,
+                    },
+                }),
+                Step::Target(name) => Err(Error::parse(0, "expected a condition")
+                    .at_token(line, name)
+                    .with_hint("conditions look like `a<2006:qkq`")),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
             conditions,
             default,
-        }
+        })
     }
 
-    fn process(&self, item: &Item) -> &str {
+    fn process(&self, item: &Item) -> Target {
         for condition in &self.conditions {
-            let item_value = item.component.get(&condition.category).unwrap();
+            let item_value = item.component[condition.category as usize];
 
-            if condition.check.compare(*item_value) {
-                return &condition.check.destination;
+            if condition.check.compare(item_value) {
+                return condition.check.destination;
             }
         }
 
-        &self.default
+        self.default
     }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
 struct Check {
     op: String,
     value: i32,
-    destination: String,
+    destination: Target,
 }
 
 impl Check {
-    fn new(op: &str, value: &str, destination: &str) -> Self {
-        Self {
-            op: op.to_owned(),
-            value: value.parse().unwrap(),
-            destination: destination.to_owned(),
-        }
-    }
-
     fn compare(&self, item_value: i32) -> bool {
         match self.op.as_str() {
             "<" => item_value < self.value,
@@ -152,9 +462,23 @@ impl Check {
             _ => unreachable!(),
         }
     }
+
+    // split an inclusive range into the part that passes the check and the part that doesn't
+    fn split(&self, min: i32, max: i32) -> (Option<Interval>, Option<Interval>) {
+        let (matched, rest) = match self.op.as_str() {
+            "<" => ((min, max.min(self.value - 1)), (min.max(self.value), max)),
+            ">" => ((min.max(self.value + 1), max), (min, max.min(self.value))),
+            _ => unreachable!(),
+        };
+
+        let non_empty = |(start, end): Interval| (start <= end).then_some((start, end));
+
+        (non_empty(matched), non_empty(rest))
+    }
 }
 
-#[derive(Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
 enum Category {
     X,
     M,
@@ -162,51 +486,94 @@ enum Category {
     S,
 }
 
-impl Category {
-    fn new(input: &str) -> Self {
+impl FromStr for Category {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Error> {
         match input {
-            "x" => Self::X,
-            "m" => Self::M,
-            "a" => Self::A,
-            "s" => Self::S,
-            _ => unreachable!(),
+            "x" => Ok(Self::X),
+            "m" => Ok(Self::M),
+            "a" => Ok(Self::A),
+            "s" => Ok(Self::S),
+            _ => Err(Error::parse(0, "unknown category").with_hint("categories are x, m, a and s")),
         }
     }
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "tooling", derive(Serialize, Deserialize))]
 struct Item {
-    component: HashMap<Category, i32>,
+    // indexed by Category as usize
+    component: [i32; 4],
 }
 
-impl Item {
-    fn new(input: &str) -> Self {
-        let mut component = HashMap::new();
-
-        for item in input.replace(['{', '}'], "").split(',') {
-            let vec = item.split('=').collect::<Vec<&str>>();
-            assert_eq!(vec.len(), 2);
-
-            let category = Category::new(vec[0]);
-            let value = vec[1].parse().unwrap();
-
-            component.insert(category, value);
+// "{x=787,m=2655,a=1222,s=2876}"
+impl FromStr for Item {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Error> {
+        let mut component = [0; 4];
+        let ratings: Vec<(&str, &str)> = parse_line(
+            0,
+            input,
+            delimited(
+                '{',
+                separated(1.., separated_pair(alpha1, '=', digit1), ','),
+                '}',
+            )
+            .context(expected("a part"))
+            .context(hint("parts look like `{x=787,m=2655,a=1222,s=2876}`")),
+        )?;
+
+        for (category, value) in ratings {
+            let category = category
+                .parse::<Category>()
+                .map_err(|f| f.at_token(input, category))?;
+            let value = value
+                .parse()
+                .map_err(|_| Error::parse(0, "expected a number").at_token(input, value))?;
+
+            component[category as usize] = value;
         }
 
-        Self { component }
+        Ok(Self { component })
     }
+}
 
+impl Item {
     fn get_total(&self) -> i32 {
-        self.component.values().sum()
+        self.component.iter().sum()
     }
 }
 
+impl Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [x, m, a, s] = self.component;
+        write!(f, "{{x={},m={},a={},s={}}}", x, m, a, s)
+    }
+}
+
+// with --parse-cache a system seen before is read back instead of parsed
+#[cfg(feature = "tooling")]
+fn parse_system(input: &str) -> Result<System, Error> {
+    cache::parse(input)
+}
+
+#[cfg(not(feature = "tooling"))]
+fn parse_system(input: &str) -> Result<System, Error> {
+    input.parse()
+}
+
 pub fn solve(input: &str) -> Result<Answer> {
-    let part2 = 0;
     let mut answer = Answer::default();
 
-    let system = System::new(input);
-    let part1 = system.get_accepted_value();
+    let system = parse_system(input)?;
+    for lint in system.lint() {
+        warn!("{}", lint);
+    }
+
+    let part1 = system.get_accepted_value()?;
+    let part2 = system.get_accepted_combinations()?;
 
     answer.part1 = Some(part1.to_string());
     answer.part2 = Some(part2.to_string());
@@ -215,47 +582,142 @@ pub fn solve(input: &str) -> Result<Answer> {
 
 #[cfg(test)]
 mod tests {
-
     use tracing_test::traced_test;
 
     use super::*;
-    use color_eyre::eyre::Result;
-
-    const TEST_INPUT: &str = "px{a<2006:qkq,m>2090:A,rfg}
-pv{a>1716:R,A}
-lnx{m>1548:A,A}
-rfg{s<537:gd,x>2440:R,A}
-qs{s>3448:A,lnx}
-qkq{x<1416:A,crn}
-crn{x>2662:A,R}
-in{s<1351:px,qqz}
-qqz{s>2770:qs,m<1801:hdj,R}
-gd{a>3333:R,R}
-hdj{m>838:A,pv}
-
-{x=787,m=2655,a=1222,s=2876}
-{x=1679,m=44,a=2067,s=496}
-{x=2036,m=264,a=79,s=2244}
-{x=2461,m=1339,a=466,s=291}
-{x=2127,m=1623,a=2188,s=1013}";
+    use crate::test_support::fixture;
 
     #[traced_test]
     #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    fn test_interned_workflows() {
+        // "in" is not the first workflow and points at one defined after it
+        let system =
+            "ab{x>10:R,A}\nin{m<5:cd,ab}\ncd{A}\n\n{x=1,m=2,a=3,s=4}\n{x=11,m=9,a=0,s=0}\n"
+                .parse::<System>()
+                .unwrap();
+
+        assert_eq!(system.start, 1);
+        assert_eq!(
+            system.workflows[1].conditions[0].check.destination,
+            Target::Workflow(2)
+        );
+        assert_eq!(system.workflows[1].default, Target::Workflow(0));
+
+        assert!(system.is_accepted(&system.items[0]).unwrap());
+        assert!(!system.is_accepted(&system.items[1]).unwrap());
+        assert_eq!(system.get_accepted_value().unwrap(), 10);
+    }
 
-        assert_eq!(answer.part1, Some("19114".to_string()));
+    #[traced_test]
+    #[test]
+    fn test_parse_errors() {
+        let error = "in{x>10:zz,A}\n".parse::<System>().unwrap_err();
+        assert_eq!(error, Error::parse(0, "unknown workflow").at(8, 10));
+
+        let error = "in{A}\n\n{x=1,q=2}\n".parse::<System>().unwrap_err();
+        assert_eq!(error.to_string(), "line 3: unknown category");
 
-        Ok(())
+        let error = "ab{A}\n".parse::<System>().unwrap_err();
+        assert_eq!(error.to_string(), "line 1: there is no `in` workflow");
     }
 
     #[traced_test]
     #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
+    fn test_lint() {
+        let system = "in{x>10:ab,x>20:R,m<5:cd,A}\nab{a<100:ab,s>0:R,s<1:A,R}\ncd{R}\nef{A}\n"
+            .parse::<System>()
+            .unwrap();
+        let lints = system.lint();
+
+        assert_eq!(
+            lints,
+            vec![
+                Lint::UnreachableCondition {
+                    workflow: "in".to_string(),
+                    position: 1
+                },
+                Lint::UnreachableCondition {
+                    workflow: "ab".to_string(),
+                    position: 2
+                },
+                Lint::UnreachableDefault("ab".to_string()),
+                Lint::UnusedWorkflow("ef".to_string()),
+                Lint::Cycle(vec!["ab".to_string()]),
+            ]
+        );
+        assert_eq!(
+            lints[0].to_string(),
+            "condition 2 of workflow `in` can never match"
+        );
+
+        let system = "in{x>10:ab,A}\nab{cd}\ncd{m<5:in,R}\n"
+            .parse::<System>()
+            .unwrap();
+        assert_eq!(
+            system.lint(),
+            vec![Lint::Cycle(vec![
+                "in".to_string(),
+                "ab".to_string(),
+                "cd".to_string()
+            ])]
+        );
+
+        // once a condition takes every part, whatever follows it is unreachable, of any category
+        let system = "in{x>0:A,m<5:R,A}\n".parse::<System>().unwrap();
+        assert_eq!(
+            system.lint(),
+            vec![
+                Lint::UnreachableCondition {
+                    workflow: "in".to_string(),
+                    position: 1
+                },
+                Lint::UnreachableDefault("in".to_string()),
+            ]
+        );
+        assert_eq!(
+            system.lint()[1].to_string(),
+            "no part ever reaches the default of workflow `in`"
+        );
+
+        assert!(fixture("day19")
+            .parse::<System>()
+            .unwrap()
+            .lint()
+            .is_empty());
+    }
 
-        assert_eq!(answer.part2, Some("".to_string()));
+    fixture_test!(test_part1, super::solve, "day19", part1 = "19114");
 
-        Ok(())
+    fixture_test!(test_part2, super::solve, "day19", part2 = "167409079868000");
+
+    #[traced_test]
+    #[test]
+    fn test_loops() {
+        // the workflows form a cycle, but no part can go around it
+        let system = "in{x>10:ab,A}\nab{x<5:in,R}\n\n{x=11,m=1,a=1,s=1}\n"
+            .parse::<System>()
+            .unwrap();
+        assert_eq!(system.get_accepted_value().unwrap(), 0);
+        assert_eq!(
+            system.get_accepted_combinations().unwrap(),
+            10 * 4000_i64.pow(3)
+        );
+
+        let system = "in{x>10:ab,A}\nab{m<5:in,R}\n\n{x=1,m=1,a=1,s=1}\n{x=11,m=1,a=1,s=1}\n"
+            .parse::<System>()
+            .unwrap();
+        assert!(system.is_accepted(&system.items[0]).unwrap());
+        assert_eq!(
+            system
+                .is_accepted(&system.items[1])
+                .unwrap_err()
+                .to_string(),
+            "part {x=11,m=1,a=1,s=1} loops through workflow `in` forever"
+        );
+        assert!(system.get_accepted_value().is_err());
+        assert_eq!(
+            system.get_accepted_combinations().unwrap_err().to_string(),
+            "parts can loop through workflow `in` forever"
+        );
     }
 }
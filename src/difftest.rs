@@ -0,0 +1,476 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Display},
+    panic::{self, AssertUnwindSafe},
+};
+
+use color_eyre::eyre::{bail, Result};
+use tracing::subscriber::{self, NoSubscriber};
+
+use crate::{
+    registry::{self, Variant},
+    solver::Answer,
+};
+
+// generated inputs grow from tiny to this size over the cases, so the first mismatch tends to be small
+const MAX_SIZE: usize = 10;
+
+// splitmix64, seeded so a mismatch can be generated again from the seed
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform in 0..bound, bound must not be 0
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn choose<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+}
+
+// a random input of about the size, the same for the same rng state
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+// the days that have more than one variant to compare
+pub fn generator(day: i32) -> Option<Generator> {
+    match day {
+        5 => Some(almanac),
+        10 => Some(maze),
+        12 => Some(springs),
+        17 => Some(heat_map),
+        _ => None,
+    }
+}
+
+// small numbers keep the brute force variant fast
+fn almanac(rng: &mut Rng, size: usize) -> String {
+    let categories = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+    let limit = 10 + size * 5;
+
+    let seeds = (0..1 + rng.below(size.min(3)))
+        .map(|_| format!("{} {}", rng.below(limit), 1 + rng.below(size + 2)))
+        .collect::<Vec<_>>();
+    let mut text = format!("seeds: {}\n", seeds.join(" "));
+
+    for pair in categories.windows(2) {
+        text.push_str(&format!("\n{}-to-{} map:\n", pair[0], pair[1]));
+
+        // the sources of a map never overlap, so they are cut out of one line of numbers
+        let mut cuts = (0..2 + 2 * rng.below(size.min(3)))
+            .map(|_| rng.below(limit))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if cuts.len() % 2 == 1 {
+            cuts.pop();
+        }
+        if cuts.is_empty() {
+            cuts = vec![0, 1];
+        }
+
+        for source in cuts.chunks(2) {
+            let length = source[1] - source[0];
+            text.push_str(&format!("{} {} {}\n", rng.below(limit), source[0], length));
+        }
+    }
+
+    text
+}
+
+// the boundary of a random polyomino drawn at twice its size, so the loop runs through the middle of
+// tiles and every corner is a pipe, with random pipes around it
+fn maze(rng: &mut Rng, size: usize) -> String {
+    loop {
+        if let Some(text) = try_maze(rng, 1 + size.min(6)) {
+            return text;
+        }
+    }
+}
+
+fn try_maze(rng: &mut Rng, cells: usize) -> Option<String> {
+    let mut shape = vec![vec![false; cells]; cells];
+    shape[rng.below(cells)][rng.below(cells)] = true;
+
+    for _ in 0..rng.below(cells * cells) {
+        let (x, y) = (rng.below(cells), rng.below(cells));
+        let touches = [(0, 1), (2, 1), (1, 0), (1, 2)].iter().any(|(dx, dy)| {
+            let (nx, ny) = ((x + dx).wrapping_sub(1), (y + dy).wrapping_sub(1));
+            shape
+                .get(ny)
+                .and_then(|f| f.get(nx))
+                .copied()
+                .unwrap_or(false)
+        });
+
+        if touches {
+            shape[y][x] = true;
+        }
+    }
+
+    let inside = |x: i64, y: i64| {
+        x >= 0
+            && y >= 0
+            && shape
+                .get(y as usize)
+                .and_then(|f| f.get(x as usize))
+                .copied()
+                == Some(true)
+    };
+    // the unit segments from a point of the fine grid that lie on the boundary, as n e s w
+    let connections = |x: i64, y: i64| {
+        let vertical = |y: i64| {
+            x % 2 == 0 && inside(x / 2 - 1, y.div_euclid(2)) != inside(x / 2, y.div_euclid(2))
+        };
+        let horizontal = |x: i64| {
+            y % 2 == 0 && inside(x.div_euclid(2), y / 2 - 1) != inside(x.div_euclid(2), y / 2)
+        };
+
+        [
+            vertical(y - 1),
+            horizontal(x),
+            vertical(y),
+            horizontal(x - 1),
+        ]
+    };
+
+    let side = 2 * cells as i64 + 1;
+    let mut rows = vec![vec!['.'; side as usize]; side as usize];
+    let mut points = vec![];
+
+    for y in 0..side {
+        for x in 0..side {
+            let tile = match connections(x, y) {
+                [false, false, false, false] => continue,
+                [true, false, true, false] => '|',
+                [false, true, false, true] => '-',
+                [true, true, false, false] => 'L',
+                [true, false, false, true] => 'J',
+                [false, false, true, true] => '7',
+                [false, true, true, false] => 'F',
+                // a pinch where two cells only touch at a corner
+                _ => return None,
+            };
+
+            rows[y as usize][x as usize] = tile;
+            points.push((x, y));
+        }
+    }
+
+    // a hole in the shape would leave a second loop
+    let (mut x, mut y) = points[0];
+    let mut previous = None;
+    let mut length = 0;
+    loop {
+        let next = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .iter()
+            .zip(connections(x, y))
+            .filter(|(_, connected)| *connected)
+            .map(|((dx, dy), _)| (x + dx, y + dy))
+            .find(|f| Some(*f) != previous)?;
+
+        previous = Some((x, y));
+        (x, y) = next;
+        length += 1;
+
+        if (x, y) == points[0] {
+            break;
+        }
+    }
+    if length != points.len() {
+        return None;
+    }
+
+    for tile in rows.iter_mut().flatten().filter(|f| **f == '.') {
+        *tile = rng.choose(&['.', '|', '-', 'L', 'J', '7', 'F']);
+    }
+
+    // the start must connect to exactly the two pipes of the loop, so its other neighbors are ground
+    let (x, y) = points[rng.below(points.len())];
+    rows[y as usize][x as usize] = 'S';
+    for (dx, dy) in [(0, -1), (1, 0), (0, 1), (-1, 0)] {
+        let (nx, ny) = (x + dx, y + dy);
+        if (0..side).contains(&nx)
+            && (0..side).contains(&ny)
+            && !connections(nx, ny).contains(&true)
+        {
+            rows[ny as usize][nx as usize] = '.';
+        }
+    }
+
+    Some(
+        rows.iter()
+            .map(|f| f.iter().collect::<String>() + "\n")
+            .collect(),
+    )
+}
+
+// the groups come from a random filling of the row, so most rows have arrangements
+fn springs(rng: &mut Rng, size: usize) -> String {
+    (0..1 + rng.below(size))
+        .map(|_| {
+            let filled = (0..1 + rng.below(size.min(12)))
+                .map(|_| rng.choose(&['#', '.']))
+                .collect::<String>();
+            let groups = filled
+                .split('.')
+                .filter(|f| !f.is_empty())
+                .map(|f| f.len().to_string())
+                .collect::<Vec<_>>();
+            let row = filled
+                .chars()
+                .map(|f| if rng.below(2) == 0 { '?' } else { f })
+                .collect::<String>();
+
+            match groups.is_empty() {
+                true => format!("{} 1\n", row),
+                false => format!("{} {}\n", row, groups.join(",")),
+            }
+        })
+        .collect()
+}
+
+// at least 5 wide and high, so the ultra crucible can reach the end
+fn heat_map(rng: &mut Rng, size: usize) -> String {
+    let (width, height) = (5 + rng.below(size), 5 + rng.below(size));
+
+    (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| char::from(b'1' + rng.below(9) as u8))
+                .collect::<String>()
+                + "\n"
+        })
+        .collect()
+}
+
+// what a variant made of an input, errors and panics all count as failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Answer(Answer),
+    Failed(String),
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Answer(answer) => write!(
+                f,
+                "{} / {}",
+                answer.part1.as_deref().unwrap_or("-"),
+                answer.part2.as_deref().unwrap_or("-")
+            ),
+            Self::Failed(message) => write!(f, "failed: {}", message),
+        }
+    }
+}
+
+// the variants run without logging, a panic is caught so it can be reported with its input
+fn outcome(variant: &Variant, input: &str) -> Outcome {
+    let result = subscriber::with_default(NoSubscriber::default(), || {
+        panic::catch_unwind(AssertUnwindSafe(|| (variant.solve)(input)))
+    });
+
+    match result {
+        Ok(Ok(answer)) => Outcome::Answer(answer),
+        Ok(Err(error)) => Outcome::Failed(error.to_string()),
+        Err(payload) => Outcome::Failed(
+            payload
+                .downcast_ref::<&str>()
+                .map(|f| f.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "panicked".to_string()),
+        ),
+    }
+}
+
+// the variants disagree when one answers differently or fails where another answers
+fn disagree(outcomes: &[(&'static str, Outcome)]) -> bool {
+    let answers = outcomes
+        .iter()
+        .map(|(_, f)| match f {
+            Outcome::Answer(answer) => Some(answer),
+            Outcome::Failed(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    answers.windows(2).any(|f| f[0] != f[1])
+}
+
+fn outcomes(day: i32, input: &str) -> Vec<(&'static str, Outcome)> {
+    registry::variants(day)
+        .iter()
+        .map(|f| (f.short_name(), outcome(f, input)))
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct Mismatch {
+    pub day: i32,
+    pub case: usize,
+    pub input: String,
+    pub outcomes: Vec<(&'static str, Outcome)>,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Day {:0>2}, the variants disagree on case {}, minimized to:",
+            self.day, self.case
+        )?;
+        write!(f, "{}", self.input)?;
+
+        let width = self
+            .outcomes
+            .iter()
+            .map(|(f, _)| f.len())
+            .max()
+            .unwrap_or(0);
+        for (name, outcome) in &self.outcomes {
+            writeln!(f, "{:<width$}  {}", name, outcome, width = width)?;
+        }
+
+        Ok(())
+    }
+}
+
+// solves cases generated inputs with every variant of the day, the first disagreement is minimized
+pub fn run(day: i32, cases: usize, seed: u64) -> Result<Option<Mismatch>> {
+    let Some(generate) = generator(day) else {
+        bail!("day {:0>2} has no input generator", day);
+    };
+    if registry::variants(day).len() < 2 {
+        bail!("day {:0>2} has only one variant", day);
+    }
+
+    let mut rng = Rng::new(seed);
+
+    for case in 0..cases {
+        let input = generate(&mut rng, 1 + case * MAX_SIZE / cases.max(1));
+
+        if disagree(&outcomes(day, &input)) {
+            let input = minimize(&input, |f| disagree(&outcomes(day, f)));
+
+            return Ok(Some(Mismatch {
+                day,
+                case,
+                outcomes: outcomes(day, &input),
+                input,
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+// drops lines, then columns of a grid, as long as the input still fails
+pub fn minimize(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let join = |lines: &[String]| lines.iter().map(|f| format!("{}\n", f)).collect::<String>();
+    let mut lines = input.lines().map(|f| f.to_string()).collect::<Vec<_>>();
+
+    let mut index = 0;
+    while index < lines.len() {
+        let mut fewer = lines.clone();
+        fewer.remove(index);
+
+        if !fewer.is_empty() && fails(&join(&fewer)) {
+            lines = fewer;
+        } else {
+            index += 1;
+        }
+    }
+
+    let is_grid = !lines.is_empty()
+        && lines
+            .iter()
+            .all(|f| f.len() == lines[0].len() && f.is_ascii());
+    let mut column = 0;
+    while is_grid && column < lines[0].len() {
+        let narrower = lines
+            .iter()
+            .map(|f| format!("{}{}", &f[..column], &f[column + 1..]))
+            .collect::<Vec<_>>();
+
+        if lines[0].len() > 1 && fails(&join(&narrower)) {
+            lines = narrower;
+        } else {
+            column += 1;
+        }
+    }
+
+    join(&lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_generators_agree() {
+        for day in [5, 10, 12, 17] {
+            let mismatch = run(day, 20, 2023).unwrap();
+            assert!(mismatch.is_none(), "{}", mismatch.unwrap());
+        }
+
+        assert!(run(1, 10, 0).is_err());
+
+        let answer = Outcome::Answer(Answer::default());
+        let failed = Outcome::Failed("no loop".to_string());
+        assert!(!disagree(&[("a", failed.clone()), ("b", failed.clone())]));
+        assert!(disagree(&[("a", answer.clone()), ("b", failed)]));
+        assert!(!disagree(&[("a", answer.clone()), ("b", answer)]));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_maze() {
+        let mut rng = Rng::new(7);
+
+        for size in 1..8 {
+            let input = maze(&mut rng, size);
+            let answers = outcomes(10, &input);
+            assert!(
+                matches!(answers[0].1, Outcome::Answer(_)),
+                "{}{}",
+                input,
+                answers[0].1
+            );
+        }
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_minimize() {
+        let fails = |input: &str| input.contains('x');
+        assert_eq!(minimize("abcd\nefxh\nijkl\n", fails), "x\n");
+        assert_eq!(minimize("", fails), "");
+        assert_eq!(minimize("x", |f| f.contains('x')), "x\n");
+        assert_eq!(minimize("ab\ncd\n", |_| false), "ab\ncd\n");
+    }
+}
@@ -0,0 +1,395 @@
+use std::{
+    env,
+    fmt::{self, Display},
+    fs,
+    io::{self, IsTerminal},
+    path::Path,
+    time::SystemTime,
+};
+
+use color_eyre::eyre::Result;
+
+use crate::{bench::BenchRun, calendar, fetch, profile, registry, stars::Stars};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    // works, but something is missing or will look wrong
+    Warning,
+    // will make commands fail
+    Problem,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+    // what to do about a warning or a problem
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            status: Status::Warning,
+            fix: Some(fix.into()),
+            ..Self::ok(name, detail)
+        }
+    }
+
+    fn problem(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            status: Status::Problem,
+            ..Self::warning(name, detail, fix)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
+
+    pub fn problems(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|f| f.status == Status::Problem)
+            .count()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = match check.status {
+                Status::Ok => "ok",
+                Status::Warning => "warn",
+                Status::Problem => "FAIL",
+            };
+
+            writeln!(f, "{:<4}  {:<16}  {}", status, check.name, check.detail)?;
+            if let Some(fix) = &check.fix {
+                writeln!(f, "{:<4}  {:<16}  fix: {}", "", "", fix)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// every check against the real environment, the session token check downloads the calendar
+pub fn diagnose() -> Report {
+    let now = SystemTime::now();
+    let days = (1..=25)
+        .filter(|f| !registry::variants(*f).is_empty() && fetch::is_unlocked(*f, now))
+        .collect::<Vec<_>>();
+
+    let mut checks = vec![];
+    checks.extend(inputs(Path::new(&profile::input_directory()), &days));
+    checks.push(session_token(fetch::session_token().as_deref(), |token| {
+        fetch::fetch_page(&fetch::calendar_url(), Some(token))
+    }));
+    checks.extend(saved_files(
+        Path::new(&calendar::answers_path()),
+        Path::new("input/bench"),
+    ));
+    checks.extend(terminal(|f| env::var(f).ok(), io::stdout().is_terminal()));
+
+    Report { checks }
+}
+
+// the input directory, then one check per day that is implemented and unlocked
+pub fn inputs(directory: &Path, days: &[i32]) -> Vec<Check> {
+    if !directory.is_dir() {
+        return vec![Check::problem(
+            "input directory",
+            format!("{} does not exist", directory.display()),
+            format!(
+                "create it next to Cargo.toml and save the inputs there, or set {} and run fetch-all",
+                fetch::session_variable()
+            ),
+        )];
+    }
+
+    let mut checks = vec![Check::ok(
+        "input directory",
+        format!("{} exists", directory.display()),
+    )];
+
+    for day in days {
+        let name = format!("input day {:0>2}", day);
+        let path = directory.join(format!("{:0>2}", day));
+
+        checks.push(match fs::metadata(&path) {
+            Ok(metadata) if metadata.len() == 0 => Check::problem(
+                &name,
+                format!("{} is empty", path.display()),
+                format!(
+                    "save your puzzle input from {} again",
+                    fetch::input_url(*day)
+                ),
+            ),
+            Ok(metadata) => Check::ok(&name, format!("{} bytes", metadata.len())),
+            Err(_) => Check::warning(
+                &name,
+                format!("{} is missing", path.display()),
+                format!(
+                    "save your puzzle input from {}, or run fetch-all",
+                    fetch::input_url(*day)
+                ),
+            ),
+        });
+    }
+
+    checks
+}
+
+// set, and logged in according to the calendar it downloads
+pub fn session_token(token: Option<&str>, calendar: impl FnOnce(&str) -> Result<String>) -> Check {
+    const NAME: &str = "session token";
+
+    let Some(token) = token else {
+        return Check::warning(
+            NAME,
+            format!(
+                "{} is not set and no token is saved, inputs can not be downloaded",
+                fetch::session_variable()
+            ),
+            format!(
+                "copy the session cookie of adventofcode.com from your browser into {}, or run login",
+                fetch::session_variable()
+            ),
+        );
+    };
+
+    let html = match calendar(token) {
+        Ok(html) => html,
+        Err(error) => {
+            return Check::problem(
+                NAME,
+                format!("could not download the calendar: {}", error),
+                "check your connection and that curl is installed",
+            )
+        }
+    };
+
+    match Stars::parse(&html) {
+        Ok(stars) => Check::ok(NAME, format!("logged in, {} stars", stars.total())),
+        Err(error) => Check::problem(
+            NAME,
+            error.to_string(),
+            format!(
+                "log in again and copy the new session cookie into {}, or run login",
+                fetch::session_variable()
+            ),
+        ),
+    }
+}
+
+// the files commands read back, the answers to check against and the saved bench runs
+pub fn saved_files(answers: &Path, baselines: &Path) -> Vec<Check> {
+    let mut checks = vec![match calendar::read_answers(answers) {
+        Ok(answers) => Check::ok(
+            "answers",
+            format!("{} answer(s) to check against", answers.len()),
+        ),
+        Err(error) => Check::problem(
+            "answers",
+            error.to_string(),
+            format!(
+                "fix the line in {}, every line is \"day part1 part2\"",
+                answers.display()
+            ),
+        ),
+    }];
+
+    let Ok(entries) = fs::read_dir(baselines) else {
+        return checks;
+    };
+
+    let mut paths = entries
+        .filter_map(|f| f.ok().map(|f| f.path()))
+        .filter(|f| f.is_file())
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    for path in paths {
+        if let Err(error) = BenchRun::load(&path) {
+            checks.push(Check::problem(
+                "bench baseline",
+                error.to_string(),
+                "save it again with bench --save, or delete it",
+            ));
+        }
+    }
+
+    if checks.len() == 1 {
+        checks.push(Check::ok(
+            "bench baselines",
+            format!("every run in {} loads", baselines.display()),
+        ));
+    }
+
+    checks
+}
+
+// whether box drawing characters and colors will show up right, var reads the environment
+pub fn terminal(var: impl Fn(&str) -> Option<String>, is_terminal: bool) -> Vec<Check> {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|f| var(f).filter(|f| !f.is_empty()));
+
+    let unicode = match locale {
+        Some(locale) if locale.to_lowercase().replace('-', "").contains("utf8") => {
+            Check::ok("unicode", format!("locale {}", locale))
+        }
+        // windows terminals have no locale variables and draw unicode anyway
+        None if cfg!(windows) => Check::ok("unicode", "windows console"),
+        locale => Check::warning(
+            "unicode",
+            format!(
+                "locale {} is not UTF-8, grids may show up garbled",
+                locale.as_deref().unwrap_or("unset")
+            ),
+            "set LANG to a UTF-8 locale like en_US.UTF-8, or pass --charset ascii",
+        ),
+    };
+
+    let no_color = "set NO_COLOR=1 to keep escape codes out of the logs";
+    let color = if var("NO_COLOR").is_some_and(|f| !f.is_empty()) {
+        Check::ok("color", "turned off by NO_COLOR")
+    } else if !is_terminal {
+        Check::warning(
+            "color",
+            "stdout is not a terminal, the logs will contain color escape codes",
+            no_color,
+        )
+    } else if var("TERM").as_deref() == Some("dumb") {
+        Check::warning("color", "TERM is dumb, colors will not show", no_color)
+    } else {
+        Check::ok("color", "terminal with colors")
+    };
+
+    vec![unicode, color]
+}
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::eyre;
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_inputs() {
+        let directory = env::temp_dir().join("advent-of-code-2023-doctor-test");
+        assert_eq!(inputs(&directory, &[1])[0].status, Status::Problem);
+
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("01"), "1abc2\n").unwrap();
+        fs::write(directory.join("02"), "").unwrap();
+
+        let statuses = inputs(&directory, &[1, 2, 3])
+            .iter()
+            .map(|f| f.status)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [Status::Ok, Status::Ok, Status::Problem, Status::Warning]
+        );
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_session_token() {
+        let calendar =
+            |_: &str| -> Result<String> { Ok(r#"aria-label="Day 1, two stars""#.to_string()) };
+        assert_eq!(session_token(None, calendar).status, Status::Warning);
+        assert_eq!(
+            session_token(Some("abc"), calendar).detail,
+            "logged in, 2 stars"
+        );
+
+        let logged_out =
+            session_token(Some("abc"), |_| Ok(r#"<a href="/auth/login">"#.to_string()));
+        assert_eq!(logged_out.status, Status::Problem);
+
+        let offline = session_token(Some("abc"), |_| Err(eyre!("no network")));
+        assert_eq!(offline.status, Status::Problem);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_saved_files() {
+        let directory = env::temp_dir().join("advent-of-code-2023-doctor-files-test");
+        let baselines = directory.join("bench");
+        fs::create_dir_all(&baselines).unwrap();
+        fs::write(directory.join("answers"), "01 142 281\n").unwrap();
+        fs::write(baselines.join("before"), "01 1000\n").unwrap();
+
+        let checks = saved_files(&directory.join("answers"), &baselines);
+        assert!(checks.iter().all(|f| f.status == Status::Ok));
+
+        fs::write(directory.join("answers"), "01 142\n").unwrap();
+        fs::write(baselines.join("after"), "01 fast\n").unwrap();
+
+        let checks = saved_files(&directory.join("answers"), &baselines);
+        let statuses = checks.iter().map(|f| f.status).collect::<Vec<_>>();
+        assert_eq!(statuses, [Status::Problem, Status::Problem]);
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_terminal() {
+        let environment = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(f, _)| *f == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        let checks = terminal(environment(&[("LANG", "en_US.UTF-8")]), true);
+        assert!(checks.iter().all(|f| f.status == Status::Ok));
+
+        let checks = terminal(
+            environment(&[("LC_ALL", "C"), ("LANG", "en_US.utf8")]),
+            false,
+        );
+        assert_eq!(checks[0].status, Status::Warning);
+        assert_eq!(checks[1].status, Status::Warning);
+
+        let checks = terminal(
+            environment(&[("LANG", "C.UTF-8"), ("NO_COLOR", "1")]),
+            false,
+        );
+        assert!(checks.iter().all(|f| f.status == Status::Ok));
+
+        let report = Report { checks };
+        assert_eq!(report.problems(), 0);
+        assert!(report
+            .to_string()
+            .starts_with("ok    unicode           locale C.UTF-8\n"));
+    }
+}
@@ -0,0 +1,271 @@
+use alloc::string::{String, ToString};
+use core::fmt::{self, Display};
+
+// errors that day modules can report without depending on std
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    // line is the 0 based index of the input line, a length of 0 points at the whole line
+    Parse {
+        line: usize,
+        column: usize,
+        length: usize,
+        message: String,
+        hint: Option<String>,
+    },
+}
+
+impl Error {
+    pub fn parse(line: usize, message: &str) -> Self {
+        Error::Parse {
+            line,
+            column: 0,
+            length: 0,
+            message: message.to_string(),
+            hint: None,
+        }
+    }
+
+    pub fn at(mut self, start: usize, end: usize) -> Self {
+        let Error::Parse { column, length, .. } = &mut self;
+        *column = start;
+        *length = end.saturating_sub(start);
+        self
+    }
+
+    // points at a token, which has to be a slice of the given line
+    pub fn at_token(self, line: &str, token: &str) -> Self {
+        let start = (token.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+        self.at(start, start + token.len())
+    }
+
+    // moves an error from a parser that only saw one line to that line of the whole input
+    pub fn on_line(mut self, index: usize) -> Self {
+        let Error::Parse { line, .. } = &mut self;
+        *line = index;
+        self
+    }
+
+    pub fn with_hint(mut self, text: &str) -> Self {
+        let Error::Parse { hint, .. } = &mut self;
+        *hint = Some(text.to_string());
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse { line, message, .. } => write!(f, "line {}: {}", line + 1, message),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl Error {
+    // renders the error with a snippet of the input and a caret under the offending span
+    pub fn render(&self, name: &str, input: &str) -> String {
+        use miette::{GraphicalReportHandler, GraphicalTheme, NamedSource};
+
+        let Error::Parse {
+            line,
+            column,
+            length,
+            message,
+            hint,
+        } = self;
+
+        // byte offset of the line, keeping line endings so the offsets match the input
+        let line_start = input
+            .split_inclusive('\n')
+            .take(*line)
+            .map(|f| f.len())
+            .sum::<usize>();
+        let line_text = input[line_start.min(input.len())..]
+            .lines()
+            .next()
+            .unwrap_or_default();
+        let length = match length {
+            0 => line_text.len().saturating_sub(*column),
+            length => *length,
+        };
+
+        let diagnostic = Diagnostic {
+            message: message.clone(),
+            hint: hint.clone(),
+            source: NamedSource::new(name, input.to_string()),
+            span: (line_start + column, length).into(),
+        };
+
+        let mut text = String::new();
+        GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+            .render_report(&mut text, &diagnostic)
+            .expect("writing to a string cannot fail");
+
+        text
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+struct Diagnostic {
+    message: String,
+    hint: Option<String>,
+    source: miette::NamedSource<String>,
+    span: miette::SourceSpan,
+}
+
+#[cfg(feature = "std")]
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Diagnostic {}
+
+#[cfg(feature = "std")]
+impl miette::Diagnostic for Diagnostic {
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.hint
+            .as_ref()
+            .map(|f| Box::new(f) as Box<dyn Display + 'a>)
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.source)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let label = miette::LabeledSpan::new_with_span(Some("here".to_string()), self.span);
+
+        Some(Box::new(std::iter::once(label)))
+    }
+}
+
+// the failures scripts around the binary tell apart by its exit code, anything else exits with 1
+// and a usage error with 2, like clap does
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    // no solution for the day, or no variant by that name
+    Unimplemented,
+    MissingInput,
+    Parse,
+    // a loop went past --max-steps
+    Timeout,
+    // answers that should agree do not, between variants or against the ones expected
+    Mismatch,
+}
+
+#[cfg(feature = "std")]
+impl FailureKind {
+    pub fn exit_code(self) -> u8 {
+        match self {
+            FailureKind::Unimplemented => 3,
+            FailureKind::MissingInput => 4,
+            FailureKind::Parse => 5,
+            FailureKind::Timeout => 6,
+            FailureKind::Mismatch => 7,
+        }
+    }
+}
+
+// an error that says which kind of failure it is, it displays as just the message
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct Failure {
+    pub kind: FailureKind,
+    message: String,
+}
+
+#[cfg(feature = "std")]
+impl Failure {
+    pub fn new(kind: FailureKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Failure {}
+
+// the exit code for an error that ended a run, from the first failure in its chain
+// a parse error a day reported without going through the solver counts as a parse failure too
+#[cfg(feature = "std")]
+pub fn exit_code(report: &color_eyre::Report) -> u8 {
+    report
+        .chain()
+        .find_map(|f| {
+            if let Some(failure) = f.downcast_ref::<Failure>() {
+                return Some(failure.kind);
+            }
+            f.downcast_ref::<Error>().map(|_| FailureKind::Parse)
+        })
+        .map_or(1, |f| f.exit_code())
+}
+
+// with std, day modules report errors through eyre like the rest of the binary
+#[cfg(feature = "std")]
+pub type Result<T, E = color_eyre::eyre::Report> = core::result::Result<T, E>;
+
+#[cfg(not(feature = "std"))]
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_render() {
+        let input = "seeds: 1 2\nseed-to-soil map:\n50 98\n";
+        let line = input.lines().nth(2).unwrap();
+
+        let error = Error::parse(2, "expected 3 numbers")
+            .at_token(line, line)
+            .with_hint("map entries are: destination source length");
+
+        assert_eq!(error.to_string(), "line 3: expected 3 numbers");
+
+        let text = error.render("input/05", input);
+        assert!(text.contains("expected 3 numbers"));
+        assert!(text.contains("input/05:3:1"));
+        assert!(text.contains("50 98"));
+        assert!(text.contains("map entries are: destination source length"));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_exit_code() {
+        use color_eyre::eyre::{eyre, WrapErr};
+
+        let missing = eyre!(Failure::new(
+            FailureKind::MissingInput,
+            "input/05 does not exist"
+        ));
+        assert_eq!(missing.to_string(), "input/05 does not exist");
+        assert_eq!(exit_code(&missing), 4);
+
+        let wrapped = Err::<(), _>(eyre!(Failure::new(FailureKind::Timeout, "too many steps")))
+            .wrap_err("day 14")
+            .unwrap_err();
+        assert_eq!(exit_code(&wrapped), 6);
+
+        assert_eq!(exit_code(&eyre!(Error::parse(0, "expected a number"))), 5);
+        assert_eq!(exit_code(&eyre!("something else")), 1);
+    }
+}
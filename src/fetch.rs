@@ -0,0 +1,458 @@
+use std::{
+    collections::hash_map::RandomState,
+    env, fs,
+    hash::BuildHasher,
+    io::{self, BufRead, IsTerminal, Write},
+    path::Path,
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::{bail, eyre, Result};
+use tracing::{debug, info, warn};
+
+use crate::{
+    error::{Failure, FailureKind},
+    solver::input_path,
+};
+
+// the value of the session cookie on adventofcode.com
+pub const SESSION_VARIABLE: &str = "AOC_SESSION";
+
+// 2023-12-01 05:00 UTC, midnight in the puzzle's timezone, each day unlocks a day later
+const FIRST_UNLOCK: u64 = 1_701_406_800;
+
+// the site asks automated tools to space out their requests and to say who they are
+pub const REQUEST_INTERVAL: Duration = Duration::from_secs(3);
+pub const USER_AGENT: &str = "github.com/harryagstian/advent-of-code-2023 via curl";
+
+// a request that failed for a reason that may pass is tried again this many times, waiting about
+// twice as long each time, set from the command line with --retries and --backoff
+pub const DEFAULT_RETRIES: u32 = 3;
+pub const DEFAULT_BACKOFF: Duration = Duration::from_secs(2);
+
+static RETRIES: AtomicU32 = AtomicU32::new(DEFAULT_RETRIES);
+static BACKOFF_MILLIS: AtomicU64 = AtomicU64::new(DEFAULT_BACKOFF.as_millis() as u64);
+
+pub fn set_retries(retries: u32) {
+    RETRIES.store(retries, Ordering::Relaxed);
+}
+
+pub fn set_backoff(backoff: Duration) {
+    BACKOFF_MILLIS.store(backoff.as_millis() as u64, Ordering::Relaxed);
+}
+
+pub fn retries() -> u32 {
+    RETRIES.load(Ordering::Relaxed)
+}
+
+// the wait before the first retry, backoff doubles it from there
+pub fn first_backoff() -> Duration {
+    Duration::from_millis(BACKOFF_MILLIS.load(Ordering::Relaxed))
+}
+
+// when the last request of this run went out, whichever command made it
+static LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
+// AOC_SESSION_WORK instead of AOC_SESSION with --profile work
+pub fn session_variable() -> String {
+    crate::profile::session_variable_of(crate::profile::name())
+}
+
+// the environment wins over the token saved by login, so a one-off account needs no logout
+pub fn session_token() -> Option<String> {
+    env::var(session_variable())
+        .ok()
+        .map(|f| f.trim().to_string())
+        .filter(|f| !f.is_empty())
+        .or_else(crate::credentials::stored_token)
+}
+
+// the session token for commands that cannot do anything without it
+pub fn require_session_token(purpose: &str) -> Result<String> {
+    session_token().ok_or_else(|| {
+        eyre!(
+            "set {} to your session cookie or run login to {}",
+            session_variable(),
+            purpose
+        )
+    })
+}
+
+pub fn calendar_url() -> String {
+    "https://adventofcode.com/2023".to_string()
+}
+
+pub fn puzzle_url(day: i32) -> String {
+    format!("{}/day/{}", calendar_url(), day)
+}
+
+pub fn input_url(day: i32) -> String {
+    format!("{}/input", puzzle_url(day))
+}
+
+// explains where the input is expected and how to get it
+pub fn missing_input_message(day: i32) -> String {
+    let path = input_path(day);
+    let mut text = format!(
+        "{} does not exist, save your puzzle input from {} there",
+        path,
+        input_url(day)
+    );
+
+    if session_token().is_some() {
+        text.push_str(", or rerun with --fetch to download it");
+    } else {
+        text.push_str(&format!(
+            ", or set {} to your session cookie (or run login) and rerun with --fetch",
+            session_variable()
+        ));
+    }
+
+    text
+}
+
+// downloads the input with curl, which keeps an http client out of the dependencies
+pub fn fetch_input(day: i32, token: &str) -> Result<()> {
+    let path = input_path(day);
+    if let Some(parent) = Path::new(&path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    info!("downloading {} to {}", input_url(day), path);
+
+    // kept in memory until it is written, encrypted when there is a passphrase, so neither a
+    // failed download nor a missing encrypt feature leaves a plain input behind
+    let input = curl(&input_url(day), Some(token))?;
+    crate::vault::write(Path::new(&path), input)
+}
+
+// a page of the site, as the logged in user sees it when there is a token
+pub fn fetch_page(url: &str, token: Option<&str>) -> Result<String> {
+    let output = curl(url, token)?;
+
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+// runs curl, with the session cookie if given, and returns what it wrote to stdout
+// every request to the site goes through here, so they all share the interval and the retries
+fn curl(url: &str, token: Option<&str>) -> Result<Vec<u8>> {
+    // every line on stdin is a header of its own
+    if token.is_some_and(|f| f.contains(['\r', '\n'])) {
+        bail!("the session token can not span more than one line");
+    }
+    let mut attempt = 0;
+
+    loop {
+        wait_for_turn();
+
+        let mut command = Command::new("curl");
+        command
+            .args(["--fail", "--silent", "--show-error"])
+            .args(["--user-agent", USER_AGENT]);
+
+        // the cookie goes in on stdin, arguments can be read by anyone through ps or /proc
+        if token.is_some() {
+            command.args(["--header", "@-"]).stdin(Stdio::piped());
+        }
+
+        let start = Instant::now();
+        let mut child = command
+            .arg(url)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|f| eyre!("could not run curl to download {}: {}", url, f))?;
+        if let Some(token) = token {
+            // dropped at the end of the statement, so curl sees the end of its headers
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(format!("Cookie: session={}\n", token).as_bytes())?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|f| eyre!("could not run curl to download {}: {}", url, f))?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if output.status.success() {
+            debug!(
+                "GET {} took {:.1?}, {} bytes",
+                url,
+                start.elapsed(),
+                output.stdout.len()
+            );
+            return Ok(output.stdout);
+        }
+        debug!("GET {} failed after {:.1?}", url, start.elapsed());
+
+        if attempt < retries() && is_transient(output.status.code(), &stderr) {
+            let delay = backoff(attempt, first_backoff(), jitter());
+            warn!(
+                "downloading {} failed, trying again in {:?}: {}",
+                url,
+                delay,
+                stderr.trim()
+            );
+            thread::sleep(delay);
+            attempt += 1;
+            continue;
+        }
+
+        bail!(
+            "downloading {} failed, is {} still valid? {}",
+            url,
+            session_variable(),
+            stderr.trim()
+        );
+    }
+}
+
+// sleeps until REQUEST_INTERVAL has passed since the last request
+fn wait_for_turn() {
+    let mut last = LAST_REQUEST.lock().unwrap_or_else(|f| f.into_inner());
+    let wait = wait_before(*last, Instant::now());
+    if !wait.is_zero() {
+        debug!("waiting {:.1?} before the next request", wait);
+        thread::sleep(wait);
+    }
+    *last = Some(Instant::now());
+}
+
+fn wait_before(last: Option<Instant>, now: Instant) -> Duration {
+    match last {
+        Some(last) => REQUEST_INTERVAL.saturating_sub(now.duration_since(last)),
+        None => Duration::ZERO,
+    }
+}
+
+// failures worth another try: the connection broke, timed out or came back empty, or the site was
+// busy (429) or had an error of its own (5xx)
+// a 4xx like a 400 for a bad token or a 404 for a locked day fails the same way every time
+fn is_transient(exit_code: Option<i32>, stderr: &str) -> bool {
+    match exit_code {
+        // with --fail, the status is at the end of "The requested URL returned error: 503"
+        Some(22) => stderr
+            .trim()
+            .rsplit(' ')
+            .next()
+            .and_then(|f| f.parse::<u16>().ok())
+            .is_some_and(|f| f == 429 || (500..600).contains(&f)),
+        Some(7 | 28 | 52 | 55 | 56) => true,
+        _ => false,
+    }
+}
+
+// first doubled for every attempt before, then spread by jitter in 0..1 over 75% to 125% of that,
+// so runs started together at unlock time do not all come back at the same moment
+fn backoff(attempt: u32, first: Duration, jitter: f64) -> Duration {
+    (first * 2u32.saturating_pow(attempt)).mul_f64(0.75 + jitter / 2.0)
+}
+
+// a number in 0..1, the keys of RandomState are random per process, which is all this needs
+fn jitter() -> f64 {
+    let random = RandomState::new().hash_one(Instant::now());
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// unix time at which the day's puzzle unlocks, day has to be 1 to 25
+pub fn unlock_timestamp(day: i32) -> u64 {
+    assert!((1..=25).contains(&day), "there is no day {}", day);
+    FIRST_UNLOCK + (day as u64 - 1) * 24 * 60 * 60
+}
+
+pub fn is_unlocked(day: i32, now: SystemTime) -> bool {
+    (1..=25).contains(&day) && now >= UNIX_EPOCH + Duration::from_secs(unlock_timestamp(day))
+}
+
+// downloads every unlocked input that is not in input/ yet, returns the days it downloaded
+pub fn fetch_all(token: &str) -> Result<Vec<i32>> {
+    let now = SystemTime::now();
+    let mut downloaded = vec![];
+
+    for day in (1..=25).filter(|f| is_unlocked(*f, now)) {
+        if Path::new(&input_path(day)).exists() {
+            info!("{} exists, skipping", input_path(day));
+            continue;
+        }
+
+        fetch_input(day, token)?;
+        downloaded.push(day);
+    }
+
+    Ok(downloaded)
+}
+
+// hands the url to the desktop's opener, which starts the default browser
+pub fn open_in_browser(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        // the empty argument is the window title start expects before the url
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+
+    command
+        .arg(url)
+        .spawn()
+        .map_err(|f| eyre!("could not open {}: {}", url, f))?;
+
+    Ok(())
+}
+
+// makes sure input/NN exists, downloading it with --fetch or after asking on a terminal
+pub fn ensure_input(day: i32, fetch: bool) -> Result<()> {
+    if Path::new(&input_path(day)).exists() {
+        return Ok(());
+    }
+
+    let Some(token) = session_token() else {
+        bail!(Failure::new(
+            FailureKind::MissingInput,
+            missing_input_message(day)
+        ));
+    };
+
+    if fetch || confirm(&format!("{} is missing, download it?", input_path(day)))? {
+        return fetch_input(day, &token);
+    }
+
+    bail!(Failure::new(
+        FailureKind::MissingInput,
+        missing_input_message(day)
+    ))
+}
+
+// asks a yes or no question, anything but a terminal on both ends counts as no
+fn confirm(question: &str) -> Result<bool> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Ok(false);
+    }
+
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_missing_input_message() {
+        assert_eq!(puzzle_url(5), "https://adventofcode.com/2023/day/5");
+        assert_eq!(input_url(5), "https://adventofcode.com/2023/day/5/input");
+
+        let message = missing_input_message(5);
+        assert!(message.starts_with("input/05 does not exist"));
+        assert!(message.contains("https://adventofcode.com/2023/day/5/input"));
+        assert!(message.contains("--fetch"));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_is_unlocked() {
+        let unlock = UNIX_EPOCH + Duration::from_secs(FIRST_UNLOCK);
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        assert!(!is_unlocked(1, unlock - Duration::from_secs(1)));
+        assert!(is_unlocked(1, unlock));
+        assert!(!is_unlocked(2, unlock + day - Duration::from_secs(1)));
+        assert!(is_unlocked(2, unlock + day));
+        assert!(is_unlocked(25, unlock + day * 24));
+        assert!(!is_unlocked(26, unlock + day * 100));
+        assert!(!is_unlocked(0, unlock + day * 100));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_wait_before() {
+        let now = Instant::now();
+
+        assert_eq!(wait_before(None, now), Duration::ZERO);
+        assert_eq!(wait_before(Some(now), now), REQUEST_INTERVAL);
+        assert_eq!(
+            wait_before(Some(now), now + Duration::from_secs(1)),
+            REQUEST_INTERVAL - Duration::from_secs(1)
+        );
+        assert_eq!(
+            wait_before(Some(now), now + REQUEST_INTERVAL * 2),
+            Duration::ZERO
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_is_transient() {
+        let error = |status| format!("curl: (22) The requested URL returned error: {}\n", status);
+
+        assert!(is_transient(Some(22), &error(503)));
+        assert!(is_transient(Some(22), &error(429)));
+        assert!(!is_transient(Some(22), &error(400)));
+        assert!(!is_transient(Some(22), &error(404)));
+        assert!(is_transient(Some(28), "curl: (28) Operation timed out"));
+        assert!(!is_transient(Some(6), "curl: (6) Could not resolve host"));
+        assert!(!is_transient(None, ""));
+
+        let first = Duration::from_secs(2);
+        assert_eq!(backoff(0, first, 0.5), Duration::from_secs(2));
+        assert_eq!(backoff(2, first, 0.5), Duration::from_secs(8));
+        assert_eq!(backoff(2, first, 0.0), Duration::from_secs(6));
+        assert_eq!(backoff(1, Duration::ZERO, 0.9), Duration::ZERO);
+        assert!((0..100).map(|_| jitter()).all(|f| (0.0..1.0).contains(&f)));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_session_cookie() {
+        use std::{io::Read, net::TcpListener};
+
+        // a server that answers with the cookie it was sent
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let request = String::from_utf8(request).unwrap();
+            let cookie = request
+                .lines()
+                .find_map(|f| f.strip_prefix("Cookie: "))
+                .unwrap_or_default()
+                .to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                cookie.len(),
+                cookie
+            )
+            .unwrap();
+        });
+
+        assert_eq!(fetch_page(&url, Some("abc")).unwrap(), "session=abc");
+        server.join().unwrap();
+
+        assert!(fetch_page(&url, Some("abc\nHost: elsewhere")).is_err());
+    }
+}
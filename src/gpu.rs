@@ -0,0 +1,508 @@
+use std::sync::{mpsc, OnceLock};
+
+use bytemuck::{Pod, Zeroable};
+use color_eyre::eyre::{bail, eyre, Result};
+use tracing::info;
+use wgpu::util::DeviceExt;
+
+// threads per workgroup of every shader below
+const WORKGROUP_SIZE: u32 = 64;
+
+// the most workgroups one dispatch dimension may have on every backend
+const MAX_WORKGROUPS: u32 = 65535;
+
+// hold times a race may have, so hold + chunk never overflows in the shader
+const MAX_RACE_TIME: u64 = 1 << 31;
+
+// counts the hold times that beat the record, every thread checks a chunk of them and adds its
+// count once, wgsl has no 64 bit integers so the distances are multiplied in 16 bit halves
+const DAY06_SHADER: &str = r#"
+struct Race {
+    time: u32,
+    distance_hi: u32,
+    distance_lo: u32,
+    chunk: u32,
+}
+
+@group(0) @binding(0) var<uniform> race: Race;
+@group(0) @binding(1) var<storage, read_write> wins: atomic<u32>;
+
+// a * b as (hi, lo)
+fn multiply(a: u32, b: u32) -> vec2<u32> {
+    let ll = (a & 0xffffu) * (b & 0xffffu);
+    let lh = (a & 0xffffu) * (b >> 16u);
+    let hl = (a >> 16u) * (b & 0xffffu);
+    let hh = (a >> 16u) * (b >> 16u);
+
+    let middle = (ll >> 16u) + (lh & 0xffffu) + (hl & 0xffffu);
+    let lo = (ll & 0xffffu) | (middle << 16u);
+    let hi = hh + (lh >> 16u) + (hl >> 16u) + (middle >> 16u);
+
+    return vec2<u32>(hi, lo);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let first = id.x * race.chunk;
+    var count = 0u;
+
+    for (var i = 0u; i < race.chunk; i++) {
+        let hold = first + i;
+        if hold > race.time {
+            break;
+        }
+
+        let distance = multiply(hold, race.time - hold);
+        if distance.x > race.distance_hi
+            || (distance.x == race.distance_hi && distance.y > race.distance_lo) {
+            count++;
+        }
+    }
+
+    if count > 0u {
+        atomicAdd(&wins, count);
+    }
+}
+"#;
+
+// one beam start per thread, the tiles it entered and in which directions are 4 bits each in its
+// own slice of entered, the beam follows one path and only the other half of a split goes on the
+// stack, which is why a stack of two entries per splitter is enough
+const DAY16_SHADER: &str = r#"
+struct Params {
+    width: u32,
+    height: u32,
+    starts: u32,
+    stack_size: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+// 0 empty, 1 /, 2 \, 3 |, 4 -
+@group(0) @binding(1) var<storage, read> tiles: array<u32>;
+// packed like the stack
+@group(0) @binding(2) var<storage, read> starts: array<u32>;
+@group(0) @binding(3) var<storage, read_write> entered: array<u32>;
+@group(0) @binding(4) var<storage, read_write> stack: array<u32>;
+@group(0) @binding(5) var<storage, read_write> energized: array<u32>;
+
+// directions are 0 up, 1 right, 2 down, 3 left with y growing downwards
+// a beam is x + 1, y + 1 and its direction, so it may stand just outside the grid
+fn pack(x: i32, y: i32, direction: u32) -> u32 {
+    return u32(x + 1) | (u32(y + 1) << 15u) | (direction << 30u);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.starts {
+        return;
+    }
+
+    var dx = array<i32, 4>(0, 1, 0, -1);
+    var dy = array<i32, 4>(-1, 0, 1, 0);
+    var slash = array<u32, 4>(1u, 0u, 3u, 2u);
+    var backslash = array<u32, 4>(3u, 2u, 1u, 0u);
+
+    let words = (params.width * params.height + 7u) / 8u;
+    let entered_base = id.x * words;
+    let stack_base = id.x * params.stack_size;
+
+    stack[stack_base] = starts[id.x];
+    var top = 1u;
+
+    while top > 0u {
+        top--;
+        let beam = stack[stack_base + top];
+        var x = i32(beam & 0x7fffu) - 1;
+        var y = i32((beam >> 15u) & 0x7fffu) - 1;
+        var direction = beam >> 30u;
+
+        loop {
+            if x < 0 || y < 0 || x >= i32(params.width) || y >= i32(params.height) {
+                break;
+            }
+
+            let tile = u32(y) * params.width + u32(x);
+            let word = entered_base + tile / 8u;
+            let bit = 1u << (direction + 4u * (tile % 8u));
+            if (entered[word] & bit) != 0u {
+                break;
+            }
+            entered[word] |= bit;
+
+            switch tiles[tile] {
+                case 1u: {
+                    direction = slash[direction];
+                }
+                case 2u: {
+                    direction = backslash[direction];
+                }
+                case 3u: {
+                    if direction == 1u || direction == 3u {
+                        direction = 0u;
+                        if top < params.stack_size {
+                            stack[stack_base + top] = pack(x, y + 1, 2u);
+                            top++;
+                        }
+                    }
+                }
+                case 4u: {
+                    if direction == 0u || direction == 2u {
+                        direction = 1u;
+                        if top < params.stack_size {
+                            stack[stack_base + top] = pack(x - 1, y, 3u);
+                            top++;
+                        }
+                    }
+                }
+                default: {}
+            }
+
+            x += dx[direction];
+            y += dy[direction];
+        }
+    }
+
+    var count = 0u;
+    for (var i = 0u; i < words; i++) {
+        let word = entered[entered_base + i];
+        for (var nibble = 0u; nibble < 8u; nibble++) {
+            if ((word >> (4u * nibble)) & 0xfu) != 0u {
+                count++;
+            }
+        }
+    }
+    energized[id.x] = count;
+}
+"#;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct Race {
+    time: u32,
+    distance_hi: u32,
+    distance_lo: u32,
+    chunk: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct BeamParams {
+    width: u32,
+    height: u32,
+    starts: u32,
+    stack_size: u32,
+}
+
+// a beam entering the grid, directions are 0 up, 1 right, 2 down, 3 left and y grows downwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeamStart {
+    pub x: i32,
+    pub y: i32,
+    pub direction: u32,
+}
+
+impl BeamStart {
+    fn pack(&self) -> u32 {
+        (self.x + 1) as u32 | ((self.y + 1) as u32) << 15 | self.direction << 30
+    }
+}
+
+#[derive(Debug)]
+pub struct Gpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+}
+
+impl Gpu {
+    // the first adapter of any backend, a software one included, shared by every solve
+    pub fn get() -> Result<&'static Self> {
+        static GPU: OnceLock<Result<Gpu, String>> = OnceLock::new();
+
+        GPU.get_or_init(|| Self::new().map_err(|f| f.to_string()))
+            .as_ref()
+            .map_err(|f| eyre!("{}", f))
+    }
+
+    fn new() -> Result<Self> {
+        let instance =
+            wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: false,
+            compatible_surface: None,
+        }))
+        .map_err(|f| eyre!("no GPU adapter for the gpu variants: {}", f))?;
+
+        let adapter_info = adapter.get_info();
+        info!("gpu: {} ({:?})", adapter_info.name, adapter_info.backend);
+
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+                label: Some("advent-of-code-2023"),
+                required_limits: adapter.limits(),
+                ..Default::default()
+            }))?;
+
+        Ok(Self { device, queue })
+    }
+
+    fn storage(&self, label: &str, contents: &[u8], read_write: bool) -> wgpu::Buffer {
+        let mut usage = wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC;
+        if read_write {
+            usage |= wgpu::BufferUsages::COPY_DST;
+        }
+
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents,
+                usage,
+            })
+    }
+
+    fn uniform(&self, label: &str, contents: &[u8]) -> wgpu::Buffer {
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents,
+                usage: wgpu::BufferUsages::UNIFORM,
+            })
+    }
+
+    // runs the main function of the shader over the buffers, bound in order, and reads back the
+    // last one
+    fn run(&self, shader: &str, buffers: &[&wgpu::Buffer], workgroups: u32) -> Result<Vec<u32>> {
+        let module = self
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(shader.into()),
+            });
+        let pipeline = self
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: None,
+                layout: None,
+                module: &module,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None,
+            });
+
+        let entries = buffers
+            .iter()
+            .enumerate()
+            .map(|(index, buffer)| wgpu::BindGroupEntry {
+                binding: index as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect::<Vec<_>>();
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        });
+
+        let output = buffers.last().unwrap();
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: output.size(),
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: None,
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(workgroups, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(output, 0, &readback, 0, output.size());
+        self.queue.submit([encoder.finish()]);
+
+        let (sender, receiver) = mpsc::channel();
+        readback.map_async(wgpu::MapMode::Read, .., move |f| {
+            let _ = sender.send(f);
+        });
+        self.device.poll(wgpu::PollType::wait_indefinitely())?;
+        receiver.recv()??;
+
+        let values = bytemuck::cast_slice(&readback.get_mapped_range(..)).to_vec();
+        Ok(values)
+    }
+
+    // ways to beat the record distance of a race
+    pub fn race_wins(&self, time: u64, distance: u64) -> Result<u64> {
+        if time >= MAX_RACE_TIME {
+            bail!(
+                "a race of {} ms is too long for the gpu variant, at most {}",
+                time,
+                MAX_RACE_TIME - 1
+            );
+        }
+
+        let threads = (time + 1).div_ceil(WORKGROUP_SIZE as u64);
+        let chunk = threads.div_ceil(MAX_WORKGROUPS as u64).max(1);
+        let workgroups = (time + 1).div_ceil(WORKGROUP_SIZE as u64 * chunk);
+
+        let race = Race {
+            time: time as u32,
+            distance_hi: (distance >> 32) as u32,
+            distance_lo: distance as u32,
+            chunk: chunk as u32,
+        };
+        let race = self.uniform("race", bytemuck::bytes_of(&race));
+        let wins = self.storage("wins", bytemuck::bytes_of(&0u32), true);
+
+        let wins = self.run(DAY06_SHADER, &[&race, &wins], workgroups as u32)?;
+        Ok(wins[0] as u64)
+    }
+
+    // tiles each beam energizes on a grid of . / \ | -
+    pub fn energized(&self, grid: &[&str], starts: &[BeamStart]) -> Result<Vec<u32>> {
+        let height = grid.len();
+        let width = grid.first().map_or(0, |f| f.len());
+        if width == 0 || starts.is_empty() {
+            return Ok(vec![0; starts.len()]);
+        }
+
+        let tiles = grid
+            .iter()
+            .flat_map(|f| f.chars())
+            .map(|f| match f {
+                '.' => Ok(0u32),
+                '/' => Ok(1),
+                '\\' => Ok(2),
+                '|' => Ok(3),
+                '-' => Ok(4),
+                _ => Err(eyre!("the gpu variant has no optic {:?}", f)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if tiles.len() != width * height {
+            bail!("the rows of the grid are not all {} tiles wide", width);
+        }
+
+        let splitters = tiles.iter().filter(|f| **f >= 3).count();
+        let params = BeamParams {
+            width: width as u32,
+            height: height as u32,
+            starts: starts.len() as u32,
+            stack_size: 2 * splitters as u32 + 1,
+        };
+        let words = (width * height).div_ceil(8);
+        let packed = starts.iter().map(|f| f.pack()).collect::<Vec<_>>();
+
+        let zeroes = |count: usize| vec![0u32; count.max(1)];
+        let params_buffer = self.uniform("params", bytemuck::bytes_of(&params));
+        let tiles = self.storage("tiles", bytemuck::cast_slice(&tiles), false);
+        let packed = self.storage("starts", bytemuck::cast_slice(&packed), false);
+        let entered = self.storage(
+            "entered",
+            bytemuck::cast_slice(&zeroes(words * starts.len())),
+            true,
+        );
+        let stack = self.storage(
+            "stack",
+            bytemuck::cast_slice(&zeroes(params.stack_size as usize * starts.len())),
+            true,
+        );
+        let energized = self.storage(
+            "energized",
+            bytemuck::cast_slice(&zeroes(starts.len())),
+            true,
+        );
+
+        let workgroups = starts.len().div_ceil(WORKGROUP_SIZE as usize) as u32;
+        let mut energized = self.run(
+            DAY16_SHADER,
+            &[
+                &params_buffer,
+                &tiles,
+                &packed,
+                &entered,
+                &stack,
+                &energized,
+            ],
+            workgroups,
+        )?;
+        energized.truncate(starts.len());
+
+        Ok(energized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::warn;
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::test_support::fixture;
+
+    // the shaders are checked against the cpu solves wherever an adapter exists
+    fn gpu() -> Option<&'static Gpu> {
+        Gpu::get()
+            .inspect_err(|f| warn!("skipping the gpu checks: {}", f))
+            .ok()
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_race_wins() {
+        let Some(gpu) = gpu() else {
+            return;
+        };
+
+        assert_eq!(gpu.race_wins(7, 9).unwrap(), 4);
+        assert_eq!(gpu.race_wins(30, 200).unwrap(), 9);
+        // a distance past 32 bits and more threads than one dispatch dimension holds
+        assert_eq!(gpu.race_wins(71530, 940200).unwrap(), 71503);
+        assert_eq!(
+            gpu.race_wins(40_000_000, 300_000_000_000_000).unwrap(),
+            crate::day06::solve("Time: 40000000\nDistance: 300000000000000\n")
+                .unwrap()
+                .part1
+                .unwrap()
+                .parse::<u64>()
+                .unwrap()
+        );
+        assert!(gpu.race_wins(MAX_RACE_TIME, 1).is_err());
+
+        let input = fixture("day06");
+        assert_eq!(
+            crate::day06::solve_gpu(&input).unwrap(),
+            crate::day06::solve(&input).unwrap()
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_energized() {
+        let Some(gpu) = gpu() else {
+            return;
+        };
+
+        let input = fixture("day16");
+        assert_eq!(
+            crate::day16::solve_gpu(&input).unwrap(),
+            crate::day16::solve(&input).unwrap()
+        );
+
+        assert!(gpu
+            .energized(
+                &["..", ".x"],
+                &[BeamStart {
+                    x: 0,
+                    y: 0,
+                    direction: 1
+                }]
+            )
+            .is_err());
+    }
+}
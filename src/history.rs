@@ -0,0 +1,311 @@
+use std::{
+    fmt::{self, Display},
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::{bail, Result};
+
+use crate::{profile, solver::Answer};
+
+// every answer ever computed, one line per part: unix time, day, part, answer, microseconds taken
+// and the revision of the code, e.g. "1702012345 05 2 46294175 1830 4c605ba-dirty"
+pub fn history_path() -> String {
+    format!("{}/history", profile::input_directory())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub timestamp: u64,
+    pub day: i32,
+    pub part: u8,
+    pub value: String,
+    pub elapsed: Duration,
+    pub revision: String,
+}
+
+impl Entry {
+    fn parse(line: &str) -> Option<Self> {
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+        let &[timestamp, day, part, value, elapsed, revision] = &parts[..] else {
+            return None;
+        };
+
+        Some(Self {
+            timestamp: timestamp.parse().ok()?,
+            day: day.parse().ok()?,
+            part: part.parse().ok()?,
+            value: value.to_string(),
+            elapsed: Duration::from_micros(elapsed.parse().ok()?),
+            revision: revision.to_string(),
+        })
+    }
+
+    fn line(&self) -> String {
+        format!(
+            "{} {:0>2} {} {} {} {}",
+            self.timestamp,
+            self.day,
+            self.part,
+            self.value,
+            self.elapsed.as_micros(),
+            self.revision
+        )
+    }
+}
+
+// the entries of both parts of a solve
+pub fn entries(day: i32, answer: &Answer, elapsed: Duration, revision: &str) -> Vec<Entry> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    [(1, &answer.part1), (2, &answer.part2)]
+        .into_iter()
+        .filter_map(|(part, value)| {
+            Some(Entry {
+                timestamp,
+                day,
+                part,
+                value: value.clone()?,
+                elapsed,
+                revision: revision.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn append(path: &Path, entries: &[Entry]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for entry in entries {
+        writeln!(file, "{}", entry.line())?;
+    }
+
+    Ok(())
+}
+
+// appends the answer to history_path(), a log that can not be written is no reason to fail the solve
+pub fn record(day: i32, answer: &Answer, elapsed: Duration) {
+    let path = history_path();
+    if let Err(error) = append(
+        Path::new(&path),
+        &entries(day, answer, elapsed, &revision()),
+    ) {
+        tracing::warn!("could not add the answer to {}: {}", path, error);
+    }
+}
+
+// the commit the binary was run from, "-dirty" when the tree had changes, "unknown" outside git
+pub fn revision() -> String {
+    Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()
+        .filter(|f| f.status.success())
+        .map(|f| String::from_utf8_lossy(&f.stdout).trim().to_string())
+        .filter(|f| !f.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// the entries of one day, oldest first, an unreadable line is an error rather than a gap
+pub fn read(path: &Path, day: i32) -> Result<History> {
+    if !path.exists() {
+        return Ok(History::default());
+    }
+
+    let mut entries = vec![];
+    for (index, line) in fs::read_to_string(path)?.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(entry) = Entry::parse(line) else {
+            bail!(
+                "{}:{}: expected \"timestamp day part answer microseconds revision\"",
+                path.display(),
+                index + 1
+            );
+        };
+        if entry.day == day {
+            entries.push(entry);
+        }
+    }
+
+    Ok(History { entries })
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<Entry>,
+}
+
+impl History {
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    // the entries whose answer differs from the one before it of the same part
+    pub fn changes(&self) -> Vec<&Entry> {
+        let mut changes = vec![];
+
+        for part in [1, 2] {
+            let mut previous: Option<&Entry> = None;
+            for entry in self.entries.iter().filter(|f| f.part == part) {
+                if previous.is_some_and(|f| f.value != entry.value) {
+                    changes.push(entry);
+                }
+                previous = Some(entry);
+            }
+        }
+
+        changes.sort_by_key(|f| f.timestamp);
+        changes
+    }
+}
+
+impl Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.entries.is_empty() {
+            return writeln!(f, "no answers recorded");
+        }
+
+        let changes = self.changes();
+        writeln!(
+            f,
+            "{:<23}  {:<4}  {:>16}  {:>10}  Revision",
+            "When", "Part", "Answer", "Time"
+        )?;
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{:<23}  {:<4}  {:>16}  {:>10}  {}{}",
+                format_timestamp(entry.timestamp),
+                entry.part,
+                entry.value,
+                format!("{:.1?}", entry.elapsed),
+                entry.revision,
+                if changes.contains(&entry) {
+                    "  changed"
+                } else {
+                    ""
+                }
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// "2023-12-05 06:12:09 UTC", with the days since 1970 turned into a date the proleptic gregorian way
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{}-{:0>2}-{:0>2} {:0>2}:{:0>2}:{:0>2} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_timestamp(1_701_406_800), "2023-12-01 05:00:00 UTC");
+        assert_eq!(format_timestamp(1_709_210_096), "2024-02-29 12:34:56 UTC");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_history() {
+        let path = std::env::temp_dir().join("advent-of-code-2023-history-test");
+        let _ = fs::remove_file(&path);
+
+        let answer = |part1: &str, part2: Option<&str>| Answer {
+            part1: Some(part1.to_string()),
+            part2: part2.map(|f| f.to_string()),
+        };
+        let mut solves = [
+            entries(
+                5,
+                &answer("35", Some("46")),
+                Duration::from_micros(1830),
+                "4c605ba",
+            ),
+            entries(
+                9,
+                &answer("114", Some("2")),
+                Duration::from_millis(2),
+                "4c605ba",
+            ),
+            entries(
+                5,
+                &answer("35", Some("47")),
+                Duration::from_millis(1),
+                "61df9ac-dirty",
+            ),
+            entries(5, &answer("35", None), Duration::from_millis(1), "61df9ac"),
+        ];
+        for (index, solve) in solves.iter_mut().enumerate() {
+            for entry in solve.iter_mut() {
+                entry.timestamp = 1_701_406_800 + index as u64 * 60;
+            }
+            append(&path, solve).unwrap();
+        }
+
+        let history = read(&path, 5).unwrap();
+        assert_eq!(history.entries().len(), 5);
+        assert_eq!(history.entries()[0], solves[0][0]);
+        assert_eq!(history.entries()[1].elapsed, Duration::from_micros(1830));
+
+        let changes = history.changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].value, "47");
+        assert_eq!(changes[0].revision, "61df9ac-dirty");
+
+        let text = history.to_string();
+        assert!(text.contains("2023-12-01 05:02:00 UTC  2"));
+        assert_eq!(text.matches("changed").count(), 1);
+        assert_eq!(read(&path, 6).unwrap().to_string(), "no answers recorded\n");
+
+        fs::write(&path, "1701406800 05 1\n").unwrap();
+        assert!(read(&path, 5).is_err());
+
+        let _ = fs::remove_file(&path);
+    }
+}
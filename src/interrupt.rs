@@ -0,0 +1,113 @@
+use std::{
+    fmt::{self, Display},
+    process,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tracing::info;
+
+use crate::leaderboard::Leaderboard;
+
+// the exit code of a process stopped by SIGINT
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+// what a run got done so far, shared with the ctrl-c handler
+#[derive(Debug, Default)]
+pub struct Progress {
+    // the answer lines of the finished days, as the solver logs them
+    answers: Vec<String>,
+    leaderboard: Leaderboard,
+    // the day being solved and since when
+    current: Option<(i32, Instant)>,
+}
+
+impl Progress {
+    pub fn start(&mut self, day: i32) {
+        self.current = Some((day, Instant::now()));
+    }
+
+    pub fn finish(
+        &mut self,
+        day: i32,
+        answer_lines: impl IntoIterator<Item = String>,
+        elapsed: Duration,
+    ) {
+        self.answers.extend(answer_lines);
+        self.leaderboard.push(day, elapsed);
+        self.current = None;
+    }
+}
+
+impl Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.current {
+            Some((day, start)) => writeln!(
+                f,
+                "interrupted while solving day {:0>2}, after {:.3?}",
+                day,
+                start.elapsed()
+            )?,
+            None => writeln!(f, "interrupted")?,
+        }
+
+        if self.answers.is_empty() {
+            return Ok(());
+        }
+
+        for line in &self.answers {
+            writeln!(f, "{}", line)?;
+        }
+        write!(f, "{}", self.leaderboard)
+    }
+}
+
+pub type SharedProgress = Arc<Mutex<Progress>>;
+
+// on ctrl-c, logs the progress as whole lines and exits instead of dying in the middle of a day
+// the solves themselves are synchronous, so the handler runs on a worker thread of the runtime
+pub fn install(progress: SharedProgress) {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+
+        // a solve that panicked while holding the lock still left its progress behind
+        let progress = progress.lock().unwrap_or_else(|f| f.into_inner());
+        info!("\n{}", progress);
+
+        process::exit(INTERRUPTED_EXIT_CODE);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_progress() {
+        let mut progress = Progress::default();
+        assert_eq!(progress.to_string(), "interrupted\n");
+
+        progress.start(1);
+        progress.finish(
+            1,
+            [
+                "Day 01 part 1: 142".to_string(),
+                "Day 01 part 2: 281".to_string(),
+            ],
+            Duration::from_millis(3),
+        );
+        progress.start(17);
+
+        let text = progress.to_string();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert!(lines[0].starts_with("interrupted while solving day 17, after "));
+        assert_eq!(lines[1..3], ["Day 01 part 1: 142", "Day 01 part 2: 281"]);
+        assert!(lines[4].starts_with("01"));
+        assert!(lines[5].starts_with("total"));
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    fmt::{self, Display},
+    time::Duration,
+};
+
+use tracing::info;
+
+// how long each day took in an all days run, slowest first
+#[derive(Debug, Default)]
+pub struct Leaderboard {
+    entries: Vec<(i32, Duration)>,
+}
+
+impl Leaderboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, day: i32, elapsed: Duration) {
+        self.entries.push((day, elapsed));
+        self.entries
+            .sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    }
+
+    pub fn entries(&self) -> &[(i32, Duration)] {
+        &self.entries
+    }
+
+    pub fn total(&self) -> Duration {
+        self.entries.iter().map(|(_, elapsed)| *elapsed).sum()
+    }
+
+    pub fn display(&self) {
+        info!("\n{}", self);
+    }
+}
+
+impl Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total();
+
+        writeln!(f, "{:<5}  {:>12}  {:>7}", "Day", "Time", "Share")?;
+
+        for (day, elapsed) in &self.entries {
+            let share = elapsed.as_secs_f64() / total.as_secs_f64().max(f64::EPSILON) * 100.0;
+            writeln!(
+                f,
+                "{:<5}  {:>12}  {:>6.1}%",
+                format!("{:0>2}", day),
+                format!("{:.3?}", elapsed),
+                share
+            )?;
+        }
+
+        writeln!(f, "{:<5}  {:>12}", "total", format!("{:.3?}", total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_leaderboard() {
+        let mut leaderboard = Leaderboard::new();
+        leaderboard.push(1, Duration::from_millis(10));
+        leaderboard.push(17, Duration::from_millis(60));
+        leaderboard.push(14, Duration::from_millis(30));
+
+        let days = leaderboard
+            .entries()
+            .iter()
+            .map(|(day, _)| *day)
+            .collect::<Vec<_>>();
+        assert_eq!(days, vec![17, 14, 1]);
+        assert_eq!(leaderboard.total(), Duration::from_millis(100));
+
+        let text = leaderboard.to_string();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert!(lines[1].starts_with("17") && lines[1].ends_with("60.0%"));
+        assert!(lines[3].starts_with("01") && lines[3].ends_with("10.0%"));
+        assert!(lines[4].starts_with("total"));
+    }
+}
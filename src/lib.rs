@@ -0,0 +1,107 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+// the tests read fixtures and use eyre, so without std they are not built
+#[cfg(all(test, feature = "std"))]
+#[macro_use]
+mod test_support;
+
+#[cfg(feature = "std")]
+pub mod analyze;
+#[cfg(feature = "tooling")]
+pub mod badge;
+#[cfg(feature = "tooling")]
+pub mod bench;
+#[cfg(feature = "tooling")]
+pub mod cache;
+#[cfg(feature = "tooling")]
+pub mod calendar;
+#[cfg(feature = "tooling")]
+pub mod checkpoint;
+#[cfg(feature = "cli")]
+pub mod clipboard;
+#[cfg(feature = "tooling")]
+pub mod compare;
+#[cfg(feature = "tooling")]
+pub mod credentials;
+pub mod day01;
+#[cfg(feature = "std")]
+pub mod day02;
+#[cfg(feature = "std")]
+pub mod day03;
+#[cfg(feature = "std")]
+pub mod day04;
+#[cfg(feature = "std")]
+pub mod day05;
+pub mod day06;
+#[cfg(feature = "std")]
+pub mod day07;
+#[cfg(feature = "std")]
+pub mod day08;
+pub mod day09;
+#[cfg(feature = "std")]
+pub mod day10;
+#[cfg(feature = "std")]
+pub mod day11;
+#[cfg(feature = "std")]
+pub mod day12;
+#[cfg(feature = "std")]
+pub mod day13;
+#[cfg(feature = "std")]
+pub mod day14;
+pub mod day15;
+#[cfg(feature = "std")]
+pub mod day16;
+#[cfg(feature = "std")]
+pub mod day17;
+#[cfg(feature = "std")]
+pub mod day18;
+#[cfg(feature = "std")]
+pub mod day19;
+#[cfg(feature = "std")]
+pub mod difftest;
+#[cfg(feature = "tooling")]
+pub mod doctor;
+pub mod error;
+#[cfg(feature = "tooling")]
+pub mod fetch;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "tooling")]
+pub mod history;
+#[cfg(feature = "cli")]
+pub mod interrupt;
+#[cfg(feature = "std")]
+pub mod leaderboard;
+#[cfg(feature = "cli")]
+pub mod notify;
+#[cfg(feature = "cli")]
+pub mod plugin;
+#[cfg(feature = "tooling")]
+pub mod profile;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "cli")]
+pub mod script;
+#[cfg(feature = "tooling")]
+pub mod scrub;
+#[cfg(feature = "std")]
+pub mod select;
+#[cfg(feature = "cli")]
+pub mod serve;
+pub mod solver;
+#[cfg(feature = "tooling")]
+pub mod stars;
+#[cfg(feature = "cli")]
+pub mod statement;
+#[cfg(feature = "cli")]
+pub mod times;
+#[cfg(feature = "std")]
+pub mod utils;
+#[cfg(feature = "std")]
+pub mod validate;
+#[cfg(feature = "tooling")]
+pub mod vault;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -1,61 +1,1306 @@
-use clap::{Arg, ArgMatches, Command};
-use color_eyre::eyre::Result;
-use tracing::Level;
-use tracing_subscriber::FmtSubscriber;
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod solver;
-mod utils;
+use std::{
+    any::Any,
+    fs,
+    io::Cursor,
+    path::Path,
+    process::ExitCode,
+    time::{Duration, Instant, SystemTime},
+};
+
+use clap::{parser::ValueSource, Arg, ArgAction, ArgMatches, Command};
+use color_eyre::eyre::{bail, eyre, Result};
+use tracing::{error, info, warn};
+use tracing_subscriber::{filter::LevelFilter, prelude::*};
+
+use advent_of_code_2023::{
+    analyze::Analysis,
+    badge,
+    bench::{self, BenchRun},
+    cache,
+    calendar::{self, Calendar},
+    checkpoint, clipboard,
+    compare::{Batch, Comparison, InputDiff},
+    credentials, day05, day14, day17, difftest, doctor,
+    error::{self, Failure, FailureKind},
+    fetch, history,
+    interrupt::{self, SharedProgress},
+    leaderboard::Leaderboard,
+    notify, plugin, profile, registry, script, scrub,
+    select::{self, Thresholds},
+    serve,
+    solver::{self, Answer, SolveContext, Solver},
+    stars::Stars,
+    statement,
+    times::{self, PrivateLeaderboard},
+    utils::{
+        charset::{self, Charset},
+        dump, explain, guard, Part,
+    },
+    validate, vault,
+};
+
+// keeps the flame graph writer alive until main returns, dropping it flushes the file
+type TracingGuard = Option<Box<dyn Any>>;
+
+// events at INFO and above go to stdout, --trace-flame also records every span
+fn init_tracing(matches: &ArgMatches) -> Result<TracingGuard> {
+    let stdout = tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO);
+    let path = matches.get_one::<String>("trace-flame");
+
+    #[cfg(feature = "flame")]
+    if let Some(path) = path {
+        let (flame, guard) = tracing_flame::FlameLayer::with_file(path)?;
+        // one stack per span path instead of one per thread, so rayon workers do not split it up
+        let flame = flame.with_threads_collapsed(true).with_file_and_line(false);
+
+        tracing_subscriber::registry()
+            .with(stdout)
+            .with(flame)
+            .init();
+        return Ok(Some(Box::new(guard)));
+    }
+
+    #[cfg(not(feature = "flame"))]
+    if path.is_some() {
+        bail!(
+            "--trace-flame needs a build with the flame feature, e.g. cargo run --features flame"
+        );
+    }
+
+    tracing_subscriber::registry().with(stdout).init();
+    Ok(None)
+}
 
 fn init() -> Result<ArgMatches> {
     color_eyre::install()?;
 
-    // a builder for `FmtSubscriber`.
-    let subscriber = FmtSubscriber::builder()
-        // all spans/events with a level higher than TRACE (e.g, debug, info, warn, etc.)
-        // will be written to stdout.
-        .with_max_level(Level::INFO)
-        // completes the builder.
-        .finish();
-
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+    Ok(command().get_matches())
+}
 
-    let matches = Command::new("Advent of Code 2023")
+fn command() -> Command {
+    Command::new("Advent of Code 2023")
         .version("1.0")
         .author("Harry Agustian <https://harryagustian.xyz>")
         .about("Solution for Advent of Code 2023 in Rust")
-        .arg(Arg::new("day").required(true).help("Day to solve"))
-        .get_matches();
+        .arg(
+            Arg::new("day")
+                .required_unless_present("todo")
+                .help("Day to solve, or all to solve every day with an input and time them"),
+        )
+        .arg(
+            Arg::new("compare")
+                .long("compare")
+                .action(ArgAction::SetTrue)
+                .help("Run every variant of the day and check that the answers agree"),
+        )
+        .arg(
+            Arg::new("plugins")
+                .long("plugins")
+                .value_name("DIRECTORY")
+                .num_args(0..=1)
+                .default_missing_value(plugin::PLUGIN_DIRECTORY)
+                .requires("compare")
+                .help("Also compare the day implementations of the shared libraries in DIRECTORY, plugins/ by default"),
+        )
+        .arg(
+            Arg::new("dump-state")
+                .long("dump-state")
+                .global(true)
+                .value_name("DIR")
+                .help("Write intermediate grid states of days 10, 14, 16 and 17 to DIR"),
+        )
+        .arg(
+            Arg::new("explain")
+                .long("explain")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Log how the answer was reached, for days that support it (days 07, 10 and 17)"),
+        )
+        .arg(
+            Arg::new("diff-inputs")
+                .long("diff-inputs")
+                .num_args(2)
+                .value_names(["A", "B"])
+                .conflicts_with_all(["compare", "algo"])
+                .help("Solve the day for two input files and show the answers side by side"),
+        )
+        .arg(
+            Arg::new("input-dir")
+                .long("input-dir")
+                .value_name("DIR")
+                .conflicts_with_all(["compare", "diff-inputs", "input", "example", "copy"])
+                .help("Solve the day for every file in DIR and show a table of the answers and times"),
+        )
+        .arg(
+            Arg::new("max-steps")
+                .long("max-steps")
+                .global(true)
+                .value_name("N")
+                .help(
+                    "Abort when a simulation loop of days 08, 14, 16 or 17 runs more than N steps",
+                ),
+        )
+        .arg(
+            Arg::new("threads")
+                .long("threads")
+                .global(true)
+                .value_name("N")
+                .help("Use at most N threads for the parallel days and tools, all cores by default"),
+        )
+        .arg(
+            Arg::new("resume")
+                .long("resume")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Go on from the last checkpoint of day 14's spin cycles or day 05's brute force"),
+        )
+        .arg(
+            Arg::new("checkpoint-every")
+                .long("checkpoint-every")
+                .global(true)
+                .value_name("SECONDS")
+                .default_value("30")
+                .help(format!(
+                    "How often long loops save their progress to {}, 0 turns it off",
+                    checkpoint::CHECKPOINT_DIRECTORY
+                )),
+        )
+        .arg(
+            Arg::new("retries")
+                .long("retries")
+                .global(true)
+                .value_name("N")
+                .env("AOC_RETRIES")
+                .default_value("3")
+                .help("How often a request to the site that timed out or got a 429 or 5xx is tried again"),
+        )
+        .arg(
+            Arg::new("backoff")
+                .long("backoff")
+                .global(true)
+                .value_name("SECONDS")
+                .env("AOC_BACKOFF")
+                .default_value("2")
+                .help("Wait before the first retry, doubled for each one after and spread by up to 25% either way"),
+        )
+        .arg(
+            Arg::new("mmap")
+                .long("mmap")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Memory-map the inputs instead of reading them into memory, for very large inputs"),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .global(true)
+                .value_name("NAME")
+                .help("Account to use, with its inputs and answers in input/profiles/NAME and its token in AOC_SESSION_NAME or saved by login --profile NAME"),
+        )
+        .arg(
+            Arg::new("parse-cache")
+                .long("parse-cache")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help(format!(
+                    "Save the parsed inputs of days 05 and 19 to {} and read them back on the next runs",
+                    cache::CACHE_DIRECTORY
+                )),
+        )
+        .arg(
+            Arg::new("trace-flame")
+                .long("trace-flame")
+                .global(true)
+                .value_name("FILE")
+                .help("Write folded stacks of the day, part and phase spans to FILE, for inferno-flamegraph"),
+        )
+        .arg(
+            Arg::new("charset")
+                .long("charset")
+                .global(true)
+                .value_name("CHARSET")
+                .value_parser(["unicode", "ascii"])
+                .default_value("unicode")
+                .help("Draw grids and routes with unicode box drawing characters or plain ascii"),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .global(true)
+                .value_name("FILE")
+                .help("Also write the answers to FILE, creating its directory if needed"),
+        )
+        .arg(
+            Arg::new("input")
+                .long("input")
+                .global(true)
+                .value_name("SOURCE")
+                .value_parser([clipboard::INPUT_SOURCE])
+                .help("Read the puzzle input from the clipboard instead of input/NN"),
+        )
+        .arg(
+            Arg::new("todo")
+                .long("todo")
+                .conflicts_with("day")
+                .action(ArgAction::SetTrue)
+                .help("Solve only the days with a part that has no accepted answer in input/answers, - marks one, and list what is left"),
+        )
+        .arg(
+            Arg::new("example")
+                .long("example")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .conflicts_with("input")
+                .help(format!(
+                    "Solve the example of the puzzle page, saved to {}, and check the answers it gives",
+                    statement::EXAMPLE_DIRECTORY
+                )),
+        )
+        .arg(
+            Arg::new("copy")
+                .long("copy")
+                .global(true)
+                .value_name("PART")
+                .value_parser(["1", "2"])
+                .help("Copy the answer of PART to the clipboard, ready to submit"),
+        )
+        .arg(
+            Arg::new("notify-after")
+                .long("notify-after")
+                .global(true)
+                .value_name("SECONDS")
+                .help("Send a desktop notification with the answers when a solve takes at least SECONDS"),
+        )
+        .arg(
+            Arg::new("fetch")
+                .long("fetch")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help(
+                "Download a missing input to input/NN, with the session cookie from AOC_SESSION",
+            ),
+        )
+        .arg(
+            Arg::new("algo")
+                .long("algo")
+                .conflicts_with("compare")
+                .help("Variant to solve with, e.g. day10::shoelace or shoelace"),
+        )
+        .arg(
+            Arg::new("script")
+                .long("script")
+                .value_name("FILE")
+                .conflicts_with_all(["compare", "algo"])
+                .help("Solve the day with a rhai script, which gets `input` and returns #{ part1: .., part2: .. }"),
+        )
+        .subcommand(
+            Command::new("analyze")
+                .about("Report the shape of a day's input")
+                .arg(Arg::new("day").required(true).help("Day to analyze")),
+        )
+        .subcommand(
+            Command::new("history")
+                .about("List every answer computed for a day, marking where one changed")
+                .arg(Arg::new("day").required(true).help("Day to list")),
+        )
+        .subcommand(
+            Command::new("validate")
+                .about("Check a day's input for obvious problems without solving it")
+                .arg(Arg::new("day").required(true).help("Day to validate")),
+        )
+        .subcommand(
+            Command::new("spin")
+                .about("Spin the day 14 platform with a custom tilt sequence and report its load")
+                .arg(
+                    Arg::new("tilts")
+                        .required(true)
+                        .help("Tilts of one cycle, e.g. NWSE for the puzzle's spin cycle"),
+                )
+                .arg(
+                    Arg::new("cycles")
+                        .required(true)
+                        .help("Number of cycles to run"),
+                )
+                .arg(
+                    Arg::new("legend")
+                        .long("legend")
+                        .value_name("LEGEND")
+                        .default_value("")
+                        .help("Characters and rock weights, e.g. \"cube=X empty=_ O=1 @=5\""),
+                ),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Solve inputs sent over HTTP and expose /metrics for prometheus")
+                .arg(
+                    Arg::new("listen")
+                        .long("listen")
+                        .value_name("ADDRESS")
+                        .default_value("127.0.0.1:8080")
+                        .help("Address to listen on"),
+                ),
+        )
+        .subcommand(
+            Command::new("stars")
+                .about("Show the stars on the site and which days solved here are missing some"),
+        )
+        .subcommand(
+            Command::new("login")
+                .about("Check a session token against the site and save it in the credential store of the os"),
+        )
+        .subcommand(
+            Command::new("logout").about("Remove the session token saved by login"),
+        )
+        .subcommand(
+            Command::new("encrypt-inputs")
+                .about("Encrypt every input under input/ with the passphrase in AOC_INPUT_PASSPHRASE"),
+        )
+        .subcommand(
+            Command::new("decrypt-inputs")
+                .about("Turn every encrypted input under input/ back into plain text"),
+        )
+        .subcommand(
+            Command::new("scrub")
+                .about("Delete the inputs and puzzle copies under input/, remove saved tokens and check git tracks nothing sensitive")
+                .arg(
+                    Arg::new("encrypt")
+                        .long("encrypt")
+                        .action(ArgAction::SetTrue)
+                        .help("Encrypt the inputs with AOC_INPUT_PASSPHRASE instead of deleting them"),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .action(ArgAction::SetTrue)
+                        .help("Report what would be done without touching anything"),
+                ),
+        )
+        .subcommand(
+            Command::new("fetch-all")
+                .about("Download every unlocked input that is missing from input/"),
+        )
+        .subcommand(
+            Command::new("calendar")
+                .about("Solve every day and show which ones are verified and fast on a calendar"),
+        )
+        .subcommand(
+            Command::new("show")
+                .about("Show a day's puzzle statement, downloaded once and cached in input/statements")
+                .arg(Arg::new("day").required(true).help("Day to show"))
+                .arg(
+                    Arg::new("refresh")
+                        .long("refresh")
+                        .action(ArgAction::SetTrue)
+                        .help("Download the statement again, e.g. once part 2 is unlocked"),
+                ),
+        )
+        .subcommand(
+            Command::new("open")
+                .about("Open a day's puzzle page in the browser")
+                .arg(Arg::new("day").required(true).help("Day to open")),
+        )
+        .subcommand(
+            Command::new("times")
+                .about("Show your completion times and ranks from your private leaderboard"),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Time the default variant of days and compare medians with a saved run")
+                .arg(
+                    Arg::new("day")
+                        .default_value("all")
+                        .help("Day to time, or all for every day with an input"),
+                )
+                .arg(
+                    Arg::new("iterations")
+                        .long("iterations")
+                        .value_name("N")
+                        .default_value("10")
+                        .help("Measured solves per day"),
+                )
+                .arg(
+                    Arg::new("warmup")
+                        .long("warmup")
+                        .value_name("N")
+                        .default_value("3")
+                        .help("Solves per day before measuring, which are not counted"),
+                )
+                .arg(
+                    Arg::new("save")
+                        .long("save")
+                        .value_name("NAME")
+                        .help("Save this run as a baseline under input/bench"),
+                )
+                .arg(
+                    Arg::new("baseline")
+                        .long("baseline")
+                        .value_name("NAME")
+                        .help("Compare this run with a saved baseline"),
+                ),
+        )
+        .subcommand(
+            Command::new("difftest")
+                .about("Solve generated inputs with every variant of a day and minimize the first disagreement")
+                .arg(Arg::new("day").required(true).help("Day with more than one variant"))
+                .arg(
+                    Arg::new("cases")
+                        .long("cases")
+                        .value_name("N")
+                        .default_value("200")
+                        .help("Inputs to generate, from tiny to larger ones"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .value_name("SEED")
+                        .default_value("2023")
+                        .help("Seed of the generator, the same seed generates the same inputs"),
+                ),
+        )
+        .subcommand(
+            Command::new("calibrate")
+                .about("Time the variants of days on generated inputs and save from which size each is used")
+                .arg(
+                    Arg::new("day")
+                        .default_value("all")
+                        .help("Day to calibrate, or all for every day with a generator"),
+                )
+                .arg(
+                    Arg::new("repeats")
+                        .long("repeats")
+                        .value_name("N")
+                        .default_value("3")
+                        .help("Solves per variant and size, the fastest one counts"),
+                ),
+        )
+        .subcommand(
+            Command::new("doctor")
+                .about("Check the inputs, the session token, the saved files and the terminal, with fixes"),
+        )
+        .subcommand(
+            Command::new("badge")
+                .about("Write shields-style SVG badges with the median of each day of a saved bench run")
+                .arg(
+                    Arg::new("baseline")
+                        .required(true)
+                        .value_name("NAME")
+                        .help("Run saved with bench --save"),
+                )
+                .arg(
+                    Arg::new("out")
+                        .long("out")
+                        .value_name("DIRECTORY")
+                        .default_value("input/badges")
+                        .help("Directory for dayNN.svg and total.svg"),
+                ),
+        )
+        .subcommands(day_commands())
+        .subcommand_negates_reqs(true)
+}
 
-    Ok(matches)
+// days with options of their own, the defaults are the puzzle's
+fn day_commands() -> [Command; 3] {
+    [
+        Command::new("day05")
+            .about("Solve day 05 and draw how the seed ranges are split on their way to locations")
+            .arg(
+                Arg::new("diagram")
+                    .long("diagram")
+                    .value_name("FILE")
+                    .help("Write the splits as graphviz dot to FILE, e.g. for dot -Tsvg"),
+            )
+            .arg(
+                Arg::new("part")
+                    .long("part")
+                    .value_name("N")
+                    .default_value("2")
+                    .value_parser(["1", "2"])
+                    .help("Seeds of which part to draw"),
+            ),
+        Command::new("day14")
+            .about("Solve day 14 with a different number of spin cycles")
+            .arg(
+                Arg::new("cycles")
+                    .long("cycles")
+                    .value_name("N")
+                    .default_value("1000000000")
+                    .help("Spin cycles before measuring the load in part 2"),
+            )
+            .arg(
+                Arg::new("legend")
+                    .long("legend")
+                    .value_name("LEGEND")
+                    .default_value("")
+                    .help("Characters and rock weights, e.g. \"cube=X empty=_ O=1 @=5\""),
+            ),
+        Command::new("day17")
+            .about("Solve day 17 with a different ultra crucible")
+            .arg(
+                Arg::new("min-run")
+                    .long("min-run")
+                    .value_name("N")
+                    .default_value("4")
+                    .help("Tiles the part 2 crucible has to move straight before it can turn"),
+            )
+            .arg(
+                Arg::new("max-run")
+                    .long("max-run")
+                    .value_name("N")
+                    .default_value("10")
+                    .help("Tiles the part 2 crucible may move straight at most"),
+            ),
+    ]
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let matches = init()?;
+type DaySolve = Box<dyn FnOnce(&str, &mut SolveContext) -> Result<Answer>>;
 
-    let day = matches.get_one::<String>("day").unwrap().parse::<i32>()?;
+// the day and its solve function for a day subcommand, None for other subcommands
+fn day_command(name: &str, matches: &ArgMatches) -> Result<Option<(i32, DaySolve)>> {
+    let value = |id: &str| matches.get_one::<String>(id).unwrap();
+
+    let command: (i32, DaySolve) = match name {
+        "day05" => {
+            let diagram = matches.get_one::<String>("diagram").cloned();
+            let part = match value("part").as_str() {
+                "1" => Part::One,
+                _ => Part::Two,
+            };
+            (
+                5,
+                Box::new(move |input, _| {
+                    if let Some(path) = diagram {
+                        fs::write(&path, day05::mapping_dot(input, part)?)?;
+                        info!("wrote the seed range diagram to {}", path);
+                    }
+
+                    day05::solve(input)
+                }),
+            )
+        }
+        "day14" => {
+            let cycles = value("cycles").parse::<usize>()?;
+            let legend = value("legend").parse::<day14::Legend>()?;
+            (
+                14,
+                Box::new(move |input, context| {
+                    day14::solve_with_legend(input, context, cycles, legend.clone())
+                }),
+            )
+        }
+        "day17" => {
+            let crucible =
+                day17::Crucible::new(value("min-run").parse()?, value("max-run").parse()?)?;
+            (
+                17,
+                Box::new(move |input, context| {
+                    day17::solve_with_crucible(input, context, crucible)
+                }),
+            )
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(command))
+}
+
+// every day that has an input, followed by how long each of them took
+// progress keeps what is done for the ctrl-c handler, output gets the answers of every day
+async fn solve_all(
+    progress: &SharedProgress,
+    output: Option<&Path>,
+    thresholds: &Thresholds,
+) -> Result<()> {
+    let mut leaderboard = Leaderboard::new();
+    let mut lines = vec![];
+
+    for day in 1..=25 {
+        if !Path::new(&solver::input_path(day)).exists() || registry::variants(day).is_empty() {
+            continue;
+        }
+
+        progress.lock().unwrap().start(day);
+        let start = Instant::now();
+        let mut solver = solver::Solver::new(day)
+            .await?
+            .with_thresholds(thresholds.clone());
+        solver.solve().await?;
+        leaderboard.push(day, start.elapsed());
+        history::record(day, solver.answer().unwrap(), start.elapsed());
+        progress
+            .lock()
+            .unwrap()
+            .finish(day, solver.answer_lines(), start.elapsed());
+
+        solver.print_answer();
+        lines.extend(solver.answer_lines());
+    }
+
+    if leaderboard.entries().is_empty() {
+        bail!("no inputs found in input/");
+    }
+
+    if let Some(path) = output {
+        solver::write_lines(path, &lines)?;
+    }
+
+    leaderboard.display();
+
+    Ok(())
+}
+
+// --todo, the days with an open part are solved and only their open parts are reported
+// the days solve both parts at once, so a day with one part open still runs the other
+async fn solve_todo(progress: &SharedProgress, thresholds: &Thresholds) -> Result<()> {
+    let answers = calendar::read_answers(Path::new(&calendar::answers_path()))?;
+    let now = SystemTime::now();
+    let mut left = vec![];
+
+    for day in (1..=25).filter(|f| fetch::is_unlocked(*f, now)) {
+        let parts = calendar::open_parts(&answers, day);
+        if parts.is_empty() {
+            continue;
+        }
+
+        let number = |part: &Part| match part {
+            Part::One => 1,
+            Part::Two => 2,
+        };
+        let names = parts
+            .iter()
+            .map(|f| format!("part {}", number(f)))
+            .collect::<Vec<_>>()
+            .join(" and ");
+        if registry::variants(day).is_empty() {
+            left.push(format!("{:0>2} {}, not solved here", day, names));
+            continue;
+        }
+        if !Path::new(&solver::input_path(day)).exists() {
+            left.push(format!("{:0>2} {}, no input", day, names));
+            continue;
+        }
+
+        progress.lock().unwrap().start(day);
+        let start = Instant::now();
+        let mut solver = solver::Solver::new(day)
+            .await?
+            .with_thresholds(thresholds.clone());
+        solver.solve().await?;
+        progress
+            .lock()
+            .unwrap()
+            .finish(day, solver.answer_lines(), start.elapsed());
+        history::record(day, solver.answer().unwrap(), start.elapsed());
+
+        let answer = solver.answer().unwrap();
+        for part in &parts {
+            let value = match part {
+                Part::One => &answer.part1,
+                Part::Two => &answer.part2,
+            };
+            info!(
+                "Day {:0>2} part {}: {}",
+                day,
+                number(part),
+                value.as_deref().unwrap_or("-")
+            );
+        }
+        left.push(format!("{:0>2} {}, to submit", day, names));
+    }
+
+    match left.is_empty() {
+        true => info!("every unlocked part has an accepted answer"),
+        false => info!("left to do:\n{}", left.join("\n")),
+    }
+
+    Ok(())
+}
+
+fn run_bench(matches: &ArgMatches) -> Result<()> {
+    let iterations = matches
+        .get_one::<String>("iterations")
+        .unwrap()
+        .parse::<u32>()?;
+    let warmup = matches
+        .get_one::<String>("warmup")
+        .unwrap()
+        .parse::<u32>()?;
+    // read before running, so a typo does not cost a whole benchmark run
+    let baseline = match matches.get_one::<String>("baseline") {
+        Some(name) => Some(BenchRun::load(Path::new(&bench::baseline_path(name)?))?),
+        None => None,
+    };
+
+    let days = match matches.get_one::<String>("day").unwrap().as_str() {
+        "all" => (1..=25)
+            .filter(|f| {
+                !registry::variants(*f).is_empty() && Path::new(&solver::input_path(*f)).exists()
+            })
+            .collect(),
+        day => vec![day.parse::<i32>()?],
+    };
+
+    let mut run = BenchRun::new();
+    for day in days {
+        fetch::ensure_input(day, false)?;
+        run.measure(day, warmup, iterations)?;
+    }
+
+    if run.is_empty() {
+        bail!("no inputs found in input/");
+    }
+
+    match &baseline {
+        Some(baseline) => info!("\n{}", run.compare(baseline)),
+        None => info!("\n{}", run),
+    }
+
+    if let Some(name) = matches.get_one::<String>("save") {
+        let path = bench::baseline_path(name)?;
+        run.save(Path::new(&path))?;
+        info!("saved the run to {}", path);
+    }
+
+    Ok(())
+}
+
+// reads input/NN, the clipboard with --input clipboard or the example of the statement with
+// --example
+async fn open_solver(day: i32, matches: &ArgMatches, thresholds: &Thresholds) -> Result<Solver> {
+    if matches.get_flag("example") {
+        let example = statement::load_example(day, false)?;
+        return Ok(Solver::from_reader(
+            day,
+            Cursor::new(example.input.into_bytes()),
+        ));
+    }
+
+    if matches.get_one::<String>("input").is_some() {
+        let input = clipboard::paste()?;
+        return Ok(Solver::from_reader(day, Cursor::new(input.into_bytes())));
+    }
+
+    fetch::ensure_input(day, matches.get_flag("fetch"))?;
+    Ok(Solver::new(day).await?.with_thresholds(thresholds.clone()))
+}
+
+// the seconds of --notify-after, checked before solving so a slow day is not solved for nothing
+fn notify_threshold(matches: &ArgMatches) -> Result<Option<Duration>> {
+    let Some(seconds) = matches.get_one::<String>("notify-after") else {
+        return Ok(None);
+    };
+
+    if cfg!(not(feature = "notify")) {
+        bail!("--notify-after needs a build with the notify feature, e.g. cargo run --features notify");
+    }
+
+    Ok(Some(Duration::try_from_secs_f64(seconds.parse()?)?))
+}
 
-    let mut solver = solver::Solver::new(day).await?;
-    solver.solve().await?;
+// logs the answer and adds it to the history, then writes it with --output, copies a part with
+// --copy and notifies with --notify-after
+// the answers of --example are checked against the statement instead of being added
+fn report_answer(day: i32, solver: &Solver, matches: &ArgMatches, elapsed: Duration) -> Result<()> {
     solver.print_answer();
+    match matches.get_flag("example") {
+        true => statement::load_example(day, false)?.check(solver.answer().unwrap())?,
+        false => history::record(day, solver.answer().unwrap(), elapsed),
+    }
+
+    if let Some(path) = matches.get_one::<String>("output") {
+        solver.write_answer(Path::new(path))?;
+    }
+
+    if let Some(part) = matches.get_one::<String>("copy") {
+        let answer = solver
+            .answer()
+            .ok_or_else(|| eyre!("the day was not solved"))?;
+        clipboard::copy_answer(answer, part.parse()?)?;
+    }
+
+    if notify_threshold(matches)?.is_some_and(|f| elapsed >= f) {
+        notify::send(day, solver.answer().unwrap(), elapsed);
+    }
+
+    Ok(())
+}
+
+// the runtime is built by hand, so --threads can bound it before anything runs
+fn start() -> Result<()> {
+    let matches = init()?;
+
+    let mut runtime = tokio::runtime::Builder::new_multi_thread();
+    runtime.enable_all();
+
+    if let Some(threads) = matches.get_one::<String>("threads") {
+        let threads = threads.parse::<usize>()?;
+        if threads == 0 {
+            bail!("--threads needs at least 1 thread");
+        }
+
+        // the pool every par_iter of the days and tools runs on
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()?;
+        runtime
+            .worker_threads(threads)
+            .max_blocking_threads(threads);
+    }
+
+    runtime.build()?.block_on(run(matches))
+}
+
+// reports an error the way returning it from main would, but exits with the code of its kind of
+// failure, see error::FailureKind
+fn main() -> ExitCode {
+    match start() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(report) => {
+            eprintln!("Error: {:?}", report);
+            ExitCode::from(error::exit_code(&report))
+        }
+    }
+}
+
+// the settings every command shares, applied before any of them runs
+fn apply_settings(matches: &ArgMatches) -> Result<()> {
+    if let Some(directory) = matches.get_one::<String>("dump-state") {
+        dump::enable(directory)?;
+    }
+
+    if matches.get_flag("explain") {
+        explain::enable();
+    }
+
+    if let Some(max_steps) = matches.get_one::<String>("max-steps") {
+        guard::set_max_steps(max_steps.parse()?);
+    }
+    // parsed inputs and checkpoints are made from the inputs, they would sit unencrypted next to
+    // the encrypted ones, so with a passphrase there are none
+    let encrypted = vault::passphrase().is_some();
+    let checkpoint_every_given =
+        matches.value_source("checkpoint-every") == Some(ValueSource::CommandLine);
+    if encrypted && (matches.get_flag("parse-cache") || checkpoint_every_given) {
+        warn!(
+            "{} is set, --parse-cache and --checkpoint-every are off to keep the inputs encrypted",
+            vault::PASSPHRASE_VARIABLE
+        );
+    }
+    cache::set_enabled(matches.get_flag("parse-cache") && !encrypted);
+    let retries = matches.get_one::<String>("retries").unwrap();
+    fetch::set_retries(
+        retries
+            .parse()
+            .map_err(|f| eyre!("--retries {:?}: {}", retries, f))?,
+    );
+    let backoff = matches.get_one::<String>("backoff").unwrap();
+    fetch::set_backoff(
+        backoff
+            .parse()
+            .ok()
+            .and_then(|f| Duration::try_from_secs_f64(f).ok())
+            .ok_or_else(|| eyre!("--backoff {:?} is not a number of seconds", backoff))?,
+    );
+    solver::set_mmap(matches.get_flag("mmap"));
+    checkpoint::set_resume(matches.get_flag("resume"));
+    checkpoint::set_interval(match encrypted {
+        true => Duration::ZERO,
+        false => Duration::try_from_secs_f64(
+            matches
+                .get_one::<String>("checkpoint-every")
+                .unwrap()
+                .parse()?,
+        )?,
+    });
 
     Ok(())
 }
+
+async fn run(matches: ArgMatches) -> Result<()> {
+    let _tracing = init_tracing(&matches)?;
+    let progress = SharedProgress::default();
+    interrupt::install(progress.clone());
+
+    charset::set(
+        matches
+            .get_one::<String>("charset")
+            .unwrap()
+            .parse::<Charset>()
+            .map_err(|f| eyre!(f))?,
+    );
+    notify_threshold(&matches)?;
+    // before anything reads an input or a token
+    if let Some(name) = matches.get_one::<String>("profile") {
+        profile::set(name)?;
+    }
+    apply_settings(&matches)?;
+
+    if let Some(matches) = matches.subcommand_matches("analyze") {
+        let day = matches.get_one::<String>("day").unwrap().parse::<i32>()?;
+        fetch::ensure_input(day, false)?;
+        let input = solver::read_input(day)?;
+
+        Analysis::new(&input).display();
+
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("validate") {
+        let day = matches.get_one::<String>("day").unwrap().parse::<i32>()?;
+        let path = solver::input_path(day);
+        fetch::ensure_input(day, false)?;
+        let input = solver::read_input(day)?;
+
+        let errors = validate::validate(day, &input);
+        for error in &errors {
+            error!("\n{}", error.render(&path, &input));
+        }
+
+        if !errors.is_empty() {
+            bail!("{} has {} problem(s)", path, errors.len());
+        }
+
+        info!("{} looks valid", path);
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("serve") {
+        return serve::serve(matches.get_one::<String>("listen").unwrap()).await;
+    }
+
+    if matches.subcommand_matches("login").is_some() {
+        let token = credentials::prompt_token()?;
+        let account = credentials::verify(&token, |token| {
+            fetch::fetch_page(&fetch::calendar_url(), Some(token))
+        })?;
+        credentials::store(&token)?;
+        info!(
+            "logged in as {} with {} stars, saved the session token, {} still wins when it is set",
+            account.name,
+            account.stars,
+            fetch::session_variable()
+        );
+        return Ok(());
+    }
+
+    if matches.subcommand_matches("logout").is_some() {
+        match credentials::delete()? {
+            true => info!("removed the saved session token"),
+            false => info!("there was no saved session token"),
+        }
+        return Ok(());
+    }
+
+    if matches.subcommand_matches("stars").is_some() {
+        let token = fetch::require_session_token("read your stars")?;
+
+        let stars = Stars::parse(&fetch::fetch_page(&fetch::calendar_url(), Some(&token))?)?;
+        let implemented = (1..=25)
+            .filter(|f| !registry::variants(*f).is_empty())
+            .collect::<Vec<_>>();
+
+        info!("\n{}", stars.summary(&implemented));
+        return Ok(());
+    }
+
+    if let Some(name) = ["encrypt-inputs", "decrypt-inputs"]
+        .into_iter()
+        .find(|f| matches.subcommand_matches(f).is_some())
+    {
+        let passphrase = vault::require_passphrase()?;
+        let mut changed = 0;
+        for day in (1..=25).filter(|f| Path::new(&solver::input_path(*f)).exists()) {
+            let path = solver::input_path(day);
+            let done = match name {
+                "encrypt-inputs" => vault::encrypt_file(Path::new(&path), &passphrase)?,
+                _ => vault::decrypt_file(Path::new(&path), &passphrase)?,
+            };
+            if done {
+                info!("{}", path);
+                changed += 1;
+            }
+        }
+
+        let verb = match name {
+            "encrypt-inputs" => "encrypted",
+            _ => "decrypted",
+        };
+        info!("{} {} input(s)", verb, changed);
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("scrub") {
+        let passphrase = match matches.get_flag("encrypt") {
+            true => Some(vault::require_passphrase()?),
+            false => None,
+        };
+        let report = scrub::scrub(
+            Path::new("input"),
+            passphrase.as_deref(),
+            matches.get_flag("dry-run"),
+        )?;
+
+        info!("\n{}", report);
+        if !report.is_clean() {
+            bail!(
+                "{} tracked file(s) would be published",
+                report.tracked.len()
+            );
+        }
+        return Ok(());
+    }
+
+    if matches.subcommand_matches("fetch-all").is_some() {
+        let token = fetch::require_session_token("download inputs")?;
+
+        let downloaded = fetch::fetch_all(&token)?;
+        info!("downloaded {} input(s)", downloaded.len());
+        return Ok(());
+    }
+
+    if matches.subcommand_matches("calendar").is_some() {
+        let answers = calendar::read_answers(Path::new(&calendar::answers_path()))?;
+        info!("\n{}", Calendar::collect(&answers));
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("show") {
+        let day = matches.get_one::<String>("day").unwrap().parse::<i32>()?;
+        let articles = statement::load(day, matches.get_flag("refresh"))?;
+
+        info!("\n{}", statement::render(&articles, statement::WIDTH));
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("open") {
+        let day = matches.get_one::<String>("day").unwrap().parse::<i32>()?;
+        if !(1..=25).contains(&day) {
+            bail!("there is no day {}", day);
+        }
+
+        let url = fetch::puzzle_url(day);
+        fetch::open_in_browser(&url)?;
+        info!("opened {}", url);
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("history") {
+        let day = matches.get_one::<String>("day").unwrap().parse::<i32>()?;
+        let history = history::read(Path::new(&history::history_path()), day)?;
+
+        info!("\n{}", history);
+        return Ok(());
+    }
+
+    if matches.subcommand_matches("times").is_some() {
+        let token = fetch::require_session_token("read the private leaderboard")?;
+        let (leaderboard, user) = times::leaderboard_ids()?;
+
+        let json = fetch::fetch_page(&times::leaderboard_url(&leaderboard), Some(&token))?;
+        info!("\n{}", PrivateLeaderboard::parse(&json)?.table(&user)?);
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("bench") {
+        return run_bench(matches);
+    }
+
+    if let Some(matches) = matches.subcommand_matches("difftest") {
+        let day = matches.get_one::<String>("day").unwrap().parse::<i32>()?;
+        let cases = matches.get_one::<String>("cases").unwrap().parse()?;
+        let seed = matches.get_one::<String>("seed").unwrap().parse()?;
+
+        if let Some(mismatch) = difftest::run(day, cases, seed)? {
+            error!("\n{}", mismatch);
+            bail!(Failure::new(
+                FailureKind::Mismatch,
+                format!("day {:0>2} variants disagree", day)
+            ));
+        }
+
+        info!(
+            "day {:0>2}: all variants agree on {} generated inputs",
+            day, cases
+        );
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("calibrate") {
+        let repeats = matches.get_one::<String>("repeats").unwrap().parse()?;
+        let days = match matches.get_one::<String>("day").unwrap().as_str() {
+            "all" => (1..=25)
+                .filter(|f| difftest::generator(*f).is_some())
+                .collect(),
+            day => vec![day.parse::<i32>()?],
+        };
+
+        let path = Path::new(select::THRESHOLDS_PATH);
+        let mut thresholds = Thresholds::load(path)?;
+        for day in days {
+            thresholds.set(day, select::calibrate(day, repeats)?);
+        }
+        thresholds.save(path)?;
+
+        info!("wrote {}\n{}", path.display(), thresholds);
+        return Ok(());
+    }
+
+    if matches.subcommand_matches("doctor").is_some() {
+        let report = doctor::diagnose();
+        info!("\n{}", report);
+
+        if report.problems() > 0 {
+            bail!("found {} problem(s)", report.problems());
+        }
+
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("badge") {
+        let path = bench::baseline_path(matches.get_one::<String>("baseline").unwrap())?;
+        let run = BenchRun::load(Path::new(&path))?;
+
+        for path in badge::write_badges(&run, Path::new(matches.get_one::<String>("out").unwrap()))?
+        {
+            info!("wrote {}", path.display());
+        }
+
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("spin") {
+        let directions = day14::parse_tilts(matches.get_one::<String>("tilts").unwrap())?;
+        let cycles = matches
+            .get_one::<String>("cycles")
+            .unwrap()
+            .parse::<usize>()?;
+        let legend = matches
+            .get_one::<String>("legend")
+            .unwrap()
+            .parse::<day14::Legend>()?;
+        fetch::ensure_input(14, false)?;
+        let input = solver::read_input(14)?;
+
+        let mut platform = day14::Platform::with_legend(&input, legend)?;
+        let cycle_info = platform.spin(&directions, cycles)?;
+        platform.display();
+
+        if let Some(cycle_info) = cycle_info {
+            info!(
+                "repeats every {} cycle(s) from cycle {}",
+                cycle_info.length,
+                cycle_info.start + 1
+            );
+        }
+
+        info!("load on the north beams: {}", platform.get_weight());
+        return Ok(());
+    }
+
+    // the rules of calibrate, read once for the whole run and handed to every solver
+    let thresholds = Thresholds::load(Path::new(select::THRESHOLDS_PATH))?;
+
+    if let Some((name, day_matches)) = matches.subcommand() {
+        if let Some((day, solve)) = day_command(name, day_matches)? {
+            let mut solver = open_solver(day, &matches, &thresholds).await?;
+            progress.lock().unwrap().start(day);
+            let start = Instant::now();
+            solver.solve_custom(solve)?;
+
+            return report_answer(day, &solver, &matches, start.elapsed());
+        }
+    }
+
+    if matches.get_flag("todo") {
+        return solve_todo(&progress, &thresholds).await;
+    }
+
+    if matches.get_one::<String>("day").unwrap() == "all" {
+        if matches.get_one::<String>("input").is_some()
+            || matches.get_one::<String>("copy").is_some()
+            || matches.get_flag("example")
+            || matches.get_one::<String>("input-dir").is_some()
+        {
+            bail!("--input, --input-dir, --copy and --example need a single day");
+        }
+
+        let output = matches.get_one::<String>("output").map(Path::new);
+        return solve_all(&progress, output, &thresholds).await;
+    }
+
+    let day = matches.get_one::<String>("day").unwrap().parse::<i32>()?;
+
+    if matches.get_flag("compare") {
+        fetch::ensure_input(day, matches.get_flag("fetch"))?;
+        let input = solver::read_input(day)?;
+        let plugins = match matches.get_one::<String>("plugins") {
+            Some(directory) => plugin::discover(Path::new(directory))?,
+            None => vec![],
+        };
+
+        Comparison::with_extra(day, &input, plugin::extra_solves(&plugins, day))?.display();
+
+        return Ok(());
+    }
+
+    if let Some(paths) = matches.get_many::<String>("diff-inputs") {
+        let paths = paths.collect::<Vec<_>>();
+        let a = solver::read_file(Path::new(paths[0]))?;
+        let b = solver::read_file(Path::new(paths[1]))?;
+
+        InputDiff::new(day, (paths[0], &a), (paths[1], &b))?.display();
+
+        return Ok(());
+    }
+
+    if let Some(directory) = matches.get_one::<String>("input-dir") {
+        let algo = matches.get_one::<String>("algo").map(String::as_str);
+        let batch = Batch::new(day, Path::new(directory), algo)?;
+        batch.display();
+
+        if batch.failures() > 0 {
+            bail!("{} of {} inputs failed", batch.failures(), batch.runs.len());
+        }
+
+        return Ok(());
+    }
+
+    let mut solver = open_solver(day, &matches, &thresholds).await?;
+    if let Some(algo) = matches.get_one::<String>("algo") {
+        solver = solver.with_algo(algo);
+    }
+    progress.lock().unwrap().start(day);
+    let start = Instant::now();
+    match matches.get_one::<String>("script") {
+        Some(path) => solver.solve_custom(|input, _| script::solve_file(Path::new(path), input))?,
+        None => solver.solve().await?,
+    }
+
+    report_answer(day, &solver, &matches, start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_fetch_all_settings() {
+        let matches = command()
+            .try_get_matches_from([
+                "advent-of-code-2023",
+                "fetch-all",
+                "--retries",
+                "5",
+                "--backoff",
+                "0.5",
+            ])
+            .unwrap();
+        assert_eq!(matches.subcommand_name(), Some("fetch-all"));
+
+        apply_settings(&matches).unwrap();
+        assert_eq!(fetch::retries(), 5);
+        assert_eq!(fetch::first_backoff(), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use crate::solver::Answer;
+
+// (summary, body) of the notification, the body has one line per part like the log does
+pub fn message(day: i32, answer: &Answer, elapsed: Duration) -> (String, String) {
+    let part = |part: &Option<String>| part.as_deref().unwrap_or("-").to_string();
+
+    (
+        format!("Day {:0>2} solved in {:.1?}", day, elapsed),
+        format!(
+            "part 1: {}\npart 2: {}",
+            part(&answer.part1),
+            part(&answer.part2)
+        ),
+    )
+}
+
+// a failed notification is only logged, the answers are already on screen
+pub fn send(day: i32, answer: &Answer, elapsed: Duration) {
+    let (summary, body) = message(day, answer, elapsed);
+
+    #[cfg(feature = "notify")]
+    if let Err(error) = notify_rust::Notification::new()
+        .appname("advent-of-code-2023")
+        .summary(&summary)
+        .body(&body)
+        .show()
+    {
+        tracing::warn!("could not send the desktop notification: {}", error);
+    }
+
+    #[cfg(not(feature = "notify"))]
+    tracing::warn!(
+        "{} ({}), build with the notify feature to get a desktop notification",
+        summary,
+        body.replace('\n', ", ")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_message() {
+        let answer = Answer {
+            part1: Some("102".to_string()),
+            part2: None,
+        };
+
+        let (summary, body) = message(17, &answer, Duration::from_millis(12_340));
+        assert_eq!(summary, "Day 17 solved in 12.3s");
+        assert_eq!(body, "part 1: 102\npart 2: -");
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    env::consts::DLL_EXTENSION,
+    ffi::{c_char, CStr},
+    fs,
+    path::Path,
+};
+
+use color_eyre::eyre::{bail, eyre, Result};
+use libloading::Library;
+use tracing::info;
+
+use crate::{compare::ExtraSolve, solver::Answer};
+
+// where --plugins looks when no directory is given
+pub const PLUGIN_DIRECTORY: &str = "plugins";
+
+// the C ABI a plugin exports, so it can be built with any toolchain that makes shared libraries
+//   uint32_t aoc_abi_version(void);                        ABI_VERSION
+//   int32_t aoc_day(void);                                 the day it solves, 1 to 25
+//   const char *aoc_name(void);                            a static, nul terminated name
+//   char *aoc_solve(const uint8_t *input, size_t length);  "part1\npart2" or "error: message"
+//   void aoc_free(char *text);                             frees what aoc_solve returned
+// the input is utf-8 and not nul terminated, what aoc_solve returns must be nul terminated
+pub const ABI_VERSION: u32 = 1;
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type DayFn = unsafe extern "C" fn() -> i32;
+type NameFn = unsafe extern "C" fn() -> *const c_char;
+type SolveFn = unsafe extern "C" fn(*const u8, usize) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+// a day implementation from a shared library, solved next to the variants of its day
+#[derive(Debug)]
+pub struct Plugin {
+    pub day: i32,
+    pub name: String,
+    solve: SolveFn,
+    free: FreeFn,
+    // keeps the functions above loaded, None for plugins linked into this binary
+    _library: Option<Library>,
+}
+
+impl Plugin {
+    pub fn load(path: &Path) -> Result<Self> {
+        let error = |f: libloading::Error| eyre!("{}: {}", path.display(), f);
+
+        // loading runs the initializers of the library, so a plugin is as trusted as this binary
+        let library = unsafe { Library::new(path) }.map_err(error)?;
+
+        // the symbols are copied out as plain function pointers, the library outlives them in self
+        let plugin = unsafe {
+            let abi_version = *library
+                .get::<AbiVersionFn>(b"aoc_abi_version")
+                .map_err(error)?;
+            let day = *library.get::<DayFn>(b"aoc_day").map_err(error)?;
+            let name = *library.get::<NameFn>(b"aoc_name").map_err(error)?;
+            let solve = *library.get::<SolveFn>(b"aoc_solve").map_err(error)?;
+            let free = *library.get::<FreeFn>(b"aoc_free").map_err(error)?;
+
+            Self::from_functions(abi_version, day, name, solve, free)
+        };
+
+        let plugin = plugin.map_err(|f| eyre!("{}: {}", path.display(), f))?;
+
+        Ok(Self {
+            _library: Some(library),
+            ..plugin
+        })
+    }
+
+    // the functions must follow the contract above
+    unsafe fn from_functions(
+        abi_version: AbiVersionFn,
+        day: DayFn,
+        name: NameFn,
+        solve: SolveFn,
+        free: FreeFn,
+    ) -> Result<Self> {
+        let version = abi_version();
+        if version != ABI_VERSION {
+            bail!(
+                "the plugin is built for ABI version {}, this harness loads version {}",
+                version,
+                ABI_VERSION
+            );
+        }
+
+        let day = day();
+        if !(1..=25).contains(&day) {
+            bail!("the plugin solves day {}, which does not exist", day);
+        }
+
+        let name = name();
+        if name.is_null() {
+            bail!("the plugin has no name");
+        }
+
+        Ok(Self {
+            day,
+            name: CStr::from_ptr(name).to_string_lossy().into_owned(),
+            solve,
+            free,
+            _library: None,
+        })
+    }
+
+    pub fn solve(&self, input: &str) -> Result<Answer> {
+        // the plugin only reads length bytes of the input and hands back a string it allocated
+        let output = unsafe {
+            let text = (self.solve)(input.as_ptr(), input.len());
+            if text.is_null() {
+                bail!("plugin {} returned nothing", self.name);
+            }
+
+            let output = CStr::from_ptr(text).to_string_lossy().into_owned();
+            (self.free)(text);
+            output
+        };
+
+        parse_output(&self.name, &output)
+    }
+}
+
+fn parse_output(name: &str, output: &str) -> Result<Answer> {
+    if let Some(message) = output.strip_prefix("error: ") {
+        bail!("plugin {} failed: {}", name, message.trim_end());
+    }
+
+    match output.trim_end().split_once('\n') {
+        Some((part1, part2)) => Ok(Answer {
+            part1: Some(part1.to_string()),
+            part2: Some(part2.to_string()),
+        }),
+        None => bail!(
+            "plugin {} returned {:?}, expected \"part1\\npart2\"",
+            name,
+            output
+        ),
+    }
+}
+
+// every shared library in the directory, in file name order, nothing when it does not exist
+pub fn discover(directory: &Path) -> Result<Vec<Plugin>> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Ok(vec![]);
+    };
+
+    let mut paths = entries
+        .filter_map(|f| f.ok().map(|f| f.path()))
+        .filter(|f| f.extension().is_some_and(|f| f == DLL_EXTENSION))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let plugin = Plugin::load(path)?;
+            info!("loaded plugin {} for day {:0>2}", plugin.name, plugin.day);
+
+            Ok(plugin)
+        })
+        .collect()
+}
+
+// the plugins of the day, to be compared with its variants
+pub fn extra_solves(plugins: &[Plugin], day: i32) -> Vec<ExtraSolve<'_>> {
+    plugins
+        .iter()
+        .filter(|f| f.day == day)
+        .map(|f| {
+            let solve = move |input: &str| f.solve(input);
+            (format!("plugin::{}", f.name), Box::new(solve) as Box<_>)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::{compare::Comparison, test_support::fixture};
+
+    // a plugin linked into the tests, which solves day 02 through this crate
+    extern "C" fn abi_version() -> u32 {
+        ABI_VERSION
+    }
+
+    extern "C" fn day() -> i32 {
+        2
+    }
+
+    extern "C" fn name() -> *const c_char {
+        c"friend".as_ptr()
+    }
+
+    extern "C" fn solve(input: *const u8, length: usize) -> *mut c_char {
+        let input = std::str::from_utf8(unsafe { std::slice::from_raw_parts(input, length) });
+        let text = match input.map_err(|f| eyre!(f)).and_then(crate::day02::solve) {
+            Ok(answer) => format!("{}\n{}", answer.part1.unwrap(), answer.part2.unwrap()),
+            Err(error) => format!("error: {}", error),
+        };
+
+        CString::new(text).unwrap().into_raw()
+    }
+
+    extern "C" fn free(text: *mut c_char) {
+        drop(unsafe { CString::from_raw(text) });
+    }
+
+    extern "C" fn future_abi_version() -> u32 {
+        ABI_VERSION + 1
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_plugin() {
+        let plugin =
+            unsafe { Plugin::from_functions(abi_version, day, name, solve, free) }.unwrap();
+        assert_eq!((plugin.day, plugin.name.as_str()), (2, "friend"));
+
+        let input = fixture("day02");
+        assert_eq!(
+            plugin.solve(&input).unwrap(),
+            crate::day02::solve(&input).unwrap()
+        );
+        assert!(plugin.solve("Game x").is_err());
+
+        let plugins = [plugin];
+        let comparison = Comparison::with_extra(2, &input, extra_solves(&plugins, 2)).unwrap();
+        assert_eq!(comparison.runs.last().unwrap().name, "plugin::friend");
+        assert!(extra_solves(&plugins, 3).is_empty());
+
+        let outdated =
+            unsafe { Plugin::from_functions(future_abi_version, day, name, solve, free) };
+        assert!(outdated.is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_parse_output() {
+        assert_eq!(
+            parse_output("friend", "8\n2286\n").unwrap(),
+            Answer {
+                part1: Some("8".to_string()),
+                part2: Some("2286".to_string()),
+            }
+        );
+        assert_eq!(
+            parse_output("friend", "error: bad game\n")
+                .unwrap_err()
+                .to_string(),
+            "plugin friend failed: bad game"
+        );
+        assert!(parse_output("friend", "8").is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_discover() {
+        let directory = std::env::temp_dir().join("advent-of-code-2023-plugin-test");
+        assert!(discover(&directory).unwrap().is_empty());
+
+        // only shared libraries are loaded, and one that is not a library is an error
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("notes.txt"), "not a plugin").unwrap();
+        assert!(discover(&directory).unwrap().is_empty());
+
+        fs::write(directory.join(format!("broken.{}", DLL_EXTENSION)), "").unwrap();
+        assert!(discover(&directory).is_err());
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::OnceLock;
+
+use color_eyre::eyre::{bail, Result};
+
+use crate::fetch::SESSION_VARIABLE;
+
+// the account the inputs and the session token belong to, set once from the command line
+// without --profile everything stays where it always was
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+// the name ends up in a directory and an environment variable, so it is kept to what both allow
+pub fn check_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|f| f.is_ascii_lowercase() || f.is_ascii_digit() || f == '_')
+    {
+        bail!(
+            "the profile {:?} should be made of lowercase letters, digits and _",
+            name
+        );
+    }
+
+    Ok(())
+}
+
+pub fn set(name: &str) -> Result<()> {
+    check_name(name)?;
+    if PROFILE.set(name.to_string()).is_err() {
+        bail!("the profile is already set");
+    }
+
+    Ok(())
+}
+
+pub fn name() -> Option<&'static str> {
+    PROFILE.get().map(|f| f.as_str())
+}
+
+// input/NN and input/answers, the parts of input/ that differ between accounts
+pub fn input_directory() -> String {
+    input_directory_of(name())
+}
+
+pub fn input_directory_of(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("input/profiles/{}", profile),
+        None => "input".to_string(),
+    }
+}
+
+// AOC_SESSION for the default profile, AOC_SESSION_WORK for --profile work
+pub fn session_variable_of(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("{}_{}", SESSION_VARIABLE, profile.to_uppercase()),
+        None => SESSION_VARIABLE.to_string(),
+    }
+}
+
+// the user of the keyring entry login saves the token under
+pub fn keyring_user_of(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("session-{}", profile),
+        None => "session".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_profile() {
+        assert!(check_name("work").is_ok());
+        assert!(check_name("team_2").is_ok());
+        assert!(check_name("").is_err());
+        assert!(check_name("../work").is_err());
+        assert!(check_name("Work").is_err());
+
+        assert_eq!(input_directory_of(None), "input");
+        assert_eq!(input_directory_of(Some("work")), "input/profiles/work");
+        assert_eq!(session_variable_of(None), "AOC_SESSION");
+        assert_eq!(session_variable_of(Some("work")), "AOC_SESSION_WORK");
+        assert_eq!(keyring_user_of(None), "session");
+        assert_eq!(keyring_user_of(Some("work")), "session-work");
+    }
+}
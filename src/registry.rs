@@ -0,0 +1,248 @@
+use color_eyre::eyre::{eyre, Result};
+
+use crate::{
+    error::{Failure, FailureKind},
+    solver::{Answer, SolveContext},
+};
+
+pub type SolveFn = fn(&str) -> Result<Answer>;
+
+pub type ContextSolveFn = fn(&str, &mut SolveContext) -> Result<Answer>;
+
+// a named implementation of a day, selectable with --algo
+// in_context is the same solve for days that leave artifacts behind, the solver prefers it
+#[derive(Debug, Clone, Copy)]
+pub struct Variant {
+    pub name: &'static str,
+    pub solve: SolveFn,
+    pub in_context: Option<ContextSolveFn>,
+}
+
+impl Variant {
+    // the name without the day prefix, e.g. "shoelace" for "day10::shoelace"
+    pub fn short_name(&self) -> &'static str {
+        self.name.rsplit("::").next().unwrap_or(self.name)
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.name == name || self.short_name() == name
+    }
+}
+
+// every variant of a day, the first one is the default
+pub fn variants(day: i32) -> &'static [Variant] {
+    match day {
+        1 => &[
+            Variant {
+                name: "day01::default",
+                solve: crate::day01::solve,
+                in_context: None,
+            },
+            #[cfg(feature = "simd")]
+            Variant {
+                name: "day01::simd",
+                solve: crate::day01::solve_simd,
+                in_context: None,
+            },
+        ],
+        2 => &[Variant {
+            name: "day02::default",
+            solve: crate::day02::solve,
+            in_context: None,
+        }],
+        3 => &[Variant {
+            name: "day03::default",
+            solve: crate::day03::solve,
+            in_context: None,
+        }],
+        4 => &[Variant {
+            name: "day04::default",
+            solve: crate::day04::solve,
+            in_context: None,
+        }],
+        5 => &[
+            Variant {
+                name: "day05::ranges",
+                solve: crate::day05::solve,
+                in_context: None,
+            },
+            Variant {
+                name: "day05::brute_force",
+                solve: crate::day05::solve_brute_force,
+                in_context: None,
+            },
+            Variant {
+                name: "day05::composed",
+                solve: crate::day05::solve_composed,
+                in_context: None,
+            },
+        ],
+        6 => &[
+            Variant {
+                name: "day06::default",
+                solve: crate::day06::solve,
+                in_context: None,
+            },
+            #[cfg(feature = "gpu")]
+            Variant {
+                name: "day06::gpu",
+                solve: crate::day06::solve_gpu,
+                in_context: None,
+            },
+        ],
+        7 => &[Variant {
+            name: "day07::default",
+            solve: crate::day07::solve,
+            in_context: None,
+        }],
+        8 => &[Variant {
+            name: "day08::default",
+            solve: crate::day08::solve,
+            in_context: None,
+        }],
+        9 => &[Variant {
+            name: "day09::default",
+            solve: crate::day09::solve,
+            in_context: None,
+        }],
+        10 => &[
+            Variant {
+                name: "day10::floodfill",
+                solve: crate::day10::solve,
+                in_context: Some(crate::day10::solve_with_context),
+            },
+            Variant {
+                name: "day10::shoelace",
+                solve: crate::day10::solve_shoelace,
+                in_context: None,
+            },
+        ],
+        11 => &[Variant {
+            name: "day11::default",
+            solve: crate::day11::solve,
+            in_context: None,
+        }],
+        12 => &[
+            Variant {
+                name: "day12::default",
+                solve: crate::day12::solve,
+                in_context: None,
+            },
+            Variant {
+                name: "day12::enumerate",
+                solve: crate::day12::solve_enumerate,
+                in_context: None,
+            },
+        ],
+        13 => &[Variant {
+            name: "day13::default",
+            solve: crate::day13::solve,
+            in_context: None,
+        }],
+        14 => &[Variant {
+            name: "day14::default",
+            solve: crate::day14::solve,
+            in_context: Some(crate::day14::solve_with_context),
+        }],
+        15 => &[
+            Variant {
+                name: "day15::default",
+                solve: crate::day15::solve,
+                in_context: None,
+            },
+            #[cfg(feature = "simd")]
+            Variant {
+                name: "day15::simd",
+                solve: crate::day15::solve_simd,
+                in_context: None,
+            },
+        ],
+        16 => &[
+            Variant {
+                name: "day16::default",
+                solve: crate::day16::solve,
+                in_context: None,
+            },
+            #[cfg(feature = "gpu")]
+            Variant {
+                name: "day16::gpu",
+                solve: crate::day16::solve_gpu,
+                in_context: None,
+            },
+        ],
+        17 => &[
+            Variant {
+                name: "day17::dijkstra",
+                solve: crate::day17::solve,
+                in_context: Some(crate::day17::solve_with_context),
+            },
+            Variant {
+                name: "day17::bidirectional",
+                solve: crate::day17::solve_bidirectional,
+                in_context: None,
+            },
+        ],
+        18 => &[Variant {
+            name: "day18::shoelace",
+            solve: crate::day18::solve,
+            in_context: None,
+        }],
+        19 => &[Variant {
+            name: "day19::default",
+            solve: crate::day19::solve,
+            in_context: None,
+        }],
+        _ => &[],
+    }
+}
+
+// accepts either the full name or the short name of a variant
+pub fn find(day: i32, name: &str) -> Result<Variant> {
+    let variants = variants(day);
+
+    variants
+        .iter()
+        .find(|f| f.matches(name))
+        .copied()
+        .ok_or_else(|| {
+            let names = variants.iter().map(|f| f.name).collect::<Vec<_>>();
+            eyre!(Failure::new(
+                FailureKind::Unimplemented,
+                format!(
+                    "day {:0>2} has no variant named {}, available: {}",
+                    day,
+                    name,
+                    names.join(", ")
+                )
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_find() -> Result<()> {
+        assert_eq!(find(10, "shoelace")?.name, "day10::shoelace");
+        assert_eq!(find(10, "day10::floodfill")?.name, "day10::floodfill");
+        assert!(find(10, "day05::ranges").is_err());
+        assert!(find(42, "default").is_err());
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_variant_names() {
+        for day in 1..=19 {
+            let prefix = format!("day{:0>2}::", day);
+
+            assert!(!variants(day).is_empty());
+            assert!(variants(day).iter().all(|f| f.name.starts_with(&prefix)));
+        }
+    }
+}
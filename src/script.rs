@@ -0,0 +1,218 @@
+use std::{fs, path::Path, sync::OnceLock};
+
+use color_eyre::eyre::{bail, eyre, Result};
+use num::Integer;
+use regex::Regex;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope};
+use tracing::{debug, info};
+
+use crate::{
+    solver::Answer,
+    utils::{self, guard, polygon, Coordinate},
+};
+
+// a day prototyped in rhai before it is ported, the input is in the constant `input` and the last
+// expression is the answer, #{ part1: .., part2: .. } or [part1, part2]
+// a part that is left out or () answers "0", like a day that only has part 1 so far
+pub fn solve(source: &str, input: &str) -> Result<Answer> {
+    let engine = engine();
+    let mut scope = Scope::new();
+    scope.push_constant("input", input.to_string());
+
+    let value = engine
+        .eval_with_scope::<Dynamic>(&mut scope, source)
+        .map_err(|f| eyre!("{}", f))?;
+
+    answer(value)
+}
+
+pub fn solve_file(path: &Path, input: &str) -> Result<Answer> {
+    let source = fs::read_to_string(path)
+        .map_err(|f| eyre!("could not read the script {}: {}", path.display(), f))?;
+
+    solve(&source, input).map_err(|f| eyre!("{}: {}", path.display(), f))
+}
+
+// print goes to the log like the days' own output, --max-steps caps the operations of a script
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+
+    engine.on_print(|text| info!("{}", text));
+    engine.on_debug(|text, _, position| debug!("{:?}: {}", position, text));
+
+    if let Some(max_steps) = guard::max_steps() {
+        engine.set_max_operations(max_steps);
+    }
+
+    register_utils(&mut engine);
+    engine
+}
+
+fn integer_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"-?\d+").unwrap())
+}
+
+// the helpers of utils that make sense on the values a script has
+fn register_utils(engine: &mut Engine) {
+    engine.register_fn("blocks", |input: &str| -> Array {
+        utils::blocks(input)
+            .into_iter()
+            .map(|block| {
+                let lines = block
+                    .into_iter()
+                    .map(|f| Dynamic::from(f.to_string()))
+                    .collect::<Array>();
+                Dynamic::from(lines)
+            })
+            .collect()
+    });
+
+    // every integer in the text, like the numbers after "Seeds:" or in "x=-3, y=12"
+    engine.register_fn("numbers", |text: &str| -> Array {
+        integer_regex()
+            .find_iter(text)
+            .filter_map(|f| f.as_str().parse::<i64>().ok())
+            .map(Dynamic::from)
+            .collect()
+    });
+
+    engine.register_fn("gcd", |a: i64, b: i64| a.gcd(&b));
+    engine.register_fn("lcm", |a: i64, b: i64| a.lcm(&b));
+
+    // twice the signed area of the polygon through [x, y] points
+    engine.register_fn(
+        "shoelace",
+        |points: Array| -> Result<i64, Box<EvalAltResult>> {
+            let vertices = points
+                .into_iter()
+                .map(|point| {
+                    let point = point.try_cast::<Array>().unwrap_or_default();
+                    match point.iter().map(|f| f.as_int().ok()).collect::<Vec<_>>()[..] {
+                        [Some(x), Some(y)] => Ok(Coordinate::new(x, y)),
+                        _ => Err("shoelace expects a list of [x, y] points".into()),
+                    }
+                })
+                .collect::<Result<Vec<_>, Box<EvalAltResult>>>()?;
+
+            Ok(polygon::shoelace(&vertices))
+        },
+    );
+}
+
+fn answer(value: Dynamic) -> Result<Answer> {
+    let [part1, part2] = if value.is_map() {
+        let map = value.cast::<Map>();
+        [map.get("part1").cloned(), map.get("part2").cloned()]
+    } else if value.is_array() {
+        match &value.cast::<Array>()[..] {
+            [part1, part2] => [Some(part1.clone()), Some(part2.clone())],
+            array => bail!(
+                "the script returned {} values, expected [part1, part2]",
+                array.len()
+            ),
+        }
+    } else {
+        bail!(
+            "the script returned {}, expected #{{ part1: .., part2: .. }} or [part1, part2]",
+            value.type_name()
+        );
+    };
+
+    let text = |part: Option<Dynamic>| match part {
+        Some(part) if !part.is_unit() => part.to_string(),
+        _ => "0".to_string(),
+    };
+
+    Ok(Answer {
+        part1: Some(text(part1)),
+        part2: Some(text(part2)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::test_support::fixture;
+
+    // day 09 prototyped in rhai
+    const DAY09: &str = r#"
+        fn next(values) {
+            if values.all(|f| f == 0) {
+                return 0;
+            }
+
+            let differences = [];
+            for i in 1..values.len() {
+                differences.push(values[i] - values[i - 1]);
+            }
+
+            values[-1] + next(differences)
+        }
+
+        let part1 = 0;
+        let part2 = 0;
+        for line in input.split("\n") {
+            let values = numbers(line);
+            if values.is_empty() {
+                continue;
+            }
+
+            part1 += next(values);
+            values.reverse();
+            part2 += next(values);
+        }
+
+        #{ part1: part1, part2: part2 }
+    "#;
+
+    #[traced_test]
+    #[test]
+    fn test_solve() {
+        let input = fixture("day09");
+        assert_eq!(
+            solve(DAY09, &input).unwrap(),
+            crate::day09::solve(&input).unwrap()
+        );
+
+        assert_eq!(
+            solve("[lcm(4, 6), gcd(4, 6)]", "").unwrap(),
+            Answer {
+                part1: Some("12".to_string()),
+                part2: Some("2".to_string()),
+            }
+        );
+        assert_eq!(
+            solve("#{ part1: blocks(input).len() }", "a\n\nb\nc\n")
+                .unwrap()
+                .part2
+                .as_deref(),
+            Some("0")
+        );
+        assert_eq!(
+            solve(
+                "[shoelace([[0, 0], [2, 0], [2, 2]]), numbers(\"x=-3, y=12\")]",
+                ""
+            )
+            .unwrap()
+            .part1
+            .as_deref(),
+            Some("4")
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_solve_errors() {
+        assert!(solve("42", "")
+            .unwrap_err()
+            .to_string()
+            .contains("returned i64"));
+        assert!(solve("[1, 2, 3]", "").is_err());
+        assert!(solve("let x = ;", "").is_err());
+        assert!(solve("shoelace([1, 2])", "").is_err());
+        assert!(solve_file(Path::new("missing.rhai"), "").is_err());
+    }
+}
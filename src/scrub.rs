@@ -0,0 +1,367 @@
+use std::{
+    env,
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::OnceLock,
+};
+
+use color_eyre::eyre::{eyre, Result};
+use regex::Regex;
+
+use crate::{credentials, profile, vault};
+
+// what lives under input/ and should not leave the machine: the inputs and copies of the puzzle
+// pages, the parse cache and checkpoints are both made from the inputs
+// answers, history, bench results and badges stay, they are ours
+const PUZZLE_DIRECTORIES: [&str; 4] = ["statements", "examples", "cache", "checkpoints"];
+
+// session cookies of the site are long runs of lowercase hex, longer than any hash in the repo
+fn token_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\b[0-9a-f]{96,}\b").unwrap())
+}
+
+// a path relative to input/, e.g. "05", "profiles/work/05" or "statements/05.html"
+pub fn is_puzzle_file(path: &str) -> bool {
+    let path = match path.strip_prefix("profiles/") {
+        Some(rest) => rest.split_once('/').map_or("", |(_, f)| f),
+        None => path,
+    };
+
+    is_input(path)
+        || PUZZLE_DIRECTORIES
+            .iter()
+            .any(|f| path.strip_prefix(f).is_some_and(|f| f.starts_with('/')))
+}
+
+fn is_input(name: &str) -> bool {
+    name.len() == 2 && name.chars().all(|f| f.is_ascii_digit())
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub deleted: Vec<PathBuf>,
+    pub encrypted: Vec<PathBuf>,
+    // saved session tokens that were removed
+    pub tokens: Vec<String>,
+    // what scrub can not do itself, like unsetting a variable of the shell
+    pub notes: Vec<String>,
+    // what git would still publish
+    pub tracked: Vec<String>,
+}
+
+impl Report {
+    pub fn is_clean(&self) -> bool {
+        self.tracked.is_empty()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections: [(&str, Vec<String>); 5] = [
+            (
+                "deleted",
+                self.deleted
+                    .iter()
+                    .map(|f| f.display().to_string())
+                    .collect(),
+            ),
+            (
+                "encrypted",
+                self.encrypted
+                    .iter()
+                    .map(|f| f.display().to_string())
+                    .collect(),
+            ),
+            ("removed tokens", self.tokens.clone()),
+            ("left to you", self.notes.clone()),
+            ("tracked by git", self.tracked.clone()),
+        ];
+
+        for (title, lines) in sections {
+            writeln!(f, "{} ({})", title, lines.len())?;
+            for line in lines {
+                writeln!(f, "  {}", line)?;
+            }
+        }
+
+        match self.is_clean() {
+            true => writeln!(f, "nothing sensitive is tracked"),
+            false => writeln!(f, "git still has puzzle files or tokens, see above"),
+        }
+    }
+}
+
+// deletes the puzzle files under root, or encrypts the inputs among them when given a passphrase
+// with dry_run the report says what would happen and nothing is touched
+pub fn scrub_files(
+    root: &Path,
+    passphrase: Option<&str>,
+    dry_run: bool,
+    report: &mut Report,
+) -> Result<()> {
+    for path in files(root)? {
+        let relative = path
+            .strip_prefix(root)?
+            .to_string_lossy()
+            .replace('\\', "/");
+        if !is_puzzle_file(&relative) {
+            continue;
+        }
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        match passphrase {
+            Some(passphrase) if is_input(&name) => {
+                if vault::is_encrypted(&fs::read(&path)?) {
+                    continue;
+                }
+                if !dry_run {
+                    vault::encrypt_file(&path, passphrase)?;
+                }
+                report.encrypted.push(path);
+            }
+            _ => {
+                if !dry_run {
+                    fs::remove_file(&path)?;
+                }
+                report.deleted.push(path);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn files(directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut found = vec![];
+    if !directory.is_dir() {
+        return Ok(found);
+    }
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        match path.is_dir() {
+            true => found.extend(files(&path)?),
+            false => found.push(path),
+        }
+    }
+
+    found.sort();
+    Ok(found)
+}
+
+// the default profile and every profile that has a directory under input/profiles
+fn profiles(root: &Path) -> Vec<Option<String>> {
+    let mut profiles = vec![None];
+    if let Ok(entries) = fs::read_dir(root.join("profiles")) {
+        let mut names = entries
+            .filter_map(|f| f.ok())
+            .filter(|f| f.path().is_dir())
+            .map(|f| f.file_name().to_string_lossy().into_owned())
+            .filter(|f| profile::check_name(f).is_ok())
+            .collect::<Vec<_>>();
+        names.sort();
+        profiles.extend(names.into_iter().map(Some));
+    }
+
+    profiles
+}
+
+// the tokens this machine knows about, to look for them in the tracked files
+pub fn known_tokens(root: &Path) -> Vec<String> {
+    profiles(root)
+        .iter()
+        .flat_map(|f| {
+            let variable = env::var(profile::session_variable_of(f.as_deref())).ok();
+            [
+                variable.and_then(|f| credentials::clean_token(&f)),
+                credentials::stored_token_of(f.as_deref()),
+            ]
+        })
+        .flatten()
+        .collect()
+}
+
+// removes the tokens login saved, a variable of the environment can only be pointed out
+pub fn wipe_tokens(root: &Path, dry_run: bool, report: &mut Report) {
+    for profile in profiles(root) {
+        let profile = profile.as_deref();
+        let variable = profile::session_variable_of(profile);
+        if env::var_os(&variable).is_some() {
+            report.notes.push(format!(
+                "{} is set, unset it and drop it from your shell profile",
+                variable
+            ));
+        }
+
+        #[cfg(feature = "keyring")]
+        {
+            let user = profile::keyring_user_of(profile);
+            let removed = match dry_run {
+                true => Ok(credentials::stored_token_of(profile).is_some()),
+                false => credentials::delete_of(profile),
+            };
+            match removed {
+                Ok(true) => report.tokens.push(format!("keyring entry {}", user)),
+                Ok(false) => {}
+                Err(error) => report.notes.push(format!(
+                    "could not remove the keyring entry {}: {}",
+                    user, error
+                )),
+            }
+        }
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    let _ = dry_run;
+}
+
+// what a tracked file gives away, path relative to the repository
+pub fn findings(path: &str, content: Option<&str>, tokens: &[String]) -> Vec<String> {
+    let mut findings = vec![];
+
+    if let Some(relative) = path.strip_prefix("input/") {
+        let encrypted = content.is_some_and(|f| vault::is_encrypted(f.as_bytes()));
+        if is_puzzle_file(relative) && !encrypted {
+            findings.push(format!("{} is a puzzle file, git rm --cached it", path));
+        }
+    }
+
+    if let Some(content) = content {
+        if tokens.iter().any(|f| content.contains(f.as_str())) || token_regex().is_match(content) {
+            findings.push(format!("{} contains what looks like a session token", path));
+        }
+    }
+
+    findings
+}
+
+// goes through every file git tracks, the ones already deleted too since they are still in the index
+pub fn check_tracked(tokens: &[String], report: &mut Report) -> Result<()> {
+    let output = Command::new("git")
+        .args(["ls-files", "-z"])
+        .output()
+        .map_err(|f| eyre!("could not run git: {}", f))?;
+    if !output.status.success() {
+        report
+            .notes
+            .push("not in a git repository, there was nothing tracked to check".to_string());
+        return Ok(());
+    }
+
+    for path in String::from_utf8_lossy(&output.stdout).split('\0') {
+        if path.is_empty() {
+            continue;
+        }
+
+        let content = fs::read(path)
+            .ok()
+            .map(|f| String::from_utf8_lossy(&f).into_owned());
+        report
+            .tracked
+            .extend(findings(path, content.as_deref(), tokens));
+    }
+
+    Ok(())
+}
+
+// the whole cleanup before the repository goes somewhere public
+pub fn scrub(root: &Path, passphrase: Option<&str>, dry_run: bool) -> Result<Report> {
+    let mut report = Report::default();
+
+    // read before the keyring entries are removed
+    let tokens = known_tokens(root);
+    scrub_files(root, passphrase, dry_run, &mut report)?;
+    wipe_tokens(root, dry_run, &mut report);
+    check_tracked(&tokens, &mut report)?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_is_puzzle_file() {
+        assert!(is_puzzle_file("05"));
+        assert!(is_puzzle_file("profiles/work/05"));
+        assert!(is_puzzle_file("statements/05.html"));
+        assert!(is_puzzle_file("examples/05.answers"));
+        assert!(is_puzzle_file("profiles/work/examples/05"));
+        assert!(!is_puzzle_file("answers"));
+        assert!(!is_puzzle_file("history"));
+        assert!(!is_puzzle_file("bench/main"));
+        assert!(!is_puzzle_file(".gitkeep"));
+        assert!(!is_puzzle_file("statements"));
+        assert!(!is_puzzle_file("profiles/05"));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_findings() {
+        let token = "53616c7465645f5f".repeat(8);
+        let tokens = ["c0ffee".to_string()];
+
+        assert_eq!(
+            findings("input/.gitkeep", Some(""), &tokens),
+            Vec::<String>::new()
+        );
+        assert_eq!(findings("input/05", Some("seeds: 79 14"), &tokens).len(), 1);
+        assert_eq!(findings("input/05", None, &tokens).len(), 1);
+        assert!(findings("input/05", Some("aoc-encrypted-v1\n..."), &tokens).is_empty());
+        assert!(findings("README.md", Some("cargo run"), &tokens).is_empty());
+        assert!(findings("notes.txt", Some("cookie c0ffee"), &tokens)[0].contains("session token"));
+        assert_eq!(
+            findings(
+                "input/answers",
+                Some(&format!("AOC_SESSION={}", token)),
+                &tokens
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_scrub_files() {
+        let root = env::temp_dir().join("advent-of-code-2023-scrub-test");
+        let _ = fs::remove_dir_all(&root);
+        for path in [
+            "05",
+            "answers",
+            "statements/05.html",
+            "profiles/work/05",
+            "bench/main",
+        ] {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "seeds: 79 14\n").unwrap();
+        }
+
+        let mut report = Report::default();
+        scrub_files(&root, None, true, &mut report).unwrap();
+        assert_eq!(report.deleted.len(), 3);
+        assert!(root.join("05").exists());
+
+        let mut report = Report::default();
+        scrub_files(&root, None, false, &mut report).unwrap();
+        assert_eq!(
+            report.deleted,
+            ["05", "profiles/work/05", "statements/05.html"].map(|f| root.join(f))
+        );
+        assert!(!root.join("05").exists());
+        assert!(root.join("answers").exists());
+        assert!(root.join("bench/main").exists());
+        assert_eq!(profiles(&root), [None, Some("work".to_string())]);
+        assert!(report.to_string().contains("deleted (3)"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,321 @@
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::Path,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use color_eyre::eyre::{bail, eyre, Result};
+use tracing::{
+    info,
+    subscriber::{self, NoSubscriber},
+};
+
+use crate::{
+    difftest::{self, Rng},
+    registry::{self, Variant},
+};
+
+// which variant a day uses from which input size on, written by calibrate and read by every solve
+pub const THRESHOLDS_PATH: &str = "input/thresholds";
+
+// generator sizes calibrate times the variants at, doubling to find roughly where they cross
+const CALIBRATION_SIZES: [usize; 7] = [1, 2, 4, 8, 16, 32, 64];
+
+// the characteristic of an input a threshold is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    Lines,
+    // characters without the line breaks, the size of a grid
+    Cells,
+}
+
+impl Measure {
+    pub fn of(&self, input: &str) -> usize {
+        match self {
+            Self::Lines => input.lines().count(),
+            Self::Cells => input.lines().map(|f| f.len()).sum(),
+        }
+    }
+}
+
+impl FromStr for Measure {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "lines" => Ok(Self::Lines),
+            "cells" => Ok(Self::Cells),
+            _ => Err(format!("unknown measure {:?}, use lines or cells", input)),
+        }
+    }
+}
+
+impl Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lines => write!(f, "lines"),
+            Self::Cells => write!(f, "cells"),
+        }
+    }
+}
+
+// use the variant for inputs that measure at least the threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub day: i32,
+    pub variant: &'static str,
+    pub measure: Measure,
+    pub threshold: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Thresholds {
+    rules: Vec<Rule>,
+}
+
+impl Thresholds {
+    // a missing file has no rules, so every day keeps its default variant
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        Self::parse(&fs::read_to_string(path)?).map_err(|f| eyre!("{}: {}", path.display(), f))
+    }
+
+    // one "day variant measure threshold" line per rule, blank lines and lines starting with # are
+    // skipped
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut rules = vec![];
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            let &[day, variant, measure, threshold] = &parts[..] else {
+                bail!(
+                    "line {}: expected \"day variant measure threshold\"",
+                    index + 1
+                );
+            };
+
+            let day = day
+                .parse::<i32>()
+                .map_err(|_| eyre!("line {}: {:?} is not a day", index + 1, day))?;
+            let variant =
+                registry::find(day, variant).map_err(|f| eyre!("line {}: {}", index + 1, f))?;
+
+            rules.push(Rule {
+                day,
+                variant: variant.name,
+                measure: measure
+                    .parse()
+                    .map_err(|f| eyre!("line {}: {}", index + 1, f))?,
+                threshold: threshold
+                    .parse()
+                    .map_err(|_| eyre!("line {}: {:?} is not a size", index + 1, threshold))?,
+            });
+        }
+
+        Ok(Self { rules })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_string())?;
+
+        Ok(())
+    }
+
+    pub fn has_rules(&self, day: i32) -> bool {
+        self.rules.iter().any(|f| f.day == day)
+    }
+
+    // replaces the rules of the day
+    pub fn set(&mut self, day: i32, rule: Option<Rule>) {
+        self.rules.retain(|f| f.day != day);
+        self.rules.extend(rule);
+        self.rules.sort_by_key(|f| (f.day, f.threshold));
+    }
+
+    // the rule with the highest threshold the input reaches, the default variant when there is none
+    pub fn choose(&self, day: i32, input: &str) -> Result<Variant> {
+        let rule = self
+            .rules
+            .iter()
+            .filter(|f| f.day == day && f.measure.of(input) >= f.threshold)
+            .max_by_key(|f| f.threshold);
+
+        let Some(rule) = rule else {
+            let variant = registry::variants(day)
+                .first()
+                .ok_or_else(|| eyre!("day {} is not implemented", day))?;
+            info!(
+                "day {:0>2}: {} below every threshold",
+                day,
+                variant.short_name()
+            );
+
+            return Ok(*variant);
+        };
+
+        info!(
+            "day {:0>2}: {} for {} {} (from {})",
+            day,
+            rule.variant,
+            rule.measure.of(input),
+            rule.measure,
+            rule.threshold
+        );
+        registry::find(day, rule.variant)
+    }
+}
+
+impl Display for Thresholds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# day variant measure threshold, written by calibrate")?;
+
+        for rule in &self.rules {
+            writeln!(
+                f,
+                "{:0>2} {} {} {}",
+                rule.day, rule.variant, rule.measure, rule.threshold
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// fastest of a few solves, without the logging of the day
+fn time(variant: &Variant, input: &str, repeats: usize) -> Result<Duration> {
+    let mut fastest = Duration::MAX;
+
+    for _ in 0..repeats.max(1) {
+        let start = Instant::now();
+        subscriber::with_default(NoSubscriber::default(), || (variant.solve)(input))?;
+        fastest = fastest.min(start.elapsed());
+    }
+
+    Ok(fastest)
+}
+
+// times the variants on generated inputs of growing size, a variant gets a rule from the smallest
+// input it is faster than the default on for every size after it
+// None when the default is still the fastest on the largest input
+pub fn calibrate(day: i32, repeats: usize) -> Result<Option<Rule>> {
+    let Some(generate) = difftest::generator(day) else {
+        bail!("day {:0>2} has no input generator to calibrate with", day);
+    };
+    let variants = registry::variants(day);
+    if variants.len() < 2 {
+        bail!("day {:0>2} has only one variant", day);
+    }
+
+    let mut rng = Rng::new(2023);
+    // (cells, time of each variant) per size
+    let mut samples = vec![];
+
+    for size in CALIBRATION_SIZES {
+        let input = generate(&mut rng, size);
+        let times = variants
+            .iter()
+            .map(|f| time(f, &input, repeats))
+            .collect::<Result<Vec<_>>>()?;
+
+        info!(
+            "day {:0>2}, {} cells: {}",
+            day,
+            Measure::Cells.of(&input),
+            variants
+                .iter()
+                .zip(&times)
+                .map(|(variant, time)| format!("{} {:.3?}", variant.short_name(), time))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        samples.push((Measure::Cells.of(&input), times));
+    }
+
+    let largest = &samples.last().unwrap().1;
+    let fastest = (0..variants.len()).min_by_key(|f| largest[*f]).unwrap();
+    if fastest == 0 {
+        return Ok(None);
+    }
+
+    let from = samples
+        .iter()
+        .rposition(|(_, times)| times[fastest] >= times[0])
+        .map_or(0, |f| f + 1);
+
+    Ok(Some(Rule {
+        day,
+        variant: variants[fastest].name,
+        measure: Measure::Cells,
+        threshold: samples[from].0,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_thresholds() {
+        let thresholds = Thresholds::parse(
+            "# comment\n17 bidirectional cells 400\n17 day17::dijkstra cells 10000\n12 enumerate lines 1\n",
+        )
+        .unwrap();
+
+        assert_eq!(Measure::Cells.of("123\n456\n"), 6);
+        assert_eq!(Measure::Lines.of("123\n456\n"), 2);
+
+        let grid = "1".repeat(25) + "\n";
+        assert_eq!(
+            thresholds.choose(17, &grid).unwrap().name,
+            "day17::dijkstra"
+        );
+        assert_eq!(
+            thresholds.choose(17, &grid.repeat(20)).unwrap().name,
+            "day17::bidirectional"
+        );
+        assert_eq!(
+            thresholds.choose(17, &grid.repeat(400)).unwrap().name,
+            "day17::dijkstra"
+        );
+        assert_eq!(
+            thresholds.choose(12, "?? 1\n").unwrap().name,
+            "day12::enumerate"
+        );
+        assert!(!thresholds.has_rules(5));
+
+        assert_eq!(
+            Thresholds::parse(&thresholds.to_string()).unwrap(),
+            thresholds
+        );
+
+        assert!(Thresholds::parse("17 astar cells 1\n").is_err());
+        assert!(Thresholds::parse("17 dijkstra area 1\n").is_err());
+        assert!(Thresholds::parse("17 dijkstra cells\n").is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_calibrate() {
+        // whatever is faster here, a rule never names the default variant
+        let rule = calibrate(12, 1).unwrap();
+        assert!(rule.is_none_or(|f| f.variant != "day12::default"));
+
+        assert!(calibrate(3, 1).is_err());
+    }
+}
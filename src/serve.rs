@@ -0,0 +1,374 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    fmt::Write as _,
+    panic,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use color_eyre::eyre::Result;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use tracing::{info, warn};
+
+use crate::{registry, solver::solve_str, utils::Part};
+
+// upper bounds of the solve duration buckets, in seconds
+const DURATION_BUCKETS: [f64; 6] = [0.001, 0.01, 0.1, 1.0, 10.0, 60.0];
+
+// inputs are a few kilobytes, anything much larger is not a puzzle input
+const MAX_BODY: usize = 1 << 20;
+
+#[derive(Debug, Default)]
+struct Histogram {
+    // one count per bucket of DURATION_BUCKETS, not cumulative
+    buckets: [u64; DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+
+        if let Some(index) = DURATION_BUCKETS.iter().position(|f| seconds <= *f) {
+            self.buckets[index] += 1;
+        }
+
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+// what /metrics reports, rendered in the prometheus text format
+#[derive(Debug, Default)]
+pub struct Metrics {
+    // by route and status code
+    requests: BTreeMap<(&'static str, u16), u64>,
+    // by day
+    solve_durations: BTreeMap<i32, Histogram>,
+    solve_errors: BTreeMap<i32, u64>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_request(&mut self, route: &'static str, status: u16) {
+        *self.requests.entry((route, status)).or_default() += 1;
+    }
+
+    pub fn record_solve(&mut self, day: i32, elapsed: Duration, failed: bool) {
+        self.solve_durations
+            .entry(day)
+            .or_default()
+            .observe(elapsed);
+
+        if failed {
+            *self.solve_errors.entry(day).or_default() += 1;
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+
+        text.push_str("# HELP aoc_requests_total HTTP requests by route and status.\n");
+        text.push_str("# TYPE aoc_requests_total counter\n");
+        for ((route, status), count) in &self.requests {
+            let _ = writeln!(
+                text,
+                "aoc_requests_total{{route=\"{}\",status=\"{}\"}} {}",
+                route, status, count
+            );
+        }
+
+        text.push_str("# HELP aoc_solve_duration_seconds Time spent solving, by day.\n");
+        text.push_str("# TYPE aoc_solve_duration_seconds histogram\n");
+        for (day, histogram) in &self.solve_durations {
+            let mut cumulative = 0;
+
+            for (bound, count) in DURATION_BUCKETS.iter().zip(histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    text,
+                    "aoc_solve_duration_seconds_bucket{{day=\"{}\",le=\"{}\"}} {}",
+                    day, bound, cumulative
+                );
+            }
+
+            let _ = writeln!(
+                text,
+                "aoc_solve_duration_seconds_bucket{{day=\"{}\",le=\"+Inf\"}} {}",
+                day, histogram.count
+            );
+            let _ = writeln!(
+                text,
+                "aoc_solve_duration_seconds_sum{{day=\"{}\"}} {}",
+                day, histogram.sum
+            );
+            let _ = writeln!(
+                text,
+                "aoc_solve_duration_seconds_count{{day=\"{}\"}} {}",
+                day, histogram.count
+            );
+        }
+
+        text.push_str("# HELP aoc_solve_errors_total Solves that failed, by day.\n");
+        text.push_str("# TYPE aoc_solve_errors_total counter\n");
+        for (day, count) in &self.solve_errors {
+            let _ = writeln!(text, "aoc_solve_errors_total{{day=\"{}\"}} {}", day, count);
+        }
+
+        text
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+// the label requests are counted under, a fixed few so the metrics stay small
+fn route(path: &str) -> &'static str {
+    match path.trim_end_matches('/') {
+        "/metrics" => "/metrics",
+        path if path.starts_with("/solve/") => "/solve",
+        _ => "other",
+    }
+}
+
+// GET /metrics, and POST /solve/<day>/<part> with the puzzle input as the body
+pub fn handle(metrics: &Mutex<Metrics>, method: &str, path: &str, body: &str) -> Response {
+    let route = route(path);
+    let response = match route {
+        "/metrics" if method == "GET" => {
+            // the scrape itself is counted once it has been answered
+            let text = metrics.lock().unwrap().render();
+            Response::new(200, text)
+        }
+        "/metrics" => Response::new(405, "use GET\n"),
+        "/solve" => solve(metrics, method, path.trim_end_matches('/'), body),
+        _ => Response::new(404, "not found\n"),
+    };
+
+    metrics
+        .lock()
+        .unwrap()
+        .record_request(route, response.status);
+
+    response
+}
+
+fn solve(metrics: &Mutex<Metrics>, method: &str, path: &str, body: &str) -> Response {
+    if method != "POST" {
+        return Response::new(405, "use POST with the input as the body\n");
+    }
+
+    let mut segments = path.trim_start_matches("/solve/").split('/');
+    let day = segments.next().and_then(|f| f.parse::<i32>().ok());
+    let part = match segments.next() {
+        Some("1") => Some(Part::One),
+        Some("2") => Some(Part::Two),
+        _ => None,
+    };
+
+    let (Some(day), Some(part), None) = (day, part, segments.next()) else {
+        return Response::new(400, "expected /solve/<day>/<part>, e.g. /solve/5/2\n");
+    };
+    // every day gets series of its own in the metrics, only the days there are count
+    if registry::variants(day).is_empty() {
+        return Response::new(404, format!("day {:0>2} is not solved yet\n", day));
+    }
+
+    let start = Instant::now();
+    // a solver that panics on a bad input is a failed solve like any other, not a dropped connection
+    let result = panic::catch_unwind(|| solve_str(day, part, body));
+    metrics
+        .lock()
+        .unwrap()
+        .record_solve(day, start.elapsed(), !matches!(result, Ok(Ok(_))));
+
+    match result {
+        Ok(Ok(answer)) => Response::new(200, format!("{}\n", answer)),
+        Ok(Err(error)) => Response::new(422, format!("{}\n", error)),
+        Err(payload) => Response::new(
+            500,
+            format!("day {} panicked: {}\n", day, panic_message(&*payload)),
+        ),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => payload
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .unwrap_or("no message"),
+    }
+}
+
+// a small HTTP/1.1 server, one request per connection
+pub async fn serve(address: &str) -> Result<()> {
+    let listener = TcpListener::bind(address).await?;
+    let metrics = Arc::new(Mutex::new(Metrics::new()));
+
+    info!("listening on {}", listener.local_addr()?);
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let metrics = metrics.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) = connection(stream, metrics).await {
+                warn!("{}: {}", peer, error);
+            }
+        });
+    }
+}
+
+async fn connection(stream: TcpStream, metrics: Arc<Mutex<Metrics>>) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let response = if content_length > MAX_BODY {
+        Response::new(413, "input too large\n")
+    } else {
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).await?;
+        let body = String::from_utf8_lossy(&body).into_owned();
+
+        // solving is cpu bound, keep it off the async workers
+        let route = route(&path);
+        let task_metrics = metrics.clone();
+        let task =
+            tokio::task::spawn_blocking(move || handle(&task_metrics, &method, &path, &body));
+
+        // solve catches the panics of the solvers, this is anything else that went wrong
+        match task.await {
+            Ok(response) => response,
+            Err(error) => {
+                warn!("handling the request failed: {}", error);
+                metrics
+                    .lock()
+                    .unwrap_or_else(|f| f.into_inner())
+                    .record_request(route, 500);
+                Response::new(500, "internal error\n")
+            }
+        }
+    };
+
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.reason(),
+        response.body.len()
+    );
+
+    let mut stream = reader.into_inner();
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+    use crate::test_support::fixture;
+
+    #[traced_test]
+    #[test]
+    fn test_handle() {
+        let metrics = Mutex::new(Metrics::new());
+
+        let response = handle(&metrics, "POST", "/solve/9/1", &fixture("day09"));
+        assert_eq!(response, Response::new(200, "114\n"));
+
+        let response = handle(&metrics, "POST", "/solve/9/1", "1 2 x\n");
+        assert_eq!(response.status, 422);
+
+        assert_eq!(handle(&metrics, "GET", "/solve/9/1", "").status, 405);
+        assert_eq!(handle(&metrics, "POST", "/solve/9/3", "").status, 400);
+        assert_eq!(handle(&metrics, "GET", "/nothing", "").status, 404);
+        assert_eq!(handle(&metrics, "POST", "/solve/26/1", "").status, 404);
+
+        // day 16 panics on a line without a grid
+        let response = handle(&metrics, "POST", "/solve/16/1", "x\n");
+        assert_eq!(response.status, 500);
+        assert!(response.body.starts_with("day 16 panicked: "));
+
+        let text = handle(&metrics, "GET", "/metrics", "").body;
+        assert!(text.contains("aoc_requests_total{route=\"/solve\",status=\"200\"} 1\n"));
+        assert!(text.contains("aoc_requests_total{route=\"/solve\",status=\"422\"} 1\n"));
+        assert!(text.contains("aoc_requests_total{route=\"other\",status=\"404\"} 1\n"));
+        assert!(text.contains("aoc_solve_duration_seconds_count{day=\"9\"} 2\n"));
+        assert!(text.contains("aoc_solve_duration_seconds_bucket{day=\"9\",le=\"+Inf\"} 2\n"));
+        assert!(text.contains("aoc_solve_errors_total{day=\"9\"} 1\n"));
+        assert!(text.contains("aoc_solve_errors_total{day=\"16\"} 1\n"));
+        assert!(text.contains("aoc_requests_total{route=\"/solve\",status=\"404\"} 1\n"));
+        assert!(!text.contains("day=\"26\""));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_histogram() {
+        let mut metrics = Metrics::new();
+        metrics.record_solve(5, Duration::from_millis(5), false);
+        metrics.record_solve(5, Duration::from_millis(500), false);
+
+        let text = metrics.render();
+        assert!(text.contains("aoc_solve_duration_seconds_bucket{day=\"5\",le=\"0.001\"} 0\n"));
+        assert!(text.contains("aoc_solve_duration_seconds_bucket{day=\"5\",le=\"0.01\"} 1\n"));
+        assert!(text.contains("aoc_solve_duration_seconds_bucket{day=\"5\",le=\"1\"} 2\n"));
+        assert!(text.contains("aoc_solve_duration_seconds_sum{day=\"5\"} 0.505\n"));
+    }
+}
@@ -1,15 +1,88 @@
-use color_eyre::eyre::Result;
-use tokio::{fs::File, io::AsyncReadExt};
-use tracing::info;
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+};
 
-#[derive(Debug)]
+#[cfg(feature = "std")]
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    io::{self, BufRead},
+};
+#[cfg(feature = "tooling")]
+use std::{
+    fs::{self, File},
+    io::{BufReader, Cursor, ErrorKind, Read},
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+#[cfg(feature = "std")]
+use color_eyre::eyre::{eyre, Result};
+#[cfg(feature = "tooling")]
+use memmap2::Mmap;
+#[cfg(feature = "tooling")]
+use tracing::{info, info_span};
+
+#[cfg(feature = "std")]
+use crate::{
+    error::{Failure, FailureKind},
+    utils::Part,
+};
+
+// reads input/NN and solves it, with std alone solve_str solves an input already in memory
+#[cfg(feature = "tooling")]
 pub struct Solver {
-    input: String,
+    input: Box<dyn BufRead>,
+    // with --mmap, the days that need the whole input get a view of this instead of a copy
+    mapped: Option<Mmap>,
     day: i32,
+    algo: Option<String>,
+    // the rules of calibrate, loaded once by the caller, None keeps every day on its default
+    thresholds: Option<crate::select::Thresholds>,
     answer: Option<Answer>,
+    context: SolveContext,
+}
+
+// typed artifacts that days leave behind while solving, like the day 10 loop or the day 17 route
+// one artifact per type, each day defines its own types for what it deposits
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct SolveContext {
+    artifacts: HashMap<TypeId, Box<dyn Any>>,
+}
+
+#[cfg(feature = "std")]
+impl SolveContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // replaces the artifact of the same type, if there is one
+    pub fn insert<T: Any>(&mut self, artifact: T) {
+        self.artifacts.insert(TypeId::of::<T>(), Box::new(artifact));
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.artifacts.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub fn take<T: Any>(&mut self) -> Option<T> {
+        let artifact = self.artifacts.remove(&TypeId::of::<T>())?;
+
+        artifact.downcast().ok().map(|f| *f)
+    }
+
+    pub fn len(&self) -> usize {
+        self.artifacts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.artifacts.is_empty()
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Answer {
     pub part1: Option<String>,
     pub part2: Option<String>,
@@ -24,49 +97,368 @@ impl Default for Answer {
     }
 }
 
+impl Answer {
+    // part is 1 or 2, any other part has no answer
+    fn part(&self, part: usize) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    // None when the part has no answer or it isn't a number, so "007" compares equal to 7
+    pub fn as_i64(&self, part: usize) -> Option<i64> {
+        self.part(part)?.parse().ok()
+    }
+
+    pub fn as_u64(&self, part: usize) -> Option<u64> {
+        self.part(part)?.parse().ok()
+    }
+
+    // for parts whose answer is drawn in capital letters, the letters read off the drawing
+    #[cfg(feature = "std")]
+    pub fn with_drawing(mut self, part: Part, grid: &crate::utils::BitGrid) -> Self {
+        let letters = Some(crate::utils::ocr::letters(grid));
+
+        match part {
+            Part::One => self.part1 = letters,
+            Part::Two => self.part2 = letters,
+        }
+
+        self
+    }
+}
+
+// inputs saved on windows start with a byte order mark and end lines with \r\n, and editors leave
+// blank lines at the end, none of which the days expect
+// the result ends with a single newline if the input had one, and only allocates to drop \r
+pub fn normalize_input(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let input = match input.contains("\r\n") {
+        true => Cow::Owned(input.replace("\r\n", "\n")),
+        false => Cow::Borrowed(input),
+    };
+
+    let mut body = input.trim_end_matches('\n');
+    while let Some(start) = body.rfind('\n') {
+        if !body[start + 1..].trim().is_empty() {
+            break;
+        }
+        body = body[..start].trim_end_matches('\n');
+    }
+    if body.trim().is_empty() {
+        body = "";
+    }
+
+    // keep the newline after the last line, it is part of the input
+    let end = match body.is_empty() || body.len() == input.len() {
+        true => body.len(),
+        false => body.len() + 1,
+    };
+
+    match input {
+        Cow::Borrowed(input) => Cow::Borrowed(&input[..end]),
+        Cow::Owned(mut input) => {
+            input.truncate(end);
+            Cow::Owned(input)
+        }
+    }
+}
+
+// the streaming days read lines themselves, which already handles \r\n, so only the mark is skipped
+#[cfg(feature = "std")]
+pub fn skip_byte_order_mark(reader: &mut dyn BufRead) -> io::Result<()> {
+    if reader.fill_buf()?.starts_with("\u{feff}".as_bytes()) {
+        reader.consume(3);
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "tooling")]
+pub fn input_path(day: i32) -> String {
+    format!("{}/{:0>2}", crate::profile::input_directory(), day)
+}
+
+// a missing input is explained instead of reported as a bare os error
+#[cfg(feature = "tooling")]
+pub fn open_input(day: i32) -> Result<File> {
+    File::open(input_path(day)).map_err(|f| match f.kind() {
+        ErrorKind::NotFound => eyre!(Failure::new(
+            FailureKind::MissingInput,
+            crate::fetch::missing_input_message(day)
+        )),
+        _ => eyre!("could not open {}: {}", input_path(day), f),
+    })
+}
+
+// set once from the command line
+#[cfg(feature = "tooling")]
+static MMAP: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "tooling")]
+pub fn set_mmap(enabled: bool) {
+    MMAP.store(enabled, Ordering::Relaxed);
+}
+
+#[cfg(feature = "tooling")]
+pub fn read_input(day: i32) -> Result<String> {
+    let file = open_input(day)?;
+    if crate::vault::is_encrypted_file(&file)? {
+        return read_file(Path::new(&input_path(day)));
+    }
+
+    let mut content = String::new();
+    (&file).read_to_string(&mut content)?;
+
+    Ok(normalize_input(&content).into_owned())
+}
+
+// an input that is not input/NN, like the files of --diff-inputs and --input-dir, read the same way
+#[cfg(feature = "tooling")]
+pub fn read_file(path: &Path) -> Result<String> {
+    let content = crate::vault::read(path)?;
+    Ok(normalize_input(&content).into_owned())
+}
+
+// the answer lines of --output, of one day or of every day solved by all
+#[cfg(feature = "tooling")]
+pub fn write_lines(path: &Path, lines: &[String]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    fs::write(path, text)?;
+
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn not_solved_yet(day: i32) -> color_eyre::Report {
+    eyre!(Failure::new(
+        FailureKind::Unimplemented,
+        format!("day {:0>2} is not solved yet", day)
+    ))
+}
+
+#[cfg(feature = "std")]
+fn render_error(report: color_eyre::Report, name: &str, input: &str) -> color_eyre::Report {
+    match report.downcast_ref::<crate::error::Error>() {
+        Some(error) => eyre!(Failure::new(FailureKind::Parse, error.render(name, input))),
+        None => report,
+    }
+}
+
+// solves one part from an input that is already in memory, with the day's default variant
+// nothing is read from disk and no async runtime is needed
+#[cfg(feature = "std")]
+pub fn solve_str(day: i32, part: Part, input: &str) -> Result<String> {
+    let variant = crate::registry::variants(day)
+        .first()
+        .ok_or_else(|| not_solved_yet(day))?;
+    let name = format!("day {:0>2} input", day);
+    let input = &normalize_input(input);
+    let answer = (variant.solve)(input).map_err(|report| render_error(report, &name, input))?;
+
+    let value = match part {
+        Part::One => answer.part1,
+        Part::Two => answer.part2,
+    };
+
+    value.ok_or_else(|| eyre!("day {:0>2} has no answer for {}", day, part.name()))
+}
+
+#[cfg(feature = "tooling")]
 impl Solver {
     pub async fn new(day: i32) -> Result<Self> {
-        let path = format!("input/{:0>2}", day);
-        let mut file = File::open(path).await?;
-        let mut content = String::new();
-        file.read_to_string(&mut content).await?;
+        let file = open_input(day)?;
+        // an encrypted input is decrypted into memory whole, there is nothing to stream or map
+        if crate::vault::is_encrypted_file(&file)? {
+            let content = crate::vault::read(Path::new(&input_path(day)))?;
+            return Ok(Self::from_reader(day, Cursor::new(content)));
+        }
+
+        if MMAP.load(Ordering::Relaxed) {
+            return Self::from_mapped_file(day, file);
+        }
+
+        Ok(Self::from_reader(day, BufReader::new(file)))
+    }
 
-        Ok(Self {
-            input: content,
+    pub fn from_reader<R: BufRead + 'static>(day: i32, reader: R) -> Self {
+        Self {
+            input: Box::new(reader),
+            mapped: None,
             day,
+            algo: None,
+            thresholds: None,
             answer: None,
+            context: SolveContext::new(),
+        }
+    }
+
+    // the days that read line by line still stream the file, the others borrow the mapping
+    pub fn from_mapped_file(day: i32, file: File) -> Result<Self> {
+        // the mapping is only valid while nobody truncates the file, which nothing does to an input
+        // while it is being solved
+        let mapped = unsafe { Mmap::map(&file) }
+            .map_err(|f| eyre!("could not map {}: {}", input_path(day), f))?;
+        let mut solver = Self::from_reader(day, BufReader::new(file));
+        solver.mapped = Some(mapped);
+
+        Ok(solver)
+    }
+
+    // solve with a named variant from the registry instead of the default implementation
+    pub fn with_algo(mut self, algo: &str) -> Self {
+        self.algo = Some(algo.to_string());
+        self
+    }
+
+    // once calibrate wrote rules for the day, the size of the input picks the variant
+    pub fn with_thresholds(mut self, thresholds: crate::select::Thresholds) -> Self {
+        self.thresholds = Some(thresholds);
+        self
+    }
+
+    // days that need the whole input at once
+    // borrowed from the mapping unless normalizing had to drop \r
+    fn read_input(&mut self) -> Result<Cow<'_, str>> {
+        if let Some(mapped) = &self.mapped {
+            let content = core::str::from_utf8(mapped)
+                .map_err(|f| eyre!("{} is not valid utf-8: {}", input_path(self.day), f))?;
+
+            return Ok(normalize_input(content));
+        }
+
+        let mut content = String::new();
+        self.input.read_to_string(&mut content)?;
+
+        Ok(Cow::Owned(normalize_input(&content).into_owned()))
+    }
+
+    // parse errors are rendered against the input, pointing at the offending line
+    fn solve_with(&mut self, solve: impl FnOnce(&str) -> Result<Answer>) -> Result<Answer> {
+        let name = input_path(self.day);
+        let input = self.read_input()?;
+
+        solve(&input).map_err(|report| render_error(report, &name, &input))
+    }
+
+    // for days that deposit artifacts, they stay in the context after solving
+    fn solve_in_context(
+        &mut self,
+        solve: impl FnOnce(&str, &mut SolveContext) -> Result<Answer>,
+    ) -> Result<Answer> {
+        let mut context = std::mem::take(&mut self.context);
+        let answer = self.solve_with(|input| solve(input, &mut context));
+        self.context = context;
+
+        answer
+    }
+
+    // a variant picked from the input, through the context when the variant leaves artifacts
+    fn solve_variant(
+        &mut self,
+        pick: impl FnOnce(&str) -> Result<crate::registry::Variant>,
+    ) -> Result<Answer> {
+        self.solve_in_context(|input, context| {
+            let variant = pick(input)?;
+            match variant.in_context {
+                Some(solve) => solve(input, context),
+                None => (variant.solve)(input),
+            }
         })
     }
 
-    pub fn print_answer(&self) {
+    // solves with a day function the caller picked, e.g. one with options from the command line
+    pub fn solve_custom(
+        &mut self,
+        solve: impl FnOnce(&str, &mut SolveContext) -> Result<Answer>,
+    ) -> Result<()> {
+        let _span = info_span!("day", day = self.day).entered();
+        let answer = self.solve_in_context(solve)?;
+        self.answer = Some(answer);
+
+        Ok(())
+    }
+
+    pub fn context(&self) -> &SolveContext {
+        &self.context
+    }
+
+    // one line per part, the same lines that print_answer logs
+    // None until the day was solved
+    pub fn answer(&self) -> Option<&Answer> {
+        self.answer.as_ref()
+    }
+
+    pub fn answer_lines(&self) -> [String; 2] {
         let p1 = self.answer.as_ref().unwrap().part1.as_ref().unwrap();
         let p2 = self.answer.as_ref().unwrap().part2.as_ref().unwrap();
-        info!("Day {:0>2} part 1: {}", self.day, p1);
-        info!("Day {:0>2} part 2: {}", self.day, p2);
+
+        [
+            format!("Day {:0>2} part 1: {}", self.day, p1),
+            format!("Day {:0>2} part 2: {}", self.day, p2),
+        ]
+    }
+
+    pub fn print_answer(&self) {
+        for line in self.answer_lines() {
+            info!("{}", line);
+        }
+    }
+
+    // writes the answer lines without the log formatting, parent directories are created as needed
+    pub fn write_answer(&self, path: &Path) -> Result<()> {
+        write_lines(path, &self.answer_lines())
     }
 
     pub async fn solve(&mut self) -> Result<()> {
+        // the root of the day -> part -> phase spans that --trace-flame records
+        let _span = info_span!("day", day = self.day).entered();
+        skip_byte_order_mark(&mut self.input)?;
+
+        if let Some(algo) = &self.algo {
+            let variant = crate::registry::find(self.day, algo)?;
+            let answer = self.solve_variant(|_| Ok(variant))?;
+            self.answer = Some(answer);
+
+            return Ok(());
+        }
+
+        let day = self.day;
+        if let Some(thresholds) = self.thresholds.clone().filter(|f| f.has_rules(day)) {
+            let answer = self.solve_variant(|input| thresholds.choose(day, input))?;
+            self.answer = Some(answer);
+
+            return Ok(());
+        }
+
         let answer = match self.day {
-            1 => crate::day01::solve(&self.input)?,
-            2 => crate::day02::solve(&self.input)?,
-            3 => crate::day03::solve(&self.input)?,
-            4 => crate::day04::solve(&self.input)?,
-            5 => crate::day05::solve(&self.input)?,
-            6 => crate::day06::solve(&self.input)?,
-            7 => crate::day07::solve(&self.input)?,
-            8 => crate::day08::solve(&self.input)?,
-            9 => crate::day09::solve(&self.input)?,
-            10 => crate::day10::solve(&self.input)?,
-            11 => crate::day11::solve(&self.input)?,
-            12 => crate::day12::solve(&self.input)?,
-            13 => crate::day13::solve(&self.input)?,
-            14 => crate::day14::solve(&self.input)?,
-            15 => crate::day15::solve(&self.input)?,
-            16 => crate::day16::solve(&self.input)?,
-            17 => crate::day17::solve(&self.input)?,
-            18 => crate::day18::solve(&self.input)?,
-            19 => crate::day19::solve(&self.input)?,
-            _ => todo!(),
+            1 => crate::day01::solve_reader(&mut self.input)?,
+            2 => self.solve_with(crate::day02::solve)?,
+            3 => self.solve_with(crate::day03::solve)?,
+            4 => crate::day04::solve_reader(&mut self.input)?,
+            5 => self.solve_with(crate::day05::solve)?,
+            6 => self.solve_with(crate::day06::solve)?,
+            7 => self.solve_with(crate::day07::solve)?,
+            8 => self.solve_with(crate::day08::solve)?,
+            9 => crate::day09::solve_reader(&mut self.input)?,
+            10 => self.solve_in_context(crate::day10::solve_with_context)?,
+            11 => self.solve_with(crate::day11::solve)?,
+            12 => self.solve_with(crate::day12::solve)?,
+            13 => self.solve_with(crate::day13::solve)?,
+            14 => self.solve_in_context(crate::day14::solve_with_context)?,
+            15 => self.solve_with(crate::day15::solve)?,
+            16 => self.solve_with(crate::day16::solve)?,
+            17 => self.solve_in_context(crate::day17::solve_with_context)?,
+            18 => self.solve_with(crate::day18::solve)?,
+            19 => self.solve_with(crate::day19::solve)?,
+            _ => return Err(not_solved_yet(self.day)),
         };
 
         self.answer = Some(answer);
@@ -74,3 +466,169 @@ impl Solver {
         Ok(())
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_answer_accessors() {
+        let answer = Answer {
+            part1: Some("007".to_string()),
+            part2: Some("-3".to_string()),
+        };
+
+        assert_eq!(answer.as_i64(1), Some(7));
+        assert_eq!(answer.as_u64(1), Some(7));
+        assert_eq!(answer.as_i64(2), Some(-3));
+        assert_eq!(answer.as_u64(2), None);
+
+        let answer = Answer {
+            part1: None,
+            part2: Some("LLR".to_string()),
+        };
+        assert_eq!(answer.as_i64(1), None);
+        assert_eq!(answer.as_i64(2), None);
+        assert_eq!(Answer::default().as_i64(3), None);
+
+        let grid = crate::utils::ocr::from_drawing("#...\n#...\n#...\n#...\n#...\n####\n");
+        let answer = Answer::default().with_drawing(Part::Two, &grid);
+        assert_eq!(answer.part2.as_deref(), Some("L"));
+        assert_eq!(answer.as_i64(1), Some(0));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_normalize_input() {
+        assert!(matches!(
+            normalize_input("1 2\n3 4\n"),
+            Cow::Borrowed("1 2\n3 4\n")
+        ));
+        assert_eq!(normalize_input("\u{feff}1 2\r\n3 4\r\n"), "1 2\n3 4\n");
+        assert_eq!(normalize_input("1 2\n3 4\n\n  \n\n"), "1 2\n3 4\n");
+        assert_eq!(normalize_input("1 2\r\n\r\n3 4"), "1 2\n\n3 4");
+        assert_eq!(normalize_input("\n\n"), "");
+
+        // blank lines inside the input separate blocks on some days and are kept
+        assert_eq!(normalize_input("#.\n\n.#\n\n"), "#.\n\n.#\n");
+
+        let mut reader = "\u{feff}1\r\n2\r\n".as_bytes();
+        skip_byte_order_mark(&mut reader).unwrap();
+        let lines = reader.lines().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(lines, ["1", "2"]);
+    }
+
+    #[cfg(feature = "tooling")]
+    #[traced_test]
+    #[test]
+    fn test_from_mapped_file() {
+        let path = std::env::temp_dir().join("advent-of-code-2023-mmap-test");
+        let input = crate::test_support::fixture("day02");
+        let solve = |content: &str| {
+            fs::write(&path, content).unwrap();
+            let mut solver = Solver::from_mapped_file(2, File::open(&path).unwrap()).unwrap();
+            solver.solve_with(crate::day02::solve)
+        };
+
+        let answer = solve(&input).unwrap();
+        assert_eq!(answer.part1.as_deref(), Some("8"));
+        assert_eq!(answer.part2.as_deref(), Some("2286"));
+        assert_eq!(solve(&input.replace('\n', "\r\n")).unwrap(), answer);
+
+        let mut solver = Solver::from_mapped_file(2, File::open(&path).unwrap()).unwrap();
+        assert!(matches!(solver.read_input().unwrap(), Cow::Owned(_)));
+        fs::write(&path, &input).unwrap();
+        let mut solver = Solver::from_mapped_file(2, File::open(&path).unwrap()).unwrap();
+        assert!(matches!(solver.read_input().unwrap(), Cow::Borrowed(_)));
+
+        fs::write(&path, b"Game 1: \xff").unwrap();
+        let mut solver = Solver::from_mapped_file(2, File::open(&path).unwrap()).unwrap();
+        let error = solver.solve_with(crate::day02::solve).unwrap_err();
+        assert!(error.to_string().contains("not valid utf-8"));
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[cfg(feature = "tooling")]
+    #[traced_test]
+    #[test]
+    fn test_read_file() {
+        let path = std::env::temp_dir().join("advent-of-code-2023-read-file-test");
+        fs::write(&path, "\u{feff}0 3 6\r\n1 2 3\r\n\r\n").unwrap();
+        assert_eq!(read_file(&path).unwrap(), "0 3 6\n1 2 3\n");
+        let _ = fs::remove_file(&path);
+
+        assert!(read_file(&path).is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_solve_str() {
+        let input = crate::test_support::fixture("day09");
+
+        assert_eq!(solve_str(9, Part::One, &input).unwrap(), "114");
+        assert_eq!(solve_str(9, Part::Two, &input).unwrap(), "2");
+        assert!(solve_str(42, Part::One, &input).is_err());
+
+        let error = solve_str(9, Part::One, "1 2 x\n").unwrap_err();
+        assert!(error.to_string().contains("expected a number"));
+        assert!(error.to_string().contains("day 09 input"));
+
+        // saved on windows, with a blank line the editor added at the end
+        let input = crate::test_support::fixture("day13").replace('\n', "\r\n");
+        let input = format!("\u{feff}{}\r\n", input);
+        assert_eq!(solve_str(13, Part::One, &input).unwrap(), "405");
+        assert_eq!(solve_str(13, Part::Two, &input).unwrap(), "400");
+    }
+
+    #[cfg(feature = "cli")]
+    #[traced_test]
+    #[tokio::test]
+    async fn test_solve_variant() -> Result<()> {
+        let input = crate::test_support::fixture("day10-part1-2");
+        let solver = || Solver::from_reader(10, Cursor::new(input.clone().into_bytes()));
+
+        // the variant of --algo and the one the thresholds pick still leave their artifacts
+        let mut algo = solver().with_algo("floodfill");
+        algo.solve().await?;
+        assert!(algo.context().get::<crate::day10::LoopPath>().is_some());
+
+        let thresholds = crate::select::Thresholds::parse("10 floodfill cells 1\n")?;
+        let mut chosen = solver().with_thresholds(thresholds);
+        chosen.solve().await?;
+        assert_eq!(chosen.answer(), algo.answer());
+        assert!(chosen.context().get::<crate::day10::LoopPath>().is_some());
+
+        let mut shoelace = solver().with_algo("shoelace");
+        shoelace.solve().await?;
+        assert_eq!(
+            shoelace.answer().unwrap().part1,
+            algo.answer().unwrap().part1
+        );
+        assert!(shoelace.context().is_empty());
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_solve_context() {
+        #[derive(Debug, PartialEq)]
+        struct Steps(Vec<i32>);
+
+        let mut context = SolveContext::new();
+        assert!(context.get::<Steps>().is_none());
+
+        context.insert(Steps(vec![1, 2]));
+        context.insert(42_usize);
+        context.insert(Steps(vec![3]));
+
+        assert_eq!(context.len(), 2);
+        assert_eq!(context.get::<Steps>(), Some(&Steps(vec![3])));
+        assert_eq!(context.take::<usize>(), Some(42));
+        assert!(context.get::<usize>().is_none());
+    }
+}
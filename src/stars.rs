@@ -0,0 +1,167 @@
+use std::{collections::BTreeMap, fmt::Write as _, sync::OnceLock};
+
+use color_eyre::eyre::{bail, Result};
+use regex::Regex;
+
+// each unlocked day on the calendar is a link labelled like "Day 5, two stars"
+fn day_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX
+        .get_or_init(|| Regex::new(r#"aria-label="Day (\d+)(?:, (one star|two stars))?""#).unwrap())
+}
+
+// the header of every page names the logged in account, or "(anonymous user #N)" for accounts
+// that hide their name
+fn user_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"<div class="user">([^<]+)"#).unwrap())
+}
+
+// the account a page was fetched as, None when it was fetched logged out
+pub fn account(html: &str) -> Option<String> {
+    user_regex()
+        .captures(html)
+        .map(|f| f[1].trim().to_string())
+        .filter(|f| !f.is_empty())
+}
+
+// stars per unlocked day, read from the calendar on the site
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Stars {
+    days: BTreeMap<i32, u8>,
+}
+
+impl Stars {
+    pub fn parse(html: &str) -> Result<Self> {
+        // a logged out calendar has no stars on it at all, which would look like no progress
+        if html.contains("/auth/login") {
+            bail!("the calendar was fetched logged out, is the session token still valid?");
+        }
+
+        let days = day_regex()
+            .captures_iter(html)
+            .map(|f| {
+                let stars = match f.get(2).map(|f| f.as_str()) {
+                    Some("two stars") => 2,
+                    Some(_) => 1,
+                    None => 0,
+                };
+
+                (f[1].parse().unwrap(), stars)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        if days.is_empty() {
+            bail!("found no days on the calendar");
+        }
+
+        Ok(Self { days })
+    }
+
+    // 0 for days that are not unlocked yet
+    pub fn get(&self, day: i32) -> u8 {
+        self.days.get(&day).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u32 {
+        self.days.values().map(|f| *f as u32).sum()
+    }
+
+    // implemented days that are unlocked but do not have both stars on the site
+    pub fn missing(&self, implemented: &[i32]) -> Vec<(i32, u8)> {
+        implemented
+            .iter()
+            .filter(|f| self.days.contains_key(f) && self.get(**f) < 2)
+            .map(|f| (*f, self.get(*f)))
+            .collect()
+    }
+
+    // one line of markers per unlocked day, then the total and what is left to submit
+    pub fn summary(&self, implemented: &[i32]) -> String {
+        let mut text = String::new();
+
+        for (day, stars) in &self.days {
+            let line = format!(
+                "{:0>2}  {:<2}  {}",
+                day,
+                "*".repeat(*stars as usize),
+                if implemented.contains(day) {
+                    "solved here"
+                } else {
+                    ""
+                }
+            );
+            let _ = writeln!(text, "{}", line.trim_end());
+        }
+
+        let _ = writeln!(text, "total  {}/{}", self.total(), self.days.len() * 2);
+
+        let missing = self.missing(implemented);
+        if missing.is_empty() {
+            text.push_str("every day solved here has both stars\n");
+        } else {
+            let days = missing
+                .iter()
+                .map(|(day, stars)| format!("{:0>2} ({}/2)", day, stars))
+                .collect::<Vec<_>>();
+            let _ = writeln!(text, "solved here but missing stars: {}", days.join(", "));
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    const CALENDAR: &str = r#"
+<pre class="calendar">
+<a aria-label="Day 1, two stars" href="/2023/day/1" class="calendar-day1 calendar-verycomplete">
+<a aria-label="Day 2, one star" href="/2023/day/2" class="calendar-day2 calendar-complete">
+<a aria-label="Day 3" href="/2023/day/3" class="calendar-day3">
+<a aria-label="Day 10, two stars" href="/2023/day/10" class="calendar-day10 calendar-verycomplete">
+</pre>
+"#;
+
+    #[traced_test]
+    #[test]
+    fn test_stars() {
+        let stars = Stars::parse(CALENDAR).unwrap();
+
+        assert_eq!(stars.get(1), 2);
+        assert_eq!(stars.get(2), 1);
+        assert_eq!(stars.get(3), 0);
+        assert_eq!(stars.get(25), 0);
+        assert_eq!(stars.total(), 5);
+        assert_eq!(stars.missing(&[1, 2, 3, 25]), vec![(2, 1), (3, 0)]);
+
+        let summary = stars.summary(&[1, 2, 3]);
+        assert!(summary.contains("02  *   solved here\n"));
+        assert!(summary.contains("total  5/8\n"));
+        assert!(summary.contains("solved here but missing stars: 02 (1/2), 03 (0/2)\n"));
+
+        assert!(Stars::parse(r#"<a href="/2023/auth/login">[Log In]</a>"#).is_err());
+        assert!(Stars::parse("").is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_account() {
+        assert_eq!(
+            account(r#"<div class="user">harry <span class="star-count">34*</span></div>"#)
+                .as_deref(),
+            Some("harry")
+        );
+        assert_eq!(
+            account(
+                r#"<div class="user">(anonymous user #1234) <span class="star-count">2*</span>"#
+            )
+            .as_deref(),
+            Some("(anonymous user #1234)")
+        );
+        assert_eq!(account(CALENDAR), None);
+    }
+}
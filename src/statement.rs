@@ -0,0 +1,293 @@
+use std::{fs, path::Path, sync::OnceLock};
+
+use color_eyre::eyre::{bail, eyre, Result};
+use regex::Regex;
+use tracing::info;
+
+use crate::{
+    error::{Failure, FailureKind},
+    fetch,
+    solver::Answer,
+    vault,
+};
+
+// statements are wrapped to this many columns
+pub const WIDTH: usize = 80;
+
+// the puzzle descriptions of a page, part 2 is only on it once part 1 is solved
+fn article_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"(?s)<article class="day-desc">.*?</article>"#).unwrap())
+}
+
+// the example of a description is its first block of code
+fn example_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"(?s)<pre><code>(.*?)</code></pre>"#).unwrap())
+}
+
+// the answers for the example are emphasized code, the last one of a description is usually it
+fn emphasized_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r#"<code><em>([^<]+)</em></code>|<em><code>([^<]+)</code></em>"#).unwrap()
+    })
+}
+
+// only the descriptions are cached, as html so they can be wrapped to any width later
+pub fn cache_path(day: i32) -> String {
+    format!("input/statements/{:0>2}.html", day)
+}
+
+pub fn extract_articles(page: &str) -> Result<String> {
+    let articles = article_regex()
+        .find_iter(page)
+        .map(|f| f.as_str())
+        .collect::<Vec<_>>();
+
+    if articles.is_empty() {
+        bail!("the page has no puzzle description, is the day unlocked yet?");
+    }
+
+    Ok(articles.join("\n"))
+}
+
+// the example of part 1 and the answers the description gives for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Example {
+    // the answers of a solve of the example against the ones in the description
+    pub fn check(&self, answer: &Answer) -> Result<()> {
+        for (part, expected, actual) in [
+            (1, &self.part1, &answer.part1),
+            (2, &self.part2, &answer.part2),
+        ] {
+            let Some(expected) = expected else {
+                info!("part {} has no example answer to check against", part);
+                continue;
+            };
+
+            if actual.as_ref() != Some(expected) {
+                bail!(Failure::new(
+                    FailureKind::Mismatch,
+                    format!(
+                        "part {} of the example should be {}, got {}",
+                        part,
+                        expected,
+                        actual.as_deref().unwrap_or("nothing")
+                    )
+                ));
+            }
+            info!("part {} matches the example", part);
+        }
+
+        Ok(())
+    }
+}
+
+// the first block of code of part 1, with the answers where the descriptions emphasize them
+// part 2 only counts when it does not bring an example of its own
+pub fn extract_example(articles: &str) -> Result<Example> {
+    let articles = article_regex()
+        .find_iter(articles)
+        .map(|f| f.as_str())
+        .collect::<Vec<_>>();
+
+    let input = articles
+        .first()
+        .and_then(|f| example_regex().captures(f))
+        .map(|f| unescape(&f[1]))
+        .ok_or_else(|| eyre!("the description has no example"))?;
+    let answer = |article: &str| {
+        emphasized_regex()
+            .captures_iter(article)
+            .last()
+            .and_then(|f| f.get(1).or(f.get(2)))
+            .map(|f| unescape(f.as_str()))
+    };
+
+    Ok(Example {
+        input,
+        part1: articles.first().and_then(|f| answer(f)),
+        part2: articles
+            .get(1)
+            .filter(|f| !example_regex().is_match(f))
+            .and_then(|f| answer(f)),
+    })
+}
+
+// the text of html, with the tags like the emphasis inside examples dropped
+fn unescape(html: &str) -> String {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    let text = TAG
+        .get_or_init(|| Regex::new(r"<[^>]+>").unwrap())
+        .replace_all(html, "");
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+// the examples, with their answers next to them
+pub const EXAMPLE_DIRECTORY: &str = "input/examples";
+
+pub fn example_path(day: i32) -> String {
+    format!("{}/{:0>2}", EXAMPLE_DIRECTORY, day)
+}
+
+// the answers next to the example, "part1 part2" with - for the ones the description does not give
+fn example_answers_path(day: i32) -> String {
+    format!("{}.answers", example_path(day))
+}
+
+// the cached example, extracted from the statement first if there is none or refresh is set
+pub fn load_example(day: i32, refresh: bool) -> Result<Example> {
+    let path = example_path(day);
+    let answers_path = example_answers_path(day);
+
+    if !refresh {
+        if let (Ok(input), Ok(answers)) = (
+            vault::read(Path::new(&path)),
+            vault::read(Path::new(&answers_path)),
+        ) {
+            let answer = |f: Option<&str>| f.filter(|f| *f != "-").map(|f| f.to_string());
+            let mut answers = answers.split_whitespace();
+            return Ok(Example {
+                input,
+                part1: answer(answers.next()),
+                part2: answer(answers.next()),
+            });
+        }
+    }
+
+    let example = extract_example(&load(day, refresh)?)?;
+
+    if let Some(parent) = Path::new(&path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+    vault::write(Path::new(&path), &example.input)?;
+    vault::write(
+        Path::new(&answers_path),
+        format!(
+            "{} {}\n",
+            example.part1.as_deref().unwrap_or("-"),
+            example.part2.as_deref().unwrap_or("-")
+        ),
+    )?;
+    info!("saved the example to {}", path);
+
+    Ok(example)
+}
+
+pub fn render(articles: &str, width: usize) -> String {
+    html2text::from_read(articles.as_bytes(), width)
+}
+
+// the cached statement, downloaded first if there is none or refresh is set
+// the session token is used when there is one, without it the page only has part 1
+pub fn load(day: i32, refresh: bool) -> Result<String> {
+    if !(1..=25).contains(&day) {
+        bail!("there is no day {}", day);
+    }
+
+    let path = cache_path(day);
+    if !refresh {
+        if let Ok(cached) = vault::read(Path::new(&path)) {
+            return Ok(cached);
+        }
+    }
+
+    let token = fetch::session_token();
+    let articles = extract_articles(&fetch::fetch_page(
+        &fetch::puzzle_url(day),
+        token.as_deref(),
+    )?)?;
+
+    if let Some(parent) = Path::new(&path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+    vault::write(Path::new(&path), &articles)?;
+    info!("saved the statement to {}", path);
+
+    Ok(articles)
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    const PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 9: Mirage Maintenance ---</h2><p>You ride the <em>camel</em> through the sandstorm.</p></article>
+<p>Your puzzle answer was <code>114</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>Of course, it would be nice to have <em>even more history</em>.</p></article>
+</main></body></html>"#;
+
+    #[traced_test]
+    #[test]
+    fn test_extract_articles() {
+        let articles = extract_articles(PAGE).unwrap();
+        assert!(articles.starts_with("<article"));
+        assert!(!articles.contains("Your puzzle answer"));
+
+        let text = render(&articles, WIDTH);
+        assert!(text.contains("--- Day 9: Mirage Maintenance ---"));
+        assert!(text.contains("--- Part Two ---"));
+        assert!(text.lines().all(|f| f.chars().count() <= WIDTH));
+
+        assert!(extract_articles("<html></html>").is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_extract_example() {
+        let articles = r#"<article class="day-desc"><h2>--- Day 9: Mirage Maintenance ---</h2>
+<p>For example:</p>
+<pre><code>0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+</code></pre>
+<p>the <em>next</em> value, <code>18</code> and <code>28</code>.</p>
+<p>the sum of these extrapolated values is <code><em>114</em></code>.</p></article>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Adding the new values on the left side of each sequence from the example gives <code><em>2</em></code>.</p></article>"#;
+
+        let example = extract_example(articles).unwrap();
+        assert_eq!(example.input, crate::test_support::fixture("day09"));
+        assert_eq!(example.part1.as_deref(), Some("114"));
+        assert_eq!(example.part2.as_deref(), Some("2"));
+
+        let answer = Answer {
+            part1: Some("114".to_string()),
+            part2: Some("2".to_string()),
+        };
+        assert!(example.check(&answer).is_ok());
+        let wrong = Answer {
+            part2: Some("3".to_string()),
+            ..answer
+        };
+        assert_eq!(
+            example.check(&wrong).unwrap_err().to_string(),
+            "part 2 of the example should be 2, got 3"
+        );
+
+        // a second example for part 2 makes the emphasized answer about that one instead
+        let articles = articles.replace(
+            "<p>Adding",
+            "<pre><code>&lt;x&gt; <em>1</em></code></pre><p>Adding",
+        );
+        let example = extract_example(&articles).unwrap();
+        assert_eq!(example.part2, None);
+        assert_eq!(unescape("&lt;x&gt; <em>1</em> &amp;lt;"), "<x> 1 &lt;");
+
+        assert!(extract_example(PAGE).is_err());
+    }
+}
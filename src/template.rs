@@ -15,31 +15,14 @@ pub fn solve(input: &str) -> Result<Answer> {
 
 #[cfg(test)]
 mod tests {
-    use tracing::info;
-    use tracing_test::traced_test;
-
     use super::*;
-    use color_eyre::eyre::Result;
+    use tracing_test::traced_test;
 
     const TEST_INPUT: &str = "placeholder";
 
     #[traced_test]
     #[test]
-    fn test_part1() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part1, Some("405".to_string()));
-
-        Ok(())
-    }
-
-    #[traced_test]
-    #[test]
-    fn test_part2() -> Result<()> {
-        let answer = solve(TEST_INPUT)?;
-
-        assert_eq!(answer.part2, Some("".to_string()));
-
-        Ok(())
+    fn test_example() {
+        assert_answers!(solve, TEST_INPUT, part1 = "405", part2 = "");
     }
 }
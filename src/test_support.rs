@@ -0,0 +1,102 @@
+use std::{fs, path::Path};
+
+// example input from tests/fixtures/<name>.txt
+pub fn fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(format!("{}.txt", name));
+
+    fs::read_to_string(&path).unwrap_or_else(|error| panic!("{}: {}", path.display(), error))
+}
+
+// "day10" out of a module path like advent_of_code_2023::day10::tests
+pub fn day_name(module_path: &str) -> &str {
+    module_path
+        .split("::")
+        .find(|f| f.starts_with("day"))
+        .unwrap_or(module_path)
+}
+
+// panics with every part that differs, instead of stopping at the first one
+pub fn check_answers(day: &str, checks: &[(&str, &str, Option<&str>)]) {
+    let lines = checks
+        .iter()
+        .filter(|(_, expected, actual)| *actual != Some(*expected))
+        .map(|(part, expected, actual)| {
+            format!("  {}: expected {:?}, got {:?}", part, expected, actual)
+        })
+        .collect::<Vec<_>>();
+
+    if !lines.is_empty() {
+        panic!("{}: wrong answer\n{}", day, lines.join("\n"));
+    }
+}
+
+// solves the input and checks the given parts, e.g.
+// assert_answers!(super::solve, &input, part1 = "6440", part2 = "5905");
+macro_rules! assert_answers {
+    ($solve:path, $input:expr $(, part1 = $part1:expr)? $(, part2 = $part2:expr)?) => {{
+        let day = $crate::test_support::day_name(module_path!());
+        let answer = match $solve($input) {
+            Ok(answer) => answer,
+            Err(error) => panic!("{}: {} failed: {}", day, stringify!($solve), error),
+        };
+
+        let checks: Vec<(&str, &str, Option<&str>)> = vec![
+            $(("part 1", $part1, answer.part1.as_deref()),)?
+            $(("part 2", $part2, answer.part2.as_deref()),)?
+        ];
+
+        $crate::test_support::check_answers(day, &checks);
+    }};
+}
+
+// solves a fixture and checks the given parts, e.g.
+// fixture_test!(test_part1, super::solve, "day07", part1 = "6440");
+macro_rules! fixture_test {
+    ($name:ident, $solve:path, $fixture:literal $(, part1 = $part1:literal)? $(, part2 = $part2:literal)?) => {
+        #[tracing_test::traced_test]
+        #[test]
+        fn $name() {
+            assert_answers!(
+                $solve,
+                &$crate::test_support::fixture($fixture)
+                $(, part1 = $part1)?
+                $(, part2 = $part2)?
+            );
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_day_name() {
+        assert_eq!(day_name("advent_of_code_2023::day10::tests"), "day10");
+        assert_eq!(
+            day_name("advent_of_code_2023::registry"),
+            "advent_of_code_2023::registry"
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    #[should_panic(
+        expected = "day07: wrong answer\n  part 2: expected \"5905\", got Some(\"5906\")"
+    )]
+    fn test_check_answers() {
+        check_answers(
+            "day07",
+            &[
+                ("part 1", "6440", Some("6440")),
+                ("part 2", "5905", Some("5906")),
+            ],
+        );
+    }
+}
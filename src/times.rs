@@ -0,0 +1,243 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    fmt::Write as _,
+};
+
+use color_eyre::eyre::{eyre, Result};
+use serde_json::Value;
+
+use crate::fetch;
+
+// the id in the url of the private leaderboard, e.g. 123456 for /leaderboard/private/view/123456
+pub const LEADERBOARD_VARIABLE: &str = "AOC_LEADERBOARD";
+
+// your member id on that leaderboard, the leaderboard id when unset since that is its owner
+pub const USER_VARIABLE: &str = "AOC_USER_ID";
+
+pub fn leaderboard_url(id: &str) -> String {
+    format!(
+        "{}/leaderboard/private/view/{}.json",
+        fetch::calendar_url(),
+        id
+    )
+}
+
+// (leaderboard id, member id) from the environment
+pub fn leaderboard_ids() -> Result<(String, String)> {
+    let leaderboard = env::var(LEADERBOARD_VARIABLE).map_err(|_| {
+        eyre!(
+            "set {} to the id of your private leaderboard",
+            LEADERBOARD_VARIABLE
+        )
+    })?;
+    let user = env::var(USER_VARIABLE).unwrap_or_else(|_| leaderboard.clone());
+
+    Ok((leaderboard, user))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub id: String,
+    pub name: String,
+    // unix time of each star by (day, part)
+    pub stars: BTreeMap<(i32, u8), u64>,
+}
+
+// the completion times of every member of a private leaderboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateLeaderboard {
+    members: Vec<Member>,
+}
+
+impl PrivateLeaderboard {
+    // the json the site serves for a private leaderboard
+    pub fn parse(json: &str) -> Result<Self> {
+        let value = serde_json::from_str::<Value>(json)?;
+        let members = value["members"]
+            .as_object()
+            .ok_or_else(|| eyre!("the leaderboard has no members, is the session token valid?"))?;
+
+        let members = members
+            .iter()
+            .map(|(id, member)| {
+                let mut stars = BTreeMap::new();
+
+                if let Some(days) = member["completion_day_level"].as_object() {
+                    for (day, parts) in days {
+                        for (part, star) in parts.as_object().into_iter().flatten() {
+                            let (Ok(day), Ok(part), Some(timestamp)) = (
+                                day.parse::<i32>(),
+                                part.parse::<u8>(),
+                                star["get_star_ts"].as_u64(),
+                            ) else {
+                                continue;
+                            };
+
+                            if !(1..=25).contains(&day) {
+                                continue;
+                            }
+
+                            stars.insert((day, part), timestamp);
+                        }
+                    }
+                }
+
+                Member {
+                    id: id.clone(),
+                    // members without a display name show up as anonymous users on the site
+                    name: member["name"]
+                        .as_str()
+                        .map(|f| f.to_string())
+                        .unwrap_or_else(|| format!("(anonymous user #{})", id)),
+                    stars,
+                }
+            })
+            .collect();
+
+        Ok(Self { members })
+    }
+
+    pub fn member(&self, id: &str) -> Option<&Member> {
+        self.members.iter().find(|f| f.id == id)
+    }
+
+    // 1 for the first member to get the star, None if the member does not have it
+    pub fn rank(&self, id: &str, day: i32, part: u8) -> Option<usize> {
+        let timestamp = *self.member(id)?.stars.get(&(day, part))?;
+        let earlier = self
+            .members
+            .iter()
+            .filter(|f| f.stars.get(&(day, part)).is_some_and(|f| *f < timestamp))
+            .count();
+
+        Some(earlier + 1)
+    }
+
+    // time after unlock of each part, the time between the parts, and the ranks on this leaderboard
+    pub fn table(&self, id: &str) -> Result<String> {
+        let member = self
+            .member(id)
+            .ok_or_else(|| eyre!("member {} is not on the leaderboard", id))?;
+        let mut text = String::new();
+
+        let _ = writeln!(
+            text,
+            "{} on a leaderboard of {}",
+            member.name,
+            self.members.len()
+        );
+        let _ = writeln!(
+            text,
+            "{:<5}  {:>10}  {:>10}  {:>10}  {:>6}  {:>6}",
+            "Day", "Part 1", "Part 2", "Delta", "Rank 1", "Rank 2"
+        );
+
+        let days = member
+            .stars
+            .keys()
+            .map(|(day, _)| *day)
+            .collect::<BTreeSet<_>>();
+
+        for day in days {
+            let unlock = fetch::unlock_timestamp(day);
+            let part1 = member.stars.get(&(day, 1));
+            let part2 = member.stars.get(&(day, 2));
+            let rank = |part| {
+                self.rank(id, day, part)
+                    .map(|f| f.to_string())
+                    .unwrap_or_else(|| "-".to_string())
+            };
+
+            let _ = writeln!(
+                text,
+                "{:<5}  {:>10}  {:>10}  {:>10}  {:>6}  {:>6}",
+                format!("{:0>2}", day),
+                format_duration(part1.map(|f| f.saturating_sub(unlock))),
+                format_duration(part2.map(|f| f.saturating_sub(unlock))),
+                format_duration(part1.zip(part2).map(|(a, b)| b.saturating_sub(*a))),
+                rank(1),
+                rank(2)
+            );
+        }
+
+        Ok(text)
+    }
+}
+
+// hours:minutes:seconds, hours grow past 24 for stars collected days later
+fn format_duration(seconds: Option<u64>) -> String {
+    match seconds {
+        Some(seconds) => format!(
+            "{}:{:0>2}:{:0>2}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        ),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    // day 1 unlocked at 1701406800
+    const LEADERBOARD: &str = r#"{
+        "event": "2023",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "id": 1,
+                "name": "me",
+                "stars": 3,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1701407172, "star_index": 1},
+                        "2": {"get_star_ts": 1701408000, "star_index": 2}
+                    },
+                    "2": {
+                        "1": {"get_star_ts": 1701500000, "star_index": 3}
+                    }
+                }
+            },
+            "2": {
+                "id": 2,
+                "name": null,
+                "stars": 2,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1701407000, "star_index": 1},
+                        "2": {"get_star_ts": 1701409000, "star_index": 2}
+                    }
+                }
+            }
+        }
+    }"#;
+
+    #[traced_test]
+    #[test]
+    fn test_private_leaderboard() {
+        let leaderboard = PrivateLeaderboard::parse(LEADERBOARD).unwrap();
+
+        assert_eq!(leaderboard.member("2").unwrap().name, "(anonymous user #2)");
+        assert_eq!(leaderboard.rank("1", 1, 1), Some(2));
+        assert_eq!(leaderboard.rank("1", 1, 2), Some(1));
+        assert_eq!(leaderboard.rank("1", 2, 2), None);
+
+        let table = leaderboard.table("1").unwrap();
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "me on a leaderboard of 2");
+        assert_eq!(
+            lines[2],
+            "01        0:06:12     0:20:00     0:13:48       2       1"
+        );
+        assert!(lines[3].starts_with("02       "));
+        assert!(lines[3].ends_with("-       1       -"));
+
+        assert!(leaderboard.table("3").is_err());
+        assert!(PrivateLeaderboard::parse("{}").is_err());
+    }
+}
@@ -1,14 +1,43 @@
-use std::str::FromStr;
+use std::{iter, str::FromStr};
 
 use num::Integer;
 use strum::EnumIter;
 
-#[derive(PartialEq)]
+mod aabb;
+mod bit_grid;
+pub mod charset;
+pub mod color;
+pub mod dump;
+pub mod explain;
+pub mod graph;
+pub mod grid;
+pub mod guard;
+pub mod ocr;
+pub mod parse;
+pub mod polygon;
+pub mod polynomial;
+mod priority_queue;
+pub mod sparse_grid;
+
+pub use aabb::Aabb3;
+pub use bit_grid::BitGrid;
+pub use priority_queue::PriorityQueue;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Part {
     One,
     Two,
 }
 
+impl Part {
+    pub fn name(&self) -> &str {
+        match self {
+            Part::One => "part1",
+            Part::Two => "part2",
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter)]
 pub enum Direction {
     North,
@@ -30,10 +59,7 @@ impl Direction {
     }
 
     pub fn is_horizontal(&self) -> bool {
-        match self {
-            Direction::East | Direction::West | Direction::Left | Direction::Right => true,
-            Direction::South | Direction::North | Direction::Up | Direction::Down => false,
-        }
+        !self.is_vertical()
     }
 
     pub fn reverse(&self) -> Direction {
@@ -60,10 +86,10 @@ impl Direction {
 
     pub fn display(&self) -> &str {
         match self {
-            Direction::North | Direction::Up => "↑",
-            Direction::East | Direction::Left => "←",
-            Direction::South | Direction::Down => "↓",
-            Direction::West | Direction::Right => "→",
+            Direction::North | Direction::Up => charset::glyph("↑", "^"),
+            Direction::East | Direction::Left => charset::glyph("←", "<"),
+            Direction::South | Direction::Down => charset::glyph("↓", "v"),
+            Direction::West | Direction::Right => charset::glyph("→", ">"),
         }
     }
 }
@@ -105,6 +131,37 @@ impl<T: Integer + Copy> Coordinate<T> {
     }
 }
 
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct Coordinate3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Integer + Copy> Coordinate3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn add(&self, x: T, y: T, z: T) -> Self {
+        Self::new(self.x + x, self.y + y, self.z + z)
+    }
+
+    // the same point seen from above
+    pub fn xy(&self) -> Coordinate<T> {
+        Coordinate::new(self.x, self.y)
+    }
+}
+
+impl Coordinate<i32> {
+    // every coordinate after this one in the direction, without end
+    pub fn ray(self, direction: Direction) -> impl Iterator<Item = Coordinate<i32>> {
+        let (x, y) = direction.get_modifier(1);
+
+        iter::successors(Some(self.add(x, y)), move |f| Some(f.add(x, y)))
+    }
+}
+
 pub fn get_column<T: Copy>(slice: &[Vec<T>], index: i32) -> Option<Vec<T>> {
     assert!(!slice.is_empty());
     let len = slice[0].len();
@@ -156,3 +213,57 @@ pub fn update_row<T: Copy>(map: &mut [Vec<T>], new: &[T], row_index: i32, should
 
     map[row_index as usize] = new;
 }
+
+// lines of each block of consecutive non blank lines, like the patterns of day 13
+pub fn blocks(input: &str) -> Vec<Vec<&str>> {
+    let mut result = vec![];
+    let mut block = vec![];
+
+    for line in input.lines() {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                result.push(std::mem::take(&mut block));
+            }
+        } else {
+            block.push(line);
+        }
+    }
+
+    if !block.is_empty() {
+        result.push(block);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_blocks() {
+        assert_eq!(
+            blocks("#.\n.#\n\n\n##\n  \n..\n"),
+            vec![vec!["#.", ".#"], vec!["##"], vec![".."]]
+        );
+        assert!(blocks("\n\n").is_empty());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_ray() {
+        let ray = Coordinate::new(2, 3).ray(Direction::Down).take(3);
+
+        assert_eq!(
+            ray.collect::<Vec<_>>(),
+            vec![
+                Coordinate::new(2, 2),
+                Coordinate::new(2, 1),
+                Coordinate::new(2, 0)
+            ]
+        );
+    }
+}
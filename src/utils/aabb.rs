@@ -0,0 +1,239 @@
+use super::{Coordinate, Coordinate3};
+
+// axis aligned box of whole cells, both corners inclusive like the bricks of day 22
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct Aabb3 {
+    min: Coordinate3<i64>,
+    max: Coordinate3<i64>,
+}
+
+impl Aabb3 {
+    // the corners can be given in any order
+    pub fn new(a: Coordinate3<i64>, b: Coordinate3<i64>) -> Self {
+        Self {
+            min: Coordinate3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Coordinate3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    pub fn min(&self) -> Coordinate3<i64> {
+        self.min
+    }
+
+    pub fn max(&self) -> Coordinate3<i64> {
+        self.max
+    }
+
+    // number of cells along each axis
+    pub fn size(&self) -> Coordinate3<i64> {
+        self.max
+            .add(1, 1, 1)
+            .add(-self.min.x, -self.min.y, -self.min.z)
+    }
+
+    pub fn volume(&self) -> i64 {
+        let size = self.size();
+        size.x * size.y * size.z
+    }
+
+    pub fn contains(&self, point: Coordinate3<i64>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    pub fn contains_box(&self, other: &Aabb3) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    // true if the boxes share at least one cell
+    pub fn intersects(&self, other: &Aabb3) -> bool {
+        self.overlaps_xy(other) && self.min.z <= other.max.z && other.min.z <= self.max.z
+    }
+
+    pub fn intersection(&self, other: &Aabb3) -> Option<Aabb3> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        Some(Aabb3 {
+            min: Coordinate3::new(
+                self.min.x.max(other.min.x),
+                self.min.y.max(other.min.y),
+                self.min.z.max(other.min.z),
+            ),
+            max: Coordinate3::new(
+                self.max.x.min(other.max.x),
+                self.max.y.min(other.max.y),
+                self.max.z.min(other.max.z),
+            ),
+        })
+    }
+
+    // true if one box would land on the other when moved along z
+    pub fn overlaps_xy(&self, other: &Aabb3) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    // true if the other box rests directly on top of this one
+    pub fn supports(&self, other: &Aabb3) -> bool {
+        other.min.z == self.max.z + 1 && self.overlaps_xy(other)
+    }
+
+    pub fn translate(&self, x: i64, y: i64, z: i64) -> Self {
+        Self {
+            min: self.min.add(x, y, z),
+            max: self.max.add(x, y, z),
+        }
+    }
+
+    // the same box moved along z so its lowest cells are at the given height
+    pub fn with_bottom(&self, z: i64) -> Self {
+        self.translate(0, 0, z - self.min.z)
+    }
+
+    // cells of the box seen from above
+    pub fn footprint(&self) -> impl Iterator<Item = Coordinate<i64>> {
+        let (min, max) = (self.min, self.max);
+
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Coordinate::new(x, y)))
+    }
+
+    // lets every box fall along z until it rests on the floor or on a box below it
+    // boxes are swept from the lowest bottom up so each one only has to look at settled boxes
+    // the result keeps the order of the input
+    pub fn settle(boxes: &[Aabb3], floor: i64) -> Vec<Aabb3> {
+        let mut order = (0..boxes.len()).collect::<Vec<_>>();
+        order.sort_by_key(|f| boxes[*f].min.z);
+
+        let mut result = boxes.to_vec();
+        let mut settled: Vec<Aabb3> = vec![];
+
+        for index in order {
+            let current = boxes[index];
+            let bottom = settled
+                .iter()
+                .filter(|f| f.overlaps_xy(&current))
+                .map(|f| f.max.z + 1)
+                .fold(floor, i64::max);
+
+            result[index] = current.with_bottom(bottom.min(current.min.z));
+            settled.push(result[index]);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    fn brick(a: (i64, i64, i64), b: (i64, i64, i64)) -> Aabb3 {
+        Aabb3::new(
+            Coordinate3::new(a.0, a.1, a.2),
+            Coordinate3::new(b.0, b.1, b.2),
+        )
+    }
+
+    fn any_box() -> impl Strategy<Value = Aabb3> {
+        let corner = (-5..5_i64, -5..5_i64, -5..5_i64);
+        (corner.clone(), corner).prop_map(|(a, b)| brick(a, b))
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_aabb() {
+        let a = brick((1, 0, 1), (1, 2, 1));
+        let b = brick((0, 0, 2), (2, 0, 2));
+        let c = brick((0, 0, 1), (2, 2, 3));
+
+        assert_eq!(a.volume(), 3);
+        assert_eq!(c.size(), Coordinate3::new(3, 3, 3));
+        assert_eq!(brick((2, 2, 3), (0, 0, 1)), c);
+
+        assert!(!a.intersects(&b));
+        assert!(a.overlaps_xy(&b));
+        assert!(a.supports(&b));
+        assert!(!b.supports(&a));
+
+        assert!(c.contains_box(&a));
+        assert!(!a.contains_box(&c));
+        assert_eq!(a.intersection(&c), Some(a));
+        assert_eq!(a.intersection(&b), None);
+
+        assert_eq!(b.with_bottom(5), brick((0, 0, 5), (2, 0, 5)));
+        assert_eq!(a.footprint().count(), 3);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_settle() {
+        // the first three bricks of the day 22 example
+        let bricks = [
+            brick((1, 0, 1), (1, 2, 1)),
+            brick((0, 0, 2), (2, 0, 2)),
+            brick((0, 2, 3), (2, 2, 3)),
+            brick((0, 0, 9), (0, 0, 9)),
+        ];
+
+        let settled = Aabb3::settle(&bricks, 1);
+        assert_eq!(
+            settled,
+            vec![
+                brick((1, 0, 1), (1, 2, 1)),
+                brick((0, 0, 2), (2, 0, 2)),
+                brick((0, 2, 2), (2, 2, 2)),
+                brick((0, 0, 3), (0, 0, 3)),
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_overlap_is_symmetric(a in any_box(), b in any_box()) {
+            prop_assert_eq!(a.intersects(&b), b.intersects(&a));
+            prop_assert_eq!(a.overlaps_xy(&b), b.overlaps_xy(&a));
+            prop_assert!(!a.intersects(&b) || a.overlaps_xy(&b));
+        }
+
+        #[test]
+        fn test_intersection_is_commutative(a in any_box(), b in any_box()) {
+            let intersection = a.intersection(&b);
+
+            prop_assert_eq!(intersection, b.intersection(&a));
+            prop_assert_eq!(intersection.is_some(), a.intersects(&b));
+
+            if let Some(intersection) = intersection {
+                prop_assert!(a.contains_box(&intersection));
+                prop_assert!(b.contains_box(&intersection));
+            }
+        }
+
+        #[test]
+        fn test_settle_keeps_boxes_apart(boxes in prop::collection::vec(any_box(), 0..8)) {
+            // settling only makes sense for boxes that do not already overlap
+            let boxes = boxes
+                .iter()
+                .enumerate()
+                .map(|(index, f)| f.translate(0, 0, index as i64 * 10))
+                .collect::<Vec<_>>();
+            let settled = Aabb3::settle(&boxes, -5);
+
+            for (index, (before, after)) in boxes.iter().zip(&settled).enumerate() {
+                prop_assert_eq!(before.volume(), after.volume());
+                prop_assert!(after.min().z <= before.min().z);
+
+                for other in &settled[index + 1..] {
+                    prop_assert!(!after.intersects(other));
+                }
+            }
+        }
+    }
+}
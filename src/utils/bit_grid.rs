@@ -0,0 +1,211 @@
+use super::Coordinate;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+// boolean grid stored as one bit per cell, each row starts on a fresh u64 word
+// bits past the width of a row are always clear so counts and set operations stay word wide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+
+        Self {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coordinate: Coordinate<i32>) -> bool {
+        coordinate.x >= 0
+            && coordinate.y >= 0
+            && (coordinate.x as usize) < self.width
+            && (coordinate.y as usize) < self.height
+    }
+
+    // word index and bit mask of a cell
+    fn position(&self, coordinate: Coordinate<i32>) -> Option<(usize, u64)> {
+        if !self.contains(coordinate) {
+            return None;
+        }
+
+        let x = coordinate.x as usize;
+        let index = coordinate.y as usize * self.words_per_row + x / WORD_BITS;
+
+        Some((index, 1 << (x % WORD_BITS)))
+    }
+
+    // cells outside the grid read as unset
+    pub fn get(&self, coordinate: Coordinate<i32>) -> bool {
+        self.position(coordinate)
+            .is_some_and(|(index, mask)| self.words[index] & mask != 0)
+    }
+
+    // returns true if the cell was not set before
+    pub fn insert(&mut self, coordinate: Coordinate<i32>) -> bool {
+        let (index, mask) = self.position(coordinate).expect("coordinate out of bounds");
+        let was_set = self.words[index] & mask != 0;
+        self.words[index] |= mask;
+
+        !was_set
+    }
+
+    // returns true if the cell was set before
+    pub fn remove(&mut self, coordinate: Coordinate<i32>) -> bool {
+        let (index, mask) = self.position(coordinate).expect("coordinate out of bounds");
+        let was_set = self.words[index] & mask != 0;
+        self.words[index] &= !mask;
+
+        was_set
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    // raw words of a row, bit i of word w is the cell at x = w * 64 + i
+    pub fn row(&self, y: usize) -> &[u64] {
+        assert!(y < self.height, "row out of bounds");
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    pub fn count_row(&self, y: usize) -> usize {
+        self.row(y).iter().map(|f| f.count_ones() as usize).sum()
+    }
+
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|f| f.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|f| *f == 0)
+    }
+
+    // keeps cells set in either grid, both grids must have the same size
+    pub fn union_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word |= other;
+        }
+    }
+
+    // keeps cells set in both grids, both grids must have the same size
+    pub fn intersect_with(&mut self, other: &BitGrid) {
+        self.assert_same_size(other);
+
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= other;
+        }
+    }
+
+    fn assert_same_size(&self, other: &BitGrid) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "bit grids have different sizes"
+        );
+    }
+
+    // set cells in row order
+    pub fn iter(&self) -> impl Iterator<Item = Coordinate<i32>> + '_ {
+        (0..self.height).flat_map(move |y| {
+            self.row(y)
+                .iter()
+                .enumerate()
+                .flat_map(move |(word_index, word)| {
+                    let mut bits = *word;
+
+                    std::iter::from_fn(move || {
+                        if bits == 0 {
+                            return None;
+                        }
+
+                        let bit = bits.trailing_zeros() as usize;
+                        bits &= bits - 1;
+
+                        Some(Coordinate::new(
+                            (word_index * WORD_BITS + bit) as i32,
+                            y as i32,
+                        ))
+                    })
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_bit_grid() {
+        let mut grid = BitGrid::new(70, 3);
+
+        assert!(grid.is_empty());
+        assert!(grid.insert(Coordinate::new(0, 0)));
+        assert!(grid.insert(Coordinate::new(69, 0)));
+        assert!(!grid.insert(Coordinate::new(69, 0)));
+        assert!(grid.insert(Coordinate::new(64, 2)));
+
+        assert!(grid.get(Coordinate::new(69, 0)));
+        assert!(!grid.get(Coordinate::new(70, 0)));
+        assert!(!grid.get(Coordinate::new(0, -1)));
+        assert_eq!(grid.row(0), &[1, 1 << 5]);
+        assert_eq!(grid.count_row(0), 2);
+        assert_eq!(grid.count_row(1), 0);
+        assert_eq!(grid.count(), 3);
+        assert_eq!(
+            grid.iter().collect::<Vec<_>>(),
+            vec![
+                Coordinate::new(0, 0),
+                Coordinate::new(69, 0),
+                Coordinate::new(64, 2)
+            ]
+        );
+
+        assert!(grid.remove(Coordinate::new(0, 0)));
+        assert!(!grid.remove(Coordinate::new(0, 0)));
+        assert_eq!(grid.count(), 2);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_bit_grid_set_operations() {
+        let mut left = BitGrid::new(4, 1);
+        let mut right = BitGrid::new(4, 1);
+        left.insert(Coordinate::new(0, 0));
+        left.insert(Coordinate::new(1, 0));
+        right.insert(Coordinate::new(1, 0));
+        right.insert(Coordinate::new(3, 0));
+
+        let mut union = left.clone();
+        union.union_with(&right);
+        assert_eq!(union.row(0), &[0b1011]);
+
+        left.intersect_with(&right);
+        assert_eq!(left.row(0), &[0b0010]);
+
+        left.clear();
+        assert!(left.is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    cell::Cell,
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+// which characters grids, routes and tables are drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Charset {
+    // box drawing characters, middle dots and arrows
+    #[default]
+    Unicode,
+    // plain ascii for terminals and log collectors that garble anything else
+    Ascii,
+}
+
+impl FromStr for Charset {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "unicode" => Ok(Self::Unicode),
+            "ascii" => Ok(Self::Ascii),
+            _ => Err(format!("unknown charset {:?}, use unicode or ascii", input)),
+        }
+    }
+}
+
+// set once from the command line
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    // overrides the global charset for one render, without affecting other threads
+    static SCOPED: Cell<Option<Charset>> = const { Cell::new(None) };
+}
+
+pub fn set(charset: Charset) {
+    ASCII.store(charset == Charset::Ascii, Ordering::Relaxed);
+}
+
+pub fn current() -> Charset {
+    SCOPED.get().unwrap_or(match ASCII.load(Ordering::Relaxed) {
+        true => Charset::Ascii,
+        false => Charset::Unicode,
+    })
+}
+
+// renders with the charset on this thread only, e.g. a test that wants ascii output
+pub fn scoped<R>(charset: Charset, render: impl FnOnce() -> R) -> R {
+    let previous = SCOPED.replace(Some(charset));
+    let result = render();
+    SCOPED.set(previous);
+
+    result
+}
+
+// the glyph for the current charset, every non ascii glyph needs an ascii stand-in
+pub fn glyph(unicode: &'static str, ascii: &'static str) -> &'static str {
+    match current() {
+        Charset::Unicode => unicode,
+        Charset::Ascii => ascii,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_scoped() {
+        assert_eq!("ascii".parse(), Ok(Charset::Ascii));
+        assert!("utf8".parse::<Charset>().is_err());
+
+        assert_eq!(glyph("·", "."), "·");
+        assert_eq!(scoped(Charset::Ascii, || glyph("·", ".")), ".");
+
+        let nested = scoped(Charset::Ascii, || {
+            scoped(Charset::Unicode, || glyph("·", ".")).to_string() + glyph("·", ".")
+        });
+        assert_eq!(nested, "·.");
+        assert_eq!(current(), Charset::Unicode);
+    }
+}
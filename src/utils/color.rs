@@ -0,0 +1,57 @@
+use std::{
+    cell::Cell,
+    env,
+    fmt::Display,
+    io::{self, IsTerminal},
+    sync::OnceLock,
+};
+
+thread_local! {
+    // overrides the detection for one render, without affecting other threads
+    static SCOPED: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+// ansi colors only when the log goes to a terminal, and NO_COLOR is not set, see no-color.org
+pub fn enabled() -> bool {
+    static DETECTED: OnceLock<bool> = OnceLock::new();
+
+    SCOPED.get().unwrap_or_else(|| {
+        *DETECTED.get_or_init(|| io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none())
+    })
+}
+
+// renders with or without colors on this thread only, e.g. a test that checks the escape codes
+pub fn scoped<R>(enabled: bool, render: impl FnOnce() -> R) -> R {
+    let previous = SCOPED.replace(Some(enabled));
+    let result = render();
+    SCOPED.set(previous);
+
+    result
+}
+
+// the text in an sgr code like 33 or "1;38;5;196", plain text when colors are off
+pub fn paint(text: &str, code: impl Display) -> String {
+    match enabled() {
+        true => format!("\x1b[{}m{}\x1b[0m", code, text),
+        false => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_paint() {
+        assert_eq!(scoped(true, || paint("#", 33)), "\x1b[33m#\x1b[0m");
+        assert_eq!(scoped(false, || paint("#", "1;38;5;196")), "#");
+
+        let nested = scoped(false, || {
+            scoped(true, || paint("<", 2)) + paint(">", 2).as_str()
+        });
+        assert_eq!(nested, "\x1b[2m<\x1b[0m>");
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        OnceLock,
+    },
+};
+
+use tracing::warn;
+
+// set once from the command line, days only pay for rendering frames when this is set
+static DIRECTORY: OnceLock<PathBuf> = OnceLock::new();
+static NEXT_FRAME: AtomicUsize = AtomicUsize::new(0);
+
+pub fn enable(directory: impl Into<PathBuf>) -> io::Result<()> {
+    let directory = directory.into();
+    fs::create_dir_all(&directory)?;
+
+    if DIRECTORY.set(directory).is_err() {
+        warn!("dump directory is already set, keeping the first one");
+    }
+
+    Ok(())
+}
+
+pub fn is_enabled() -> bool {
+    DIRECTORY.get().is_some()
+}
+
+// writes the next numbered frame, render is only called when dumping is enabled
+// a failed write is logged instead of aborting the solve
+pub fn frame(name: &str, render: impl FnOnce() -> String) {
+    let Some(directory) = DIRECTORY.get() else {
+        return;
+    };
+
+    let index = NEXT_FRAME.fetch_add(1, Ordering::Relaxed);
+
+    if let Err(error) = write_frame(directory, index, name, &render()) {
+        warn!("failed to dump frame {} ({}): {}", index, name, error);
+    }
+}
+
+fn write_frame(directory: &Path, index: usize, name: &str, content: &str) -> io::Result<PathBuf> {
+    // numbered first so the files sort in the order they were written
+    let path = directory.join(format!("{:0>6}-{}.txt", index, name));
+    fs::write(&path, content)?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_write_frame() -> io::Result<()> {
+        let directory = std::env::temp_dir().join(format!("aoc-dump-{}", std::process::id()));
+        fs::create_dir_all(&directory)?;
+
+        let path = write_frame(&directory, 7, "day14-north", "O.#\n")?;
+
+        assert_eq!(path.file_name().unwrap(), "000007-day14-north.txt");
+        assert_eq!(fs::read_to_string(&path)?, "O.#\n");
+
+        fs::remove_dir_all(&directory)?;
+
+        Ok(())
+    }
+}
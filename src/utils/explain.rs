@@ -0,0 +1,21 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tracing::info;
+
+// set once from the command line, days only pay for building explanations when this is set
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// logs a human readable step of the solution, render is only called when explaining is enabled
+pub fn line(render: impl FnOnce() -> String) {
+    if is_enabled() {
+        info!("{}", render());
+    }
+}
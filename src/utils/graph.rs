@@ -0,0 +1,518 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use super::PriorityQueue;
+
+// directed graph stored as adjacency lists, nodes are addressed by the index they were added at
+// undirected graphs store every edge both ways, edges carry a weight like a corridor length
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    edges: Vec<Vec<(usize, i64)>>,
+}
+
+impl<N> Default for Graph<N> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            indices: HashMap::new(),
+            edges: vec![],
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone> Graph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // every edge has a weight of 1
+    pub fn from_edges(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Self::new();
+        for (from, to) in edges {
+            graph.add_edge(from, to, 1);
+        }
+        graph
+    }
+
+    pub fn from_undirected_edges(edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Self::new();
+        for (a, b) in edges {
+            graph.add_undirected_edge(a, b, 1);
+        }
+        graph
+    }
+
+    // returns the index of the node, adding it if it is new
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.indices.get(&node) {
+            return index;
+        }
+
+        let index = self.nodes.len();
+        self.indices.insert(node.clone(), index);
+        self.nodes.push(node);
+        self.edges.push(vec![]);
+
+        index
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, weight: i64) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.edges[from].push((to, weight));
+    }
+
+    pub fn add_undirected_edge(&mut self, a: N, b: N, weight: i64) {
+        self.add_edge(a.clone(), b.clone(), weight);
+        self.add_edge(b, a, weight);
+    }
+
+    pub fn index(&self, node: &N) -> Option<usize> {
+        self.indices.get(node).copied()
+    }
+}
+
+impl<N> Graph<N> {
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, index: usize) -> &N {
+        &self.nodes[index]
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    // outgoing edges as (target, weight)
+    pub fn neighbors(&self, index: usize) -> &[(usize, i64)] {
+        &self.edges[index]
+    }
+
+    pub fn degree(&self, index: usize) -> usize {
+        self.edges[index].len()
+    }
+
+    // every edge as (from, to, weight), undirected edges show up once per direction
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, i64)> + '_ {
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |&(to, weight)| (from, to, weight)))
+    }
+
+    // tarjan's algorithm, components come out in reverse topological order
+    // the depth first search keeps its own stack so long chains cannot overflow the real one
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let mut index_of = vec![None; self.len()];
+        let mut low_link = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut next_index = 0;
+
+        for root in 0..self.len() {
+            if index_of[root].is_some() {
+                continue;
+            }
+
+            // (node, next edge to look at)
+            let mut search = vec![(root, 0)];
+            index_of[root] = Some(next_index);
+            low_link[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, edge)) = search.last_mut() {
+                let node = *node;
+
+                if let Some(&(next, _)) = self.edges[node].get(*edge) {
+                    *edge += 1;
+
+                    match index_of[next] {
+                        None => {
+                            index_of[next] = Some(next_index);
+                            low_link[next] = next_index;
+                            next_index += 1;
+                            stack.push(next);
+                            on_stack[next] = true;
+                            search.push((next, 0));
+                        }
+                        Some(found) if on_stack[next] => {
+                            low_link[node] = low_link[node].min(found);
+                        }
+                        Some(_) => {}
+                    }
+
+                    continue;
+                }
+
+                search.pop();
+                if let Some(&(parent, _)) = search.last() {
+                    low_link[parent] = low_link[parent].min(low_link[node]);
+                }
+
+                if Some(low_link[node]) == index_of[node] {
+                    let mut component = vec![];
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    // kahn's algorithm, None if the graph has a cycle
+    // ties are broken by index so the order is stable
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
+        let mut incoming = vec![0; self.len()];
+        for (_, to, _) in self.edges() {
+            incoming[to] += 1;
+        }
+
+        let mut queue = (0..self.len())
+            .filter(|f| incoming[*f] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = vec![];
+
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+
+            for &(next, _) in &self.edges[node] {
+                incoming[next] -= 1;
+                if incoming[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        (order.len() == self.len()).then_some(order)
+    }
+
+    // stoer-wagner on the graph as undirected, None with fewer than two nodes
+    // each phase grows a maximum adjacency order and merges its last two nodes,
+    // the lightest cut between the last node and the rest over all phases is the minimum cut
+    pub fn min_cut(&self) -> Option<MinCut> {
+        if self.len() < 2 {
+            return None;
+        }
+
+        let mut directed = HashMap::<(usize, usize), i64>::new();
+        for (from, to, weight) in self.edges().filter(|(from, to, _)| from != to) {
+            *directed.entry((from, to)).or_default() += weight;
+        }
+
+        // an edge stored one way counts as undirected all the same, a pair stored both ways like
+        // add_undirected_edge does is a single edge, so the two directions are halved
+        let mut adjacency = vec![HashMap::<usize, i64>::new(); self.len()];
+        for (&(from, to), &weight) in &directed {
+            let weight = match directed.get(&(to, from)) {
+                Some(_) if from > to => continue,
+                Some(back) => (weight + back) / 2,
+                None => weight,
+            };
+            adjacency[from].insert(to, weight);
+            adjacency[to].insert(from, weight);
+        }
+
+        let mut members = (0..self.len()).map(|f| vec![f]).collect::<Vec<_>>();
+        let mut active = (0..self.len()).collect::<Vec<_>>();
+        let mut best: Option<MinCut> = None;
+
+        while active.len() > 1 {
+            let mut queue = PriorityQueue::new();
+            let mut connection = vec![0; self.len()];
+            let mut added = vec![false; self.len()];
+            for &node in &active {
+                queue.push(node, 0);
+            }
+
+            let (mut previous, mut last) = (active[0], active[0]);
+            while let Some((node, _)) = queue.pop() {
+                added[node] = true;
+                (previous, last) = (last, node);
+
+                for (&next, &weight) in &adjacency[node] {
+                    if !added[next] {
+                        connection[next] += weight;
+                        // the queue pops the lowest priority first
+                        queue.push(next, -connection[next]);
+                    }
+                }
+            }
+
+            if best.as_ref().is_none_or(|f| connection[last] < f.weight) {
+                best = Some(MinCut {
+                    weight: connection[last],
+                    partition: members[last].clone(),
+                });
+            }
+
+            // merge the last node into the one before it
+            for (next, weight) in std::mem::take(&mut adjacency[last]) {
+                adjacency[next].remove(&last);
+                if next != previous {
+                    *adjacency[previous].entry(next).or_default() += weight;
+                    *adjacency[next].entry(previous).or_default() += weight;
+                }
+            }
+
+            let moved = std::mem::take(&mut members[last]);
+            members[previous].extend(moved);
+            active.retain(|f| *f != last);
+        }
+
+        best.map(|mut f| {
+            f.partition.sort();
+            f
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+    // total weight of the edges crossing the cut
+    pub weight: i64,
+    // node indices on one side of the cut, every other node is on the other side
+    pub partition: Vec<usize>,
+}
+
+impl<N: Hash + Eq + Clone> Graph<N> {
+    // removes every node with exactly two edges, joining its neighbors with the summed weight
+    // meant for undirected graphs like a maze of corridors, nodes for which keep is true always stay
+    // junctions keep their order, so index 0 of the result is the first junction of this graph
+    pub fn contract(&self, keep: impl Fn(usize) -> bool) -> Graph<N> {
+        let is_junction = |index: usize| self.degree(index) != 2 || keep(index);
+        let mut result = Graph::new();
+
+        for junction in (0..self.len()).filter(|f| is_junction(*f)) {
+            result.add_node(self.nodes[junction].clone());
+        }
+
+        for junction in (0..self.len()).filter(|f| is_junction(*f)) {
+            for &(first, weight) in &self.edges[junction] {
+                let (mut previous, mut current, mut total) = (junction, first, weight);
+                let mut steps = 0;
+
+                // a ring without any junction on it would walk forever
+                while !is_junction(current) && steps < self.len() {
+                    let Some(&(next, weight)) =
+                        self.edges[current].iter().find(|(f, _)| *f != previous)
+                    else {
+                        break;
+                    };
+
+                    (previous, current) = (current, next);
+                    total += weight;
+                    steps += 1;
+                }
+
+                if is_junction(current) {
+                    result.add_edge(
+                        self.nodes[junction].clone(),
+                        self.nodes[current].clone(),
+                        total,
+                    );
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_graph() {
+        let graph = Graph::from_edges([("a", "b"), ("b", "c"), ("a", "c")]);
+
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.index(&"c"), Some(2));
+        assert_eq!(graph.index(&"d"), None);
+        assert_eq!(graph.node(1), &"b");
+        assert_eq!(graph.neighbors(0), &[(1, 1), (2, 1)]);
+        assert_eq!(graph.degree(2), 0);
+
+        let graph = Graph::from_undirected_edges([("a", "b")]);
+        assert_eq!(
+            graph.edges().collect::<Vec<_>>(),
+            vec![(0, 1, 1), (1, 0, 1)]
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_strongly_connected_components() {
+        let graph = Graph::from_edges([(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (4, 5)]);
+
+        let mut components = graph.strongly_connected_components();
+        assert_eq!(components.len(), 3);
+
+        // sinks first
+        assert_eq!(components[0], vec![5]);
+
+        for component in &mut components {
+            component.sort();
+        }
+        assert_eq!(components, vec![vec![5], vec![3, 4], vec![0, 1, 2]]);
+
+        // a long chain does not recurse
+        let chain = Graph::from_edges((0..100_000).map(|f| (f, f + 1)));
+        assert_eq!(chain.strongly_connected_components().len(), 100_001);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_topological_sort() {
+        let graph = Graph::from_edges([("shirt", "tie"), ("tie", "jacket"), ("pants", "shoes")]);
+        let order = graph.topological_sort().unwrap();
+        let position = |name| order.iter().position(|f| graph.node(*f) == &name).unwrap();
+
+        assert!(position("shirt") < position("tie"));
+        assert!(position("tie") < position("jacket"));
+        assert!(position("pants") < position("shoes"));
+
+        let cycle = Graph::from_edges([(1, 2), (2, 1)]);
+        assert_eq!(cycle.topological_sort(), None);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_contract() {
+        // start - a - b - junction - c - end, with a dead end d off the junction
+        let graph = Graph::from_undirected_edges([
+            ("start", "a"),
+            ("a", "b"),
+            ("b", "junction"),
+            ("junction", "c"),
+            ("c", "end"),
+            ("junction", "d"),
+        ]);
+
+        let contracted = graph.contract(|_| false);
+        let edge = |from: &str, to: &str| {
+            let from = contracted.index(&from).unwrap();
+            let to = contracted.index(&to).unwrap();
+            contracted
+                .neighbors(from)
+                .iter()
+                .find(|(f, _)| *f == to)
+                .map(|(_, weight)| *weight)
+        };
+
+        assert_eq!(contracted.len(), 4);
+        assert_eq!(contracted.node(0), &"start");
+        assert_eq!(edge("start", "junction"), Some(3));
+        assert_eq!(edge("junction", "start"), Some(3));
+        assert_eq!(edge("junction", "end"), Some(2));
+        assert_eq!(edge("junction", "d"), Some(1));
+        assert_eq!(edge("start", "end"), None);
+
+        // kept nodes are never contracted away
+        let keep = graph.index(&"b").unwrap();
+        let contracted = graph.contract(|f| f == keep);
+        assert_eq!(contracted.len(), 5);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_min_cut() {
+        // the example from the stoer-wagner paper
+        let mut graph = Graph::new();
+        for (a, b, weight) in [
+            (1, 2, 2),
+            (1, 5, 3),
+            (2, 3, 3),
+            (2, 5, 2),
+            (2, 6, 2),
+            (3, 4, 4),
+            (3, 7, 2),
+            (4, 7, 2),
+            (4, 8, 2),
+            (5, 6, 3),
+            (6, 7, 1),
+            (7, 8, 3),
+        ] {
+            graph.add_undirected_edge(a, b, weight);
+        }
+
+        let cut = graph.min_cut().unwrap();
+        let mut side = cut
+            .partition
+            .iter()
+            .map(|f| *graph.node(*f))
+            .collect::<Vec<_>>();
+        side.sort();
+        if side[0] == 1 {
+            side = (1..=8).filter(|f| !side.contains(f)).collect();
+        }
+
+        assert_eq!(cut.weight, 4);
+        assert_eq!(side, vec![3, 4, 7, 8]);
+
+        // two triangles joined by a single edge
+        let graph = Graph::from_undirected_edges([
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "d"),
+            ("d", "e"),
+            ("e", "f"),
+            ("f", "d"),
+        ]);
+        let cut = graph.min_cut().unwrap();
+        assert_eq!(cut.weight, 1);
+        assert_eq!(cut.partition.len(), 3);
+
+        // the same triangles with every edge stored one way only, some of them against the others
+        let graph = Graph::from_edges([
+            ("a", "b"),
+            ("c", "b"),
+            ("c", "a"),
+            ("d", "c"),
+            ("d", "e"),
+            ("f", "e"),
+            ("f", "d"),
+        ]);
+        let cut = graph.min_cut().unwrap();
+        assert_eq!(cut.weight, 1);
+        assert_eq!(cut.partition.len(), 3);
+
+        // both ways and one way mixed, the heavy pair has to stay together
+        let mut mixed = Graph::new();
+        mixed.add_undirected_edge(1, 2, 5);
+        mixed.add_edge(2, 3, 2);
+        mixed.add_edge(3, 1, 2);
+        assert_eq!(mixed.min_cut().unwrap().weight, 4);
+
+        let disconnected = Graph::from_undirected_edges([(1, 2), (3, 4)]);
+        assert_eq!(disconnected.min_cut().unwrap().weight, 0);
+
+        let mut single = Graph::new();
+        single.add_node(1);
+        assert_eq!(single.min_cut(), None);
+    }
+}
@@ -0,0 +1,308 @@
+use std::fmt::{self, Display};
+
+use tracing::info;
+
+use super::{Coordinate, Direction};
+
+// cells that can be rendered as part of a grid
+// Display implementations should write UNKNOWN for values without a glyph instead of panicking,
+// and pick non ascii glyphs through charset::glyph so --charset ascii applies to them
+pub trait GridCell: Display {
+    const UNKNOWN: &'static str = "?";
+}
+
+// dense grid stored row by row, row 0 is the first line of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map(|f| f.len()).unwrap_or(0);
+        assert!(rows.iter().all(|f| f.len() == width));
+
+        Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coordinate: Coordinate<i32>) -> bool {
+        coordinate.x >= 0
+            && coordinate.y >= 0
+            && (coordinate.x as usize) < self.width
+            && (coordinate.y as usize) < self.height
+    }
+
+    fn index(&self, coordinate: Coordinate<i32>) -> Option<usize> {
+        if self.contains(coordinate) {
+            Some(coordinate.y as usize * self.width + coordinate.x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, coordinate: Coordinate<i32>) -> Option<&T> {
+        self.index(coordinate).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, coordinate: Coordinate<i32>) -> Option<&mut T> {
+        self.index(coordinate).map(|index| &mut self.cells[index])
+    }
+
+    pub fn set(&mut self, coordinate: Coordinate<i32>, value: T) {
+        let index = self.index(coordinate).expect("coordinate out of bounds");
+        self.cells[index] = value;
+    }
+
+    // cells after the coordinate in the direction until the edge, Up and North go towards larger y
+    pub fn walk_from(
+        &self,
+        coordinate: Coordinate<i32>,
+        direction: Direction,
+    ) -> impl Iterator<Item = (Coordinate<i32>, &T)> {
+        coordinate
+            .ray(direction)
+            .map_while(|f| self.get(f).map(|cell| (f, cell)))
+    }
+
+    // every cell in reading order
+    pub fn cells(&self) -> impl Iterator<Item = (Coordinate<i32>, &T)> {
+        let width = self.width.max(1);
+
+        self.cells.iter().enumerate().map(move |(index, cell)| {
+            let coordinate = Coordinate::new((index % width) as i32, (index / width) as i32);
+            (coordinate, cell)
+        })
+    }
+
+    // cells at most radius steps away, diagonals included, in reading order and without the center
+    // radius 1 is the 3x3 kernel, radius k the (2k + 1)x(2k + 1) one
+    pub fn window(
+        &self,
+        coordinate: Coordinate<i32>,
+        radius: i32,
+    ) -> impl Iterator<Item = (Coordinate<i32>, &T)> {
+        (-radius..=radius)
+            .flat_map(move |y| (-radius..=radius).map(move |x| (x, y)))
+            .filter(|&(x, y)| (x, y) != (0, 0))
+            .filter_map(move |(x, y)| {
+                let neighbor = coordinate.add(x, y);
+                self.get(neighbor).map(|cell| (neighbor, cell))
+            })
+    }
+
+    // the 8 surrounding cells, fewer at the edges
+    pub fn neighbors(
+        &self,
+        coordinate: Coordinate<i32>,
+    ) -> impl Iterator<Item = (Coordinate<i32>, &T)> {
+        self.window(coordinate, 1)
+    }
+
+    // every cell together with its window, for convolution style scans
+    pub fn windows(
+        &self,
+        radius: i32,
+    ) -> impl Iterator<
+        Item = (
+            Coordinate<i32>,
+            &T,
+            impl Iterator<Item = (Coordinate<i32>, &T)>,
+        ),
+    > {
+        self.cells()
+            .map(move |(coordinate, cell)| (coordinate, cell, self.window(coordinate, radius)))
+    }
+
+    // every cell together with its 8-neighborhood
+    pub fn neighborhoods(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            Coordinate<i32>,
+            &T,
+            impl Iterator<Item = (Coordinate<i32>, &T)>,
+        ),
+    > {
+        self.windows(1)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    // one line per row with each cell drawn by glyph, for cells drawn differently than their
+    // Display, like a path over a map
+    pub fn render(&self, glyph: impl Fn(Coordinate<i32>, &T) -> String) -> String {
+        let mut text = String::new();
+
+        for (coordinate, cell) in self.cells() {
+            text.push_str(&glyph(coordinate, cell));
+            if coordinate.x as usize + 1 == self.width {
+                text.push('\n');
+            }
+        }
+
+        text
+    }
+}
+
+impl<T: GridCell> Grid<T> {
+    pub fn display(&self) {
+        info!("\n{}", self);
+    }
+}
+
+impl<T: GridCell> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(|_, cell| cell.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Cell {
+        Wall,
+        Floor,
+        Other(char),
+    }
+
+    impl Display for Cell {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let text = match self {
+                Cell::Wall => "#",
+                Cell::Floor => ".",
+                Cell::Other(_) => Self::UNKNOWN,
+            };
+
+            write!(f, "{}", text)
+        }
+    }
+
+    impl GridCell for Cell {}
+
+    #[traced_test]
+    #[test]
+    fn test_grid_access() {
+        let mut grid = Grid::new(3, 2, Cell::Floor);
+
+        grid.set(Coordinate::new(2, 1), Cell::Wall);
+        *grid.get_mut(Coordinate::new(0, 0)).unwrap() = Cell::Other('x');
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(Coordinate::new(2, 1)), Some(&Cell::Wall));
+        assert_eq!(grid.get(Coordinate::new(3, 1)), None);
+        assert_eq!(grid.get(Coordinate::new(0, -1)), None);
+        assert_eq!(grid.to_string(), "?..\n..#\n");
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_walk_from() {
+        let mut grid = Grid::new(4, 3, Cell::Floor);
+        grid.set(Coordinate::new(3, 1), Cell::Wall);
+
+        let walked = grid
+            .walk_from(Coordinate::new(0, 1), Direction::Right)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            walked,
+            vec![
+                (Coordinate::new(1, 1), &Cell::Floor),
+                (Coordinate::new(2, 1), &Cell::Floor),
+                (Coordinate::new(3, 1), &Cell::Wall)
+            ]
+        );
+
+        // line of sight from outside the grid
+        let first_wall = grid
+            .walk_from(Coordinate::new(4, 1), Direction::Left)
+            .find(|(_, cell)| **cell == Cell::Wall);
+        assert_eq!(first_wall.map(|f| f.0), Some(Coordinate::new(3, 1)));
+
+        assert_eq!(
+            grid.walk_from(Coordinate::new(0, 2), Direction::Up).count(),
+            0
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_neighborhoods() {
+        let grid = Grid::from_rows(vec![
+            vec![1, 2, 3, 4],
+            vec![5, 6, 7, 8],
+            vec![9, 10, 11, 12],
+        ]);
+
+        let values = |cells: Vec<(Coordinate<i32>, &i32)>| {
+            cells.into_iter().map(|f| *f.1).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            values(grid.neighbors(Coordinate::new(1, 1)).collect()),
+            vec![1, 2, 3, 5, 7, 9, 10, 11]
+        );
+        assert_eq!(
+            values(grid.neighbors(Coordinate::new(0, 0)).collect()),
+            vec![2, 5, 6]
+        );
+        assert_eq!(grid.window(Coordinate::new(1, 1), 2).count(), 11);
+
+        // sum of each 3x3 kernel, the center included
+        let sums = grid
+            .neighborhoods()
+            .map(|(_, cell, neighbors)| cell + neighbors.map(|f| f.1).sum::<i32>())
+            .collect::<Vec<_>>();
+        assert_eq!(sums, vec![14, 24, 30, 22, 33, 54, 63, 45, 30, 48, 54, 38]);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_grid_from_rows() {
+        let grid = Grid::from_rows(vec![
+            vec![Cell::Wall, Cell::Floor],
+            vec![Cell::Floor, Cell::Wall],
+        ]);
+
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.to_string(), "#.\n.#\n");
+
+        let diagonal = grid.render(|coordinate, cell| match coordinate.x == coordinate.y {
+            true => "\\".to_string(),
+            false => cell.to_string(),
+        });
+        assert_eq!(diagonal, "\\.\n.\\\n");
+    }
+}
@@ -0,0 +1,88 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use color_eyre::eyre::{bail, Result};
+
+use crate::error::{Failure, FailureKind};
+
+// set once from the command line, 0 means no limit
+static MAX_STEPS: AtomicU64 = AtomicU64::new(0);
+
+pub fn set_max_steps(max_steps: u64) {
+    MAX_STEPS.store(max_steps, Ordering::Relaxed);
+}
+
+pub fn max_steps() -> Option<u64> {
+    match MAX_STEPS.load(Ordering::Relaxed) {
+        0 => None,
+        max_steps => Some(max_steps),
+    }
+}
+
+// counts the iterations of one hot loop and fails once they exceed the step budget
+#[derive(Debug)]
+pub struct Guard {
+    name: &'static str,
+    steps: u64,
+    limit: Option<u64>,
+}
+
+impl Guard {
+    // the name shows up in the error, like "day 16 beam queue"
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            steps: 0,
+            limit: max_steps(),
+        }
+    }
+
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn step(&mut self) -> Result<()> {
+        self.steps += 1;
+
+        if let Some(limit) = self.limit {
+            if self.steps > limit {
+                bail!(Failure::new(
+                    FailureKind::Timeout,
+                    format!(
+                        "{} exceeded the step budget of {} (--max-steps), the input may be malformed",
+                        self.name, limit
+                    )
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_guard() {
+        let mut guard = Guard::new("day 16 beam queue").with_limit(2);
+
+        assert!(guard.step().is_ok());
+        assert!(guard.step().is_ok());
+
+        let error = guard.step().unwrap_err();
+        assert_eq!(guard.steps(), 3);
+        assert_eq!(
+            error.to_string(),
+            "day 16 beam queue exceeded the step budget of 2 (--max-steps), the input may be malformed"
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use super::{BitGrid, Coordinate};
+
+// the letters of the 6 pixel high font puzzles draw their answers in, 4 wide except for Y
+const HEIGHT: usize = 6;
+
+const LETTERS: [(char, [&str; HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+// lit pixels as # and dark ones as ., one line per row, row 0 first
+pub fn render(grid: &BitGrid) -> String {
+    let mut text = String::new();
+
+    for y in 0..grid.height() as i32 {
+        for x in 0..grid.width() as i32 {
+            text.push(if grid.get(Coordinate::new(x, y)) {
+                '#'
+            } else {
+                '.'
+            });
+        }
+        text.push('\n');
+    }
+
+    text
+}
+
+// the opposite of render, anything but # is dark
+pub fn from_drawing(drawing: &str) -> BitGrid {
+    let rows = drawing
+        .lines()
+        .filter(|f| !f.is_empty())
+        .collect::<Vec<_>>();
+    let width = rows.iter().map(|f| f.len()).max().unwrap_or(0);
+    let mut grid = BitGrid::new(width, rows.len());
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            if c == '#' {
+                grid.insert(Coordinate::new(x as i32, y as i32));
+            }
+        }
+    }
+
+    grid
+}
+
+// letters are told apart by their pixels without the blank columns around them, so the font can be
+// drawn with any spacing and letters of different widths need no special casing
+fn glyph(grid: &BitGrid, top: i32, columns: std::ops::Range<i32>) -> String {
+    (top..top + HEIGHT as i32)
+        .map(|y| {
+            columns
+                .clone()
+                .map(|x| match grid.get(Coordinate::new(x, y)) {
+                    true => '#',
+                    false => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn letter(pixels: &str) -> Option<char> {
+    LETTERS.iter().find_map(|(letter, rows)| {
+        let drawing = from_drawing(&rows.join("\n"));
+        let width = drawing.width() as i32;
+        let first =
+            (0..width).find(|&x| (0..HEIGHT as i32).any(|y| drawing.get(Coordinate::new(x, y))))?;
+        let last = (0..width)
+            .rev()
+            .find(|&x| (0..HEIGHT as i32).any(|y| drawing.get(Coordinate::new(x, y))))?;
+
+        (glyph(&drawing, 0, first..last + 1) == pixels).then_some(*letter)
+    })
+}
+
+// reads the letters off a drawing, None if it is not 6 pixels high once blank rows are dropped or
+// has a letter the font does not know
+pub fn recognize(grid: &BitGrid) -> Option<String> {
+    let lit_row = |y: i32| (0..grid.width() as i32).any(|x| grid.get(Coordinate::new(x, y)));
+    let top = (0..grid.height() as i32).find(|&y| lit_row(y))?;
+    let bottom = (0..grid.height() as i32).rev().find(|&y| lit_row(y))?;
+
+    if (bottom - top + 1) as usize != HEIGHT {
+        return None;
+    }
+
+    let lit_column = |x: i32| (top..=bottom).any(|y| grid.get(Coordinate::new(x, y)));
+    let mut text = String::new();
+    let mut x = 0;
+
+    while x < grid.width() as i32 {
+        if !lit_column(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < grid.width() as i32 && lit_column(x) {
+            x += 1;
+        }
+
+        text.push(letter(&glyph(grid, top, start..x))?);
+    }
+
+    Some(text)
+}
+
+// the answer of a part that is drawn, the drawing itself when the letters can not be read
+pub fn letters(grid: &BitGrid) -> String {
+    recognize(grid).unwrap_or_else(|| format!("\n{}", render(grid)))
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_recognize() {
+        let drawing = "\
+#..#.####.#....#.....##..#...#
+#..#.#....#....#....#..#.#...#
+####.###..#....#....#..#..#.#.
+#..#.#....#....#....#..#...#..
+#..#.#....#....#....#..#...#..
+#..#.####.####.####..##....#..
+";
+        let grid = from_drawing(drawing);
+
+        assert_eq!(render(&grid), drawing);
+        assert_eq!(recognize(&grid), Some("HELLOY".to_string()));
+        assert_eq!(letters(&grid), "HELLOY");
+
+        // blank rows around the letters are fine, a letter outside the font is not
+        let padded = from_drawing(&format!("......\n{}......\n", drawing));
+        assert_eq!(recognize(&padded), Some("HELLOY".to_string()));
+
+        let unknown = from_drawing("#.#\n.#.\n#.#\n.#.\n#.#\n.#.\n");
+        assert_eq!(recognize(&unknown), None);
+        assert_eq!(letters(&unknown), "\n#.#\n.#.\n#.#\n.#.\n#.#\n.#.\n");
+
+        assert_eq!(recognize(&from_drawing("##\n##\n")), None);
+        assert_eq!(recognize(&BitGrid::new(4, 6)), None);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_every_letter() {
+        // each letter drawn with a blank column after it, like the puzzles do
+        let rows = (0..HEIGHT)
+            .map(|y| {
+                LETTERS
+                    .iter()
+                    .map(|(_, rows)| format!("{}.", rows[y]))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        let expected = LETTERS.iter().map(|(f, _)| *f).collect::<String>();
+
+        assert_eq!(recognize(&from_drawing(&rows.join("\n"))), Some(expected));
+    }
+}
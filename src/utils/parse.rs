@@ -0,0 +1,106 @@
+use winnow::{
+    ascii::{alphanumeric1, dec_int, space0, space1},
+    combinator::separated,
+    error::{ContextError, StrContext, StrContextValue},
+    Parser,
+};
+
+use crate::error::Error;
+
+// the parsers below work on one line at a time, the day keeps track of which line it is
+pub type Result<O> = winnow::Result<O, ContextError>;
+
+// what the line should have had where parsing stopped, becomes the error message
+pub fn expected(what: &'static str) -> StrContext {
+    StrContext::Expected(StrContextValue::Description(what))
+}
+
+// how the line should look, becomes the hint of the error
+pub fn hint(text: &'static str) -> StrContext {
+    StrContext::Label(text)
+}
+
+// runs the parser over the whole line, an error points from where it stopped to the end of the line
+// the innermost expected and hint contexts are used, so the most specific description wins
+pub fn parse_line<'a, O>(
+    index: usize,
+    line: &'a str,
+    mut parser: impl Parser<&'a str, O, ContextError>,
+) -> core::result::Result<O, Error> {
+    parser.parse(line).map_err(|f| {
+        let message = f
+            .inner()
+            .context()
+            .find_map(|f| match f {
+                StrContext::Expected(value) => Some(format!("expected {}", value)),
+                _ => None,
+            })
+            .unwrap_or_else(|| "unexpected text".to_string());
+
+        let error = Error::parse(index, &message).at(f.offset(), f.offset());
+
+        match f.inner().context().find_map(|f| match f {
+            StrContext::Label(text) => Some(*text),
+            _ => None,
+        }) {
+            Some(text) => error.with_hint(text),
+            None => error,
+        }
+    })
+}
+
+pub fn number(input: &mut &str) -> Result<i64> {
+    dec_int.parse_next(input)
+}
+
+// at least one number, separated by spaces
+pub fn numbers(input: &mut &str) -> Result<Vec<i64>> {
+    separated(1.., number, space1).parse_next(input)
+}
+
+// names of things the input refers to, like workflows or nodes
+pub fn name<'a>(input: &mut &'a str) -> Result<&'a str> {
+    alphanumeric1.parse_next(input)
+}
+
+// a literal with optional spaces around it, like the "=" in "AAA = (BBB, CCC)"
+pub fn spaced<'a>(literal: &'static str) -> impl Parser<&'a str, &'a str, ContextError> {
+    (space0, literal, space0).map(|(_, f, _)| f)
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+    use winnow::combinator::{delimited, separated_pair};
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_parse_line() {
+        let pair = || {
+            separated_pair(name, spaced("="), number)
+                .context(expected("a name and a number"))
+                .context(hint("pairs look like `abc = 12`"))
+        };
+
+        assert_eq!(parse_line(0, "abc = -12", pair()), Ok(("abc", -12)));
+
+        let error = parse_line(3, "abc = twelve", pair()).unwrap_err();
+        assert_eq!(
+            error,
+            Error::parse(3, "expected a name and a number")
+                .at(6, 6)
+                .with_hint("pairs look like `abc = 12`")
+        );
+        assert_eq!(error.to_string(), "line 4: expected a name and a number");
+
+        // text after a complete parse is an error too
+        let error = parse_line(0, "1 2 3)", delimited(space0, numbers, space0)).unwrap_err();
+        assert_eq!(error, Error::parse(0, "unexpected text").at(5, 5));
+        assert_eq!(
+            parse_line(0, " 1 2  3 ", delimited(space0, numbers, space0)),
+            Ok(vec![1, 2, 3])
+        );
+    }
+}
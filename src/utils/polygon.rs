@@ -0,0 +1,266 @@
+use std::cmp::Ordering;
+
+use super::Coordinate;
+
+// x position where a polygon edge crosses a row, kept as an exact fraction (numerator, denominator)
+#[derive(Debug, Clone, Copy)]
+struct Crossing {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Crossing {
+    fn floor(&self) -> i64 {
+        self.numerator.div_euclid(self.denominator)
+    }
+
+    fn ceil(&self) -> i64 {
+        -(-self.numerator).div_euclid(self.denominator)
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numerator as i128 * other.denominator as i128)
+            .cmp(&(other.numerator as i128 * self.denominator as i128))
+    }
+}
+
+fn edges(
+    vertices: &[Coordinate<i64>],
+) -> impl Iterator<Item = (Coordinate<i64>, Coordinate<i64>)> + '_ {
+    (0..vertices.len()).map(|index| (vertices[index], vertices[(index + 1) % vertices.len()]))
+}
+
+// twice the signed area, positive when the vertices wind counter-clockwise
+pub fn shoelace(vertices: &[Coordinate<i64>]) -> i64 {
+    // reference:
+    // https://en.wikipedia.org/wiki/Shoelace_formula
+    edges(vertices)
+        .map(|(current, next)| current.x * next.y - next.x * current.y)
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+// winding of the vertices with y pointing up, None when the polygon has no area
+pub fn orientation(vertices: &[Coordinate<i64>]) -> Option<Orientation> {
+    match shoelace(vertices).cmp(&0) {
+        Ordering::Greater => Some(Orientation::CounterClockwise),
+        Ordering::Less => Some(Orientation::Clockwise),
+        Ordering::Equal => None,
+    }
+}
+
+// reorders the vertices to wind counter-clockwise, so shoelace is never negative
+pub fn normalize(vertices: &mut [Coordinate<i64>]) {
+    if orientation(vertices) == Some(Orientation::Clockwise) {
+        vertices.reverse();
+    }
+}
+
+pub fn on_boundary(vertices: &[Coordinate<i64>], point: Coordinate<i64>) -> bool {
+    edges(vertices).any(|(a, b)| {
+        let cross = (b.x - a.x) * (point.y - a.y) - (b.y - a.y) * (point.x - a.x);
+
+        cross == 0
+            && point.x >= a.x.min(b.x)
+            && point.x <= a.x.max(b.x)
+            && point.y >= a.y.min(b.y)
+            && point.y <= a.y.max(b.y)
+    })
+}
+
+// even-odd rule, points lying on the boundary are not considered inside
+pub fn contains(vertices: &[Coordinate<i64>], point: Coordinate<i64>) -> bool {
+    if on_boundary(vertices, point) {
+        return false;
+    }
+
+    let crossings = row_crossings(vertices, point.y);
+    let right_of_point = Crossing {
+        numerator: point.x,
+        denominator: 1,
+    };
+
+    crossings
+        .iter()
+        .filter(|f| f.cmp(&right_of_point) == Ordering::Greater)
+        .count()
+        % 2
+        == 1
+}
+
+// sorted x positions where the edges cross row y
+// half-open on y so a vertex shared by two edges is only counted once
+fn row_crossings(vertices: &[Coordinate<i64>], y: i64) -> Vec<Crossing> {
+    let mut crossings = vec![];
+
+    for (a, b) in edges(vertices) {
+        if (a.y > y) == (b.y > y) {
+            continue;
+        }
+
+        let (a, b) = if a.y < b.y { (a, b) } else { (b, a) };
+
+        crossings.push(Crossing {
+            numerator: a.x * (b.y - a.y) + (y - a.y) * (b.x - a.x),
+            denominator: b.y - a.y,
+        });
+    }
+
+    crossings.sort_by(|a, b| a.cmp(b));
+    crossings
+}
+
+// interior lattice points of row y, from left to right
+pub fn interior_row(vertices: &[Coordinate<i64>], y: i64) -> Vec<i64> {
+    let crossings = row_crossings(vertices, y);
+    let mut result = vec![];
+
+    for pair in crossings.chunks(2) {
+        assert_eq!(pair.len(), 2, "polygon must be closed");
+
+        // strictly between the two crossings
+        let start = pair[0].floor() + 1;
+        let end = pair[1].ceil() - 1;
+
+        for x in start..=end {
+            if !on_boundary(vertices, Coordinate::new(x, y)) {
+                result.push(x);
+            }
+        }
+    }
+
+    result
+}
+
+// scanline rasterizer yielding every interior lattice point, row by row from the bottom
+pub fn interior_points(vertices: &[Coordinate<i64>]) -> impl Iterator<Item = Coordinate<i64>> + '_ {
+    let min_y = vertices.iter().map(|f| f.y).min().unwrap_or(0);
+    let max_y = vertices.iter().map(|f| f.y).max().unwrap_or(-1);
+
+    (min_y..=max_y).flat_map(move |y| {
+        interior_row(vertices, y)
+            .into_iter()
+            .map(move |x| Coordinate::new(x, y))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    fn square() -> Vec<Coordinate<i64>> {
+        vec![
+            Coordinate::new(0, 0),
+            Coordinate::new(4, 0),
+            Coordinate::new(4, 4),
+            Coordinate::new(0, 4),
+        ]
+    }
+
+    fn notched() -> Vec<Coordinate<i64>> {
+        // U shape, the notch points into the polygon from the top
+        vec![
+            Coordinate::new(0, 0),
+            Coordinate::new(6, 0),
+            Coordinate::new(6, 6),
+            Coordinate::new(4, 6),
+            Coordinate::new(4, 2),
+            Coordinate::new(2, 2),
+            Coordinate::new(2, 6),
+            Coordinate::new(0, 6),
+        ]
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_shoelace() {
+        assert_eq!(shoelace(&square()), 32);
+
+        let mut clockwise = square();
+        clockwise.reverse();
+        assert_eq!(shoelace(&clockwise), -32);
+
+        assert_eq!(shoelace(&notched()), 2 * (36 - 8));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_orientation() {
+        let mut polygon = square();
+        assert_eq!(orientation(&polygon), Some(Orientation::CounterClockwise));
+
+        polygon.reverse();
+        assert_eq!(orientation(&polygon), Some(Orientation::Clockwise));
+
+        normalize(&mut polygon);
+        assert_eq!(orientation(&polygon), Some(Orientation::CounterClockwise));
+        assert_eq!(shoelace(&polygon), 32);
+
+        let line = vec![Coordinate::new(0, 0), Coordinate::new(3, 0)];
+        assert_eq!(orientation(&line), None);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_contains() {
+        let polygon = notched();
+
+        assert!(contains(&polygon, Coordinate::new(1, 1)));
+        assert!(contains(&polygon, Coordinate::new(1, 5)));
+        assert!(contains(&polygon, Coordinate::new(5, 3)));
+
+        // inside the notch
+        assert!(!contains(&polygon, Coordinate::new(3, 4)));
+        // on the boundary
+        assert!(!contains(&polygon, Coordinate::new(3, 2)));
+        assert!(!contains(&polygon, Coordinate::new(0, 3)));
+        assert!(!contains(&polygon, Coordinate::new(4, 6)));
+        // outside
+        assert!(!contains(&polygon, Coordinate::new(7, 3)));
+        assert!(!contains(&polygon, Coordinate::new(-1, 6)));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_interior_points() {
+        let points = interior_points(&square()).collect::<Vec<_>>();
+        assert_eq!(points.len(), 9);
+        assert_eq!(points.first(), Some(&Coordinate::new(1, 1)));
+        assert_eq!(points.last(), Some(&Coordinate::new(3, 3)));
+
+        let polygon = notched();
+        let points = interior_points(&polygon).collect::<Vec<_>>();
+
+        // pick's theorem: interior = area - boundary / 2 + 1
+        let boundary = 32;
+        assert_eq!(
+            points.len() as i64,
+            shoelace(&polygon) / 2 - boundary / 2 + 1
+        );
+        assert!(points.iter().all(|&f| contains(&polygon, f)));
+
+        // a triangle with non lattice crossings
+        let triangle = vec![
+            Coordinate::new(0, 0),
+            Coordinate::new(5, 0),
+            Coordinate::new(0, 3),
+        ];
+        let points = interior_points(&triangle).collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![
+                Coordinate::new(1, 1),
+                Coordinate::new(2, 1),
+                Coordinate::new(3, 1),
+                Coordinate::new(1, 2)
+            ]
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use num::{rational::Ratio, One, ToPrimitive, Zero};
+
+// polynomial with exact rational coefficients, lowest degree first
+// for "simulate a few periods, then extrapolate" puzzles where the answer is known to be polynomial
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    coefficients: Vec<Ratio<i128>>,
+}
+
+impl Polynomial {
+    // the lowest degree polynomial through every point, lagrange style
+    // n points give a degree of at most n - 1, None if two points share an x
+    pub fn fit(points: &[(i64, i64)]) -> Option<Self> {
+        let mut coefficients = vec![Ratio::zero(); points.len()];
+
+        for (i, &(xi, yi)) in points.iter().enumerate() {
+            // basis polynomial that is 1 at xi and 0 at every other sample
+            let mut basis = vec![Ratio::one()];
+            let mut denominator = 1_i128;
+
+            for (j, &(xj, _)) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+
+                if xi == xj {
+                    return None;
+                }
+
+                basis = multiply_linear(&basis, -(xj as i128));
+                denominator *= (xi - xj) as i128;
+            }
+
+            let scale = Ratio::new(yi as i128, denominator);
+            for (coefficient, value) in coefficients.iter_mut().zip(basis) {
+                *coefficient += value * scale;
+            }
+        }
+
+        while coefficients.len() > 1 && coefficients.last().is_some_and(|f| f.is_zero()) {
+            coefficients.pop();
+        }
+
+        Some(Self { coefficients })
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    pub fn coefficients(&self) -> &[Ratio<i128>] {
+        &self.coefficients
+    }
+
+    pub fn evaluate(&self, x: i64) -> Ratio<i128> {
+        let x = Ratio::from_integer(x as i128);
+
+        // horner's method
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Ratio::zero(), |acc, coefficient| acc * x + coefficient)
+    }
+
+    // None when the value is not a whole number or does not fit an i64
+    pub fn evaluate_integer(&self, x: i64) -> Option<i64> {
+        let value = self.evaluate(x);
+
+        if value.is_integer() {
+            value.to_integer().to_i64()
+        } else {
+            None
+        }
+    }
+}
+
+// multiplies the polynomial by (x + constant)
+fn multiply_linear(polynomial: &[Ratio<i128>], constant: i128) -> Vec<Ratio<i128>> {
+    let mut result = vec![Ratio::zero(); polynomial.len() + 1];
+
+    for (degree, coefficient) in polynomial.iter().enumerate() {
+        result[degree] += coefficient * constant;
+        result[degree + 1] += coefficient;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_fit() {
+        // the last day 09 example, extrapolated both ways
+        let points = [10, 13, 16, 21, 30, 45]
+            .iter()
+            .enumerate()
+            .map(|(x, y)| (x as i64, *y))
+            .collect::<Vec<_>>();
+        let polynomial = Polynomial::fit(&points).unwrap();
+
+        assert_eq!(polynomial.degree(), 3);
+        assert_eq!(polynomial.evaluate_integer(6), Some(68));
+        assert_eq!(polynomial.evaluate_integer(-1), Some(5));
+
+        // a quadratic sampled once per period, evaluated far away
+        let quadratic = |x: i64| 3 * x * x - 7 * x + 11;
+        let points = [65, 196, 327].map(|x| (x, quadratic(x)));
+        let polynomial = Polynomial::fit(&points).unwrap();
+
+        assert_eq!(polynomial.degree(), 2);
+        assert_eq!(
+            polynomial.coefficients(),
+            &[Ratio::from(11), Ratio::from(-7), Ratio::from(3)]
+        );
+        assert_eq!(
+            polynomial.evaluate_integer(26501365),
+            Some(quadratic(26501365))
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_fit_exact() {
+        // x / 2 through (0, 0) and (2, 1) is not an integer at 1
+        let polynomial = Polynomial::fit(&[(0, 0), (2, 1)]).unwrap();
+        assert_eq!(polynomial.evaluate(1), Ratio::new(1, 2));
+        assert_eq!(polynomial.evaluate_integer(1), None);
+        assert_eq!(polynomial.evaluate_integer(4), Some(2));
+
+        let constant = Polynomial::fit(&[(1, 7), (5, 7), (9, 7)]).unwrap();
+        assert_eq!(constant.degree(), 0);
+        assert_eq!(constant.evaluate_integer(100), Some(7));
+
+        assert!(Polynomial::fit(&[(1, 2), (1, 3)]).is_none());
+    }
+}
@@ -0,0 +1,161 @@
+use std::{collections::HashMap, hash::Hash};
+
+// min-heap keyed by K, so the priority of a key already in the queue can be lowered in place
+// instead of pushing a stale duplicate entry and filtering it later
+#[derive(Debug)]
+pub struct PriorityQueue<K, P> {
+    heap: Vec<(K, P)>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K, P> Default for PriorityQueue<K, P> {
+    fn default() -> Self {
+        Self {
+            heap: vec![],
+            positions: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord + Copy> PriorityQueue<K, P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // insert a new key, or decrease the priority of an existing one
+    // returns false if the key is already queued with an equal or lower priority
+    pub fn push(&mut self, key: K, priority: P) -> bool {
+        match self.positions.get(&key) {
+            Some(&index) => {
+                if priority >= self.heap[index].1 {
+                    return false;
+                }
+
+                self.heap[index].1 = priority;
+                self.sift_up(index);
+            }
+            None => {
+                let index = self.heap.len();
+                self.positions.insert(key.clone(), index);
+                self.heap.push((key, priority));
+                self.sift_up(index);
+            }
+        }
+
+        true
+    }
+
+    // the lowest priority without removing it
+    pub fn peek(&self) -> Option<P> {
+        self.heap.first().map(|(_, priority)| *priority)
+    }
+
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+
+        let (key, priority) = self.heap.pop().unwrap();
+        self.positions.remove(&key);
+
+        if !self.heap.is_empty() {
+            self.sift_down(0);
+        }
+
+        Some((key, priority))
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        *self.positions.get_mut(&self.heap[a].0).unwrap() = a;
+        *self.positions.get_mut(&self.heap[b].0).unwrap() = b;
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+
+            if self.heap[index].1 >= self.heap[parent].1 {
+                break;
+            }
+
+            self.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        let len = self.heap.len();
+
+        loop {
+            let left = index * 2 + 1;
+            let right = left + 1;
+            let mut smallest = index;
+
+            if left < len && self.heap[left].1 < self.heap[smallest].1 {
+                smallest = left;
+            }
+
+            if right < len && self.heap[right].1 < self.heap[smallest].1 {
+                smallest = right;
+            }
+
+            if smallest == index {
+                break;
+            }
+
+            self.swap(index, smallest);
+            index = smallest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::PriorityQueue;
+
+    #[traced_test]
+    #[test]
+    fn test_pop_order() {
+        let mut items = vec![100, 20, 50, 25, 30, 0, 21, -5, 1];
+        let mut queue = PriorityQueue::new();
+
+        for (key, item) in items.iter().enumerate() {
+            queue.push(key, *item);
+        }
+
+        let mut result = vec![];
+        while let Some((_, priority)) = queue.pop() {
+            result.push(priority);
+        }
+
+        items.sort();
+        assert_eq!(items, result);
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_decrease_key() {
+        let mut queue = PriorityQueue::new();
+
+        queue.push("a", 10);
+        queue.push("b", 20);
+        queue.push("c", 30);
+
+        // higher priority is ignored, lower priority updates in place
+        assert!(!queue.push("c", 40));
+        assert!(queue.push("c", 5));
+
+        assert_eq!(queue.peek(), Some(5));
+        assert_eq!(queue.pop(), Some(("c", 5)));
+        assert_eq!(queue.pop(), Some(("a", 10)));
+        assert_eq!(queue.pop(), Some(("b", 20)));
+        assert_eq!(queue.pop(), None);
+        assert_eq!(queue.peek(), None);
+    }
+}
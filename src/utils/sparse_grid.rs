@@ -0,0 +1,224 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+use tracing::info;
+
+use super::{grid::GridCell, Coordinate};
+
+// grid that only stores the cells that were set, for maps that are huge or have no fixed size
+// every other cell reads as the default value, coordinates may be negative
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    default: T,
+    cells: HashMap<Coordinate<i32>, T>,
+    // smallest and largest coordinate ever set, kept even when cells are removed
+    bounds: Option<(Coordinate<i32>, Coordinate<i32>)>,
+}
+
+impl<T: Clone> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        Self {
+            default,
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>, default: T) -> Self {
+        let mut grid = Self::new(default);
+
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, value) in row.into_iter().enumerate() {
+                grid.set(Coordinate::new(x as i32, y as i32), value);
+            }
+        }
+
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.bounds
+            .map(|(min, max)| (max.x - min.x + 1) as usize)
+            .unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.bounds
+            .map(|(min, max)| (max.y - min.y + 1) as usize)
+            .unwrap_or(0)
+    }
+
+    // bounding box of every cell that was set, inclusive on both ends
+    pub fn bounds(&self) -> Option<(Coordinate<i32>, Coordinate<i32>)> {
+        self.bounds
+    }
+
+    pub fn contains(&self, coordinate: Coordinate<i32>) -> bool {
+        self.bounds.is_some_and(|(min, max)| {
+            coordinate.x >= min.x
+                && coordinate.y >= min.y
+                && coordinate.x <= max.x
+                && coordinate.y <= max.y
+        })
+    }
+
+    // never None, cells that were not set read as the default value
+    pub fn get(&self, coordinate: Coordinate<i32>) -> Option<&T> {
+        Some(self.cells.get(&coordinate).unwrap_or(&self.default))
+    }
+
+    // a cell that was not set is filled with the default value first
+    pub fn get_mut(&mut self, coordinate: Coordinate<i32>) -> Option<&mut T> {
+        self.extend_bounds(coordinate);
+
+        Some(
+            self.cells
+                .entry(coordinate)
+                .or_insert_with(|| self.default.clone()),
+        )
+    }
+
+    pub fn set(&mut self, coordinate: Coordinate<i32>, value: T) {
+        self.extend_bounds(coordinate);
+        self.cells.insert(coordinate, value);
+    }
+
+    // the cell reads as the default value again
+    pub fn remove(&mut self, coordinate: Coordinate<i32>) -> Option<T> {
+        self.cells.remove(&coordinate)
+    }
+
+    // number of stored cells, not the area of the bounding box
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Coordinate<i32>, &T)> {
+        self.cells.iter()
+    }
+
+    // rows of the bounding box from the smallest y, missing cells are filled with the default value
+    pub fn rows(&self) -> impl Iterator<Item = Vec<&T>> {
+        let (min, max) = self
+            .bounds
+            .unwrap_or((Coordinate::new(0, 0), Coordinate::new(-1, -1)));
+
+        (min.y..=max.y).map(move |y| {
+            (min.x..=max.x)
+                .map(|x| self.get(Coordinate::new(x, y)).unwrap())
+                .collect()
+        })
+    }
+
+    fn extend_bounds(&mut self, coordinate: Coordinate<i32>) {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (
+                Coordinate::new(min.x.min(coordinate.x), min.y.min(coordinate.y)),
+                Coordinate::new(max.x.max(coordinate.x), max.y.max(coordinate.y)),
+            ),
+            None => (coordinate, coordinate),
+        });
+    }
+}
+
+impl<T: GridCell + Clone> SparseGrid<T> {
+    pub fn display(&self) {
+        info!("\n{}", self);
+    }
+}
+
+impl<T: GridCell + Clone> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Cell {
+        Trench,
+        Ground,
+    }
+
+    impl Display for Cell {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let text = match self {
+                Cell::Trench => "#",
+                Cell::Ground => ".",
+            };
+
+            write!(f, "{}", text)
+        }
+    }
+
+    impl GridCell for Cell {}
+
+    #[traced_test]
+    #[test]
+    fn test_sparse_grid_access() {
+        let mut grid = SparseGrid::new(Cell::Ground);
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.to_string(), "");
+
+        grid.set(Coordinate::new(-2, 1), Cell::Trench);
+        grid.set(Coordinate::new(1_000_000, -3), Cell::Trench);
+
+        assert_eq!(grid.len(), 2);
+        assert_eq!(
+            grid.bounds(),
+            Some((Coordinate::new(-2, -3), Coordinate::new(1_000_000, 1)))
+        );
+        assert_eq!(grid.width(), 1_000_003);
+        assert_eq!(grid.height(), 5);
+        assert!(grid.contains(Coordinate::new(0, 0)));
+        assert!(!grid.contains(Coordinate::new(0, 2)));
+
+        // unset cells read as the default, even outside the bounding box
+        assert_eq!(grid.get(Coordinate::new(5, 5)), Some(&Cell::Ground));
+        assert_eq!(grid.get(Coordinate::new(-2, 1)), Some(&Cell::Trench));
+
+        *grid.get_mut(Coordinate::new(3, 7)).unwrap() = Cell::Trench;
+        assert_eq!(grid.height(), 11);
+
+        assert_eq!(grid.remove(Coordinate::new(-2, 1)), Some(Cell::Trench));
+        assert_eq!(grid.get(Coordinate::new(-2, 1)), Some(&Cell::Ground));
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_sparse_grid_display() {
+        let grid = SparseGrid::from_rows(
+            vec![
+                vec![Cell::Trench, Cell::Ground],
+                vec![Cell::Ground, Cell::Trench],
+            ],
+            Cell::Ground,
+        );
+
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.to_string(), "#.\n.#\n");
+
+        let mut grid = SparseGrid::new(Cell::Ground);
+        grid.set(Coordinate::new(-1, -1), Cell::Trench);
+        grid.set(Coordinate::new(1, 0), Cell::Trench);
+        assert_eq!(grid.to_string(), "#..\n..#\n");
+    }
+}
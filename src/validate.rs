@@ -0,0 +1,237 @@
+use crate::error::Error;
+
+// a check on the raw input that runs without solving, to catch truncated or mangled pastes early
+pub trait Validator {
+    fn validate(&self, input: &str) -> Vec<Error>;
+}
+
+// the input has at least one non blank line
+pub struct NotEmpty;
+
+impl Validator for NotEmpty {
+    fn validate(&self, input: &str) -> Vec<Error> {
+        if input.lines().any(|f| !f.trim().is_empty()) {
+            vec![]
+        } else {
+            vec![Error::parse(0, "input is empty").with_hint("is input/NN downloaded?")]
+        }
+    }
+}
+
+// every line of a block has the width of the block's first line, blocks are split by blank lines
+pub struct Rectangular;
+
+impl Validator for Rectangular {
+    fn validate(&self, input: &str) -> Vec<Error> {
+        let mut errors = vec![];
+        let mut width = None;
+
+        for (index, line) in input.lines().enumerate() {
+            if line.is_empty() {
+                width = None;
+                continue;
+            }
+
+            let expected = *width.get_or_insert(line.len());
+
+            if line.len() != expected {
+                let message = format!("expected {} columns, got {}", expected, line.len());
+                errors.push(Error::parse(index, &message).with_hint("grids must be rectangular"));
+            }
+        }
+
+        errors
+    }
+}
+
+// only the given characters appear in the input
+pub struct Alphabet(pub &'static str);
+
+impl Validator for Alphabet {
+    fn validate(&self, input: &str) -> Vec<Error> {
+        let mut errors = vec![];
+
+        for (index, line) in input.lines().enumerate() {
+            for (column, c) in line.char_indices() {
+                if !self.0.contains(c) {
+                    let message = format!("unexpected character {:?}", c);
+                    let hint = format!("expected one of {:?}", self.0);
+
+                    errors.push(
+                        Error::parse(index, &message)
+                            .at(column, column + c.len_utf8())
+                            .with_hint(&hint),
+                    );
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+// the character appears exactly once, like the starting point of day 10
+pub struct ExactlyOne(pub char);
+
+impl Validator for ExactlyOne {
+    fn validate(&self, input: &str) -> Vec<Error> {
+        let positions = input
+            .lines()
+            .enumerate()
+            .flat_map(|(index, line)| {
+                line.char_indices()
+                    .filter(|(_, c)| *c == self.0)
+                    .map(move |(column, _)| (index, column))
+            })
+            .collect::<Vec<_>>();
+
+        if positions.is_empty() {
+            let message = format!("no {:?} in the input", self.0);
+            return vec![Error::parse(0, &message)];
+        }
+
+        // point at every occurrence after the first one
+        positions
+            .iter()
+            .skip(1)
+            .map(|&(index, column)| {
+                let message = format!("expected exactly one {:?}", self.0);
+                Error::parse(index, &message).at(column, column + self.0.len_utf8())
+            })
+            .collect()
+    }
+}
+
+// every line opens and closes its braces, like the workflows of day 19
+pub struct BalancedBraces;
+
+impl Validator for BalancedBraces {
+    fn validate(&self, input: &str) -> Vec<Error> {
+        let mut errors = vec![];
+
+        for (index, line) in input.lines().enumerate() {
+            let mut depth = 0;
+
+            for c in line.chars() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+
+                if depth < 0 {
+                    break;
+                }
+            }
+
+            if depth != 0 {
+                errors.push(
+                    Error::parse(index, "unbalanced braces")
+                        .with_hint("the line may have been cut off"),
+                );
+            }
+        }
+
+        errors
+    }
+}
+
+// the maps of day 05 chain from seed to location and their source ranges do not overlap
+pub struct AlmanacMaps;
+
+impl Validator for AlmanacMaps {
+    fn validate(&self, input: &str) -> Vec<Error> {
+        crate::day05::diagnostics(input)
+    }
+}
+
+pub fn validators(day: i32) -> Vec<Box<dyn Validator>> {
+    let mut result: Vec<Box<dyn Validator>> = vec![Box::new(NotEmpty)];
+
+    match day {
+        3 => result.push(Box::new(Rectangular)),
+        5 => result.push(Box::new(AlmanacMaps)),
+        10 => {
+            result.push(Box::new(Rectangular));
+            result.push(Box::new(Alphabet("|-LJ7F.S")));
+            result.push(Box::new(ExactlyOne('S')));
+        }
+        11 => {
+            result.push(Box::new(Rectangular));
+            result.push(Box::new(Alphabet(".#")));
+        }
+        13 => {
+            result.push(Box::new(Rectangular));
+            result.push(Box::new(Alphabet(".#")));
+        }
+        14 => {
+            result.push(Box::new(Rectangular));
+            result.push(Box::new(Alphabet(".#O")));
+        }
+        16 => {
+            result.push(Box::new(Rectangular));
+            result.push(Box::new(Alphabet(r".|-\/")));
+        }
+        17 => {
+            result.push(Box::new(Rectangular));
+            result.push(Box::new(Alphabet("0123456789")));
+        }
+        19 => result.push(Box::new(BalancedBraces)),
+        _ => {}
+    }
+
+    result
+}
+
+// every problem found by the validators of the day, in input order
+pub fn validate(day: i32, input: &str) -> Vec<Error> {
+    let mut errors = validators(day)
+        .iter()
+        .flat_map(|f| f.validate(input))
+        .collect::<Vec<_>>();
+
+    errors.sort_by_key(|f| {
+        let Error::Parse { line, column, .. } = f;
+        (*line, *column)
+    });
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_day10() {
+        assert!(validate(10, ".....\n.S-7.\n.|.|.\n.L-J.\n.....\n").is_empty());
+
+        let errors = validate(10, ".....\n.S-7S\n.|x|.\n.L-J\n");
+        let messages = errors.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            messages,
+            vec![
+                "line 2: expected exactly one 'S'",
+                "line 3: unexpected character 'x'",
+                "line 4: expected 5 columns, got 4",
+            ]
+        );
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_blocks_and_braces() {
+        // day 13 patterns may have different sizes
+        assert!(validate(13, "#.#\n.#.\n\n##\n..\n").is_empty());
+
+        let errors = validate(19, "px{a<2006:qkq,m>2090:A,rfg}\npv{a>1716:R\n\n{x=787}\n");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "line 2: unbalanced braces");
+
+        assert_eq!(validate(1, "\n\n").len(), 1);
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::{Read, Seek},
+    path::Path,
+};
+
+use color_eyre::eyre::{bail, eyre, Result};
+
+// inputs are not to be redistributed, encrypted ones start with this line so a public copy of
+// input/ only leaks that there are inputs
+pub const MAGIC: &[u8] = b"aoc-encrypted-v1\n";
+
+pub const PASSPHRASE_VARIABLE: &str = "AOC_INPUT_PASSPHRASE";
+
+// after MAGIC: the salt of the key, the nonce, then the sealed input with its tag
+#[cfg(feature = "encrypt")]
+const SALT_LENGTH: usize = 16;
+#[cfg(feature = "encrypt")]
+const NONCE_LENGTH: usize = 12;
+// PBKDF2-HMAC-SHA256, slow enough to make guessing a short passphrase expensive
+#[cfg(feature = "encrypt")]
+const ITERATIONS: u32 = 200_000;
+
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+// peeks at the start of an open input and puts the file back where it was
+pub fn is_encrypted_file(mut file: &File) -> Result<bool> {
+    let mut header = [0; MAGIC.len()];
+    let encrypted = file.read_exact(&mut header).is_ok() && is_encrypted(&header);
+    file.rewind()?;
+
+    Ok(encrypted)
+}
+
+pub fn passphrase() -> Option<String> {
+    env::var(PASSPHRASE_VARIABLE).ok().filter(|f| !f.is_empty())
+}
+
+pub fn require_passphrase() -> Result<String> {
+    passphrase().ok_or_else(|| {
+        eyre!(
+            "set {} to the passphrase of the inputs",
+            PASSPHRASE_VARIABLE
+        )
+    })
+}
+
+// an input as text, decrypted when it was saved encrypted
+pub fn read(path: &Path) -> Result<String> {
+    let bytes = fs::read(path)?;
+    let bytes = match is_encrypted(&bytes) {
+        true => decrypt(&bytes, &require_passphrase()?)
+            .map_err(|f| eyre!("could not decrypt {}: {}", path.display(), f))?,
+        false => bytes,
+    };
+
+    String::from_utf8(bytes).map_err(|f| eyre!("{} is not valid utf-8: {}", path.display(), f))
+}
+
+// writes an input or what was made from one, like a saved example, encrypted while a passphrase
+// is set, the plain text never reaches the disk then
+pub fn write(path: &Path, content: impl AsRef<[u8]>) -> Result<()> {
+    match passphrase() {
+        Some(passphrase) => replace(path, &encrypt(content.as_ref(), &passphrase)?),
+        None => replace(path, content.as_ref()),
+    }
+}
+
+// false when the file was already encrypted
+pub fn encrypt_file(path: &Path, passphrase: &str) -> Result<bool> {
+    let bytes = fs::read(path)?;
+    if is_encrypted(&bytes) {
+        return Ok(false);
+    }
+
+    replace(path, &encrypt(&bytes, passphrase)?)?;
+    Ok(true)
+}
+
+// false when the file was not encrypted
+pub fn decrypt_file(path: &Path, passphrase: &str) -> Result<bool> {
+    let bytes = fs::read(path)?;
+    if !is_encrypted(&bytes) {
+        return Ok(false);
+    }
+
+    let plain = decrypt(&bytes, passphrase)
+        .map_err(|f| eyre!("could not decrypt {}: {}", path.display(), f))?;
+    replace(path, &plain)?;
+    Ok(true)
+}
+
+// through a temporary file, so a crash while writing leaves the old file whole, not a half
+// written one or an input that was only half encrypted
+fn replace(path: &Path, bytes: &[u8]) -> Result<()> {
+    let temporary = path.with_extension("tmp");
+    fs::write(&temporary, bytes)?;
+    if let Err(error) = fs::rename(&temporary, path) {
+        let _ = fs::remove_file(&temporary);
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "encrypt")]
+pub fn encrypt(plain: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    use ring::{
+        aead::{Aad, LessSafeKey, Nonce},
+        rand::{SecureRandom, SystemRandom},
+    };
+
+    let random = SystemRandom::new();
+    let mut salt = [0; SALT_LENGTH];
+    let mut nonce = [0; NONCE_LENGTH];
+    random
+        .fill(&mut salt)
+        .and_then(|_| random.fill(&mut nonce))
+        .map_err(|_| eyre!("the system has no randomness to offer"))?;
+
+    let mut sealed = plain.to_vec();
+    LessSafeKey::new(key(passphrase, &salt))
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(MAGIC),
+            &mut sealed,
+        )
+        .map_err(|_| eyre!("could not encrypt"))?;
+
+    Ok([MAGIC, &salt, &nonce, &sealed].concat())
+}
+
+#[cfg(feature = "encrypt")]
+pub fn decrypt(bytes: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    use ring::aead::{Aad, LessSafeKey, Nonce};
+
+    let Some(rest) = bytes.strip_prefix(MAGIC) else {
+        bail!("not an encrypted input");
+    };
+    if rest.len() < SALT_LENGTH + NONCE_LENGTH {
+        bail!("the encrypted input is cut short");
+    }
+
+    let (salt, rest) = rest.split_at(SALT_LENGTH);
+    let (nonce, sealed) = rest.split_at(NONCE_LENGTH);
+    let mut sealed = sealed.to_vec();
+    let plain = LessSafeKey::new(key(passphrase, salt))
+        .open_in_place(
+            Nonce::try_assume_unique_for_key(nonce).unwrap(),
+            Aad::from(MAGIC),
+            &mut sealed,
+        )
+        .map_err(|_| eyre!("wrong passphrase, or the file was changed"))?;
+
+    Ok(plain.to_vec())
+}
+
+#[cfg(feature = "encrypt")]
+fn key(passphrase: &str, salt: &[u8]) -> ring::aead::UnboundKey {
+    use ring::{aead, pbkdf2};
+
+    let mut key = [0; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        std::num::NonZeroU32::new(ITERATIONS).unwrap(),
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+
+    aead::UnboundKey::new(&aead::CHACHA20_POLY1305, &key).unwrap()
+}
+
+#[cfg(not(feature = "encrypt"))]
+pub fn encrypt(_plain: &[u8], _passphrase: &str) -> Result<Vec<u8>> {
+    bail!(missing_feature())
+}
+
+#[cfg(not(feature = "encrypt"))]
+pub fn decrypt(_bytes: &[u8], _passphrase: &str) -> Result<Vec<u8>> {
+    bail!(missing_feature())
+}
+
+#[cfg(not(feature = "encrypt"))]
+fn missing_feature() -> String {
+    "encrypted inputs need a build with the encrypt feature, e.g. cargo run --features encrypt"
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_is_encrypted() {
+        assert!(is_encrypted(b"aoc-encrypted-v1\n\x00\x01"));
+        assert!(!is_encrypted(b"Game 1: 3 blue\n"));
+
+        // without the feature an encrypted input explains what is missing instead of parsing garbage
+        #[cfg(not(feature = "encrypt"))]
+        assert!(decrypt(MAGIC, "secret")
+            .unwrap_err()
+            .to_string()
+            .contains("encrypt feature"));
+    }
+
+    #[cfg(feature = "encrypt")]
+    #[traced_test]
+    #[test]
+    fn test_encrypt() {
+        let input = crate::test_support::fixture("day02");
+        let sealed = encrypt(input.as_bytes(), "secret").unwrap();
+
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed.windows(4).any(|f| f == b"Game"));
+        assert_ne!(sealed, encrypt(input.as_bytes(), "secret").unwrap());
+        assert_eq!(decrypt(&sealed, "secret").unwrap(), input.as_bytes());
+        assert!(decrypt(&sealed, "guess").is_err());
+        assert!(decrypt(&sealed[..MAGIC.len() + 4], "secret").is_err());
+
+        let mut changed = sealed.clone();
+        *changed.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&changed, "secret").is_err());
+    }
+
+    #[traced_test]
+    #[test]
+    fn test_encrypt_file() {
+        let directory = env::temp_dir().join("advent-of-code-2023-vault-test");
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("05");
+        fs::write(&path, "old").unwrap();
+
+        replace(&path, b"seeds: 79 14").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "seeds: 79 14");
+        assert!(!path.with_extension("tmp").exists());
+
+        // the file is only replaced once the encrypted input is ready
+        #[cfg(not(feature = "encrypt"))]
+        assert!(encrypt_file(&path, "secret").is_err());
+        #[cfg(feature = "encrypt")]
+        {
+            assert!(encrypt_file(&path, "secret").unwrap());
+            assert!(is_encrypted(&fs::read(&path).unwrap()));
+            assert!(!encrypt_file(&path, "secret").unwrap());
+            assert!(decrypt_file(&path, "guess").is_err());
+            assert!(decrypt_file(&path, "secret").unwrap());
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "seeds: 79 14");
+        assert!(!path.with_extension("tmp").exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{error::Result, solver::Answer};
+
+// wasm32-unknown-unknown has no clock, the page times the calls with performance.now()
+#[wasm_bindgen]
+pub struct Solution {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Solution {
+    #[wasm_bindgen(getter)]
+    pub fn part1(&self) -> Option<String> {
+        self.part1.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn part2(&self) -> Option<String> {
+        self.part2.clone()
+    }
+}
+
+impl From<Answer> for Solution {
+    fn from(answer: Answer) -> Self {
+        Self {
+            part1: answer.part1,
+            part2: answer.part2,
+        }
+    }
+}
+
+// the default variant of every day, without std only the no_std capable days are there
+#[cfg(feature = "std")]
+fn solver(day: i32) -> Option<fn(&str) -> Result<Answer>> {
+    crate::registry::variants(day).first().map(|f| f.solve)
+}
+
+#[cfg(not(feature = "std"))]
+fn solver(day: i32) -> Option<fn(&str) -> Result<Answer>> {
+    match day {
+        1 => Some(crate::day01::solve),
+        6 => Some(crate::day06::solve),
+        9 => Some(crate::day09::solve),
+        15 => Some(crate::day15::solve),
+        _ => None,
+    }
+}
+
+// the days this build can solve, for the selector of the page
+#[wasm_bindgen]
+pub fn days() -> Vec<i32> {
+    (1..=25).filter(|f| solver(*f).is_some()).collect()
+}
+
+// errors reach javascript as the thrown string, with the line of the input they are about
+#[wasm_bindgen]
+pub fn solve(day: i32, input: &str) -> core::result::Result<Solution, String> {
+    let solve = solver(day).ok_or_else(|| alloc::format!("day {} is not in this build", day))?;
+    let input = crate::solver::normalize_input(input);
+
+    solve(&input).map(Solution::from).map_err(|f| f.to_string())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[traced_test]
+    #[test]
+    fn test_solve() {
+        assert!(days().contains(&9));
+
+        let solution = solve(9, &crate::test_support::fixture("day09")).unwrap();
+        assert_eq!(solution.part1().as_deref(), Some("114"));
+        assert_eq!(solution.part2().as_deref(), Some("2"));
+
+        assert_eq!(solve(26, "").err().unwrap(), "day 26 is not in this build");
+        assert!(solve(9, "1 x 3\n").is_err());
+    }
+}
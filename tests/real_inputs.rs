@@ -0,0 +1,67 @@
+// runs every day against its real input and compares with input/answers
+// enabled with AOC_REAL_INPUTS=1, since puzzle inputs are personal and not part of the repository
+//
+// input/answers has one line per day: the day, then the part 1 and part 2 answers, - for a part
+// without an accepted answer yet
+// 01 54601 54078
+
+// reads input/ like the binary does, through the profile and the vault
+#![cfg(feature = "tooling")]
+
+use std::{env, path::Path};
+
+use advent_of_code_2023::{calendar, registry, solver};
+
+#[test]
+fn test_real_inputs() {
+    if env::var("AOC_REAL_INPUTS").as_deref() != Ok("1") {
+        eprintln!("skipped, set AOC_REAL_INPUTS=1 to run against input/");
+        return;
+    }
+
+    // tests run from the package root, where input/ lives
+    let answers_path = calendar::answers_path();
+    assert!(
+        Path::new(&answers_path).exists(),
+        "{} is missing",
+        answers_path
+    );
+    let answers = calendar::read_answers(Path::new(&answers_path)).unwrap();
+    let mut failures = vec![];
+
+    for (day, (part1, part2)) in answers {
+        let path = solver::input_path(day);
+        if !Path::new(&path).exists() {
+            eprintln!("day {:0>2}: skipped, {} is missing", day, path);
+            continue;
+        }
+        // a wrong passphrase or a file that can not be read is a failure, not a missing input
+        let input = match solver::read_file(Path::new(&path)) {
+            Ok(input) => input,
+            Err(error) => {
+                failures.push(format!("day {:0>2}: {}", day, error));
+                continue;
+            }
+        };
+
+        // only the default variant, alternatives like the day 05 brute force are too slow here
+        if let Some(variant) = registry::variants(day).first() {
+            match (variant.solve)(&input) {
+                Ok(answer) => {
+                    let differs = |answer: &Option<String>, expected: &String| {
+                        expected != "-" && answer.as_ref() != Some(expected)
+                    };
+                    if differs(&answer.part1, &part1) || differs(&answer.part2, &part2) {
+                        failures.push(format!(
+                            "{}: expected {} / {}, got {:?} / {:?}",
+                            variant.name, part1, part2, answer.part1, answer.part2
+                        ));
+                    }
+                }
+                Err(error) => failures.push(format!("{}: {}", variant.name, error)),
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}